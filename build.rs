@@ -0,0 +1,13 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Only the grpc feature has anything to generate
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/gimbal.proto");
+        let mut config = prost_build::Config::new();
+        config.protoc_executable(protoc_bin_vendored::protoc_bin_path()?);
+        tonic_build::configure()
+            .build_client(false)
+            .compile_protos_with_config(config, &["proto/gimbal.proto"], &["proto"])?;
+    }
+    Ok(())
+}
@@ -0,0 +1,39 @@
+// Deflection at which an axis presses its virtual button, and the one it has to come back
+// inside to release it. The gap keeps a hat resting near the threshold from chattering.
+const PRESS_AT: f64 = 0.5;
+const RELEASE_AT: f64 = 0.3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Positive,
+    Negative,
+}
+
+// The two virtual buttons of one [[controls.axis_buttons]] entry; at most one is down
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VirtualButton {
+    down: Option<Side>,
+}
+
+impl VirtualButton {
+    // Feeds the axis' latest value; returns the side that was just pressed, if any. An axis
+    // flipping straight across center releases one side and presses the other.
+    pub fn update(&mut self, value: f64) -> Option<Side> {
+        let held = match self.down {
+            Some(Side::Positive) => value >= RELEASE_AT,
+            Some(Side::Negative) => value <= -RELEASE_AT,
+            None => false,
+        };
+        if held {
+            return None;
+        }
+        self.down = if value >= PRESS_AT {
+            Some(Side::Positive)
+        } else if value <= -PRESS_AT {
+            Some(Side::Negative)
+        } else {
+            None
+        };
+        self.down
+    }
+}
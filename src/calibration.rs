@@ -0,0 +1,127 @@
+use crate::config::AxisCalibration;
+use crate::input_source::PadId;
+use gilrs::Axis;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+const CENTER_DURATION: Duration = Duration::from_secs(3);
+const EXTREMES_DURATION: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationStep {
+    WaitCenter, // Waiting for the user to confirm the stick is centered
+    Center,     // Averaging resting values
+    Extremes,   // Tracking min/max while the user sweeps every axis
+    Review,     // Showing results, waiting for save or cancel
+}
+
+pub struct CalibrationWizard {
+    pub gamepad_id: PadId,
+    pub gamepad_name: String,
+    pub selected: usize,
+    pub message: Option<String>,
+    step: CalibrationStep,
+    step_started: Instant,
+    center_sums: HashMap<Axis, (f64, u32)>,
+    ranges: HashMap<Axis, (f64, f64)>,
+    discarded: Vec<Axis>,
+}
+
+impl CalibrationWizard {
+    pub fn new(gamepad_id: PadId, gamepad_name: String) -> Self {
+        Self {
+            gamepad_id,
+            gamepad_name,
+            selected: 0,
+            message: None,
+            step: CalibrationStep::WaitCenter,
+            step_started: Instant::now(),
+            center_sums: HashMap::new(),
+            ranges: HashMap::new(),
+            discarded: Vec::new(),
+        }
+    }
+
+    pub fn step(&self) -> CalibrationStep {
+        self.step
+    }
+
+    pub fn prompt(&self) -> String {
+        match self.step {
+            CalibrationStep::WaitCenter => {
+                "Center the stick and let go, then press Enter".to_string()
+            }
+            CalibrationStep::Center => format!(
+                "Hold still - recording center ({:.1}s)",
+                Self::remaining(self.step_started, CENTER_DURATION)
+            ),
+            CalibrationStep::Extremes => format!(
+                "Move every axis to its extremes ({:.1}s)",
+                Self::remaining(self.step_started, EXTREMES_DURATION)
+            ),
+            CalibrationStep::Review => {
+                "Done - Enter to save, Esc to discard".to_string()
+            }
+        }
+    }
+
+    fn remaining(started: Instant, duration: Duration) -> f64 {
+        duration.saturating_sub(started.elapsed()).as_secs_f64()
+    }
+
+    // Enter only starts the timed part; the rest advances on its own
+    pub fn advance(&mut self) {
+        if self.step == CalibrationStep::WaitCenter {
+            self.step = CalibrationStep::Center;
+            self.step_started = Instant::now();
+        }
+    }
+
+    pub fn sample(&mut self, axes: &HashMap<Axis, f32>) {
+        match self.step {
+            CalibrationStep::Center => {
+                for (&axis, &value) in axes {
+                    let entry = self.center_sums.entry(axis).or_insert((0.0, 0));
+                    entry.0 += value as f64;
+                    entry.1 += 1;
+                }
+                if self.step_started.elapsed() >= CENTER_DURATION {
+                    self.step = CalibrationStep::Extremes;
+                    self.step_started = Instant::now();
+                }
+            }
+            CalibrationStep::Extremes => {
+                for (&axis, &value) in axes {
+                    let value = value as f64;
+                    let entry = self.ranges.entry(axis).or_insert((value, value));
+                    entry.0 = entry.0.min(value);
+                    entry.1 = entry.1.max(value);
+                }
+                if self.step_started.elapsed() >= EXTREMES_DURATION {
+                    self.step = CalibrationStep::Review;
+                }
+            }
+            CalibrationStep::WaitCenter | CalibrationStep::Review => {}
+        }
+    }
+
+    // Calibration recorded so far; axes that never moved keep the full -1..1 range
+    pub fn results(&self) -> HashMap<Axis, AxisCalibration> {
+        self.center_sums
+            .iter()
+            .filter(|(axis, _)| !self.discarded.contains(axis))
+            .map(|(&axis, &(sum, count))| {
+                let center = sum / count.max(1) as f64;
+                let (min, max) = self.ranges.get(&axis).copied().unwrap_or((-1.0, 1.0));
+                (axis, AxisCalibration { min: min.min(center), center, max: max.max(center) })
+            })
+            .collect()
+    }
+
+    // Drops an axis from the pending results so saving doesn't re-add a cleared calibration
+    pub fn discard(&mut self, axis: Axis) {
+        if !self.discarded.contains(&axis) {
+            self.discarded.push(axis);
+        }
+    }
+}
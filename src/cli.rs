@@ -0,0 +1,136 @@
+use crate::simulate::SimulationPattern;
+use std::path::PathBuf;
+
+pub const DEFAULT_CONFIG_PATH: &str = "config.toml";
+
+const USAGE: &str = "Usage: joystick_test [OPTIONS]
+
+Options:
+  -c, --config <path>  Config file to load (default: config.toml)
+      --hardware <name>
+                       Start with this [[hardware]] profile (alias: --profile)
+      --debug          Start in debug mode regardless of config
+      --no-gamepad     Skip gamepad initialization (keyboard only)
+      --strict-config  Refuse to start on invalid config values instead of
+                       replacing them with defaults
+      --simulate <pattern>
+                       Drive the mapped axes with synthetic input instead of a
+                       gamepad (sine, circle, step)
+      --virtual-gamepad
+                       Add a synthetic gamepad driven by [virtual_gamepad]
+                       signals instead of real devices
+      --sequence <path>
+                       Run a scripted motion sequence (TOML or JSON) at startup
+      --export-frame <path>
+                       Write the gimbal canvas at the startup pose to an SVG
+                       file and exit (uses --config and --hardware)
+  -h, --help           Show this help";
+
+#[derive(Debug, Clone)]
+pub struct Args {
+    pub config_path: PathBuf,
+    pub hardware: Option<String>,
+    pub force_debug: bool,
+    pub no_gamepad: bool,
+    pub strict_config: bool,
+    pub simulate: Option<SimulationPattern>,
+    pub virtual_gamepad: bool,
+    pub sequence: Option<PathBuf>,
+    pub export_frame: Option<PathBuf>,
+    pub help: bool,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Self {
+            config_path: PathBuf::from(DEFAULT_CONFIG_PATH),
+            hardware: None,
+            force_debug: false,
+            no_gamepad: false,
+            strict_config: false,
+            simulate: None,
+            virtual_gamepad: false,
+            sequence: None,
+            export_frame: None,
+            help: false,
+        }
+    }
+}
+
+impl Args {
+    pub fn parse() -> Result<Self, String> {
+        Self::parse_from(std::env::args().skip(1))
+    }
+
+    pub fn parse_from<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut parsed = Args::default();
+        let mut iter = args.into_iter();
+
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-c" | "--config" => {
+                    let path = iter
+                        .next()
+                        .ok_or_else(|| format!("{} requires a path\n\n{}", arg, USAGE))?;
+                    parsed.config_path = PathBuf::from(path);
+                }
+                "--hardware" | "--profile" => {
+                    let name = iter
+                        .next()
+                        .ok_or_else(|| format!("{} requires a profile name\n\n{}", arg, USAGE))?;
+                    parsed.hardware = Some(name);
+                }
+                "--debug" => parsed.force_debug = true,
+                "--no-gamepad" => parsed.no_gamepad = true,
+                "--strict-config" => parsed.strict_config = true,
+                "--virtual-gamepad" => parsed.virtual_gamepad = true,
+                "--simulate" => {
+                    let name = iter
+                        .next()
+                        .ok_or_else(|| format!("{} requires a pattern\n\n{}", arg, USAGE))?;
+                    parsed.simulate = Some(parse_pattern(&name)?);
+                }
+                "--sequence" => {
+                    let path = iter
+                        .next()
+                        .ok_or_else(|| format!("{} requires a path\n\n{}", arg, USAGE))?;
+                    parsed.sequence = Some(PathBuf::from(path));
+                }
+                "--export-frame" => {
+                    let path = iter
+                        .next()
+                        .ok_or_else(|| format!("{} requires a path\n\n{}", arg, USAGE))?;
+                    parsed.export_frame = Some(PathBuf::from(path));
+                }
+                "-h" | "--help" => parsed.help = true,
+                other => {
+                    if let Some(path) = other.strip_prefix("--config=") {
+                        parsed.config_path = PathBuf::from(path);
+                    } else if let Some(name) = other.strip_prefix("--hardware=").or_else(|| other.strip_prefix("--profile=")) {
+                        parsed.hardware = Some(name.to_string());
+                    } else if let Some(name) = other.strip_prefix("--simulate=") {
+                        parsed.simulate = Some(parse_pattern(name)?);
+                    } else if let Some(path) = other.strip_prefix("--sequence=") {
+                        parsed.sequence = Some(PathBuf::from(path));
+                    } else if let Some(path) = other.strip_prefix("--export-frame=") {
+                        parsed.export_frame = Some(PathBuf::from(path));
+                    } else {
+                        return Err(format!("Unknown argument: {}\n\n{}", other, USAGE));
+                    }
+                }
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    pub fn usage() -> &'static str {
+        USAGE
+    }
+}
+
+fn parse_pattern(name: &str) -> Result<SimulationPattern, String> {
+    SimulationPattern::parse(name).ok_or_else(|| {
+        format!("Unknown simulation pattern: {} (expected one of: {})\n\n{}", name, SimulationPattern::NAMES, USAGE)
+    })
+}
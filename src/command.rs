@@ -0,0 +1,22 @@
+use crate::sequence::Channel;
+
+pub const USAGE: &str = "expected e.g. 'pitch 10' or 'roll -5 lift 3'";
+
+// Parses a command-entry line: channel/value pairs, applied together. A channel named
+// twice takes the last value.
+pub fn parse(line: &str) -> Result<Vec<(Channel, f64)>, String> {
+    let mut words = line.split_whitespace();
+    let mut targets = Vec::new();
+    while let Some(word) = words.next() {
+        let channel = Channel::parse(word).ok_or_else(|| format!("unknown channel '{}' ({})", word, USAGE))?;
+        let value = words.next().ok_or_else(|| format!("missing value for {} ({})", channel.name(), USAGE))?;
+        let value: f64 = value
+            .parse()
+            .ok()
+            .filter(|v: &f64| v.is_finite())
+            .ok_or_else(|| format!("'{}' is not a number", value))?;
+        targets.retain(|(c, _)| *c != channel);
+        targets.push((channel, value));
+    }
+    Ok(targets)
+}
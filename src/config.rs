@@ -1,123 +1,2672 @@
+use crate::projection::Projection;
+use crate::sequence::Channel;
+use crate::theme::Role;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item, Table, Value};
+
+/// Bumped whenever a setting is renamed or reinterpreted; Config::migrate brings older
+/// files up to date
+pub const CONFIG_VERSION: u32 = 1;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
+    /// Files from before versioning have none and count as version 0
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
+    pub app: AppConfig,
+    #[serde(default)]
     pub gimbal: GimbalConfig,
+    #[serde(default)]
     pub controls: ControlsConfig,
+    #[serde(default)]
     pub debug: DebugConfig,
+    #[serde(default)]
+    pub geometry: GeometryConfig,
+    #[serde(default)]
+    pub output: OutputConfig,
+    #[serde(default)]
+    pub dynamics: DynamicsConfig,
+    #[serde(default)]
+    pub safety: SafetyConfig,
+    #[serde(default)]
+    pub homing: HomingConfig,
+    #[serde(default)]
+    pub sequence: SequenceConfig,
+    #[serde(default)]
+    pub server: ServerConfig,
+    #[serde(default)]
+    pub feedback: FeedbackConfig,
+    /// Synthetic gamepad for --virtual-gamepad
+    #[serde(default)]
+    pub virtual_gamepad: VirtualGamepadConfig,
+    /// Named target poses for the command server's PRESET command
+    #[serde(default)]
+    pub presets: HashMap<String, PosePreset>,
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    /// Alternative joystick mappings picked by gamepad name
+    #[serde(default)]
+    pub profiles: Vec<MappingProfile>,
+    /// Alternative limits, sensitivities and geometry for other rigs; `[gimbal]` + `[geometry]` are "default"
+    #[serde(default)]
+    pub hardware: Vec<HardwareProfile>,
+    /// Per-gamepad axis calibration, keyed by gamepad name then axis name
+    #[serde(default)]
+    pub calibration: HashMap<String, HashMap<String, AxisCalibration>>,
+    /// Set by Config::load when the file was upgraded from an older version
+    #[serde(skip)]
+    pub migration: Option<Migration>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Migration {
+    pub from: u32,
+    /// Why the upgraded file couldn't be written back, if it couldn't
+    pub save_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    /// How long transient status bar messages stay visible
+    pub status_message_secs: f64,
+    /// Ask for a second quit key press before exiting
+    pub confirm_quit: bool,
+    /// Plain text labels, ASCII borders and a dot canvas marker for terminals without emoji/braille
+    pub ascii_mode: bool,
+    /// Hardware profile to start with (a `[[hardware]]` name); `[gimbal]` + `[geometry]` when unset
+    pub hardware_profile: Option<String>,
+    /// Fixed rate of state updates, and the most redraws per second (the screen is only
+    /// redrawn when something changed)
+    pub update_rate_hz: f64,
+    pub render_rate_hz: f64,
+    /// Older configs gave the periods in milliseconds; read once and converted by migrate()
+    #[serde(skip_serializing)]
+    pub tick_rate_ms: Option<u64>,
+    #[serde(skip_serializing)]
+    pub draw_rate_ms: Option<u64>,
+    /// Gimbal canvas view at startup: "isometric", "top_down" or "side"
+    pub view: Projection,
+    /// Roll and pitch lines across the plate, labeled with their angles; the
+    /// toggle_tilt_lines key flips them at runtime
+    pub tilt_lines: bool,
+    /// Save the pose and trims on exit (next to the config as `<name>.state.toml`) and start from them
+    pub restore_last_state: bool,
+    /// Save the same file, but start from rest and slew to the saved pose (pose only, no trims)
+    pub restore_last_pose: bool,
+    /// Below this terminal size a "too small" message replaces the layout
+    pub min_width: u16,
+    pub min_height: u16,
+}
+
+/// The header, gamepad list and status bar alone fill 7 rows; below these sizes no layout leaves
+/// any room for the canvas, so min_width/min_height can't go lower
+pub const SMALLEST_WIDTH: u16 = 30;
+pub const SMALLEST_HEIGHT: u16 = 10;
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            status_message_secs: 4.0,
+            confirm_quit: false,
+            ascii_mode: false,
+            hardware_profile: None,
+            update_rate_hz: 200.0,
+            render_rate_hz: 60.0,
+            tick_rate_ms: None,
+            draw_rate_ms: None,
+            view: Projection::Isometric,
+            tilt_lines: true,
+            restore_last_state: false,
+            restore_last_pose: false,
+            min_width: 60,
+            min_height: 24,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GimbalConfig {
     pub max_pitch: f64,
     pub max_roll: f64,
-    pub max_lift: f64,
+    /// Uneven travel, e.g. pitch_min = -10.0 with pitch_max = 20.0; full stick deflection
+    /// reaches each end. Either one left out falls back to -max_*/max_*
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pitch_min: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pitch_max: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub roll_min: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub roll_max: Option<f64>,
+    /// Absolute lift travel in mm (e.g. 0 fully collapsed to 40 fully raised); reset returns
+    /// to lift_home and full stick deflection reaches lift_min/lift_max
+    #[serde(default = "default_lift_min")]
+    pub lift_min: f64,
+    #[serde(default = "default_lift_max")]
+    pub lift_max: f64,
+    #[serde(default)]
+    pub lift_home: f64,
+    /// Older configs gave a symmetric ±max_lift; read once and converted by migrate()
+    #[serde(skip_serializing)]
+    pub max_lift: Option<f64>,
+    /// Yaw is disabled (clamped to zero) unless max_yaw is set
+    #[serde(default)]
+    pub max_yaw: f64,
     pub pitch_sensitivity: f64,
     pub roll_sensitivity: f64,
     pub lift_sensitivity: f64,
+    #[serde(default = "default_sensitivity")]
+    pub yaw_sensitivity: f64,
+    /// Position maps the input straight to the state; velocity integrates it as a rate
+    #[serde(default)]
+    pub pitch_mode: ChannelMode,
+    #[serde(default)]
+    pub roll_mode: ChannelMode,
+    #[serde(default)]
+    pub lift_mode: ChannelMode,
+    #[serde(default)]
+    pub yaw_mode: ChannelMode,
+    /// Fraction of a velocity channel's max travelled per second at full deflection
+    #[serde(default = "default_velocity_rate")]
+    pub velocity_rate: f64,
+    /// Combined tilt, sqrt(pitch² + roll²) in degrees, above which the UI warns that the
+    /// mechanism is losing authority; informational only, unset = no warning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warn_tilt_threshold: Option<f64>,
+    /// Mixes the (pitch, roll) command before the limits, to cancel cross-talk measured on
+    /// the mechanism: [[1.0, 0.0], [-0.08, 1.0]] takes 8% of pitch off roll. In stick units,
+    /// so with different pitch and roll ranges the degrees scale by the range ratio.
+    #[serde(default = "default_coupling")]
+    pub coupling: [[f64; 2]; 2],
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChannelMode {
+    #[default]
+    Position,
+    Velocity,
+}
+
+impl GimbalConfig {
+    pub fn yaw_enabled(&self) -> bool {
+        self.max_yaw > 0.0
+    }
+
+    /// The combined tilt when it is past warn_tilt_threshold
+    pub fn tilt_warning(&self, pitch: f64, roll: f64) -> Option<f64> {
+        let tilt = pitch.hypot(roll);
+        self.warn_tilt_threshold.filter(|&threshold| tilt > threshold).map(|_| tilt)
+    }
+
+    pub fn coupling_enabled(&self) -> bool {
+        self.coupling != default_coupling()
+    }
+
+    /// The coupling matrix times the (pitch, roll) column vector
+    pub fn couple(&self, pitch: f64, roll: f64) -> (f64, f64) {
+        let [[pp, pr], [rp, rr]] = self.coupling;
+        (pp * pitch + pr * roll, rp * pitch + rr * roll)
+    }
+
+    pub fn pitch_range(&self) -> (f64, f64) {
+        (self.pitch_min.unwrap_or(-self.max_pitch), self.pitch_max.unwrap_or(self.max_pitch))
+    }
+
+    pub fn roll_range(&self) -> (f64, f64) {
+        (self.roll_min.unwrap_or(-self.max_roll), self.roll_max.unwrap_or(self.max_roll))
+    }
+
+    /// Travel from level to the far end of the pitch range; rates are a fraction of it
+    pub fn pitch_span(&self) -> f64 {
+        let (min, max) = self.pitch_range();
+        max.max(-min)
+    }
+
+    pub fn roll_span(&self) -> f64 {
+        let (min, max) = self.roll_range();
+        max.max(-min)
+    }
+
+    /// Travel from lift_home to the far end of the range, the lift counterpart of pitch_span
+    pub fn lift_span(&self) -> f64 {
+        (self.lift_max - self.lift_home).max(self.lift_home - self.lift_min)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ControlsConfig {
     pub keyboard_enabled: bool,
     pub keyboard_step: f64,
+    #[serde(default)]
+    pub keyboard_mode: KeyboardMode,
+    /// How the stick, keyboard and mouse combine on a channel they drive at the same time
+    #[serde(default)]
+    pub mixing: InputMixing,
+    /// joystick_priority: stick deflection (axis units) below which it counts as at rest
+    #[serde(default = "default_mixing_deadzone")]
+    pub mixing_deadzone: f64,
+    /// Drag in the gimbal canvas for pitch/roll, scroll for lift
+    #[serde(default)]
+    pub mouse_enabled: bool,
+    /// Return pitch/roll to center when the mouse button is released
+    #[serde(default)]
+    pub mouse_spring_return: bool,
+    /// Buzz the active gamepad when an input pushes a channel past its limit
+    #[serde(default)]
+    pub rumble_on_limit: bool,
+    #[serde(default, alias = "keybindings")]
+    pub keyboard_bindings: KeyboardBindings,
+    /// Gamepad button name (gilrs, e.g. "Start") -> app action, fired once per press
+    #[serde(default)]
+    pub button_actions: HashMap<String, ButtonAction>,
+    /// Gamepad button name -> sensitivity multiplier while it is held, e.g. 0.5 for precision
+    #[serde(default = "default_modifiers")]
+    pub modifiers: HashMap<String, f64>,
+    /// Axes (typically a hat the driver reports as DPadX/DPadY) that act as a pair of buttons
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub axis_buttons: Vec<AxisButton>,
+    #[serde(default)]
+    pub dpad_mode: DpadMode,
+    /// An axis dropping to exactly 0.0 from beyond dropout_threshold is held at its last value
+    /// for up to this many update steps, to ride out lost wireless reports; 0 = off
+    #[serde(default = "default_dropout_frames")]
+    pub dropout_frames: u32,
+    #[serde(default = "default_dropout_threshold")]
+    pub dropout_threshold: f64,
+    /// A button press fires its action once the button has stayed down this long, so a
+    /// controller chattering on the edge can't fire it twice; 0 = off (fire on the press)
+    #[serde(default)]
+    pub button_debounce_ms: u64,
     pub joystick: JoystickConfig,
 }
 
+/// Pushing `axis` past half deflection presses a virtual button, which fires the positive or
+/// negative side's action once; it releases when the axis comes back near center
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AxisButton {
+    pub axis: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub positive: Option<ButtonAction>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub negative: Option<ButtonAction>,
+}
+
+/// What a hat reported as DPadX/DPadY axes does. Continuous: they are plain axes, for
+/// mapping to a channel or to axis_buttons. Step: each click moves pitch (DPadY) or roll
+/// (DPadX) by keyboard_step, like a tap of the movement keys.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DpadMode {
+    #[default]
+    Continuous,
+    Step,
+}
+
+impl ControlsConfig {
+    /// axis_buttons, plus the D-pad axes it doesn't list itself when dpad_mode is step
+    pub fn axis_button_entries(&self) -> Vec<AxisButton> {
+        let mut entries = self.axis_buttons.clone();
+        if self.dpad_mode == DpadMode::Step {
+            let steps = [
+                ("DPadY", ButtonAction::PitchUp, ButtonAction::PitchDown),
+                ("DPadX", ButtonAction::RollRight, ButtonAction::RollLeft),
+            ];
+            for (axis, positive, negative) in steps {
+                if !entries.iter().any(|entry| parse_axis_name(&entry.axis) == parse_axis_name(axis)) {
+                    entries.push(AxisButton {
+                        axis: axis.to_string(),
+                        positive: Some(positive),
+                        negative: Some(negative),
+                    });
+                }
+            }
+        }
+        entries
+    }
+
+    pub fn button_action(&self, button: gilrs::Button) -> Option<ButtonAction> {
+        self.button_actions
+            .iter()
+            .find(|(name, _)| parse_button_name(name) == Some(button))
+            .map(|(_, action)| *action)
+    }
+
+    /// Product of the factors of every held modifier button; 1.0 when none is held
+    pub fn sensitivity_modifier(&self, buttons: &HashMap<gilrs::Button, bool>) -> f64 {
+        self.modifiers
+            .iter()
+            .filter(|(name, _)| parse_button_name(name).is_some_and(|button| buttons.get(&button) == Some(&true)))
+            .map(|(_, factor)| factor)
+            .product()
+    }
+}
+
+/// App actions a gamepad button can trigger. The movement ones step their channel by
+/// keyboard_step per press instead of moving it continuously like a stick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ButtonAction {
+    Reset,
+    Home,
+    Pause,
+    ToggleDebug,
+    CycleView,
+    CaptureCenter,
+    SaveConfig,
+    ToggleCsvLog,
+    CycleProfile,
+    CycleHardware,
+    ToggleTiming,
+    CycleProjection,
+    RunSequence,
+    ExportFrame,
+    Help,
+    PitchUp,
+    PitchDown,
+    RollLeft,
+    RollRight,
+    /// Nudge the active mapping's trims by TRIM_STEP, in the direction the plate should move
+    TrimPitchUp,
+    TrimPitchDown,
+    TrimRollLeft,
+    TrimRollRight,
+}
+
+/// Axis units per trim action; a D-pad click is a small, repeatable correction
+pub const TRIM_STEP: f64 = 0.01;
+
+impl ButtonAction {
+    /// None for the trim actions, which have no key
+    pub fn key_action(self) -> Option<KeyAction> {
+        let action = match self {
+            ButtonAction::Reset => KeyAction::Reset,
+            ButtonAction::Home => KeyAction::Home,
+            ButtonAction::Pause => KeyAction::Pause,
+            ButtonAction::ToggleDebug => KeyAction::ToggleDebug,
+            ButtonAction::CycleView => KeyAction::CycleView,
+            ButtonAction::CaptureCenter => KeyAction::CaptureCenter,
+            ButtonAction::SaveConfig => KeyAction::SaveConfig,
+            ButtonAction::ToggleCsvLog => KeyAction::ToggleCsvLog,
+            ButtonAction::CycleProfile => KeyAction::CycleProfile,
+            ButtonAction::CycleHardware => KeyAction::CycleHardware,
+            ButtonAction::ToggleTiming => KeyAction::ToggleTiming,
+            ButtonAction::CycleProjection => KeyAction::CycleProjection,
+            ButtonAction::RunSequence => KeyAction::RunSequence,
+            ButtonAction::ExportFrame => KeyAction::ExportFrame,
+            ButtonAction::Help => KeyAction::Help,
+            ButtonAction::PitchUp => KeyAction::PitchUp,
+            ButtonAction::PitchDown => KeyAction::PitchDown,
+            ButtonAction::RollLeft => KeyAction::RollLeft,
+            ButtonAction::RollRight => KeyAction::RollRight,
+            ButtonAction::TrimPitchUp | ButtonAction::TrimPitchDown | ButtonAction::TrimRollLeft | ButtonAction::TrimRollRight => {
+                return None;
+            }
+        };
+        Some(action)
+    }
+
+    /// The channel and the direction its output moves, for the trim actions
+    pub fn trim(self) -> Option<(Channel, f64)> {
+        match self {
+            ButtonAction::TrimPitchUp => Some((Channel::Pitch, 1.0)),
+            ButtonAction::TrimPitchDown => Some((Channel::Pitch, -1.0)),
+            ButtonAction::TrimRollLeft => Some((Channel::Roll, -1.0)),
+            ButtonAction::TrimRollRight => Some((Channel::Roll, 1.0)),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ButtonAction::TrimPitchUp => "trim_pitch_up",
+            ButtonAction::TrimPitchDown => "trim_pitch_down",
+            ButtonAction::TrimRollLeft => "trim_roll_left",
+            ButtonAction::TrimRollRight => "trim_roll_right",
+            other => other.key_action().map_or("", KeyAction::name),
+        }
+    }
+}
+
+/// Rate: a tap nudges by keyboard_step and holding keeps moving at keyboard_step per second.
+/// Direct: holding a key applies exactly ±keyboard_step, releasing returns to zero (or to the
+/// opposite key, if that is still down).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyboardMode {
+    #[default]
+    Rate,
+    Direct,
+}
+
+/// "sum" adds every contribution, "max" keeps the one furthest from zero, and
+/// "joystick_priority" ignores the keyboard and mouse while the stick is deflected
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputMixing {
+    #[default]
+    Sum,
+    Max,
+    JoystickPriority,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyboardBindings {
+    pub pitch_up: char,
+    pub pitch_down: char,
+    pub roll_left: char,
+    pub roll_right: char,
+    pub lift_up: char,
+    pub lift_down: char,
+    #[serde(default = "default_yaw_left_key")]
+    pub yaw_left: char,
+    #[serde(default = "default_yaw_right_key")]
+    pub yaw_right: char,
+    pub reset: char,
+    pub quit: char,
+    pub toggle_debug: char,
+    #[serde(default = "default_calibrate_key")]
+    pub calibrate: char,
+    #[serde(default = "default_capture_center_key")]
+    pub capture_center: char,
+    #[serde(default = "default_save_config_key")]
+    pub save_config: char,
+    #[serde(default = "default_toggle_csv_log_key")]
+    pub toggle_csv_log: char,
+    #[serde(default = "default_cycle_profile_key")]
+    pub cycle_profile: char,
+    #[serde(default = "default_help_key")]
+    pub help: char,
+    #[serde(default = "default_prev_gamepad_key")]
+    pub prev_gamepad: char,
+    #[serde(default = "default_next_gamepad_key")]
+    pub next_gamepad: char,
+    #[serde(default = "default_cycle_hardware_key")]
+    pub cycle_hardware: char,
+    #[serde(default = "default_estop_key")]
+    pub estop: char,
+    /// Held for safety.rearm_hold_secs to leave the emergency stop
+    #[serde(default = "default_rearm_key")]
+    pub rearm: char,
+    #[serde(default = "default_toggle_timing_key")]
+    pub toggle_timing: char,
+    /// Swaps the debug log pane for the raw gamepad event stream
+    #[serde(default = "default_toggle_event_log_key")]
+    pub toggle_event_log: char,
+    #[serde(default = "default_home_key")]
+    pub home: char,
+    #[serde(default = "default_cycle_projection_key")]
+    pub cycle_projection: char,
+    #[serde(default = "default_toggle_tilt_lines_key")]
+    pub toggle_tilt_lines: char,
+    /// Shows the solved plate plane, leg lengths and feasibility instead of the current view
+    #[serde(default = "default_toggle_solver_key")]
+    pub toggle_solver: char,
+    /// Opens the live settings editor
+    #[serde(default = "default_settings_key")]
+    pub settings: char,
+    /// Flip the active mapping's invert_* flags live
+    #[serde(default = "default_invert_pitch_key")]
+    pub invert_pitch: char,
+    #[serde(default = "default_invert_roll_key")]
+    pub invert_roll: char,
+    #[serde(default = "default_invert_lift_key")]
+    pub invert_lift: char,
+    /// Exchanges the pitch and roll axes (and their trims) on the active mapping
+    #[serde(default = "default_swap_pitch_roll_key")]
+    pub swap_pitch_roll: char,
+    #[serde(default = "default_pause_key")]
+    pub pause: char,
+    /// Starts the axis auto-detect mode for pitch/roll/lift
+    #[serde(default = "default_detect_axes_key")]
+    pub detect_axes: char,
+    /// Walks through pitch, roll and lift asking whether the plate moves the right way
+    #[serde(default = "default_check_inversion_key")]
+    pub check_inversion: char,
+    #[serde(default = "default_run_sequence_key")]
+    pub run_sequence: char,
+    /// Opens the command line for typing a target pose
+    #[serde(default = "default_command_key")]
+    pub command: char,
+    /// Writes the gimbal canvas to an SVG file
+    #[serde(default = "default_export_frame_key")]
+    pub export_frame: char,
+    /// Steps the log level through info, debug and trace
+    #[serde(default = "default_cycle_log_level_key")]
+    pub cycle_log_level: char,
+    /// Up/Down and Left/Right tilt pitch and roll as well as their letter keys
+    #[serde(default = "default_arrow_keys")]
+    pub arrow_keys: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyAction {
+    PitchUp,
+    PitchDown,
+    RollLeft,
+    RollRight,
+    LiftUp,
+    LiftDown,
+    YawLeft,
+    YawRight,
+    Reset,
+    Quit,
+    ToggleDebug,
+    Calibrate,
+    CaptureCenter,
+    SaveConfig,
+    ToggleCsvLog,
+    CycleProfile,
+    Help,
+    PrevGamepad,
+    NextGamepad,
+    CycleHardware,
+    Estop,
+    Rearm,
+    ToggleTiming,
+    ToggleEventLog,
+    Home,
+    CycleProjection,
+    ToggleTiltLines,
+    ToggleSolver,
+    /// Gimbal, debug and solver views in turn; a button action with no key of its own
+    CycleView,
+    Settings,
+    InvertPitch,
+    InvertRoll,
+    InvertLift,
+    SwapPitchRoll,
+    Pause,
+    DetectAxes,
+    CheckInversion,
+    RunSequence,
+    Command,
+    ExportFrame,
+    CycleLogLevel,
+}
+
+impl KeyAction {
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyAction::PitchUp => "pitch_up",
+            KeyAction::PitchDown => "pitch_down",
+            KeyAction::RollLeft => "roll_left",
+            KeyAction::RollRight => "roll_right",
+            KeyAction::LiftUp => "lift_up",
+            KeyAction::LiftDown => "lift_down",
+            KeyAction::YawLeft => "yaw_left",
+            KeyAction::YawRight => "yaw_right",
+            KeyAction::Reset => "reset",
+            KeyAction::Quit => "quit",
+            KeyAction::ToggleDebug => "toggle_debug",
+            KeyAction::Calibrate => "calibrate",
+            KeyAction::CaptureCenter => "capture_center",
+            KeyAction::SaveConfig => "save_config",
+            KeyAction::ToggleCsvLog => "toggle_csv_log",
+            KeyAction::CycleProfile => "cycle_profile",
+            KeyAction::Help => "help",
+            KeyAction::PrevGamepad => "prev_gamepad",
+            KeyAction::NextGamepad => "next_gamepad",
+            KeyAction::CycleHardware => "cycle_hardware",
+            KeyAction::Estop => "estop",
+            KeyAction::Rearm => "rearm",
+            KeyAction::ToggleTiming => "toggle_timing",
+            KeyAction::ToggleEventLog => "toggle_event_log",
+            KeyAction::Home => "home",
+            KeyAction::CycleProjection => "cycle_projection",
+            KeyAction::ToggleTiltLines => "toggle_tilt_lines",
+            KeyAction::ToggleSolver => "toggle_solver",
+            KeyAction::CycleView => "cycle_view",
+            KeyAction::Settings => "settings",
+            KeyAction::InvertPitch => "invert_pitch",
+            KeyAction::InvertRoll => "invert_roll",
+            KeyAction::InvertLift => "invert_lift",
+            KeyAction::SwapPitchRoll => "swap_pitch_roll",
+            KeyAction::Pause => "pause",
+            KeyAction::DetectAxes => "detect_axes",
+            KeyAction::CheckInversion => "check_inversion",
+            KeyAction::RunSequence => "run_sequence",
+            KeyAction::Command => "command",
+            KeyAction::ExportFrame => "export_frame",
+            KeyAction::CycleLogLevel => "cycle_log_level",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct JoystickConfig {
     pub enabled: bool,
     pub pitch_axis: String,
     pub roll_axis: String,
     pub lift_axis: String,
+    #[serde(default = "default_yaw_axis")]
+    pub yaw_axis: String,
     pub invert_pitch: bool,
     pub invert_roll: bool,
     pub invert_lift: bool,
+    #[serde(default)]
+    pub invert_yaw: bool,
+    /// Whether lift_axis is a centered stick or a trigger resting at one end
+    #[serde(default)]
+    pub lift_axis_mode: AxisMode,
+    /// How the lift axis drives the plate; see LiftControl
+    #[serde(default)]
+    pub lift_control: LiftControl,
+    /// Absolute lift only, in place of invert_lift: the lever's low end raises the plate
+    #[serde(default)]
+    pub lift_reversed: bool,
+    /// Resting offsets subtracted from each axis (in axis units, ±1.0)
+    #[serde(default)]
+    pub pitch_trim: f64,
+    #[serde(default)]
+    pub roll_trim: f64,
+    #[serde(default)]
+    pub lift_trim: f64,
+    #[serde(default)]
+    pub yaw_trim: f64,
+    /// Exponential smoothing of joystick axes: 0.0 = off, toward 1.0 = heavy
+    #[serde(default)]
+    pub smoothing: f64,
     pub fallback_axes: Vec<String>,
+    /// Further axes summed into a channel with the main axis, e.g. a fine trim on the other stick
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pitch_sources: Vec<AxisSource>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roll_sources: Vec<AxisSource>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lift_sources: Vec<AxisSource>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub yaw_sources: Vec<AxisSource>,
+    /// Gamepad each channel reads from (case-insensitive substring of its name), so two people
+    /// can share the plate; unset channels follow whichever gamepad moved last
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pitch_device: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub roll_device: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lift_device: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub yaw_device: Option<String>,
+}
+
+impl JoystickConfig {
+    /// (channel, device) for every channel bound to a gamepad
+    pub fn channel_devices(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        [
+            ("pitch", &self.pitch_device),
+            ("roll", &self.roll_device),
+            ("lift", &self.lift_device),
+            ("yaw", &self.yaw_device),
+        ]
+        .into_iter()
+        .filter_map(|(channel, device)| Some((channel, device.as_deref()?)))
+    }
+}
+
+/// Bipolar: a stick resting at center, -1..1. Unipolar: a trigger resting at 0 and pulled to 1.
+/// UnipolarSigned: a trigger resting at -1 and pulled to 1. Both trigger kinds read 0..1.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AxisMode {
+    #[default]
+    Bipolar,
+    Unipolar,
+    UnipolarSigned,
+}
+
+/// Centered: lift works like the other channels, around lift_home with sensitivity and the
+/// channel mode. Absolute: for a throttle lever, whose whole range is the whole lift range
+/// (low end lift_min, high end lift_max) with no rest position, trim or invert.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LiftControl {
+    #[default]
+    Centered,
+    Absolute,
+}
+
+// Pull (0..1) a trigger must pass before it counts, so one resting slightly open reads 0
+const TRIGGER_DEADZONE: f64 = 0.02;
+
+impl AxisMode {
+    /// A calibrated axis value in this axis's own range, as input: bipolar values pass through,
+    /// triggers become 0..1 with a small dead zone at rest
+    pub fn shape(self, value: f64) -> f64 {
+        let pull = match self {
+            AxisMode::Bipolar => return value,
+            AxisMode::Unipolar => value,
+            AxisMode::UnipolarSigned => (value + 1.0) / 2.0,
+        };
+        ((pull - TRIGGER_DEADZONE) / (1.0 - TRIGGER_DEADZONE)).clamp(0.0, 1.0)
+    }
+
+    /// Where a shaped value sits in the axis's travel, as -1 (one end) to 1 (the other)
+    pub fn position(self, shaped: f64) -> f64 {
+        match self {
+            AxisMode::Bipolar => shaped,
+            AxisMode::Unipolar | AxisMode::UnipolarSigned => shaped * 2.0 - 1.0,
+        }
+    }
+}
+
+/// How profiles and channel devices pick a gamepad: `pattern` appears in its name, ignoring case
+pub fn name_matches(pattern: &str, gamepad_name: &str) -> bool {
+    gamepad_name.to_lowercase().contains(&pattern.to_lowercase())
 }
 
+/// One extra axis feeding a channel: its smoothed value times gain, flipped if inverted
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DebugConfig {
+pub struct AxisSource {
+    pub axis: String,
+    #[serde(default = "default_source_gain")]
+    pub gain: f64,
+    #[serde(default)]
+    pub invert: bool,
+}
+
+fn default_source_gain() -> f64 {
+    1.0
+}
+
+/// A full joystick mapping used instead of [controls.joystick] for matching gamepads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MappingProfile {
+    pub name: String,
+    /// Case-insensitive substring of the gamepad name
+    pub match_name: String,
+    pub joystick: JoystickConfig,
+}
+
+/// A complete gimbal section plus geometry for one physical rig, switchable at runtime
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HardwareProfile {
+    pub name: String,
+    pub gimbal: GimbalConfig,
+    #[serde(default)]
+    pub geometry: GeometryConfig,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlatformType {
+    Scissor3,
+    Stewart6,
+}
+
+/// Off: only flag over travel. Scale: shrink the whole pose toward neutral until it fits.
+/// Clamp: hold each actuator at its stroke and refit the plate to them (scissor lifts;
+/// Stewart platforms fall back to scaling).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnvelopeMode {
+    Off,
+    #[default]
+    Scale,
+    Clamp,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeometryConfig {
+    pub platform_type: PlatformType,
+    /// Actuator travel (± mm from lift 0) before an over-travel warning; legs may also reach
+    /// as far as gimbal.lift_min/lift_max
+    pub actuator_stroke: f64,
+    /// Joint-to-joint leg length limits (mm), checked alongside the stroke when set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_leg: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_leg: Option<f64>,
+    /// What to do with a commanded pose that would push an actuator past its stroke
+    pub envelope: EnvelopeMode,
+    /// Plate outline and heights shared by both platform types (mm)
+    pub plate_radius: f64,
+    pub base_height: f64,
+    /// Plate height at lift 0; gimbal lift travel is added on top of it
+    pub nominal_height: f64,
+    /// Scissor lift positions around the plate (degrees), placed at 75% of plate_radius
+    pub scissor_angles: Vec<f64>,
+    /// Stewart platform attachment points; leg i runs from `base_angles[i]` to `platform_angles[i]`
+    pub base_radius: f64,
+    pub platform_radius: f64,
+    pub base_angles: Vec<f64>,
+    pub platform_angles: Vec<f64>,
+}
+
+impl Default for GeometryConfig {
+    fn default() -> Self {
+        Self {
+            platform_type: PlatformType::Scissor3,
+            actuator_stroke: 25.0,
+            min_leg: None,
+            max_leg: None,
+            envelope: EnvelopeMode::Scale,
+            plate_radius: 100.0,
+            base_height: -30.0,
+            nominal_height: 15.0,
+            scissor_angles: vec![0.0, 120.0, 240.0],
+            base_radius: 90.0,
+            platform_radius: 60.0,
+            base_angles: vec![345.0, 15.0, 105.0, 135.0, 225.0, 255.0],
+            platform_angles: vec![315.0, 45.0, 75.0, 165.0, 195.0, 285.0],
+        }
+    }
+}
+
+impl GeometryConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.scissor_angles.len() < 3 {
+            return Err(format!(
+                "geometry.scissor_angles needs at least 3 angles, got {}",
+                self.scissor_angles.len()
+            ));
+        }
+        if self.base_angles.len() != self.platform_angles.len() || self.base_angles.len() < 3 {
+            return Err(format!(
+                "geometry.base_angles and platform_angles need the same count (at least 3), got {} and {}",
+                self.base_angles.len(),
+                self.platform_angles.len()
+            ));
+        }
+        if self.plate_radius <= 0.0 {
+            return Err("geometry.plate_radius must be positive".to_string());
+        }
+        if self.nominal_height <= self.base_height {
+            return Err("geometry.nominal_height must be above base_height".to_string());
+        }
+        for (name, leg) in [("min_leg", self.min_leg), ("max_leg", self.max_leg)] {
+            if let Some(leg) = leg
+                && !(leg.is_finite() && leg > 0.0)
+            {
+                return Err(format!("geometry.{} = {} must be a positive length", name, leg));
+            }
+        }
+        if let (Some(min_leg), Some(max_leg)) = (self.min_leg, self.max_leg)
+            && min_leg >= max_leg
+        {
+            return Err(format!("geometry.min_leg = {} must be below max_leg = {}", min_leg, max_leg));
+        }
+        Ok(())
+    }
+}
+
+/// Hold: freeze the output where it was. Zero: ramp back to level attitude and lift_home.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EstopBehavior {
+    #[default]
+    Hold,
+    Zero,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SafetyConfig {
+    /// What the output does while the emergency stop is engaged
+    pub estop_behavior: EstopBehavior,
+    /// Gamepad button that also triggers the stop (gilrs name, e.g. "Select"); none when unset
+    pub estop_button: Option<String>,
+    /// How long the re-arm key has to be held
+    pub rearm_hold_secs: f64,
+    /// Time for the zero behavior to bring a full deflection back to neutral
+    pub zero_ramp_secs: f64,
+    /// Input is eased in from nothing over this long after launch, reset and re-arm; 0 = off
+    pub soft_start_secs: f64,
+    /// Ignore input at those times until the stick has been back to center once
+    pub require_center_on_start: bool,
+}
+
+impl Default for SafetyConfig {
+    fn default() -> Self {
+        Self {
+            estop_behavior: EstopBehavior::Hold,
+            estop_button: Some("Select".to_string()),
+            rearm_hold_secs: 1.0,
+            zero_ramp_secs: 2.0,
+            soft_start_secs: 1.0,
+            require_center_on_start: false,
+        }
+    }
+}
+
+/// Pose the home command moves to; position-mode channels are centered on it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HomingConfig {
+    pub pitch: f64,
+    pub roll: f64,
+    pub lift: f64,
+    pub yaw: f64,
+    /// Time to bring a full deflection to the home pose
+    pub move_secs: f64,
+    /// Seconds without stick, key, mouse or command input before the plate homes by itself; 0 = off
+    pub idle_timeout_secs: f64,
+}
+
+impl Default for HomingConfig {
+    fn default() -> Self {
+        Self {
+            pitch: 0.0,
+            roll: 0.0,
+            lift: 0.0,
+            yaw: 0.0,
+            move_secs: 2.0,
+            idle_timeout_secs: 0.0,
+        }
+    }
+}
+
+/// Scripted motion run with the run_sequence key (--sequence overrides the file)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SequenceConfig {
+    pub file: Option<String>,
+    /// Stick deflection (axis units) that aborts a running sequence; 0.0 = sticks never abort
+    pub abort_threshold: f64,
+    /// Completed and aborted runs are appended here with their start/end times
+    pub run_log: Option<String>,
+}
+
+impl Default for SequenceConfig {
+    fn default() -> Self {
+        Self {
+            file: None,
+            abort_threshold: 0.5,
+            run_log: None,
+        }
+    }
+}
+
+/// Line-based TCP server for driving the gimbal from scripts while the TUI runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
     pub enabled: bool,
-    pub show_all_axes: bool,
-    pub show_button_states: bool,
-    pub log_input_values: bool,
+    pub address: String,
 }
 
-impl Default for Config {
+impl Default for ServerConfig {
     fn default() -> Self {
         Self {
-            gimbal: GimbalConfig {
-                max_pitch: 20.0,
-                max_roll: 20.0,
-                max_lift: 15.0,
-                pitch_sensitivity: 1.0,
-                roll_sensitivity: 1.0,
-                lift_sensitivity: 1.0,
-            },
-            controls: ControlsConfig {
-                keyboard_enabled: true,
-                keyboard_step: 0.1,
-                joystick: JoystickConfig {
-                    enabled: true,
-                    pitch_axis: "RightStickY".to_string(),
-                    roll_axis: "RightStickX".to_string(),
-                    lift_axis: "RightZ".to_string(),
-                    invert_pitch: false,
-                    invert_roll: false,
-                    invert_lift: false,
-                    fallback_axes: vec![
-                        "LeftStickY".to_string(),
-                        "LeftStickX".to_string(),
-                        "LeftZ".to_string(),
-                        "Tz".to_string(),
-                        "Ty".to_string(),
-                        "Tx".to_string(),
-                    ],
-                },
-            },
-            debug: DebugConfig {
-                enabled: false,
-                show_all_axes: true,
-                show_button_states: true,
-                log_input_values: false,
-            },
+            enabled: false,
+            address: "127.0.0.1:9100".to_string(),
         }
     }
 }
 
-impl Config {
-    pub fn load_or_create<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
-        let path = path.as_ref();
-        
-        if path.exists() {
-            let content = fs::read_to_string(path)?;
-            let config: Config = toml::from_str(&content)?;
-            Ok(config)
-        } else {
-            let default_config = Config::default();
-            let toml_string = toml::to_string_pretty(&default_config)?;
-            fs::write(path, toml_string)?;
-            println!("Created default config file at {}", path.display());
-            Ok(default_config)
+/// Measured pose sent back by the hardware, drawn as a ghost plate over the commanded one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeedbackConfig {
+    pub enabled: bool,
+    /// UDP address to listen on
+    pub address: String,
+    /// Warn when a channel is off by more than this (deg, mm for lift) for over a second
+    pub error_threshold: f64,
+    /// Feedback older than this counts as lost and the ghost plate is hidden
+    pub timeout_secs: f64,
+}
+
+impl Default for FeedbackConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: "0.0.0.0:9001".to_string(),
+            error_threshold: 2.0,
+            timeout_secs: 1.0,
         }
     }
 }
 
-// Helper to parse axis names to gilrs Axis enum
-pub fn parse_axis_name(name: &str) -> Option<gilrs::Axis> {
-    match name {
-        "LeftStickX" => Some(gilrs::Axis::LeftStickX),
-        "LeftStickY" => Some(gilrs::Axis::LeftStickY),
-        "LeftZ" => Some(gilrs::Axis::LeftZ),
-        "RightStickX" => Some(gilrs::Axis::RightStickX),
-        "RightStickY" => Some(gilrs::Axis::RightStickY),
-        "RightZ" => Some(gilrs::Axis::RightZ),
-        "DPadX" => Some(gilrs::Axis::DPadX),
-        "DPadY" => Some(gilrs::Axis::DPadY),
-        _ => None,
+/// The pad --virtual-gamepad adds. Each axis entry drives one gilrs axis with a signal; with
+/// none listed, the mapped pitch, roll and lift axes get slow sines.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VirtualGamepadConfig {
+    /// Shown in the gamepad list and matched by profiles and device bindings like a real name
+    pub name: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub axes: Vec<VirtualAxis>,
+}
+
+impl Default for VirtualGamepadConfig {
+    fn default() -> Self {
+        Self {
+            name: "Virtual Pad".to_string(),
+            axes: Vec::new(),
+        }
+    }
+}
+
+/// offset + amplitude × signal, clamped to ±1. Sine and step repeat every period_secs; noise
+/// is a fresh random value each sample.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VirtualAxis {
+    pub axis: String,
+    #[serde(default)]
+    pub signal: VirtualSignal,
+    #[serde(default = "default_virtual_amplitude")]
+    pub amplitude: f64,
+    #[serde(default = "default_virtual_period")]
+    pub period_secs: f64,
+    #[serde(default)]
+    pub offset: f64,
+}
+
+fn default_virtual_amplitude() -> f64 {
+    1.0
+}
+
+fn default_virtual_period() -> f64 {
+    4.0
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VirtualSignal {
+    #[default]
+    Sine,
+    Step,
+    Noise,
+}
+
+/// Channels left out keep the current target
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PosePreset {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pitch: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roll: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lift: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub yaw: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemePreset {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+/// A built-in preset plus per-role color overrides, e.g. `warning = "#b58900"` or `muted = "244"`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    pub preset: ThemePreset,
+    #[serde(flatten)]
+    pub colors: HashMap<String, String>,
+}
+
+/// Second-order response of the real plate, drawn instead of the instant commanded pose
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DynamicsConfig {
+    pub simulate_dynamics: bool,
+    /// Natural frequency of the critically damped response (Hz); higher settles faster
+    pub response_hz: f64,
+    /// Pitch/roll/yaw limits (deg/s, deg/s²)
+    pub max_angular_velocity: f64,
+    pub max_angular_acceleration: f64,
+    /// Lift limits (mm/s, mm/s²)
+    pub max_lift_velocity: f64,
+    pub max_lift_acceleration: f64,
+    /// Fastest any actuator can change length (mm/s); a move that needs more is slowed down
+    /// as a whole. 0 leaves the channels limited independently.
+    pub max_leg_speed: f64,
+}
+
+impl Default for DynamicsConfig {
+    fn default() -> Self {
+        Self {
+            simulate_dynamics: false,
+            response_hz: 1.5,
+            max_angular_velocity: 60.0,
+            max_angular_acceleration: 240.0,
+            max_lift_velocity: 40.0,
+            max_lift_acceleration: 160.0,
+            max_leg_speed: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OutputConfig {
+    /// Dead-man's switch: with either set, the outputs only follow the gimbal while that
+    /// gamepad button (gilrs name, e.g. "RightTrigger") or key is held
+    #[serde(default)]
+    pub enable_button: Option<String>,
+    #[serde(default)]
+    pub enable_key: Option<char>,
+    /// What the outputs send while it is released, as for the e-stop
+    #[serde(default)]
+    pub on_release: EstopBehavior,
+    #[serde(default)]
+    pub udp: UdpOutputConfig,
+    #[serde(default)]
+    pub actuators: ActuatorOutputConfig,
+    #[serde(default)]
+    pub mavlink: MavlinkOutputConfig,
+    #[serde(default)]
+    pub websocket: WebSocketOutputConfig,
+    #[serde(default)]
+    pub osc: OscOutputConfig,
+    #[serde(default)]
+    pub grpc: GrpcOutputConfig,
+    #[serde(default)]
+    pub mqtt: MqttOutputConfig,
+}
+
+/// Drive commands for each leg, computed from its extension and sent alongside the pose
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActuatorOutputConfig {
+    pub format: ActuatorFormat,
+    /// steps: motor steps per mm of leg travel, counted from the neutral length
+    pub steps_per_mm: f64,
+    /// servo: pulse widths at either end of mm_range, with the neutral length in the middle
+    pub min_us: f64,
+    pub max_us: f64,
+    pub mm_range: f64,
+}
+
+impl Default for ActuatorOutputConfig {
+    fn default() -> Self {
+        Self {
+            format: ActuatorFormat::None,
+            steps_per_mm: 100.0,
+            min_us: 1000.0,
+            max_us: 2000.0,
+            mm_range: 50.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActuatorFormat {
+    #[default]
+    None,
+    Steps,
+    Servo,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UdpOutputConfig {
+    pub enabled: bool,
+    pub address: String,
+    pub rate_hz: f64,
+}
+
+impl Default for UdpOutputConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: "127.0.0.1:9000".to_string(),
+            rate_hz: 50.0,
+        }
+    }
+}
+
+/// JSON state pushed to browser clients, one message per update
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebSocketOutputConfig {
+    pub enabled: bool,
+    pub address: String,
+}
+
+impl Default for WebSocketOutputConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: "127.0.0.1:9002".to_string(),
+        }
+    }
+}
+
+/// The gimbal.Gimbal service from proto/gimbal.proto; needs a build with `--features grpc`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GrpcOutputConfig {
+    pub enabled: bool,
+    pub address: String,
+    /// WatchState messages per second
+    pub rate_hz: f64,
+}
+
+impl Default for GrpcOutputConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: "127.0.0.1:50051".to_string(),
+            rate_hz: 30.0,
+        }
     }
-}
\ No newline at end of file
+}
+
+/// State, status and commands through an MQTT broker; needs a build with `--features mqtt`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttOutputConfig {
+    pub enabled: bool,
+    /// mqtt://host:port (plain TCP)
+    pub broker: String,
+    pub client_id: String,
+    /// Topics are <topic_prefix>/state, /status, /command and /reply
+    pub topic_prefix: String,
+    /// 0, 1 or 2, for every publish and the command subscription
+    pub qos: u8,
+    /// State messages per second
+    pub rate_hz: f64,
+}
+
+impl Default for MqttOutputConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            broker: "mqtt://127.0.0.1:1883".to_string(),
+            client_id: "joystick_test".to_string(),
+            topic_prefix: "gimbal".to_string(),
+            qos: 0,
+            rate_hz: 10.0,
+        }
+    }
+}
+
+/// OSC float messages for audio/visual tools: <base_path>/pitch, /roll, /lift and /yaw
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OscOutputConfig {
+    pub enabled: bool,
+    pub address: String,
+    pub port: u16,
+    pub base_path: String,
+    /// true: each channel as a fraction of its limit (-1..1); false: degrees, and mm for lift
+    pub normalize: bool,
+    pub rate_hz: f64,
+}
+
+impl Default for OscOutputConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: "127.0.0.1".to_string(),
+            port: 9000,
+            base_path: "/gimbal".to_string(),
+            normalize: false,
+            rate_hz: 30.0,
+        }
+    }
+}
+
+/// MAVLink attitude commands for a gimbal driven by a flight controller or companion computer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MavlinkOutputConfig {
+    pub enabled: bool,
+    /// "udpout:host:port"; serial links need a bridge such as mavproxy
+    pub connection: String,
+    /// Who the messages come from (255/190 is the usual ground station) and which gimbal they are for
+    pub system_id: u8,
+    pub component_id: u8,
+    pub target_system: u8,
+    pub target_component: u8,
+    pub message: MavlinkMessage,
+    pub rate_hz: f64,
+}
+
+impl Default for MavlinkOutputConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            connection: "udpout:127.0.0.1:14550".to_string(),
+            system_id: 255,
+            component_id: 190,
+            target_system: 1,
+            target_component: 154,
+            message: MavlinkMessage::GimbalDeviceSetAttitude,
+            rate_hz: 20.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MavlinkMessage {
+    /// GIMBAL_DEVICE_SET_ATTITUDE with the attitude as a quaternion (gimbal protocol v2)
+    #[default]
+    GimbalDeviceSetAttitude,
+    /// COMMAND_LONG carrying MAV_CMD_DO_MOUNT_CONTROL with angles in degrees (older mounts)
+    MountControl,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct AxisCalibration {
+    pub min: f64,
+    pub center: f64,
+    pub max: f64,
+}
+
+impl AxisCalibration {
+    /// Maps the raw range onto -1..1 with the recorded center at zero, so
+    /// asymmetric or off-center axes still reach full deflection both ways
+    pub fn normalize(&self, raw: f64) -> f64 {
+        let value = if raw >= self.center {
+            if self.max > self.center { (raw - self.center) / (self.max - self.center) } else { 0.0 }
+        } else if self.center > self.min {
+            (raw - self.center) / (self.center - self.min)
+        } else {
+            0.0
+        };
+        value.clamp(-1.0, 1.0)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DebugConfig {
+    pub enabled: bool,
+    pub show_all_axes: bool,
+    pub show_button_states: bool,
+    /// Centered bar per axis and a pressed/released button grid instead of plain numbers
+    #[serde(default = "default_axis_bars")]
+    pub axis_bars: bool,
+    /// Every update's input and resulting state, written to log_file
+    pub log_input_values: bool,
+    /// Where tracing output goes when log_level, log_input_values or RUST_LOG turns it on
+    #[serde(default = "default_log_file")]
+    pub log_file: String,
+    /// Level written to log_file from the start: off, error, warn, info, debug or trace.
+    /// RUST_LOG wins over it, and the cycle_log_level key changes it while running.
+    #[serde(default = "default_log_level")]
+    pub log_level: String,
+    /// Per-tick CSV recording started at launch; `{timestamp}` expands per session
+    #[serde(default)]
+    pub csv_log: Option<String>,
+    /// Current FPS and average update time at the top of the debug panel
+    #[serde(default)]
+    pub show_fps: bool,
+    /// Axis events in the event pane are only kept once the axis has moved this far; 0 keeps all
+    #[serde(default = "default_event_axis_threshold")]
+    pub event_axis_threshold: f64,
+}
+
+fn default_event_axis_threshold() -> f64 {
+    0.05
+}
+
+fn default_log_file() -> String {
+    "joystick_test.log".to_string()
+}
+
+fn default_log_level() -> String {
+    "off".to_string()
+}
+
+fn default_sensitivity() -> f64 {
+    1.0
+}
+
+fn default_axis_bars() -> bool {
+    true
+}
+
+fn default_coupling() -> [[f64; 2]; 2] {
+    [[1.0, 0.0], [0.0, 1.0]]
+}
+
+fn default_velocity_rate() -> f64 {
+    1.0
+}
+
+fn default_lift_min() -> f64 {
+    -15.0
+}
+
+fn default_lift_max() -> f64 {
+    15.0
+}
+
+fn default_yaw_axis() -> String {
+    "LeftStickX".to_string()
+}
+
+// Shift+Q so yaw sits next to W without taking plain 'q' away from quit
+fn default_yaw_left_key() -> char {
+    'Q'
+}
+
+fn default_yaw_right_key() -> char {
+    'e'
+}
+
+fn default_calibrate_key() -> char {
+    'c'
+}
+
+fn default_capture_center_key() -> char {
+    'z'
+}
+
+fn default_save_config_key() -> char {
+    'k'
+}
+
+fn default_toggle_csv_log_key() -> char {
+    'l'
+}
+
+fn default_cycle_profile_key() -> char {
+    'p'
+}
+
+fn default_help_key() -> char {
+    '?'
+}
+
+fn default_cycle_hardware_key() -> char {
+    'h'
+}
+
+fn default_estop_key() -> char {
+    ' '
+}
+
+fn default_rearm_key() -> char {
+    'R'
+}
+
+fn default_prev_gamepad_key() -> char {
+    '['
+}
+
+fn default_next_gamepad_key() -> char {
+    ']'
+}
+
+fn default_toggle_timing_key() -> char {
+    'i'
+}
+
+fn default_toggle_event_log_key() -> char {
+    'n'
+}
+
+fn default_home_key() -> char {
+    'g'
+}
+
+fn default_cycle_projection_key() -> char {
+    'v'
+}
+
+fn default_toggle_tilt_lines_key() -> char {
+    'u'
+}
+
+fn default_toggle_solver_key() -> char {
+    'j'
+}
+
+// Capital O, as 'o' exports the canvas
+fn default_settings_key() -> char {
+    'O'
+}
+
+// Shift+1..3, leaving the plain digits to the config profiles
+fn default_invert_pitch_key() -> char {
+    '!'
+}
+
+fn default_invert_roll_key() -> char {
+    '@'
+}
+
+fn default_invert_lift_key() -> char {
+    '#'
+}
+
+fn default_swap_pitch_roll_key() -> char {
+    'b'
+}
+
+fn default_cycle_log_level_key() -> char {
+    'y'
+}
+
+fn default_pause_key() -> char {
+    'P'
+}
+
+// Capital I, for inversion; 'i' is the timing overlay
+fn default_check_inversion_key() -> char {
+    'I'
+}
+
+fn default_detect_axes_key() -> char {
+    'm'
+}
+
+fn default_run_sequence_key() -> char {
+    'x'
+}
+
+fn default_command_key() -> char {
+    ':'
+}
+
+fn default_export_frame_key() -> char {
+    'o'
+}
+
+fn default_arrow_keys() -> bool {
+    true
+}
+
+fn default_dropout_frames() -> u32 {
+    4
+}
+
+fn default_dropout_threshold() -> f64 {
+    0.5
+}
+
+fn default_mixing_deadzone() -> f64 {
+    0.05
+}
+
+fn default_modifiers() -> HashMap<String, f64> {
+    HashMap::from([("LeftTrigger2".to_string(), 0.5), ("RightTrigger2".to_string(), 2.0)])
+}
+
+impl Default for GimbalConfig {
+    fn default() -> Self {
+        Self {
+            max_pitch: 20.0,
+            max_roll: 20.0,
+            pitch_min: None,
+            pitch_max: None,
+            roll_min: None,
+            roll_max: None,
+            lift_min: default_lift_min(),
+            lift_max: default_lift_max(),
+            lift_home: 0.0,
+            max_lift: None,
+            max_yaw: 0.0,
+            pitch_sensitivity: 1.0,
+            roll_sensitivity: 1.0,
+            lift_sensitivity: 1.0,
+            yaw_sensitivity: 1.0,
+            pitch_mode: ChannelMode::Position,
+            roll_mode: ChannelMode::Position,
+            lift_mode: ChannelMode::Position,
+            yaw_mode: ChannelMode::Position,
+            velocity_rate: default_velocity_rate(),
+            warn_tilt_threshold: None,
+            coupling: default_coupling(),
+        }
+    }
+}
+
+impl Default for ControlsConfig {
+    fn default() -> Self {
+        Self {
+            keyboard_enabled: true,
+            keyboard_step: 0.1,
+            keyboard_mode: KeyboardMode::Rate,
+            mixing: InputMixing::Sum,
+            mixing_deadzone: default_mixing_deadzone(),
+            mouse_enabled: false,
+            mouse_spring_return: false,
+            rumble_on_limit: false,
+            keyboard_bindings: KeyboardBindings::default(),
+            button_actions: HashMap::new(),
+            modifiers: default_modifiers(),
+            axis_buttons: Vec::new(),
+            dpad_mode: DpadMode::Continuous,
+            dropout_frames: default_dropout_frames(),
+            dropout_threshold: default_dropout_threshold(),
+            button_debounce_ms: 0,
+            joystick: JoystickConfig::default(),
+        }
+    }
+}
+
+impl Default for JoystickConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            pitch_axis: "RightStickY".to_string(),
+            roll_axis: "RightStickX".to_string(),
+            lift_axis: "RightZ".to_string(),
+            yaw_axis: default_yaw_axis(),
+            invert_pitch: false,
+            invert_roll: false,
+            invert_lift: false,
+            invert_yaw: false,
+            lift_axis_mode: AxisMode::Bipolar,
+            lift_control: LiftControl::Centered,
+            lift_reversed: false,
+            pitch_trim: 0.0,
+            roll_trim: 0.0,
+            lift_trim: 0.0,
+            yaw_trim: 0.0,
+            smoothing: 0.0,
+            fallback_axes: vec![
+                "LeftStickY".to_string(),
+                "LeftStickX".to_string(),
+                "LeftZ".to_string(),
+            ],
+            pitch_sources: Vec::new(),
+            roll_sources: Vec::new(),
+            lift_sources: Vec::new(),
+            yaw_sources: Vec::new(),
+            pitch_device: None,
+            roll_device: None,
+            lift_device: None,
+            yaw_device: None,
+        }
+    }
+}
+
+impl Default for DebugConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            show_all_axes: true,
+            show_button_states: true,
+            axis_bars: default_axis_bars(),
+            log_input_values: false,
+            log_file: default_log_file(),
+            log_level: default_log_level(),
+            csv_log: None,
+            show_fps: false,
+            event_axis_threshold: default_event_axis_threshold(),
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            app: AppConfig::default(),
+            gimbal: GimbalConfig::default(),
+            controls: ControlsConfig::default(),
+            debug: DebugConfig::default(),
+            geometry: GeometryConfig::default(),
+            output: OutputConfig::default(),
+            dynamics: DynamicsConfig::default(),
+            safety: SafetyConfig::default(),
+            homing: HomingConfig::default(),
+            sequence: SequenceConfig::default(),
+            server: ServerConfig::default(),
+            feedback: FeedbackConfig::default(),
+            virtual_gamepad: VirtualGamepadConfig::default(),
+            presets: HashMap::new(),
+            theme: ThemeConfig::default(),
+            profiles: Vec::new(),
+            hardware: Vec::new(),
+            calibration: HashMap::new(),
+            migration: None,
+        }
+    }
+}
+
+impl Default for KeyboardBindings {
+    fn default() -> Self {
+        Self {
+            pitch_up: 'w',
+            pitch_down: 's',
+            roll_left: 'a',
+            roll_right: 'd',
+            lift_up: 'r',
+            lift_down: 'f',
+            yaw_left: default_yaw_left_key(),
+            yaw_right: default_yaw_right_key(),
+            reset: '0',
+            quit: 'q',
+            toggle_debug: 't',
+            calibrate: default_calibrate_key(),
+            capture_center: default_capture_center_key(),
+            save_config: default_save_config_key(),
+            toggle_csv_log: default_toggle_csv_log_key(),
+            cycle_profile: default_cycle_profile_key(),
+            help: default_help_key(),
+            prev_gamepad: default_prev_gamepad_key(),
+            next_gamepad: default_next_gamepad_key(),
+            cycle_hardware: default_cycle_hardware_key(),
+            estop: default_estop_key(),
+            rearm: default_rearm_key(),
+            toggle_timing: default_toggle_timing_key(),
+            toggle_event_log: default_toggle_event_log_key(),
+            home: default_home_key(),
+            cycle_projection: default_cycle_projection_key(),
+            toggle_tilt_lines: default_toggle_tilt_lines_key(),
+            toggle_solver: default_toggle_solver_key(),
+            settings: default_settings_key(),
+            invert_pitch: default_invert_pitch_key(),
+            invert_roll: default_invert_roll_key(),
+            invert_lift: default_invert_lift_key(),
+            swap_pitch_roll: default_swap_pitch_roll_key(),
+            pause: default_pause_key(),
+            detect_axes: default_detect_axes_key(),
+            check_inversion: default_check_inversion_key(),
+            run_sequence: default_run_sequence_key(),
+            command: default_command_key(),
+            export_frame: default_export_frame_key(),
+            cycle_log_level: default_cycle_log_level_key(),
+            arrow_keys: default_arrow_keys(),
+        }
+    }
+}
+
+impl KeyboardBindings {
+    /// Ordered by precedence: app actions win over movement when a key is bound twice
+    pub fn entries(&self) -> [(KeyAction, char); 40] {
+        [
+            (KeyAction::Estop, self.estop),
+            (KeyAction::Quit, self.quit),
+            (KeyAction::Help, self.help),
+            (KeyAction::ToggleDebug, self.toggle_debug),
+            (KeyAction::ToggleTiming, self.toggle_timing),
+            (KeyAction::ToggleEventLog, self.toggle_event_log),
+            (KeyAction::CycleProjection, self.cycle_projection),
+            (KeyAction::ToggleTiltLines, self.toggle_tilt_lines),
+            (KeyAction::ToggleSolver, self.toggle_solver),
+            (KeyAction::Settings, self.settings),
+            (KeyAction::Calibrate, self.calibrate),
+            (KeyAction::DetectAxes, self.detect_axes),
+            (KeyAction::CheckInversion, self.check_inversion),
+            (KeyAction::CaptureCenter, self.capture_center),
+            (KeyAction::SaveConfig, self.save_config),
+            (KeyAction::ToggleCsvLog, self.toggle_csv_log),
+            (KeyAction::CycleProfile, self.cycle_profile),
+            (KeyAction::CycleHardware, self.cycle_hardware),
+            (KeyAction::InvertPitch, self.invert_pitch),
+            (KeyAction::InvertRoll, self.invert_roll),
+            (KeyAction::InvertLift, self.invert_lift),
+            (KeyAction::SwapPitchRoll, self.swap_pitch_roll),
+            (KeyAction::PrevGamepad, self.prev_gamepad),
+            (KeyAction::NextGamepad, self.next_gamepad),
+            (KeyAction::Rearm, self.rearm),
+            (KeyAction::Pause, self.pause),
+            (KeyAction::RunSequence, self.run_sequence),
+            (KeyAction::Command, self.command),
+            (KeyAction::ExportFrame, self.export_frame),
+            (KeyAction::CycleLogLevel, self.cycle_log_level),
+            (KeyAction::Reset, self.reset),
+            (KeyAction::Home, self.home),
+            (KeyAction::PitchUp, self.pitch_up),
+            (KeyAction::PitchDown, self.pitch_down),
+            (KeyAction::RollLeft, self.roll_left),
+            (KeyAction::RollRight, self.roll_right),
+            (KeyAction::LiftUp, self.lift_up),
+            (KeyAction::LiftDown, self.lift_down),
+            (KeyAction::YawLeft, self.yaw_left),
+            (KeyAction::YawRight, self.yaw_right),
+        ]
+    }
+
+    /// Exact matches win, so 'q' and 'Q' can be bound to different actions;
+    /// otherwise bindings are case-insensitive
+    pub fn action_for(&self, key: char) -> Option<KeyAction> {
+        let entries = self.entries();
+        entries
+            .iter()
+            .find(|(_, bound)| *bound == key)
+            .or_else(|| entries.iter().find(|(_, bound)| bound.eq_ignore_ascii_case(&key)))
+            .map(|(action, _)| *action)
+    }
+
+    pub fn conflicts(&self) -> Vec<String> {
+        let entries = self.entries();
+        let mut conflicts = Vec::new();
+        for (i, (action, key)) in entries.iter().enumerate() {
+            for (other, other_key) in &entries[i + 1..] {
+                if key == other_key {
+                    conflicts.push(format!(
+                        "key '{}' is bound to both {} and {} ({} wins)",
+                        key, action.name(), other.name(), action.name()
+                    ));
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+/// Whole-config files the digit keys switch between, in a directory next to the main config
+pub const PROFILE_DIR: &str = "profiles";
+pub const MAX_CONFIG_PROFILES: usize = 9;
+
+/// The *.toml files in `dir` sorted by name, or none if it doesn't exist
+pub fn config_profiles(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else { return Vec::new() };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+impl Config {
+    /// With `strict` an invalid value is an error; otherwise it is replaced by its default
+    /// and the problems are returned so the caller can report them. A missing file is
+    /// written out with the defaults.
+    pub fn load_or_create<P: AsRef<Path>>(path: P, strict: bool) -> Result<(Self, Vec<String>), Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        
+        if path.exists() {
+            Self::load(path, strict)
+        } else {
+            let default_config = Config::default();
+            let toml_string = toml::to_string_pretty(&default_config)?;
+            fs::write(path, toml_string)?;
+            Ok((default_config, Vec::new()))
+        }
+    }
+
+    /// Like load_or_create, but a missing file is an error rather than a new default config.
+    /// A file from an older version is upgraded and written back before it is validated, so
+    /// out-of-range values stay in the file for the user to fix.
+    pub fn load<P: AsRef<Path>>(path: P, strict: bool) -> Result<(Self, Vec<String>), Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+        let mut config: Config = toml::from_str(&content)?;
+        if let Some(from) = config.migrate() {
+            let save_error = config.save(path).err().map(|e| e.to_string());
+            config.migration = Some(Migration { from, save_error });
+        }
+        let problems = match config.validate() {
+            Ok(()) => Vec::new(),
+            Err(problems) if strict => {
+                return Err(format!("Invalid config {}:\n  {}", path.display(), problems.join("\n  ")).into());
+            }
+            Err(_) => config.repair(),
+        };
+        Ok((config, problems))
+    }
+
+    /// Carries settings older configs spelled differently over to their current form and
+    /// stamps the current version. Missing settings already came in as their defaults while
+    /// parsing. Returns the version the config had, if it was older.
+    pub fn migrate(&mut self) -> Option<u32> {
+        let from = self.version;
+        if from >= CONFIG_VERSION {
+            return None;
+        }
+        if let Some(ms) = self.app.tick_rate_ms.take() {
+            self.app.update_rate_hz = 1000.0 / ms.max(1) as f64;
+        }
+        if let Some(ms) = self.app.draw_rate_ms.take() {
+            self.app.render_rate_hz = 1000.0 / ms.max(1) as f64;
+        }
+        let profiles = self.hardware.iter_mut().map(|profile| &mut profile.gimbal);
+        for gimbal in std::iter::once(&mut self.gimbal).chain(profiles) {
+            if let Some(max) = gimbal.max_lift.take() {
+                gimbal.lift_min = -max;
+                gimbal.lift_max = max;
+                gimbal.lift_home = 0.0;
+            }
+        }
+        self.version = CONFIG_VERSION;
+        Some(from)
+    }
+
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let problems = self.clone().repair();
+        if problems.is_empty() { Ok(()) } else { Err(problems) }
+    }
+
+    // Resets every out-of-range field to its default and describes what was wrong
+    fn repair(&mut self) -> Vec<String> {
+        let defaults = Config::default();
+        let mut problems = Vec::new();
+        let non_negative = |v: f64| v >= 0.0;
+        let positive = |v: f64| v > 0.0;
+
+        check_gimbal(&mut problems, "gimbal", &mut self.gimbal, &defaults.gimbal);
+        for (name, value, default) in [
+            ("app.update_rate_hz", &mut self.app.update_rate_hz, defaults.app.update_rate_hz),
+            ("app.render_rate_hz", &mut self.app.render_rate_hz, defaults.app.render_rate_hz),
+        ] {
+            check_number(&mut problems, name, value, default, positive, "must be above zero");
+        }
+        for (name, value, smallest, default) in [
+            ("app.min_width", &mut self.app.min_width, SMALLEST_WIDTH, defaults.app.min_width),
+            ("app.min_height", &mut self.app.min_height, SMALLEST_HEIGHT, defaults.app.min_height),
+        ] {
+            if *value < smallest {
+                problems.push(format!("{} = {} must be at least {}", name, value, smallest));
+                *value = default;
+            }
+        }
+        check_number(
+            &mut problems,
+            "app.status_message_secs",
+            &mut self.app.status_message_secs,
+            defaults.app.status_message_secs,
+            non_negative,
+            "must be zero or more",
+        );
+        if self.debug.log_level.parse::<tracing::level_filters::LevelFilter>().is_err() {
+            problems.push(format!(
+                "debug.log_level = \"{}\" must be off, error, warn, info, debug or trace",
+                self.debug.log_level
+            ));
+            self.debug.log_level = defaults.debug.log_level.clone();
+        }
+        check_number(
+            &mut problems,
+            "debug.event_axis_threshold",
+            &mut self.debug.event_axis_threshold,
+            defaults.debug.event_axis_threshold,
+            non_negative,
+            "must be zero or more",
+        );
+        check_number(
+            &mut problems,
+            "controls.keyboard_step",
+            &mut self.controls.keyboard_step,
+            defaults.controls.keyboard_step,
+            positive,
+            "must be above zero",
+        );
+        check_number(
+            &mut problems,
+            "controls.mixing_deadzone",
+            &mut self.controls.mixing_deadzone,
+            defaults.controls.mixing_deadzone,
+            non_negative,
+            "must be zero or more",
+        );
+        check_number(
+            &mut problems,
+            "controls.dropout_threshold",
+            &mut self.controls.dropout_threshold,
+            defaults.controls.dropout_threshold,
+            |value| (0.0..1.0).contains(&value),
+            "must be from 0 up to (not including) 1",
+        );
+        check_number(
+            &mut problems,
+            "output.udp.rate_hz",
+            &mut self.output.udp.rate_hz,
+            defaults.output.udp.rate_hz,
+            positive,
+            "must be above zero",
+        );
+        check_number(
+            &mut problems,
+            "output.mavlink.rate_hz",
+            &mut self.output.mavlink.rate_hz,
+            defaults.output.mavlink.rate_hz,
+            positive,
+            "must be above zero",
+        );
+        for (name, value, default) in [
+            ("feedback.error_threshold", &mut self.feedback.error_threshold, defaults.feedback.error_threshold),
+            ("feedback.timeout_secs", &mut self.feedback.timeout_secs, defaults.feedback.timeout_secs),
+        ] {
+            check_number(&mut problems, name, value, default, positive, "must be above zero");
+        }
+        check_number(
+            &mut problems,
+            "output.osc.rate_hz",
+            &mut self.output.osc.rate_hz,
+            defaults.output.osc.rate_hz,
+            positive,
+            "must be above zero",
+        );
+        if !self.output.osc.base_path.starts_with('/') {
+            problems.push(format!("output.osc.base_path = \"{}\" must start with '/'", self.output.osc.base_path));
+            self.output.osc.base_path = defaults.output.osc.base_path.clone();
+        }
+        check_number(
+            &mut problems,
+            "output.grpc.rate_hz",
+            &mut self.output.grpc.rate_hz,
+            defaults.output.grpc.rate_hz,
+            positive,
+            "must be above zero",
+        );
+        check_number(
+            &mut problems,
+            "output.mqtt.rate_hz",
+            &mut self.output.mqtt.rate_hz,
+            defaults.output.mqtt.rate_hz,
+            positive,
+            "must be above zero",
+        );
+        let mqtt = &mut self.output.mqtt;
+        if mqtt.qos > 2 {
+            problems.push(format!("output.mqtt.qos = {} must be 0, 1 or 2", mqtt.qos));
+            mqtt.qos = defaults.output.mqtt.qos;
+        }
+        let prefix = mqtt.topic_prefix.trim_end_matches('/');
+        if prefix.is_empty() || prefix.contains(['+', '#']) {
+            problems.push(format!("output.mqtt.topic_prefix = \"{}\" must be a topic without wildcards", mqtt.topic_prefix));
+            mqtt.topic_prefix = defaults.output.mqtt.topic_prefix.clone();
+        }
+        let actuators = &mut self.output.actuators;
+        for (name, value, default) in [
+            ("output.actuators.steps_per_mm", &mut actuators.steps_per_mm, defaults.output.actuators.steps_per_mm),
+            ("output.actuators.mm_range", &mut actuators.mm_range, defaults.output.actuators.mm_range),
+            ("output.actuators.min_us", &mut actuators.min_us, defaults.output.actuators.min_us),
+            ("output.actuators.max_us", &mut actuators.max_us, defaults.output.actuators.max_us),
+        ] {
+            check_number(&mut problems, name, value, default, positive, "must be above zero");
+        }
+        if actuators.min_us >= actuators.max_us {
+            problems.push(format!(
+                "output.actuators.min_us = {} must be below max_us = {}",
+                actuators.min_us, actuators.max_us
+            ));
+            actuators.min_us = defaults.output.actuators.min_us;
+            actuators.max_us = defaults.output.actuators.max_us;
+        }
+
+        let dynamics = &mut self.dynamics;
+        for (name, value, default) in [
+            ("dynamics.response_hz", &mut dynamics.response_hz, defaults.dynamics.response_hz),
+            ("dynamics.max_angular_velocity", &mut dynamics.max_angular_velocity, defaults.dynamics.max_angular_velocity),
+            ("dynamics.max_angular_acceleration", &mut dynamics.max_angular_acceleration, defaults.dynamics.max_angular_acceleration),
+            ("dynamics.max_lift_velocity", &mut dynamics.max_lift_velocity, defaults.dynamics.max_lift_velocity),
+            ("dynamics.max_lift_acceleration", &mut dynamics.max_lift_acceleration, defaults.dynamics.max_lift_acceleration),
+        ] {
+            check_number(&mut problems, name, value, default, positive, "must be above zero");
+        }
+        check_number(
+            &mut problems,
+            "dynamics.max_leg_speed",
+            &mut self.dynamics.max_leg_speed,
+            defaults.dynamics.max_leg_speed,
+            non_negative,
+            "must be zero or more",
+        );
+
+        check_number(
+            &mut problems,
+            "safety.rearm_hold_secs",
+            &mut self.safety.rearm_hold_secs,
+            defaults.safety.rearm_hold_secs,
+            positive,
+            "must be above zero",
+        );
+        check_number(
+            &mut problems,
+            "safety.zero_ramp_secs",
+            &mut self.safety.zero_ramp_secs,
+            defaults.safety.zero_ramp_secs,
+            positive,
+            "must be above zero",
+        );
+        check_number(
+            &mut problems,
+            "safety.soft_start_secs",
+            &mut self.safety.soft_start_secs,
+            defaults.safety.soft_start_secs,
+            non_negative,
+            "must not be negative",
+        );
+        check_number(
+            &mut problems,
+            "homing.move_secs",
+            &mut self.homing.move_secs,
+            defaults.homing.move_secs,
+            positive,
+            "must be above zero",
+        );
+        check_number(
+            &mut problems,
+            "homing.idle_timeout_secs",
+            &mut self.homing.idle_timeout_secs,
+            defaults.homing.idle_timeout_secs,
+            non_negative,
+            "must not be negative",
+        );
+        check_number(
+            &mut problems,
+            "sequence.abort_threshold",
+            &mut self.sequence.abort_threshold,
+            defaults.sequence.abort_threshold,
+            |v| (0.0..=1.0).contains(&v),
+            "must be between 0.0 and 1.0",
+        );
+        for (name, preset) in &mut self.presets {
+            for (channel, value) in [
+                ("pitch", &mut preset.pitch),
+                ("roll", &mut preset.roll),
+                ("lift", &mut preset.lift),
+                ("yaw", &mut preset.yaw),
+            ] {
+                if value.is_some_and(|v| !v.is_finite()) {
+                    problems.push(format!("presets.{}.{} must be a finite number", name, channel));
+                    *value = None;
+                }
+            }
+        }
+        let homing = &mut self.homing;
+        for (name, value, default) in [
+            ("homing.pitch", &mut homing.pitch, defaults.homing.pitch),
+            ("homing.roll", &mut homing.roll, defaults.homing.roll),
+            ("homing.lift", &mut homing.lift, defaults.homing.lift),
+            ("homing.yaw", &mut homing.yaw, defaults.homing.yaw),
+        ] {
+            check_number(&mut problems, name, value, default, |_| true, "must be a finite number");
+        }
+        if let Some(button) = &self.safety.estop_button
+            && parse_button_name(button).is_none()
+        {
+            problems.push(format!("safety.estop_button = \"{}\" is not a known button name", button));
+            self.safety.estop_button = defaults.safety.estop_button;
+        }
+        self.theme.colors.retain(|name, value| {
+            if Role::parse(name).is_none() {
+                problems.push(format!("theme.{} is not a known color role", name));
+                false
+            } else if value.parse::<ratatui::style::Color>().is_err() {
+                problems.push(format!("theme.{} = \"{}\" is not a color name, index or #rrggbb", name, value));
+                false
+            } else {
+                true
+            }
+        });
+        let estop_button = self.safety.estop_button.as_deref().and_then(parse_button_name);
+        if let Some(button) = self.output.enable_button.clone() {
+            let problem = match parse_button_name(&button) {
+                None => Some("is not a known button name"),
+                Some(parsed) if Some(parsed) == estop_button => Some("is the safety.estop_button"),
+                Some(_) => None,
+            };
+            if let Some(problem) = problem {
+                problems.push(format!("output.enable_button = \"{}\" {}", button, problem));
+                self.output.enable_button = None;
+            }
+        }
+        if let Some(key) = self.output.enable_key
+            && let Some(action) = self.controls.keyboard_bindings.action_for(key)
+        {
+            problems.push(format!("output.enable_key = \"{}\" is already the {} key", key, action.name()));
+            self.output.enable_key = None;
+        }
+        self.controls.button_actions.retain(|button, _| match parse_button_name(button) {
+            None => {
+                problems.push(format!("controls.button_actions: \"{}\" is not a known button name", button));
+                false
+            }
+            Some(parsed) if Some(parsed) == estop_button => {
+                problems.push(format!("controls.button_actions: \"{}\" is the safety.estop_button", button));
+                false
+            }
+            Some(_) => true,
+        });
+        let actions = &self.controls.button_actions;
+        self.controls.modifiers.retain(|button, factor| match parse_button_name(button) {
+            None => {
+                problems.push(format!("controls.modifiers: \"{}\" is not a known button name", button));
+                false
+            }
+            Some(parsed) if Some(parsed) == estop_button => {
+                problems.push(format!("controls.modifiers: \"{}\" is the safety.estop_button", button));
+                false
+            }
+            Some(parsed) if actions.keys().any(|name| parse_button_name(name) == Some(parsed)) => {
+                problems.push(format!("controls.modifiers: \"{}\" is already in controls.button_actions", button));
+                false
+            }
+            Some(_) if !(factor.is_finite() && *factor > 0.0) => {
+                problems.push(format!("controls.modifiers.{} = {} must be above zero", button, factor));
+                false
+            }
+            Some(_) => true,
+        });
+
+        self.controls.axis_buttons.retain(|entry| {
+            if parse_axis_name(&entry.axis).is_none() {
+                problems.push(format!("controls.axis_buttons: \"{}\" is not a known axis name", entry.axis));
+                false
+            } else if entry.positive.is_none() && entry.negative.is_none() {
+                problems.push(format!("controls.axis_buttons: \"{}\" has neither a positive nor a negative action", entry.axis));
+                false
+            } else {
+                true
+            }
+        });
+
+        self.virtual_gamepad.axes.retain(|entry| {
+            let numbers = [entry.amplitude, entry.period_secs, entry.offset];
+            if parse_axis_name(&entry.axis).is_none() {
+                problems.push(format!("virtual_gamepad.axes: \"{}\" is not a known axis name", entry.axis));
+                false
+            } else if !numbers.iter().all(|v| v.is_finite()) || entry.period_secs <= 0.0 {
+                problems.push(format!("virtual_gamepad.axes: \"{}\" needs finite values and period_secs above zero", entry.axis));
+                false
+            } else {
+                true
+            }
+        });
+
+        check_joystick(&mut problems, "controls.joystick", &mut self.controls.joystick, &defaults.controls.joystick);
+        for profile in &mut self.profiles {
+            let prefix = format!("profiles.{}.joystick", profile.name);
+            check_joystick(&mut problems, &prefix, &mut profile.joystick, &defaults.controls.joystick);
+        }
+
+        // A hat that steps can't also drive a channel continuously
+        if self.controls.dpad_mode == DpadMode::Step {
+            let mappings = std::iter::once(&self.controls.joystick).chain(self.profiles.iter().map(|profile| &profile.joystick));
+            let dpad_axis = mappings
+                .flat_map(|joystick| [&joystick.pitch_axis, &joystick.roll_axis, &joystick.lift_axis, &joystick.yaw_axis])
+                .find(|axis| matches!(parse_axis_name(axis), Some(gilrs::Axis::DPadX | gilrs::Axis::DPadY)));
+            if let Some(axis) = dpad_axis {
+                problems.push(format!("controls.dpad_mode = \"step\" but {} is mapped to a channel", axis));
+                self.controls.dpad_mode = defaults.controls.dpad_mode;
+            }
+        }
+
+        if let Err(e) = self.geometry.validate() {
+            problems.push(e);
+            self.geometry = defaults.geometry.clone();
+        }
+
+        for profile in &mut self.hardware {
+            check_gimbal(&mut problems, &format!("hardware.{}.gimbal", profile.name), &mut profile.gimbal, &defaults.gimbal);
+            if let Err(e) = profile.geometry.validate() {
+                problems.push(format!("hardware.{}: {}", profile.name, e));
+                profile.geometry = defaults.geometry.clone();
+            }
+        }
+        if let Some(name) = &self.app.hardware_profile
+            && self.hardware_index(name).is_none()
+        {
+            problems.push(format!("app.hardware_profile = \"{}\" does not match any [[hardware]] name", name));
+            self.app.hardware_profile = None;
+        }
+        problems
+    }
+
+    /// Writes the config into the file at `path`, keeping its comments, key order and the
+    /// formatting of every value that didn't change; a missing or unreadable file is replaced
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        let updated: DocumentMut = toml::to_string_pretty(self)?.parse()?;
+        let document = match fs::read_to_string(path).map(|text| text.parse::<DocumentMut>()) {
+            Ok(Ok(mut document)) => {
+                merge_table(document.as_table_mut(), updated.as_table());
+                document
+            }
+            _ => updated,
+        };
+        fs::write(path, document.to_string())?;
+        Ok(())
+    }
+
+    pub fn hardware_index(&self, name: &str) -> Option<usize> {
+        self.hardware.iter().position(|profile| profile.name == name)
+    }
+
+    /// First profile whose match_name appears in the gamepad name
+    pub fn profile_for(&self, gamepad_name: &str) -> Option<usize> {
+        self.profiles.iter().position(|profile| name_matches(&profile.match_name, gamepad_name))
+    }
+
+    pub fn axis_calibration(&self, gamepad: Option<&str>, axis: gilrs::Axis) -> Option<&AxisCalibration> {
+        self.calibration.get(gamepad?)?.get(&axis_key(axis))
+    }
+}
+
+// Brings `existing` in line with `updated` in place. Keys the config no longer has are
+// dropped; changed values keep the comment that followed them.
+fn merge_table(existing: &mut Table, updated: &Table) {
+    existing.retain(|key, _| updated.contains_key(key));
+    for (key, new) in updated.iter() {
+        match (existing.get_mut(key), new) {
+            (Some(Item::Table(old)), Item::Table(new)) => merge_table(old, new),
+            (Some(Item::ArrayOfTables(old)), Item::ArrayOfTables(new)) if old.len() == new.len() => {
+                for (old, new) in old.iter_mut().zip(new.iter()) {
+                    merge_table(old, new);
+                }
+            }
+            (Some(Item::Value(old)), Item::Value(new)) => {
+                if !same_value(old, new) {
+                    let decor = old.decor().clone();
+                    *old = new.clone();
+                    *old.decor_mut() = decor;
+                }
+            }
+            (Some(old), new) => *old = new.clone(),
+            // Empty lists and sections would only add noise to a hand-written file
+            (None, Item::Table(new)) if new.is_empty() => {}
+            (None, Item::Value(Value::Array(new))) if new.is_empty() => {}
+            (None, new) => {
+                existing.insert(key, new.clone());
+            }
+        }
+    }
+}
+
+// Equal as config values, however they are written: `20` and `20.0`, 'a' and "a"
+fn same_value(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::String(a), Value::String(b)) => a.value() == b.value(),
+        (Value::Integer(a), Value::Integer(b)) => a.value() == b.value(),
+        (Value::Float(a), Value::Float(b)) => a.value() == b.value(),
+        (Value::Integer(a), Value::Float(b)) | (Value::Float(b), Value::Integer(a)) => *a.value() as f64 == *b.value(),
+        (Value::Boolean(a), Value::Boolean(b)) => a.value() == b.value(),
+        (Value::Array(a), Value::Array(b)) => a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| same_value(a, b)),
+        (Value::InlineTable(a), Value::InlineTable(b)) => {
+            a.len() == b.len() && a.iter().all(|(key, a)| b.get(key).is_some_and(|b| same_value(a, b)))
+        }
+        _ => false,
+    }
+}
+
+fn check_number(
+    problems: &mut Vec<String>,
+    name: &str,
+    value: &mut f64,
+    default: f64,
+    valid: impl Fn(f64) -> bool,
+    rule: &str,
+) {
+    if !value.is_finite() || !valid(*value) {
+        problems.push(format!("{} = {} {}", name, value, rule));
+        *value = default;
+    }
+}
+
+fn check_gimbal(problems: &mut Vec<String>, prefix: &str, gimbal: &mut GimbalConfig, defaults: &GimbalConfig) {
+    for (name, value, default) in [
+        ("max_pitch", &mut gimbal.max_pitch, defaults.max_pitch),
+        ("max_roll", &mut gimbal.max_roll, defaults.max_roll),
+        ("max_yaw", &mut gimbal.max_yaw, defaults.max_yaw),
+        ("pitch_sensitivity", &mut gimbal.pitch_sensitivity, defaults.pitch_sensitivity),
+        ("roll_sensitivity", &mut gimbal.roll_sensitivity, defaults.roll_sensitivity),
+        ("lift_sensitivity", &mut gimbal.lift_sensitivity, defaults.lift_sensitivity),
+        ("yaw_sensitivity", &mut gimbal.yaw_sensitivity, defaults.yaw_sensitivity),
+        ("velocity_rate", &mut gimbal.velocity_rate, defaults.velocity_rate),
+    ] {
+        let name = format!("{}.{}", prefix, name);
+        check_number(problems, &name, value, default, |v| v >= 0.0, "must be zero or more");
+    }
+    for (name, value, default) in [
+        ("lift_min", &mut gimbal.lift_min, defaults.lift_min),
+        ("lift_max", &mut gimbal.lift_max, defaults.lift_max),
+        ("lift_home", &mut gimbal.lift_home, defaults.lift_home),
+    ] {
+        let name = format!("{}.{}", prefix, name);
+        check_number(problems, &name, value, default, |_| true, "must be a finite number");
+    }
+    if gimbal.lift_min >= gimbal.lift_max {
+        problems.push(format!(
+            "{}.lift_min = {} must be below lift_max = {}",
+            prefix, gimbal.lift_min, gimbal.lift_max
+        ));
+        (gimbal.lift_min, gimbal.lift_max) = (defaults.lift_min, defaults.lift_max);
+    }
+    // Level has to stay reachable: it is where reset and a centered stick go
+    for (channel, min, max, max_default) in [
+        ("pitch", &mut gimbal.pitch_min, &mut gimbal.pitch_max, gimbal.max_pitch),
+        ("roll", &mut gimbal.roll_min, &mut gimbal.roll_max, gimbal.max_roll),
+    ] {
+        if let Some(value) = *min
+            && !(value.is_finite() && value <= 0.0)
+        {
+            problems.push(format!("{}.{}_min = {} must be zero or below", prefix, channel, value));
+            *min = None;
+        }
+        if let Some(value) = *max
+            && !(value.is_finite() && value >= 0.0)
+        {
+            problems.push(format!("{}.{}_max = {} must be zero or more", prefix, channel, value));
+            *max = None;
+        }
+        if min.unwrap_or(-max_default) >= max.unwrap_or(max_default) {
+            problems.push(format!("{}.{}_min must be below {}_max", prefix, channel, channel));
+            (*min, *max) = (None, None);
+        }
+    }
+    if !(gimbal.lift_min..=gimbal.lift_max).contains(&gimbal.lift_home) {
+        problems.push(format!(
+            "{}.lift_home = {} must be within lift_min..lift_max ({}..{})",
+            prefix, gimbal.lift_home, gimbal.lift_min, gimbal.lift_max
+        ));
+        gimbal.lift_home = gimbal.lift_home.clamp(gimbal.lift_min, gimbal.lift_max);
+    }
+    if let Some(threshold) = gimbal.warn_tilt_threshold
+        && !(threshold.is_finite() && threshold > 0.0)
+    {
+        problems.push(format!("{}.warn_tilt_threshold = {} must be above zero", prefix, threshold));
+        gimbal.warn_tilt_threshold = defaults.warn_tilt_threshold;
+    }
+    if !gimbal.coupling.iter().flatten().all(|v| v.is_finite()) {
+        problems.push(format!("{}.coupling = {:?} must hold finite numbers", prefix, gimbal.coupling));
+        gimbal.coupling = defaults.coupling;
+    }
+}
+
+fn check_joystick(problems: &mut Vec<String>, prefix: &str, joystick: &mut JoystickConfig, defaults: &JoystickConfig) {
+    for (channel, axis, default) in [
+        ("pitch_axis", &mut joystick.pitch_axis, &defaults.pitch_axis),
+        ("roll_axis", &mut joystick.roll_axis, &defaults.roll_axis),
+        ("lift_axis", &mut joystick.lift_axis, &defaults.lift_axis),
+        ("yaw_axis", &mut joystick.yaw_axis, &defaults.yaw_axis),
+    ] {
+        if parse_axis_name(axis).is_none() {
+            problems.push(format!("{}.{} = \"{}\" is not a known axis name", prefix, channel, axis));
+            *axis = default.clone();
+        }
+    }
+    joystick.fallback_axes.retain(|axis| {
+        let known = parse_axis_name(axis).is_some();
+        if !known {
+            problems.push(format!("{}.fallback_axes: \"{}\" is not a known axis name", prefix, axis));
+        }
+        known
+    });
+    for (channel, sources) in [
+        ("pitch_sources", &mut joystick.pitch_sources),
+        ("roll_sources", &mut joystick.roll_sources),
+        ("lift_sources", &mut joystick.lift_sources),
+        ("yaw_sources", &mut joystick.yaw_sources),
+    ] {
+        sources.retain(|source| {
+            let known = parse_axis_name(&source.axis).is_some();
+            if !known {
+                problems.push(format!("{}.{}: \"{}\" is not a known axis name", prefix, channel, source.axis));
+            }
+            known
+        });
+        for (i, source) in sources.iter_mut().enumerate() {
+            let name = format!("{}.{}[{}].gain", prefix, channel, i);
+            check_number(problems, &name, &mut source.gain, 1.0, |_| true, "must be a finite number");
+        }
+    }
+    for (channel, device) in [
+        ("pitch_device", &mut joystick.pitch_device),
+        ("roll_device", &mut joystick.roll_device),
+        ("lift_device", &mut joystick.lift_device),
+        ("yaw_device", &mut joystick.yaw_device),
+    ] {
+        // An empty pattern would match every gamepad
+        if device.as_deref().is_some_and(|name| name.trim().is_empty()) {
+            problems.push(format!("{}.{} must not be empty (leave it out to follow the active gamepad)", prefix, channel));
+            *device = None;
+        }
+    }
+
+    let within_one = |v: f64| v.abs() <= 1.0;
+    for (name, value) in [
+        ("pitch_trim", &mut joystick.pitch_trim),
+        ("roll_trim", &mut joystick.roll_trim),
+        ("lift_trim", &mut joystick.lift_trim),
+        ("yaw_trim", &mut joystick.yaw_trim),
+    ] {
+        check_number(problems, &format!("{}.{}", prefix, name), value, 0.0, within_one, "must be within ±1.0");
+    }
+    check_number(
+        problems,
+        &format!("{}.smoothing", prefix),
+        &mut joystick.smoothing,
+        defaults.smoothing,
+        |v| (0.0..1.0).contains(&v),
+        "must be at least 0.0 and below 1.0",
+    );
+}
+
+/// Name used for an axis in config files; matches what parse_axis_name accepts
+pub fn axis_key(axis: gilrs::Axis) -> String {
+    format!("{:?}", axis)
+}
+
+/// Helper to parse axis names to gilrs Axis enum
+pub fn parse_axis_name(name: &str) -> Option<gilrs::Axis> {
+    match name {
+        "LeftStickX" => Some(gilrs::Axis::LeftStickX),
+        "LeftStickY" => Some(gilrs::Axis::LeftStickY),
+        "LeftZ" => Some(gilrs::Axis::LeftZ),
+        "RightStickX" => Some(gilrs::Axis::RightStickX),
+        "RightStickY" => Some(gilrs::Axis::RightStickY),
+        "RightZ" => Some(gilrs::Axis::RightZ),
+        "DPadX" => Some(gilrs::Axis::DPadX),
+        "DPadY" => Some(gilrs::Axis::DPadY),
+        _ => None,
+    }
+}
+
+pub fn parse_button_name(name: &str) -> Option<gilrs::Button> {
+    use gilrs::Button;
+    match name {
+        "South" => Some(Button::South),
+        "East" => Some(Button::East),
+        "North" => Some(Button::North),
+        "West" => Some(Button::West),
+        "C" => Some(Button::C),
+        "Z" => Some(Button::Z),
+        "LeftTrigger" => Some(Button::LeftTrigger),
+        "LeftTrigger2" => Some(Button::LeftTrigger2),
+        "RightTrigger" => Some(Button::RightTrigger),
+        "RightTrigger2" => Some(Button::RightTrigger2),
+        "Select" => Some(Button::Select),
+        "Start" => Some(Button::Start),
+        "Mode" => Some(Button::Mode),
+        "LeftThumb" => Some(Button::LeftThumb),
+        "RightThumb" => Some(Button::RightThumb),
+        "DPadUp" => Some(Button::DPadUp),
+        "DPadDown" => Some(Button::DPadDown),
+        "DPadLeft" => Some(Button::DPadLeft),
+        "DPadRight" => Some(Button::DPadRight),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A config from before versioning: rates as periods and a single symmetric max_lift
+    const UNVERSIONED: &str = r#"
+[app]
+tick_rate_ms = 20
+draw_rate_ms = 50
+
+[gimbal]
+max_pitch = 25.0
+max_roll = 15.0
+max_lift = 30.0
+
+[[hardware]]
+name = "bench"
+
+[hardware.gimbal]
+max_pitch = 10.0
+max_roll = 10.0
+max_lift = 8.0
+"#;
+
+    #[test]
+    fn unversioned_config_migrates_to_the_current_version() {
+        let mut config: Config = toml::from_str(UNVERSIONED).unwrap();
+        assert_eq!(config.version, 0);
+        assert_eq!(config.migrate(), Some(0));
+        assert_eq!(config.version, CONFIG_VERSION);
+
+        assert_eq!((config.app.update_rate_hz, config.app.render_rate_hz), (50.0, 20.0));
+        assert_eq!((config.app.tick_rate_ms, config.app.draw_rate_ms), (None, None));
+        let gimbal = &config.gimbal;
+        assert_eq!((gimbal.lift_min, gimbal.lift_max, gimbal.lift_home, gimbal.max_lift), (-30.0, 30.0, 0.0, None));
+        assert_eq!((gimbal.max_pitch, gimbal.max_roll), (25.0, 15.0));
+        let bench = &config.hardware[0].gimbal;
+        assert_eq!((bench.lift_min, bench.lift_max, bench.max_lift), (-8.0, 8.0, None));
+
+        // Everything the file left out is the default
+        let defaults = Config::default();
+        assert_eq!(config.controls.keyboard_step, defaults.controls.keyboard_step);
+        assert_eq!(config.geometry.actuator_stroke, defaults.geometry.actuator_stroke);
+        assert_eq!(config.gimbal.velocity_rate, defaults.gimbal.velocity_rate);
+        assert_eq!(config.validate(), Ok(()));
+    }
+
+    #[test]
+    fn migrated_config_saves_in_the_new_form_only_once() {
+        let mut config: Config = toml::from_str(UNVERSIONED).unwrap();
+        config.migrate();
+        let saved = toml::to_string(&config).unwrap();
+        let old_keys = ["max_lift =", "tick_rate_ms =", "draw_rate_ms ="];
+        let stale: Vec<&str> = saved.lines().filter(|line| old_keys.iter().any(|key| line.starts_with(key))).collect();
+        assert!(stale.is_empty(), "{:?}", stale);
+
+        let mut reloaded: Config = toml::from_str(&saved).unwrap();
+        assert_eq!(reloaded.version, CONFIG_VERSION);
+        assert_eq!(reloaded.migrate(), None);
+        assert_eq!((reloaded.gimbal.lift_min, reloaded.gimbal.lift_max), (-30.0, 30.0));
+    }
+
+    #[test]
+    fn identity_coupling_is_a_no_op() {
+        let gimbal = GimbalConfig::default();
+        assert!(!gimbal.coupling_enabled());
+        for (pitch, roll) in [(0.0, 0.0), (0.5, -0.25), (-1.0, 1.0)] {
+            assert_eq!(gimbal.couple(pitch, roll), (pitch, roll));
+        }
+    }
+
+    #[test]
+    fn off_diagonal_term_cancels_cross_talk() {
+        // A plate that rolls 0.2 for every unit of pitch it is given
+        let cross_talk = |(pitch, roll): (f64, f64)| (pitch, roll + 0.2 * pitch);
+        let gimbal = GimbalConfig { coupling: [[1.0, 0.0], [-0.2, 1.0]], ..GimbalConfig::default() };
+        assert!(gimbal.coupling_enabled());
+        for (pitch, roll) in [(0.5, 0.0), (-0.8, 0.3), (1.0, -1.0)] {
+            let (actual_pitch, actual_roll) = cross_talk(gimbal.couple(pitch, roll));
+            assert_eq!(actual_pitch, pitch);
+            assert!((actual_roll - roll).abs() < 1e-12, "roll {} came out {}", roll, actual_roll);
+        }
+    }
+
+    #[test]
+    fn cycle_view_is_its_own_action_not_the_debug_toggle() {
+        let actions: HashMap<String, ButtonAction> = toml::from_str(r#"North = "cycle_view""#).unwrap();
+        let action = actions["North"];
+        assert_eq!(action, ButtonAction::CycleView);
+        assert_eq!(action.key_action(), Some(KeyAction::CycleView));
+        assert_eq!(action.name(), "cycle_view");
+    }
+}
@@ -0,0 +1,87 @@
+use crate::gimbal::{GimbalState, InputState};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// Rows are buffered in memory and pushed to disk at most this often
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+pub const DEFAULT_CSV_LOG: &str = "session-{timestamp}.csv";
+
+const HEADER: &str = "timestamp_ms,pitch_axis,roll_axis,lift_axis,keyboard_pitch,keyboard_roll,keyboard_lift,pitch,roll,lift";
+
+// Raw values of the three mapped joystick axes; empty when the axis isn't reporting
+pub struct AxisSample {
+    pub pitch: Option<f32>,
+    pub roll: Option<f32>,
+    pub lift: Option<f32>,
+}
+
+// One row per update tick, for loading a session into a spreadsheet or script
+pub struct CsvLog {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    last_flush: Instant,
+    rows: u64,
+}
+
+impl CsvLog {
+    // `{timestamp}` in the configured path becomes the session start time (Unix seconds)
+    pub fn create(pattern: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = PathBuf::from(pattern.replace("{timestamp}", &(unix_millis() / 1000).to_string()));
+        let mut writer = BufWriter::new(File::create(&path)?);
+        writeln!(writer, "{}", HEADER)?;
+
+        Ok(Self {
+            path,
+            writer,
+            last_flush: Instant::now(),
+            rows: 0,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn rows(&self) -> u64 {
+        self.rows
+    }
+
+    pub fn write_row(&mut self, axes: &AxisSample, input: &InputState, state: &GimbalState) -> std::io::Result<()> {
+        let axis = |value: Option<f32>| value.map(|v| format!("{:.4}", v)).unwrap_or_default();
+        writeln!(
+            self.writer,
+            "{},{},{},{},{:.4},{:.4},{:.4},{:.3},{:.3},{:.3}",
+            unix_millis(),
+            axis(axes.pitch),
+            axis(axes.roll),
+            axis(axes.lift),
+            input.keyboard_pitch,
+            input.keyboard_roll,
+            input.keyboard_lift,
+            state.pitch,
+            state.roll,
+            state.lift,
+        )?;
+        self.rows += 1;
+
+        if self.last_flush.elapsed() >= FLUSH_INTERVAL {
+            self.last_flush = Instant::now();
+            self.writer.flush()?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+pub fn unix_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
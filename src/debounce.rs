@@ -0,0 +1,105 @@
+use std::time::{Duration, Instant};
+
+// Trailing-edge debounce for one gamepad button. A raw press or release only becomes the
+// button's settled state once nothing else has happened to it for the debounce time, and the
+// action fires when the settled state goes down. Contact chatter on either edge restarts the
+// wait instead of firing, so one physical press fires once, after the contacts have settled.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Debouncer {
+    raw: bool,
+    settled: bool,
+    // When `raw` last changed
+    changed: Option<Instant>,
+}
+
+impl Debouncer {
+    // Records the button's raw state at `at`; true when it changed again within the debounce
+    // time of its last change, i.e. the contacts bounced
+    pub fn set(&mut self, down: bool, at: Instant, debounce: Duration) -> bool {
+        if down == self.raw {
+            return false;
+        }
+        let bounced = self.changed.is_some_and(|last| at.saturating_duration_since(last) < debounce);
+        self.raw = down;
+        self.changed = Some(at);
+        bounced
+    }
+
+    // True once per press: the first call at least `debounce` after the button went down and
+    // stayed down. With no debounce that is the first call after the press.
+    pub fn poll(&mut self, now: Instant, debounce: Duration) -> bool {
+        if self.raw == self.settled || self.changed.is_some_and(|last| now.saturating_duration_since(last) < debounce) {
+            return false;
+        }
+        self.settled = self.raw;
+        self.settled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEBOUNCE: Duration = Duration::from_millis(30);
+
+    fn ms(start: Instant, ms: u64) -> Instant {
+        start + Duration::from_millis(ms)
+    }
+
+    // Feeds (time in ms, down) events, polling after each one and every millisecond up to
+    // `until`, as the update loop would; returns how many times the action fired
+    fn presses(events: &[(u64, bool)], until: u64, debounce: Duration) -> usize {
+        let start = Instant::now();
+        let mut button = Debouncer::default();
+        let mut events = events.iter().peekable();
+        let mut fired = 0;
+        for t in 0..=until {
+            while let Some(&(_, down)) = events.next_if(|(at, _)| *at == t) {
+                button.set(down, ms(start, t), debounce);
+                fired += button.poll(ms(start, t), debounce) as usize;
+            }
+            fired += button.poll(ms(start, t), debounce) as usize;
+        }
+        fired
+    }
+
+    #[test]
+    fn bouncing_press_fires_exactly_once() {
+        // Chatter going down, a held press, chatter coming back up
+        let events = [(0, true), (2, false), (3, true), (5, false), (6, true), (200, false), (201, true), (203, false)];
+        assert_eq!(presses(&events, 400, DEBOUNCE), 1);
+    }
+
+    #[test]
+    fn press_fires_once_it_has_held_for_the_debounce_time() {
+        let start = Instant::now();
+        let mut button = Debouncer::default();
+        button.set(true, start, DEBOUNCE);
+        assert!(!button.poll(ms(start, 29), DEBOUNCE));
+        assert!(button.poll(ms(start, 30), DEBOUNCE));
+        assert!(!button.poll(ms(start, 100), DEBOUNCE));
+    }
+
+    #[test]
+    fn bounce_restarts_the_wait() {
+        let start = Instant::now();
+        let mut button = Debouncer::default();
+        assert!(!button.set(true, start, DEBOUNCE));
+        assert!(button.set(false, ms(start, 10), DEBOUNCE));
+        assert!(button.set(true, ms(start, 12), DEBOUNCE));
+        assert!(!button.poll(ms(start, 40), DEBOUNCE));
+        assert!(button.poll(ms(start, 42), DEBOUNCE));
+    }
+
+    #[test]
+    fn separate_presses_each_fire() {
+        let events = [(0, true), (100, false), (200, true), (300, false)];
+        assert_eq!(presses(&events, 400, DEBOUNCE), 2);
+    }
+
+    #[test]
+    fn without_debounce_every_press_fires_at_once() {
+        let events = [(0, true), (1, false), (2, true), (3, false)];
+        assert_eq!(presses(&events, 10, Duration::ZERO), 2);
+    }
+}
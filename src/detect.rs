@@ -0,0 +1,191 @@
+use crate::input_source::PadId;
+use gilrs::Axis;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// How long each channel listens once the control starts moving
+const LISTEN_DURATION: Duration = Duration::from_secs(2);
+// Distance from rest that counts as the user moving a control, and jitter ignored when summing
+const MOVE_THRESHOLD: f64 = 0.3;
+const NOISE_FLOOR: f64 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetectChannel {
+    Pitch,
+    Roll,
+    Lift,
+}
+
+impl DetectChannel {
+    pub const ALL: [DetectChannel; 3] = [DetectChannel::Pitch, DetectChannel::Roll, DetectChannel::Lift];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DetectChannel::Pitch => "PITCH",
+            DetectChannel::Roll => "ROLL",
+            DetectChannel::Lift => "LIFT",
+        }
+    }
+
+    // The direction the user is asked to move first; it becomes the positive direction
+    fn direction(self) -> &'static str {
+        match self {
+            DetectChannel::Pitch => "forward/up",
+            DetectChannel::Roll => "right",
+            DetectChannel::Lift => "up",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetectStep {
+    Waiting,   // Rest values recorded, waiting for a control to move
+    Listening, // Summing each axis' excursion from rest
+    Review,    // All channels assigned, waiting for save or cancel
+}
+
+// An axis picked for a channel, and whether its first movement went negative
+#[derive(Debug, Clone, Copy)]
+pub struct Detected {
+    pub axis: Axis,
+    pub invert: bool,
+}
+
+pub struct AxisDetector {
+    pub gamepad_id: PadId,
+    pub gamepad_name: String,
+    pub message: Option<String>,
+    step: DetectStep,
+    channel: usize,
+    step_started: Instant,
+    // Value of each axis when the channel started; noisy axes resting off zero are measured from here
+    rest: HashMap<Axis, f64>,
+    excursions: HashMap<Axis, f64>,
+    first_sign: HashMap<Axis, f64>,
+    results: Vec<(DetectChannel, Detected)>,
+}
+
+impl AxisDetector {
+    pub fn new(gamepad_id: PadId, gamepad_name: String) -> Self {
+        Self {
+            gamepad_id,
+            gamepad_name,
+            message: None,
+            step: DetectStep::Waiting,
+            channel: 0,
+            step_started: Instant::now(),
+            rest: HashMap::new(),
+            excursions: HashMap::new(),
+            first_sign: HashMap::new(),
+            results: Vec::new(),
+        }
+    }
+
+    pub fn step(&self) -> DetectStep {
+        self.step
+    }
+
+    pub fn results(&self) -> &[(DetectChannel, Detected)] {
+        &self.results
+    }
+
+    fn current(&self) -> Option<DetectChannel> {
+        DetectChannel::ALL.get(self.channel).copied()
+    }
+
+    pub fn prompt(&self) -> String {
+        match (self.step, self.current()) {
+            (DetectStep::Waiting, Some(channel)) => format!(
+                "Move the control for {} - {} first, then back and forth",
+                channel.name(),
+                channel.direction()
+            ),
+            (DetectStep::Listening, Some(channel)) => format!(
+                "Keep moving the control for {} ({:.1}s)",
+                channel.name(),
+                LISTEN_DURATION.saturating_sub(self.step_started.elapsed()).as_secs_f64()
+            ),
+            _ => "Done - Enter to save, Backspace to redo the last channel, Esc to discard".to_string(),
+        }
+    }
+
+    pub fn sample(&mut self, axes: &HashMap<Axis, f32>) {
+        // Axes already assigned to an earlier channel can't be picked again
+        let taken: Vec<Axis> = self.results.iter().map(|(_, detected)| detected.axis).collect();
+        match self.step {
+            DetectStep::Waiting => {
+                for (&axis, &value) in axes {
+                    self.rest.entry(axis).or_insert(value as f64);
+                }
+                let moved = axes.iter().any(|(axis, &value)| {
+                    !taken.contains(axis) && (value as f64 - self.rest[axis]).abs() >= MOVE_THRESHOLD
+                });
+                if moved {
+                    self.step = DetectStep::Listening;
+                    self.step_started = Instant::now();
+                    self.accumulate(axes, &taken);
+                }
+            }
+            DetectStep::Listening => {
+                self.accumulate(axes, &taken);
+                if self.step_started.elapsed() >= LISTEN_DURATION {
+                    self.finish_channel();
+                }
+            }
+            DetectStep::Review => {}
+        }
+    }
+
+    fn accumulate(&mut self, axes: &HashMap<Axis, f32>, taken: &[Axis]) {
+        for (&axis, &value) in axes {
+            if taken.contains(&axis) {
+                continue;
+            }
+            let rest = *self.rest.entry(axis).or_insert(value as f64);
+            let offset = value as f64 - rest;
+            if offset.abs() < NOISE_FLOOR {
+                continue;
+            }
+            *self.excursions.entry(axis).or_insert(0.0) += offset.abs();
+            if offset.abs() >= MOVE_THRESHOLD {
+                self.first_sign.entry(axis).or_insert(offset.signum());
+            }
+        }
+    }
+
+    fn finish_channel(&mut self) {
+        let best = self
+            .excursions
+            .iter()
+            .filter(|(axis, _)| self.first_sign.contains_key(axis))
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(&axis, _)| axis);
+        match (best, self.current()) {
+            (Some(axis), Some(channel)) => {
+                let invert = self.first_sign[&axis] < 0.0;
+                self.results.push((channel, Detected { axis, invert }));
+                self.channel += 1;
+                self.message = None;
+            }
+            _ => self.message = Some("No control moved far enough - try again".to_string()),
+        }
+        self.restart_channel();
+    }
+
+    fn restart_channel(&mut self) {
+        self.rest.clear();
+        self.excursions.clear();
+        self.first_sign.clear();
+        self.step_started = Instant::now();
+        self.step = if self.current().is_some() { DetectStep::Waiting } else { DetectStep::Review };
+    }
+
+    // Drops the last assignment (or the one being listened for) and listens for it again
+    pub fn redo(&mut self) {
+        if self.step != DetectStep::Listening && self.results.pop().is_some() {
+            self.channel -= 1;
+        }
+        self.message = None;
+        self.restart_channel();
+    }
+}
@@ -0,0 +1,28 @@
+// Wireless pads sometimes lose a report and the axis reads exactly 0.0 for a step or two
+// before snapping back. One guard per axis holds the last value across such a gap.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DropoutGuard {
+    // Last value passed through that wasn't part of a gap
+    last: f64,
+    // Consecutive exact zeros being held right now
+    zeros: u32,
+    // Gaps that ended with the axis coming back, i.e. ones that were held for good reason
+    pub count: u64,
+}
+
+impl DropoutGuard {
+    // A zero straight after a value beyond `threshold` is held for up to `frames` steps. If the
+    // axis is still at zero after that it really went to center, and the zero passes through.
+    pub fn pass(&mut self, value: f64, frames: u32, threshold: f64) -> f64 {
+        if value == 0.0 && self.last.abs() > threshold && self.zeros < frames {
+            self.zeros += 1;
+            return self.last;
+        }
+        if value != 0.0 && self.zeros > 0 {
+            self.count += 1;
+        }
+        self.zeros = 0;
+        self.last = value;
+        value
+    }
+}
@@ -0,0 +1,184 @@
+use crate::config::{DynamicsConfig, GeometryConfig};
+use crate::gimbal::GimbalState;
+use crate::kinematics;
+use std::f64::consts::TAU;
+
+// Longest integration step; larger frame times are split so the spring stays stable
+const MAX_STEP: f64 = 0.005;
+// Frame times beyond this (e.g. after a stall) are dropped rather than simulated
+const MAX_DT: f64 = 0.25;
+// Refinements of a leg speed cut; each one shrinks the overshoot by orders of magnitude
+const LEG_SPEED_ITERATIONS: usize = 4;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Channel {
+    position: f64,
+    velocity: f64,
+}
+
+impl Channel {
+    // Critically damped spring toward `target`, with the drive's velocity and acceleration limits
+    fn step(&mut self, target: f64, omega: f64, max_velocity: f64, max_acceleration: f64, dt: f64) {
+        let acceleration = (omega * omega * (target - self.position) - 2.0 * omega * self.velocity)
+            .clamp(-max_acceleration, max_acceleration);
+        self.velocity = (self.velocity + acceleration * dt).clamp(-max_velocity, max_velocity);
+        self.position += self.velocity * dt;
+    }
+}
+
+/// Where the physical plate would be while it chases the commanded GimbalState
+#[derive(Debug, Clone, Default)]
+pub struct SimulatedState {
+    pitch: Channel,
+    roll: Channel,
+    lift: Channel,
+    yaw: Channel,
+    // Some leg hit max_leg_speed during the last update
+    leg_limited: bool,
+}
+
+impl SimulatedState {
+    /// Moves `dt` seconds further toward `target`, within the drive limits in `config`
+    pub fn update(&mut self, config: &DynamicsConfig, geometry: &GeometryConfig, target: &GimbalState, dt: f64) {
+        let omega = TAU * config.response_hz;
+        let mut remaining = dt.clamp(0.0, MAX_DT);
+        self.leg_limited = false;
+        while remaining > 0.0 {
+            let step = remaining.min(MAX_STEP);
+            let before = self.clone();
+            let (angular_v, angular_a) = (config.max_angular_velocity, config.max_angular_acceleration);
+            self.pitch.step(target.pitch, omega, angular_v, angular_a, step);
+            self.roll.step(target.roll, omega, angular_v, angular_a, step);
+            self.yaw.step(target.yaw, omega, angular_v, angular_a, step);
+            self.lift.step(target.lift, omega, config.max_lift_velocity, config.max_lift_acceleration, step);
+            if config.max_leg_speed > 0.0 {
+                self.limit_leg_speed(&before, config.max_leg_speed, geometry, target, step);
+            }
+            remaining -= step;
+        }
+    }
+
+    // The channel limits above are independent, but the plate only moves as fast as its
+    // slowest leg allows: a step that would take any leg past `max_speed` is shortened as a
+    // whole, so the move keeps its direction and just takes longer
+    fn limit_leg_speed(&mut self, before: &SimulatedState, max_speed: f64, geometry: &GeometryConfig, target: &GimbalState, step: f64) {
+        let legs_before = kinematics::solve(geometry, &before.pose(target));
+        let fastest = |state: &SimulatedState| {
+            legs_before
+                .iter()
+                .zip(&kinematics::solve(geometry, &state.pose(target)))
+                .map(|(from, to)| (to.length() - from.length()).abs() / step)
+                .fold(0.0, f64::max)
+        };
+        let mut speed = fastest(self);
+        if speed <= max_speed {
+            return;
+        }
+        // Leg lengths aren't linear in the angles, so the proportional cut is refined a few times
+        let commanded = self.clone();
+        let mut scale = 1.0;
+        for _ in 0..LEG_SPEED_ITERATIONS {
+            scale *= max_speed / speed;
+            *self = commanded.scaled_from(before, scale);
+            speed = fastest(self);
+        }
+        self.leg_limited = true;
+    }
+
+    // This step shortened by `scale` from `before`, velocities included
+    fn scaled_from(&self, before: &SimulatedState, scale: f64) -> SimulatedState {
+        let channel = |to: Channel, from: Channel| Channel {
+            position: from.position + (to.position - from.position) * scale,
+            velocity: to.velocity * scale,
+        };
+        SimulatedState {
+            pitch: channel(self.pitch, before.pitch),
+            roll: channel(self.roll, before.roll),
+            lift: channel(self.lift, before.lift),
+            yaw: channel(self.yaw, before.yaw),
+            leg_limited: self.leg_limited,
+        }
+    }
+
+    /// Some leg hit max_leg_speed during the last update
+    pub fn leg_limited(&self) -> bool {
+        self.leg_limited
+    }
+
+    /// The commanded state with the simulated pose swapped in, for drawing
+    pub fn pose(&self, commanded: &GimbalState) -> GimbalState {
+        GimbalState {
+            pitch: self.pitch.position,
+            roll: self.roll.position,
+            lift: self.lift.position,
+            yaw: self.yaw.position,
+            ..commanded.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Velocities are arbitrary, only there to check they are scaled with the positions
+    fn at(pitch: f64, roll: f64, lift: f64) -> SimulatedState {
+        let channel = |position| Channel { position, velocity: position * 10.0 };
+        SimulatedState { pitch: channel(pitch), roll: channel(roll), lift: channel(lift), ..SimulatedState::default() }
+    }
+
+    // Fastest leg length change between two simulated poses, in mm
+    fn fastest_leg(geometry: &GeometryConfig, from: &SimulatedState, to: &SimulatedState) -> f64 {
+        let target = GimbalState::default();
+        let legs_from = kinematics::solve(geometry, &from.pose(&target));
+        let legs_to = kinematics::solve(geometry, &to.pose(&target));
+        legs_from.iter().zip(&legs_to).map(|(a, b)| (b.length() - a.length()).abs()).fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn large_step_is_cut_to_the_leg_speed_along_its_direction() {
+        let geometry = GeometryConfig::default();
+        let (max_speed, step) = (20.0, MAX_STEP);
+        let before = SimulatedState::default();
+        let mut state = at(10.0, -5.0, 8.0);
+        assert!(fastest_leg(&geometry, &before, &state) > max_speed * step);
+
+        state.limit_leg_speed(&before, max_speed, &geometry, &GimbalState::default(), step);
+        assert!(state.leg_limited());
+        assert!((fastest_leg(&geometry, &before, &state) - max_speed * step).abs() < 1e-9);
+
+        // Every channel shrinks by the same factor, velocities included
+        let scale = state.pitch.position / 10.0;
+        assert!(scale > 0.0 && scale < 1.0, "scale {}", scale);
+        assert!((state.roll.position - -5.0 * scale).abs() < 1e-12);
+        assert!((state.lift.position - 8.0 * scale).abs() < 1e-12);
+        assert!((state.pitch.velocity - 100.0 * scale).abs() < 1e-9);
+    }
+
+    #[test]
+    fn step_within_the_leg_speed_is_left_alone() {
+        let geometry = GeometryConfig::default();
+        let before = SimulatedState::default();
+        let mut state = at(0.0, 0.0, 0.05);
+        state.limit_leg_speed(&before, 20.0, &geometry, &GimbalState::default(), MAX_STEP);
+        assert!(!state.leg_limited());
+        assert_eq!(state.lift.position, 0.05);
+    }
+
+    #[test]
+    fn simulated_legs_never_outrun_max_leg_speed() {
+        let geometry = GeometryConfig::default();
+        let config = DynamicsConfig { max_leg_speed: 15.0, ..DynamicsConfig::default() };
+        let target = GimbalState { pitch: 20.0, roll: 10.0, lift: 15.0, ..GimbalState::default() };
+        let mut state = SimulatedState::default();
+        let mut limited = false;
+        for _ in 0..200 {
+            let before = state.clone();
+            state.update(&config, &geometry, &target, MAX_STEP);
+            limited |= state.leg_limited();
+            assert!(fastest_leg(&geometry, &before, &state) <= config.max_leg_speed * MAX_STEP + 1e-9);
+        }
+        assert!(limited);
+        assert!(state.pitch.position > 0.0 && state.lift.position > 0.0);
+    }
+}
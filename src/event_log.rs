@@ -0,0 +1,192 @@
+use crate::input_source::{InputEvent, PadEvent, PadId};
+use std::collections::{HashMap, VecDeque};
+
+// Events kept for the debug event pane; older ones are dropped
+const CAPACITY: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Button,
+    Axis,
+    Connection,
+    // Dropped, force feedback and anything newer gilrs versions add
+    Other,
+}
+
+impl EventKind {
+    const ALL: [EventKind; 4] = [EventKind::Button, EventKind::Axis, EventKind::Connection, EventKind::Other];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EventKind::Button => "buttons",
+            EventKind::Axis => "axes",
+            EventKind::Connection => "connection",
+            EventKind::Other => "other",
+        }
+    }
+}
+
+pub struct EventRecord {
+    // Seconds since startup, when the source saw the event
+    pub at: f64,
+    pub gamepad: PadId,
+    pub name: String,
+    pub kind: EventKind,
+    // Event type, element and raw code, e.g. "AxisChanged LeftStickX (3)"
+    pub description: String,
+    pub value: Option<f32>,
+}
+
+impl EventRecord {
+    pub fn new(at: f64, input: &InputEvent) -> Self {
+        // The virtual pad has no driver, so no raw code
+        let code = input.code.as_ref().map(|code| format!(" ({})", code)).unwrap_or_default();
+        let (kind, description, value) = match &input.event {
+            PadEvent::ButtonPressed(button) => (EventKind::Button, format!("ButtonPressed {:?}{}", button, code), None),
+            PadEvent::ButtonRepeated(button) => (EventKind::Button, format!("ButtonRepeated {:?}{}", button, code), None),
+            PadEvent::ButtonReleased(button) => (EventKind::Button, format!("ButtonReleased {:?}{}", button, code), None),
+            PadEvent::ButtonChanged(button, value) => (EventKind::Button, format!("ButtonChanged {:?}{}", button, code), Some(*value)),
+            PadEvent::AxisChanged(axis, value) => (EventKind::Axis, format!("AxisChanged {:?}{}", axis, code), Some(*value)),
+            PadEvent::Connected | PadEvent::Disconnected => (EventKind::Connection, format!("{:?}", input.event), None),
+            PadEvent::Other(text) => (EventKind::Other, text.clone(), None),
+        };
+        Self {
+            at,
+            gamepad: input.pad,
+            name: input.name.clone(),
+            kind,
+            description,
+            value,
+        }
+    }
+
+    pub fn line(&self) -> String {
+        let value = self.value.map(|v| format!(" {:+.4}", v)).unwrap_or_default();
+        format!(
+            "[{:>8.3}s] #{} {} {}{}",
+            self.at,
+            usize::from(self.gamepad),
+            self.name,
+            self.description,
+            value
+        )
+    }
+}
+
+// The raw gilrs event stream for diagnosing flaky controllers. Pausing freezes the list
+// (new events are counted, not stored, so nothing being read scrolls away) and the view
+// can be narrowed to one gamepad or one kind of event.
+pub struct EventLog {
+    events: VecDeque<EventRecord>,
+    // Last value kept for each gamepad's axis (keyed by description, which names the axis and code)
+    axis_values: HashMap<(PadId, String), f32>,
+    // Axis changes too small to keep; see push
+    pub hidden: u64,
+    pub paused: bool,
+    pub skipped: u64,
+    // Matching events hidden below the view; 0 follows the newest
+    scroll: usize,
+    pub gamepad_filter: Option<PadId>,
+    pub kind_filter: Option<EventKind>,
+}
+
+impl EventLog {
+    pub fn new() -> Self {
+        Self {
+            events: VecDeque::new(),
+            axis_values: HashMap::new(),
+            hidden: 0,
+            paused: false,
+            skipped: 0,
+            scroll: 0,
+            gamepad_filter: None,
+            kind_filter: None,
+        }
+    }
+
+    // An axis change is only kept once the axis has moved `axis_threshold` from the last value
+    // kept for it, so a resting stick's noise doesn't bury everything else. A change to exactly
+    // 0.0 is always kept, since that is what a dropout looks like.
+    pub fn push(&mut self, record: EventRecord, axis_threshold: f32) {
+        if record.kind == EventKind::Axis
+            && let Some(value) = record.value
+        {
+            let last = self.axis_values.entry((record.gamepad, record.description.clone())).or_insert(f32::NAN);
+            if value != 0.0 && (value - *last).abs() < axis_threshold {
+                self.hidden += 1;
+                return;
+            }
+            *last = value;
+        }
+        if self.paused {
+            self.skipped += 1;
+            return;
+        }
+        if self.events.len() == CAPACITY {
+            self.events.pop_front();
+        }
+        // Keep a scrolled-back view on the same events as new ones arrive below it
+        if self.scroll > 0 && self.matches(&record) {
+            self.scroll += 1;
+        }
+        self.events.push_back(record);
+    }
+
+    fn matches(&self, record: &EventRecord) -> bool {
+        self.gamepad_filter.is_none_or(|id| record.gamepad == id) && self.kind_filter.is_none_or(|kind| record.kind == kind)
+    }
+
+    pub fn filtered(&self) -> impl Iterator<Item = &EventRecord> {
+        self.events.iter().filter(|record| self.matches(record))
+    }
+
+    // Up to `rows` matching events ending `scroll` entries before the newest, oldest first
+    pub fn visible(&self, rows: usize) -> Vec<&EventRecord> {
+        let matching: Vec<_> = self.filtered().collect();
+        let scroll = self.scroll.min(matching.len().saturating_sub(rows));
+        let end = matching.len() - scroll;
+        matching[end.saturating_sub(rows)..end].to_vec()
+    }
+
+    pub fn scroll(&self) -> usize {
+        self.scroll
+    }
+
+    // Positive moves back in time, stopping when the oldest event reaches the top of `rows`
+    pub fn scroll_by(&mut self, lines: isize, rows: usize) {
+        let max = self.filtered().count().saturating_sub(rows);
+        self.scroll = self.scroll.min(max).saturating_add_signed(lines).min(max);
+    }
+
+    pub fn scroll_to_oldest(&mut self, rows: usize) {
+        self.scroll = self.filtered().count().saturating_sub(rows);
+    }
+
+    pub fn scroll_to_newest(&mut self) {
+        self.scroll = 0;
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.skipped = 0;
+    }
+
+    // All gamepads, then each of `ids` in turn
+    pub fn cycle_gamepad(&mut self, ids: &[PadId]) {
+        let next = match self.gamepad_filter.and_then(|id| ids.iter().position(|&other| other == id)) {
+            None => ids.first().copied(),
+            Some(index) => ids.get(index + 1).copied(),
+        };
+        self.gamepad_filter = next;
+        self.scroll = 0;
+    }
+
+    // All kinds, then each kind in turn
+    pub fn cycle_kind(&mut self) {
+        self.kind_filter = match self.kind_filter {
+            None => Some(EventKind::ALL[0]),
+            Some(kind) => EventKind::ALL.iter().position(|&k| k == kind).and_then(|i| EventKind::ALL.get(i + 1)).copied(),
+        };
+        self.scroll = 0;
+    }
+}
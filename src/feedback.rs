@@ -0,0 +1,125 @@
+use crate::config::FeedbackConfig;
+use crate::gimbal::GimbalState;
+use serde::Deserialize;
+use std::net::{SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+// A fragment longer than any sane pose line is junk, not the start of one
+const MAX_PARTIAL: usize = 256;
+
+#[derive(Deserialize)]
+struct JsonPose {
+    pitch: f64,
+    roll: f64,
+    lift: f64,
+    #[serde(default)]
+    yaw: f64,
+}
+
+// "pitch roll lift [yaw]", split on spaces or commas, or {"pitch":..,"roll":..,"lift":..,"yaw":..}
+fn parse_pose(line: &str) -> Result<GimbalState, String> {
+    let (pitch, roll, lift, yaw) = if line.starts_with('{') {
+        let pose: JsonPose = serde_json::from_str(line).map_err(|e| e.to_string())?;
+        (pose.pitch, pose.roll, pose.lift, pose.yaw)
+    } else {
+        let values = line
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|part| !part.is_empty())
+            .map(|part| part.parse::<f64>().map_err(|_| format!("'{}' is not a number", part)))
+            .collect::<Result<Vec<_>, _>>()?;
+        match values[..] {
+            [pitch, roll, lift] => (pitch, roll, lift, 0.0),
+            [pitch, roll, lift, yaw] => (pitch, roll, lift, yaw),
+            _ => return Err(format!("expected 3 or 4 values, got {}", values.len())),
+        }
+    };
+    if ![pitch, roll, lift, yaw].iter().all(|v| v.is_finite()) {
+        return Err("values must be finite".to_string());
+    }
+    Ok(GimbalState { pitch, roll, lift, yaw, ..GimbalState::default() })
+}
+
+// Measured pose reported back by the hardware over UDP, one line per pose. Lines may be
+// split across datagrams: a trailing piece that doesn't parse yet is kept for the next one.
+pub struct FeedbackInput {
+    socket: UdpSocket,
+    partial: String,
+    latest: Option<(GimbalState, Instant)>,
+    pub poses: u64,
+    pub errors: u64,
+}
+
+impl FeedbackInput {
+    pub fn open(config: &FeedbackConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let socket = UdpSocket::bind(config.address.as_str())?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket,
+            partial: String::new(),
+            latest: None,
+            poses: 0,
+            errors: 0,
+        })
+    }
+
+    pub fn address(&self) -> Option<SocketAddr> {
+        self.socket.local_addr().ok()
+    }
+
+    // Reads every datagram waiting and returns a message for each line that didn't parse
+    pub fn poll(&mut self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let mut buffer = [0u8; 2048];
+        while let Ok((length, _)) = self.socket.recv_from(&mut buffer) {
+            self.partial.push_str(&String::from_utf8_lossy(&buffer[..length]));
+            while let Some(end) = self.partial.find('\n') {
+                let line: String = self.partial.drain(..=end).collect();
+                self.accept(line.trim(), &mut problems);
+            }
+            // Senders that send one pose per datagram often leave off the newline
+            let rest = self.partial.trim();
+            if !rest.is_empty() && let Ok(pose) = parse_pose(rest) {
+                self.partial.clear();
+                self.store(pose);
+            } else if self.partial.len() > MAX_PARTIAL {
+                problems.push(format!("dropped {} bytes without a line end", self.partial.len()));
+                self.errors += 1;
+                self.partial.clear();
+            }
+        }
+        problems
+    }
+
+    fn accept(&mut self, line: &str, problems: &mut Vec<String>) {
+        if line.is_empty() {
+            return;
+        }
+        match parse_pose(line) {
+            Ok(pose) => self.store(pose),
+            Err(e) => {
+                problems.push(format!("'{}': {}", line, e));
+                self.errors += 1;
+            }
+        }
+    }
+
+    fn store(&mut self, pose: GimbalState) {
+        self.latest = Some((pose, Instant::now()));
+        self.poses += 1;
+    }
+
+    // The last pose received, unless it is older than `timeout`
+    pub fn latest(&self, timeout: Duration) -> Option<&GimbalState> {
+        self.latest.as_ref().filter(|(_, at)| at.elapsed() <= timeout).map(|(pose, _)| pose)
+    }
+}
+
+// Commanded minus measured for each channel, in degrees (mm for lift)
+pub fn pose_error(commanded: &GimbalState, measured: &GimbalState) -> [(&'static str, f64); 4] {
+    [
+        ("Pitch", commanded.pitch - measured.pitch),
+        ("Roll", commanded.roll - measured.roll),
+        ("Lift", commanded.lift - measured.lift),
+        ("Yaw", commanded.yaw - measured.yaw),
+    ]
+}
@@ -1,12 +1,41 @@
-use crate::config::{Config, parse_axis_name};
+use crate::config::{
+    AxisMode, AxisSource, ChannelMode, Config, EnvelopeMode, EstopBehavior, InputMixing, KeyAction, KeyboardMode, LiftControl, name_matches,
+    parse_axis_name,
+};
+use crate::dropout::DropoutGuard;
+use crate::kinematics;
 use gilrs::{Axis, Button};
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tracing::{debug, debug_span};
 
-#[derive(Debug, Clone)]
+// Rate at which `smoothing` is defined; other frame rates scale the filter to match
+const SMOOTHING_REFERENCE_HZ: f64 = 60.0;
+// Longest gap treated as one step, so a stall doesn't snap the filter or jump velocity channels
+const MAX_DT: f64 = 0.25;
+// Presses closer together than this are terminal key repeat, i.e. the key is being held
+const KEY_REPEAT_GAP: Duration = Duration::from_millis(120);
+// Homing is done once every channel is this close to the home pose (degrees or mm)
+const HOME_EPSILON: f64 = 0.01;
+// Mixed input below which a position channel counts as centered and drops a modifier blend
+const MODIFIER_CENTER: f64 = 0.01;
+// Stick deflection (axis units) below which require_center_on_start counts the stick as centered
+const START_CENTER: f64 = 0.05;
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct GimbalState {
-    pub pitch: f64,  // Forward/back tilt in degrees
-    pub roll: f64,   // Left/right tilt in degrees
-    pub lift: f64,   // Up/down movement in mm
+    /// Forward/back tilt in degrees
+    pub pitch: f64,
+    /// Left/right tilt in degrees
+    pub roll: f64,
+    /// Up/down movement in mm
+    pub lift: f64,
+    /// Rotation about the vertical axis in degrees
+    pub yaw: f64,
+    /// The commanded pose was outside the actuator envelope and has been pulled back
+    pub envelope_limited: bool,
+    /// Actuators that were past their stroke for the commanded pose
+    pub limiting_actuators: Vec<usize>,
 }
 
 impl Default for GimbalState {
@@ -15,136 +44,1408 @@ impl Default for GimbalState {
             pitch: 0.0,
             roll: 0.0,
             lift: 0.0,
+            yaw: 0.0,
+            envelope_limited: false,
+            limiting_actuators: Vec::new(),
         }
     }
 }
 
+/// Armed follows input. Stopped ignores all input and holds or ramps the output to neutral
+/// (safety.estop_behavior) until explicitly re-armed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArmState {
+    Armed,
+    Stopped,
+}
+
+/// Input easing in after launch, reset or re-arm (`[safety]` soft_start_secs)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SoftStart {
+    /// require_center_on_start: input is ignored until the stick has been centered
+    AwaitingCenter,
+    /// Seconds into the ramp; input is scaled by the fraction of soft_start_secs done
+    Ramping(f64),
+    Done,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct HeldKey {
+    last_event: Instant,
+    repeating: bool,
+}
+
 #[derive(Debug)]
 pub struct InputState {
+    /// Latest value of each axis from whichever gamepad reported it last
     pub axes: HashMap<Axis, f32>,
+    /// The same per connected gamepad, by name, for channels bound to a device
+    pub devices: HashMap<String, HashMap<Axis, f32>>,
     pub buttons: HashMap<Button, bool>,
     pub keyboard_pitch: f64,
     pub keyboard_roll: f64,
     pub keyboard_lift: f64,
+    pub keyboard_yaw: f64,
+    /// Mouse drag/scroll contributions, ±1.0 like the keyboard values
+    pub mouse_pitch: f64,
+    pub mouse_roll: f64,
+    pub mouse_lift: f64,
+    /// Movement keys seen recently, used to integrate held keys in rate mode
+    pub held_keys: HashMap<KeyAction, HeldKey>,
+    /// Movement keys down in direct mode, oldest press first
+    pub pressed_keys: Vec<KeyAction>,
+    /// Name of the gamepad that produced the latest input, used to look up its calibration
+    pub active_gamepad: Option<String>,
 }
 
 impl Default for InputState {
     fn default() -> Self {
         Self {
             axes: HashMap::new(),
+            devices: HashMap::new(),
             buttons: HashMap::new(),
             keyboard_pitch: 0.0,
             keyboard_roll: 0.0,
             keyboard_lift: 0.0,
+            keyboard_yaw: 0.0,
+            mouse_pitch: 0.0,
+            mouse_roll: 0.0,
+            mouse_lift: 0.0,
+            held_keys: HashMap::new(),
+            pressed_keys: Vec::new(),
+            active_gamepad: None,
         }
     }
 }
 
+// Fixtures for tests: InputState::default().with_axis(Axis::RightStickY, 0.5).with_keyboard(0.2, 0.0, 0.0)
+#[cfg(test)]
+impl InputState {
+    pub fn with_axis(mut self, axis: Axis, value: f32) -> Self {
+        self.axes.insert(axis, value);
+        self
+    }
+
+    pub fn with_keyboard(mut self, pitch: f64, roll: f64, lift: f64) -> Self {
+        (self.keyboard_pitch, self.keyboard_roll, self.keyboard_lift) = (pitch, roll, lift);
+        self
+    }
+}
+
+/// Intermediate values for one channel from the last update, for the debug view
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChannelPipeline {
+    /// Axis value as reported by the gamepad, if mapped
+    pub raw: Option<f64>,
+    /// After calibration, smoothing, trim and inversion, plus extra sources
+    pub joystick: f64,
+    /// Part of `joystick` from the channel's extra sources
+    pub sources: f64,
+    pub keyboard: f64,
+    pub mouse: f64,
+    /// Joystick + keyboard + mouse times sensitivity
+    pub scaled: f64,
+    /// Scaled after the pitch/roll coupling matrix; lift and yaw pass through
+    pub coupled: f64,
+    /// State the input asks for, before the channel limit and envelope
+    pub unclamped: f64,
+    /// Final clamped state value
+    pub output: f64,
+    /// Input asked for more than the channel's max
+    pub saturated: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InputPipeline {
+    pub pitch: ChannelPipeline,
+    pub roll: ChannelPipeline,
+    pub lift: ChannelPipeline,
+    pub yaw: ChannelPipeline,
+}
+
+impl InputPipeline {
+    pub fn any_saturated(&self) -> bool {
+        [self.pitch, self.roll, self.lift, self.yaw].iter().any(|channel| channel.saturated)
+    }
+}
+
 pub struct GimbalController {
     config: Config,
     state: GimbalState,
+    filtered_axes: HashMap<Axis, f64>,
+    // Smoothed axes of each gamepad in InputState::devices
+    filtered_devices: HashMap<String, HashMap<Axis, f64>>,
+    // Dropout holds for the merged axes and for each gamepad's own, like the filtered values
+    dropouts: HashMap<Axis, DropoutGuard>,
+    device_dropouts: HashMap<String, HashMap<Axis, DropoutGuard>>,
+    pipeline: InputPipeline,
+    arm_state: ArmState,
+    // Moving to the center pose; input is ignored until it gets there
+    homing: bool,
+    // Pose typed in command mode; replaces home as the center until home, reset or e-stop
+    setpoint: Option<(f64, f64, f64, f64)>,
+    // Sensitivity multiplier from the held [controls.modifiers] buttons
+    modifier: f64,
+    // Pitch, roll, lift and yaw, for position channels caught off center by a modifier change
+    modifier_blends: [ModifierBlend; 4],
+    soft_start: SoftStart,
+    // Absolute lift: where keyboard and mouse have moved the plate from the lever's position (mm)
+    lift_nudge: f64,
+}
+
+// With the built-in defaults, as when no config file exists yet
+impl Default for GimbalController {
+    fn default() -> Self {
+        Self::new(Config::default())
+    }
+}
+
+// For tests: input reaches the state on the first update, with no soft start ramp and no
+// dropout hold keeping an axis that returns to zero at its old value
+#[cfg(test)]
+impl GimbalController {
+    pub fn for_test(mut config: Config) -> Self {
+        config.controls.dropout_frames = 0;
+        config.safety.require_center_on_start = false;
+        let mut controller = Self::new(config);
+        controller.soft_start = SoftStart::Done;
+        controller
+    }
 }
 
 impl GimbalController {
     pub fn new(config: Config) -> Self {
         Self {
+            state: rest_state(&config),
+            soft_start: soft_start(&config),
             config,
-            state: GimbalState::default(),
+            filtered_axes: HashMap::new(),
+            filtered_devices: HashMap::new(),
+            dropouts: HashMap::new(),
+            device_dropouts: HashMap::new(),
+            pipeline: InputPipeline::default(),
+            arm_state: ArmState::Armed,
+            homing: false,
+            setpoint: None,
+            modifier: 1.0,
+            modifier_blends: [ModifierBlend::default(); 4],
+            lift_nudge: 0.0,
         }
     }
 
-    pub fn update(&mut self, input: &InputState) {
-        let mut pitch = 0.0;
-        let mut roll = 0.0;
-        let mut lift = 0.0;
+    pub fn absolute_lift(&self) -> bool {
+        let joystick = &self.config.controls.joystick;
+        joystick.enabled && joystick.lift_control == LiftControl::Absolute
+    }
+
+    // Absolute lift: the lever's position is a point between lift_min and lift_max, and keyboard
+    // and mouse move the plate from there at the velocity rate rather than adding to the lever.
+    // The nudge is kept within the range, so the plate answers as soon as it is pushed back.
+    fn lever_lift(&mut self, channel: &mut ChannelPipeline, (min, max): (f64, f64), rate: f64, ramp: f64, home: f64) -> f64 {
+        let half_span = (max - min) / 2.0;
+        let lever = min + (channel.joystick + 1.0) * half_span;
+        let nudge = self.lift_nudge + (channel.keyboard + channel.mouse) * half_span * rate;
+        self.lift_nudge = nudge.clamp(min - lever, max - lever);
+        let target = lever + self.lift_nudge;
+        channel.scaled = (target - min) / half_span - 1.0;
+        // A soft start eases in from home, as it does for the other channels
+        home + (target - home) * ramp
+    }
+
+    /// `dt` is the time since the previous update in seconds
+    pub fn update(&mut self, input: &InputState, dt: f64) {
+        let _span = debug_span!("gimbal_update").entered();
+        let dt = dt.clamp(0.0, MAX_DT);
+        let modifier = self.config.controls.sensitivity_modifier(&input.buttons);
+        if self.arm_state == ArmState::Stopped || self.homing {
+            // Position channels start over from the stick afterwards, so there is nothing to blend
+            self.modifier = modifier;
+            self.modifier_blends = [ModifierBlend::default(); 4];
+            if self.arm_state == ArmState::Stopped {
+                self.update_stopped(input, dt);
+            } else {
+                self.update_homing(input, dt);
+            }
+            return;
+        }
+        let ramp = self.advance_soft_start(input, dt);
+        let mut pipeline = InputPipeline::default();
 
         // Process joystick input
         if self.config.controls.joystick.enabled {
-            pitch += self.get_joystick_axis_value(input, &self.config.controls.joystick.pitch_axis)
-                * if self.config.controls.joystick.invert_pitch { -1.0 } else { 1.0 };
-            
-            roll += self.get_joystick_axis_value(input, &self.config.controls.joystick.roll_axis)
-                * if self.config.controls.joystick.invert_roll { -1.0 } else { 1.0 };
-            
-            lift += self.get_joystick_axis_value(input, &self.config.controls.joystick.lift_axis)
-                * if self.config.controls.joystick.invert_lift { -1.0 } else { 1.0 };
+            self.filter_axes(input, dt);
+
+            // Trims are removed before inversion so a captured center stays centered when flipped
+            let channel = |axis_name: &str, mode: AxisMode, trim: f64, invert: bool, sources: &[AxisSource], device: &Option<String>| {
+                // A bound device that isn't there falls back to the active gamepad
+                let owner = device_owner(input, device.as_deref());
+                let resolved = self.resolve_joystick_axis(axis_name, |axis| self.owner_filtered(owner, axis));
+                let value = resolved.map_or(0.0, |(_, value)| mode.shape(value));
+                let sources: f64 = self.source_contributions(sources, owner).iter().map(|(_, value)| value).sum();
+                let raw_axes = owner.and_then(|name| input.devices.get(name)).unwrap_or(&input.axes);
+                ChannelPipeline {
+                    raw: resolved.and_then(|(axis, _)| raw_axes.get(&axis)).map(|&raw| raw as f64),
+                    joystick: (value - trim) * if invert { -1.0 } else { 1.0 } + sources,
+                    sources,
+                    ..ChannelPipeline::default()
+                }
+            };
+
+            let joystick = &self.config.controls.joystick;
+            pipeline.pitch = channel(
+                &joystick.pitch_axis,
+                AxisMode::Bipolar,
+                joystick.pitch_trim,
+                joystick.invert_pitch,
+                &joystick.pitch_sources,
+                &joystick.pitch_device,
+            );
+            pipeline.roll = channel(
+                &joystick.roll_axis,
+                AxisMode::Bipolar,
+                joystick.roll_trim,
+                joystick.invert_roll,
+                &joystick.roll_sources,
+                &joystick.roll_device,
+            );
+            pipeline.lift = match joystick.lift_control {
+                LiftControl::Centered => channel(
+                    &joystick.lift_axis,
+                    joystick.lift_axis_mode,
+                    joystick.lift_trim,
+                    joystick.invert_lift,
+                    &joystick.lift_sources,
+                    &joystick.lift_device,
+                ),
+                // The lever's own position, -1 at its low end: no trim, invert or extra sources
+                LiftControl::Absolute => {
+                    let lever = channel(&joystick.lift_axis, joystick.lift_axis_mode, 0.0, false, &[], &joystick.lift_device);
+                    let position = joystick.lift_axis_mode.position(lever.joystick);
+                    ChannelPipeline { joystick: if joystick.lift_reversed { -position } else { position }, ..lever }
+                }
+            };
+            pipeline.yaw = channel(
+                &joystick.yaw_axis,
+                AxisMode::Bipolar,
+                joystick.yaw_trim,
+                joystick.invert_yaw,
+                &joystick.yaw_sources,
+                &joystick.yaw_device,
+            );
         }
 
         // Process keyboard input
         if self.config.controls.keyboard_enabled {
-            pitch += input.keyboard_pitch;
-            roll += input.keyboard_roll;
-            lift += input.keyboard_lift;
+            pipeline.pitch.keyboard = input.keyboard_pitch;
+            pipeline.roll.keyboard = input.keyboard_roll;
+            pipeline.lift.keyboard = input.keyboard_lift;
+            pipeline.yaw.keyboard = input.keyboard_yaw;
+        }
+
+        // Process mouse input
+        if self.config.controls.mouse_enabled {
+            pipeline.pitch.mouse = input.mouse_pitch;
+            pipeline.roll.mouse = input.mouse_roll;
+            pipeline.lift.mouse = input.mouse_lift;
         }
 
         // Apply sensitivity and limits
-        self.state.pitch = (pitch * self.config.gimbal.pitch_sensitivity * self.config.gimbal.max_pitch)
-            .clamp(-self.config.gimbal.max_pitch, self.config.gimbal.max_pitch);
-        
-        self.state.roll = (roll * self.config.gimbal.roll_sensitivity * self.config.gimbal.max_roll)
-            .clamp(-self.config.gimbal.max_roll, self.config.gimbal.max_roll);
-        
-        self.state.lift = (lift * self.config.gimbal.lift_sensitivity * self.config.gimbal.max_lift)
-            .clamp(-self.config.gimbal.max_lift, self.config.gimbal.max_lift);
-
-        // Debug logging
-        if self.config.debug.log_input_values {
-            println!(
-                "Input: pitch={:.3}, roll={:.3}, lift={:.3} -> State: pitch={:.1}°, roll={:.1}°, lift={:.1}mm",
-                pitch, roll, lift, self.state.pitch, self.state.roll, self.state.lift
-            );
+        let gimbal = &self.config.gimbal;
+        let rate = gimbal.velocity_rate * dt;
+        let home = self.center_pose();
+        let controls = &self.config.controls;
+        let mix = |channel: &ChannelPipeline| mix_inputs(controls.mixing, controls.mixing_deadzone, channel);
+        let channels = [
+            (&mut pipeline.pitch, gimbal.pitch_sensitivity, gimbal.pitch_mode),
+            (&mut pipeline.roll, gimbal.roll_sensitivity, gimbal.roll_mode),
+            (&mut pipeline.lift, gimbal.lift_sensitivity, gimbal.lift_mode),
+            (&mut pipeline.yaw, gimbal.yaw_sensitivity, gimbal.yaw_mode),
+        ];
+        for ((channel, sensitivity, mode), blend) in channels.into_iter().zip(&mut self.modifier_blends) {
+            let input = mix(channel);
+            // A velocity channel only changes speed; a position channel keeps its current
+            // output and reaches center again when the stick does
+            if mode == ChannelMode::Position && modifier != self.modifier {
+                let before = input * sensitivity * self.modifier + blend.value(input);
+                *blend = ModifierBlend { offset: before - input * sensitivity * modifier, anchor: input };
+            }
+            if mode != ChannelMode::Position || input.abs() < MODIFIER_CENTER {
+                *blend = ModifierBlend::default();
+            }
+            channel.scaled = (input * sensitivity * modifier + blend.value(input)) * ramp;
+            channel.coupled = channel.scaled;
         }
+        self.modifier = modifier;
+        (pipeline.pitch.coupled, pipeline.roll.coupled) = gimbal.couple(pipeline.pitch.scaled, pipeline.roll.scaled);
+
+        // Lift travels different distances below and above its home, and so do pitch and
+        // roll either side of level when pitch_min/pitch_max or roll_min/roll_max are set
+        let lift_travel = (gimbal.lift_home - gimbal.lift_min, gimbal.lift_max - gimbal.lift_home);
+        let (pitch_min, pitch_max) = gimbal.pitch_range();
+        let (roll_min, roll_max) = gimbal.roll_range();
+        pipeline.pitch.unclamped =
+            channel_target(self.state.pitch, pipeline.pitch.coupled, gimbal.pitch_mode, (-pitch_min, pitch_max), rate, home.0);
+        pipeline.roll.unclamped =
+            channel_target(self.state.roll, pipeline.roll.coupled, gimbal.roll_mode, (-roll_min, roll_max), rate, home.1);
+        pipeline.lift.unclamped = if self.absolute_lift() && pipeline.lift.raw.is_some() {
+            let (lift_min, lift_max) = (gimbal.lift_min, gimbal.lift_max);
+            self.lever_lift(&mut pipeline.lift, (lift_min, lift_max), rate, ramp, home.2)
+        } else {
+            self.lift_nudge = 0.0;
+            channel_target(self.state.lift, pipeline.lift.scaled, gimbal.lift_mode, lift_travel, rate, home.2)
+        };
+        let gimbal = &self.config.gimbal;
+        pipeline.yaw.unclamped =
+            channel_target(self.state.yaw, pipeline.yaw.scaled, gimbal.yaw_mode, (gimbal.max_yaw, gimbal.max_yaw), rate, home.3);
+        self.state.pitch = pipeline.pitch.unclamped.clamp(pitch_min, pitch_max);
+        self.state.roll = pipeline.roll.unclamped.clamp(roll_min, roll_max);
+        self.state.lift = pipeline.lift.unclamped.clamp(gimbal.lift_min, gimbal.lift_max);
+        self.state.yaw = pipeline.yaw.unclamped.clamp(-gimbal.max_yaw, gimbal.max_yaw);
+
+        let geometry = &self.config.geometry;
+        self.state.limiting_actuators = kinematics::limit_to_envelope(&self.config, &mut self.state);
+        self.state.envelope_limited =
+            geometry.envelope != EnvelopeMode::Off && !self.state.limiting_actuators.is_empty();
+
+        pipeline.pitch.output = self.state.pitch;
+        pipeline.roll.output = self.state.roll;
+        pipeline.lift.output = self.state.lift;
+        pipeline.yaw.output = self.state.yaw;
+        pipeline.pitch.saturated = is_saturated(&pipeline.pitch, pitch_min, pitch_max);
+        pipeline.roll.saturated = is_saturated(&pipeline.roll, roll_min, roll_max);
+        pipeline.lift.saturated = is_saturated(&pipeline.lift, gimbal.lift_min, gimbal.lift_max);
+        pipeline.yaw.saturated = is_saturated(&pipeline.yaw, -gimbal.max_yaw, gimbal.max_yaw);
+        self.pipeline = pipeline;
     }
 
-    fn get_joystick_axis_value(&self, input: &InputState, axis_name: &str) -> f64 {
+    // Runs every calibrated axis through an exponential moving average; axes seen
+    // for the first time start at their current value instead of ramping up from zero.
+    // `smoothing` is the fraction kept per 60 Hz frame, raised to the elapsed frame
+    // count so the response time is the same at any update rate.
+    fn filter_axes(&mut self, input: &InputState, dt: f64) {
+        let smoothing = self
+            .config
+            .controls
+            .joystick
+            .smoothing
+            .clamp(0.0, 0.99)
+            .powf(dt * SMOOTHING_REFERENCE_HZ);
+        let (frames, threshold) = (self.config.controls.dropout_frames, self.config.controls.dropout_threshold);
+        // Dropouts are raw zeros, so they are held before calibration moves zero elsewhere
+        let held: Vec<(Axis, f64)> = input
+            .axes
+            .iter()
+            .map(|(&axis, &raw)| (axis, self.dropouts.entry(axis).or_default().pass(raw as f64, frames, threshold)))
+            .collect();
+        let active = input.active_gamepad.as_deref();
+        let calibrated = held.into_iter().map(|(axis, raw)| (axis, self.calibrate(active, axis, raw))).collect();
+        smooth(&mut self.filtered_axes, calibrated, smoothing);
+
+        // Each gamepad on its own as well, for channels bound to a device
+        self.filtered_devices.retain(|name, _| input.devices.contains_key(name));
+        self.device_dropouts.retain(|name, _| input.devices.contains_key(name));
+        for (name, axes) in &input.devices {
+            let guards = self.device_dropouts.entry(name.clone()).or_default();
+            let held: Vec<(Axis, f64)> = axes
+                .iter()
+                .map(|(&axis, &raw)| (axis, guards.entry(axis).or_default().pass(raw as f64, frames, threshold)))
+                .collect();
+            let calibrated = held.into_iter().map(|(axis, raw)| (axis, self.calibrate(Some(name), axis, raw))).collect();
+            smooth(self.filtered_devices.entry(name.clone()).or_default(), calibrated, smoothing);
+        }
+    }
+
+    /// Dropouts held on an axis this session, on the gamepad `owner` or the merged axes
+    pub fn dropouts(&self, owner: Option<&str>, axis: Axis) -> u64 {
+        let guard = match owner {
+            Some(name) => self.device_dropouts.get(name).and_then(|guards| guards.get(&axis)),
+            None => self.dropouts.get(&axis),
+        };
+        guard.map_or(0, |guard| guard.count)
+    }
+
+    // The mapping's value as input, with a trigger remapped to 0..1 by `mode`
+    fn get_joystick_axis_value(&self, axis_name: &str, mode: AxisMode, value_of: impl Fn(Axis) -> Option<f64>) -> f64 {
+        self.resolve_joystick_axis(axis_name, value_of).map_or(0.0, |(_, value)| mode.shape(value))
+    }
+
+    // The axis actually used for a mapping (primary or first active fallback) and its value
+    fn resolve_joystick_axis(&self, axis_name: &str, value_of: impl Fn(Axis) -> Option<f64>) -> Option<(Axis, f64)> {
         // Try primary axis
-        if let Some(axis) = parse_axis_name(axis_name) {
-            if let Some(&value) = input.axes.get(&axis) {
-                return value as f64;
-            }
+        if let Some(axis) = parse_axis_name(axis_name)
+            && let Some(value) = value_of(axis)
+        {
+            return Some((axis, value));
         }
 
         // Try fallback axes
         for fallback_name in &self.config.controls.joystick.fallback_axes {
-            if let Some(axis) = parse_axis_name(fallback_name) {
-                if let Some(&value) = input.axes.get(&axis) {
-                    if value.abs() > 0.01 { // Only use if significant input
-                        return value as f64;
-                    }
-                }
+            if let Some(axis) = parse_axis_name(fallback_name)
+                && let Some(value) = value_of(axis)
+                && value.abs() > 0.01 // Only use if significant input
+            {
+                return Some((axis, value));
+            }
+        }
+
+        None
+    }
+
+    /// What each extra source adds to its channel, from the smoothed axes of the last update.
+    /// Sources have no fallbacks; an axis the gamepad doesn't report adds nothing.
+    pub fn source_contributions<'a>(&self, sources: &'a [AxisSource], owner: Option<&str>) -> Vec<(&'a AxisSource, f64)> {
+        sources
+            .iter()
+            .map(|source| {
+                let value = parse_axis_name(&source.axis).and_then(|axis| self.owner_filtered(owner, axis)).unwrap_or(0.0);
+                (source, value * source.gain * if source.invert { -1.0 } else { 1.0 })
+            })
+            .collect()
+    }
+
+    /// Axis a mapping currently reads from after fallbacks, for display
+    pub fn resolved_axis(&self, input: &InputState, axis_name: &str, owner: Option<&str>) -> Option<Axis> {
+        self.resolve_joystick_axis(axis_name, |axis| self.owner_calibrated(input, owner, axis))
+            .map(|(axis, _)| axis)
+    }
+
+    /// Stores the current resting value of each mapped axis (on its channel's gamepad) as its trim
+    pub fn capture_center(&mut self, input: &InputState) {
+        let joystick = &self.config.controls.joystick;
+        let center = |axis_name: &str, mode: AxisMode, device: &Option<String>| {
+            let owner = device_owner(input, device.as_deref());
+            self.get_joystick_axis_value(axis_name, mode, |axis| self.owner_calibrated(input, owner, axis))
+        };
+        let pitch = center(&joystick.pitch_axis, AxisMode::Bipolar, &joystick.pitch_device);
+        let roll = center(&joystick.roll_axis, AxisMode::Bipolar, &joystick.roll_device);
+        let lift = center(&joystick.lift_axis, joystick.lift_axis_mode, &joystick.lift_device);
+        let yaw = center(&joystick.yaw_axis, AxisMode::Bipolar, &joystick.yaw_device);
+
+        let joystick = &mut self.config.controls.joystick;
+        joystick.pitch_trim = pitch.clamp(-1.0, 1.0);
+        joystick.roll_trim = roll.clamp(-1.0, 1.0);
+        joystick.lift_trim = lift.clamp(-1.0, 1.0);
+        joystick.yaw_trim = yaw.clamp(-1.0, 1.0);
+    }
+
+    /// Raw axis value normalized through the active gamepad's calibration, if any
+    pub fn calibrated_axis_value(&self, input: &InputState, axis: Axis) -> Option<f64> {
+        let raw = *input.axes.get(&axis)? as f64;
+        Some(self.calibrate(input.active_gamepad.as_deref(), axis, raw))
+    }
+
+    fn calibrate(&self, gamepad: Option<&str>, axis: Axis, raw: f64) -> f64 {
+        self.config.axis_calibration(gamepad, axis).map_or(raw, |c| c.normalize(raw))
+    }
+
+    // Calibrated axis of the gamepad `owner`, or of whichever moved last when None
+    fn owner_calibrated(&self, input: &InputState, owner: Option<&str>, axis: Axis) -> Option<f64> {
+        match owner {
+            Some(name) => {
+                let raw = *input.devices.get(name)?.get(&axis)? as f64;
+                Some(self.calibrate(Some(name), axis, raw))
             }
+            None => self.calibrated_axis_value(input, axis),
         }
+    }
 
-        0.0
+    /// Smoothed counterpart of calibrated_axis_value, as last fed into the gimbal state
+    pub fn filtered_axis_value(&self, axis: Axis) -> Option<f64> {
+        self.filtered_axes.get(&axis).copied()
     }
 
-    pub fn handle_keyboard(&mut self, input: &mut InputState, key: char, pressed: bool) {
+    fn owner_filtered(&self, owner: Option<&str>, axis: Axis) -> Option<f64> {
+        match owner {
+            Some(name) => self.filtered_devices.get(name)?.get(&axis).copied(),
+            None => self.filtered_axis_value(axis),
+        }
+    }
+
+    /// Called for presses, terminal key repeats and (where the terminal reports them) releases
+    /// of whatever key is bound to `action`
+    pub fn handle_keyboard(&mut self, input: &mut InputState, action: KeyAction, pressed: bool) {
         if !self.config.controls.keyboard_enabled {
             return;
         }
 
-        let step = if pressed { self.config.controls.keyboard_step } else { 0.0 };
-        
-        match key.to_ascii_lowercase() {
-            'w' => input.keyboard_pitch = step,      // Pitch forward
-            's' => input.keyboard_pitch = -step,     // Pitch back
-            'a' => input.keyboard_roll = -step,      // Roll left
-            'd' => input.keyboard_roll = step,       // Roll right
-            'r' => input.keyboard_lift = step,       // Lift up
-            'f' => input.keyboard_lift = -step,      // Lift down
-            _ => {}
+        if keyboard_channel(input, action).is_none() {
+            return;
+        }
+        let step = self.config.controls.keyboard_step;
+
+        match self.config.controls.keyboard_mode {
+            KeyboardMode::Direct => {
+                let direction = held_direction(input, action, pressed);
+                if let Some((value, _)) = keyboard_channel(input, action) {
+                    *value = direction * step;
+                }
+            }
+            KeyboardMode::Rate => {
+                if !pressed {
+                    input.held_keys.remove(&action);
+                    return;
+                }
+                // A fresh press is one discrete increment; repeats only mark the key as held
+                let now = Instant::now();
+                let repeating = input
+                    .held_keys
+                    .get(&action)
+                    .is_some_and(|held| now.duration_since(held.last_event) < KEY_REPEAT_GAP);
+                input.held_keys.insert(action, HeldKey { last_event: now, repeating });
+                if !repeating && let Some((value, direction)) = keyboard_channel(input, action) {
+                    *value = (*value + direction * step).clamp(-1.0, 1.0);
+                }
+            }
+        }
+    }
+
+    /// One keyboard_step on the channel `action` moves, whatever keyboard_mode is; for D-pad
+    /// clicks and gamepad buttons bound to movement actions
+    pub fn step_channel(&self, input: &mut InputState, action: KeyAction) {
+        let step = self.config.controls.keyboard_step;
+        if let Some((value, direction)) = keyboard_channel(input, action) {
+            *value = (*value + direction * step).clamp(-1.0, 1.0);
+        }
+    }
+
+    /// Rate mode: held keys keep moving their channel at keyboard_step per second
+    pub fn integrate_keyboard(&self, input: &mut InputState, dt: f64) {
+        if !self.config.controls.keyboard_enabled || self.config.controls.keyboard_mode != KeyboardMode::Rate {
+            return;
+        }
+
+        let dt = dt.clamp(0.0, MAX_DT);
+        let step = self.config.controls.keyboard_step;
+        input.held_keys.retain(|_, held| held.last_event.elapsed() < KEY_REPEAT_GAP);
+        let held: Vec<KeyAction> = input
+            .held_keys
+            .iter()
+            .filter(|(_, held)| held.repeating)
+            .map(|(&action, _)| action)
+            .collect();
+        for action in held {
+            if let Some((value, direction)) = keyboard_channel(input, action) {
+                *value = (*value + direction * step * dt).clamp(-1.0, 1.0);
+            }
+        }
+    }
+
+    /// Input is ignored from here on; the output holds or ramps to neutral each update
+    pub fn estop(&mut self) -> bool {
+        let changed = self.arm_state == ArmState::Armed;
+        self.arm_state = ArmState::Stopped;
+        self.homing = false;
+        self.setpoint = None;
+        if changed {
+            debug!(pitch = self.state.pitch, roll = self.state.roll, lift = self.state.lift, "e-stop engaged");
+        }
+        changed
+    }
+
+    pub fn rearm(&mut self) -> bool {
+        let changed = self.arm_state == ArmState::Stopped;
+        self.arm_state = ArmState::Armed;
+        if changed {
+            self.soft_start = soft_start(&self.config);
+            debug!("re-armed");
         }
+        changed
+    }
+
+    pub fn soft_start(&self) -> SoftStart {
+        self.soft_start
+    }
+
+    // Moves the soft start on by `dt` and returns the share of input to let through
+    fn advance_soft_start(&mut self, input: &InputState, dt: f64) -> f64 {
+        if self.soft_start == SoftStart::AwaitingCenter && self.stick_deflection(input) < START_CENTER {
+            self.soft_start = SoftStart::Ramping(0.0);
+        }
+        match self.soft_start {
+            SoftStart::AwaitingCenter => 0.0,
+            SoftStart::Ramping(elapsed) => {
+                let elapsed = elapsed + dt;
+                let secs = self.config.safety.soft_start_secs;
+                if elapsed >= secs {
+                    debug!("soft start done");
+                    self.soft_start = SoftStart::Done;
+                    1.0
+                } else {
+                    self.soft_start = SoftStart::Ramping(elapsed);
+                    elapsed / secs
+                }
+            }
+            SoftStart::Done => 1.0,
+        }
+    }
+
+    pub fn arm_state(&self) -> ArmState {
+        self.arm_state
+    }
+
+    fn update_stopped(&mut self, input: &InputState, dt: f64) {
+        // Keep the filter current so re-arming doesn't start from stale values
+        if self.config.controls.joystick.enabled {
+            self.filter_axes(input, dt);
+        }
+        if self.config.safety.estop_behavior == EstopBehavior::Zero {
+            let rest = rest_state(&self.config);
+            ease_toward(&mut self.state, &rest, &self.config, dt);
+        }
+
+        self.output_only_pipeline();
     }
 
     pub fn reset(&mut self) {
-        self.state = GimbalState::default();
+        self.lift_nudge = 0.0;
+        self.state = rest_state(&self.config);
+        self.filtered_axes.clear();
+        self.homing = false;
+        self.setpoint = None;
+        self.soft_start = soft_start(&self.config);
+    }
+
+    /// Starts a slew to the home pose; refused while stopped
+    pub fn home(&mut self) -> bool {
+        if self.arm_state == ArmState::Stopped {
+            return false;
+        }
+        self.setpoint = None;
+        self.homing = true;
+        self.lift_nudge = 0.0;
+        debug!(pose = ?self.home_pose(), "homing");
+        true
+    }
+
+    /// Slews to a typed pose and keeps position channels centered on it; refused while stopped
+    pub fn set_setpoint(&mut self, pose: (f64, f64, f64, f64)) -> bool {
+        if self.arm_state == ArmState::Stopped {
+            return false;
+        }
+        self.setpoint = Some(pose);
+        self.homing = true;
+        true
+    }
+
+    /// Picks up a pose left by an earlier session: it becomes the state and, like a typed
+    /// setpoint, the center position channels hold, so the plate stays where the hardware is
+    pub fn set_state(&mut self, pose: (f64, f64, f64, f64)) {
+        self.setpoint = Some(pose);
+        let (pitch, roll, lift, yaw) = self.center_pose();
+        self.state.pitch = pitch;
+        self.state.roll = roll;
+        self.state.lift = lift;
+        self.state.yaw = yaw;
+        let geometry = &self.config.geometry;
+        self.state.limiting_actuators = kinematics::limit_to_envelope(&self.config, &mut self.state);
+        self.state.envelope_limited =
+            geometry.envelope != EnvelopeMode::Off && !self.state.limiting_actuators.is_empty();
+    }
+
+    pub fn setpoint(&self) -> Option<(f64, f64, f64, f64)> {
+        self.setpoint
+    }
+
+    /// What position channels are centered on and homing moves to: the setpoint if one was
+    /// typed, else home; clamped to the current limits
+    pub fn center_pose(&self) -> (f64, f64, f64, f64) {
+        let Some((pitch, roll, lift, yaw)) = self.setpoint else {
+            return self.home_pose();
+        };
+        let gimbal = &self.config.gimbal;
+        (
+            clamp_to(pitch, gimbal.pitch_range()),
+            clamp_to(roll, gimbal.roll_range()),
+            lift.clamp(gimbal.lift_min, gimbal.lift_max),
+            yaw.clamp(-gimbal.max_yaw, gimbal.max_yaw),
+        )
+    }
+
+    /// Ends a slew where it is; input takes over from the next update
+    pub fn stop_homing(&mut self) {
+        self.homing = false;
+    }
+
+    pub fn is_homing(&self) -> bool {
+        self.homing
+    }
+
+    /// Sensitivity multiplier in effect from the held modifier buttons, 1.0 for none
+    pub fn sensitivity_modifier(&self) -> f64 {
+        self.modifier
+    }
+
+    /// Configured home (pitch, roll, lift, yaw), clamped to the current limits; `[homing]` lift
+    /// is measured from gimbal.lift_home
+    pub fn home_pose(&self) -> (f64, f64, f64, f64) {
+        let (home, gimbal) = (&self.config.homing, &self.config.gimbal);
+        (
+            clamp_to(home.pitch, gimbal.pitch_range()),
+            clamp_to(home.roll, gimbal.roll_range()),
+            (gimbal.lift_home + home.lift).clamp(gimbal.lift_min, gimbal.lift_max),
+            home.yaw.clamp(-gimbal.max_yaw, gimbal.max_yaw),
+        )
+    }
+
+    // Each channel moves toward the center pose at max / move_secs per second, so a full
+    // deflection takes move_secs whatever the starting pose
+    fn update_homing(&mut self, input: &InputState, dt: f64) {
+        // Keep the filter current so input picks up from the stick, not stale values
+        if self.config.controls.joystick.enabled {
+            self.filter_axes(input, dt);
+        }
+        let fraction = dt / self.config.homing.move_secs;
+        let gimbal = &self.config.gimbal;
+        let (pitch, roll, lift, yaw) = self.center_pose();
+        let toward = |value: f64, target: f64, max: f64| {
+            let step = max * fraction;
+            value + (target - value).clamp(-step, step)
+        };
+        let before = (self.state.pitch, self.state.roll, self.state.lift, self.state.yaw);
+        self.state.pitch = toward(self.state.pitch, pitch, gimbal.pitch_span());
+        self.state.roll = toward(self.state.roll, roll, gimbal.roll_span());
+        self.state.lift = toward(self.state.lift, lift, gimbal.lift_span());
+        self.state.yaw = toward(self.state.yaw, yaw, gimbal.max_yaw);
+        let arrived = [
+            (self.state.pitch, pitch),
+            (self.state.roll, roll),
+            (self.state.lift, lift),
+            (self.state.yaw, yaw),
+        ]
+        .iter()
+        .all(|(value, target)| (value - target).abs() < HOME_EPSILON);
+
+        let geometry = &self.config.geometry;
+        self.state.limiting_actuators = kinematics::limit_to_envelope(&self.config, &mut self.state);
+        self.state.envelope_limited =
+            geometry.envelope != EnvelopeMode::Off && !self.state.limiting_actuators.is_empty();
+        // A home outside the envelope is as close as the plate gets; stop once it stops moving
+        let stalled = before == (self.state.pitch, self.state.roll, self.state.lift, self.state.yaw);
+        if arrived || stalled {
+            debug!(arrived, "homing finished");
+            self.homing = false;
+        }
+
+        self.output_only_pipeline();
+    }
+
+    /// Follows a scripted target (a sequence step) instead of input; the limits and the
+    /// envelope still apply
+    pub fn update_scripted(&mut self, input: &InputState, target: (f64, f64, f64, f64), dt: f64) {
+        let dt = dt.clamp(0.0, MAX_DT);
+        // The script has taken over from any slew in progress
+        self.homing = false;
+        if self.config.controls.joystick.enabled {
+            self.filter_axes(input, dt);
+        }
+        let gimbal = &self.config.gimbal;
+        self.state.pitch = clamp_to(target.0, gimbal.pitch_range());
+        self.state.roll = clamp_to(target.1, gimbal.roll_range());
+        self.state.lift = target.2.clamp(gimbal.lift_min, gimbal.lift_max);
+        self.state.yaw = target.3.clamp(-gimbal.max_yaw, gimbal.max_yaw);
+
+        let geometry = &self.config.geometry;
+        self.state.limiting_actuators = kinematics::limit_to_envelope(&self.config, &mut self.state);
+        self.state.envelope_limited =
+            geometry.envelope != EnvelopeMode::Off && !self.state.limiting_actuators.is_empty();
+        self.output_only_pipeline();
+    }
+
+    /// Largest deflection of a mapped stick from its trimmed center, in axis units
+    pub fn stick_deflection(&self, input: &InputState) -> f64 {
+        let joystick = &self.config.controls.joystick;
+        if !joystick.enabled {
+            return 0.0;
+        }
+        [
+            (&joystick.pitch_axis, AxisMode::Bipolar, joystick.pitch_trim),
+            (&joystick.roll_axis, AxisMode::Bipolar, joystick.roll_trim),
+            (&joystick.lift_axis, joystick.lift_axis_mode, joystick.lift_trim),
+            (&joystick.yaw_axis, AxisMode::Bipolar, joystick.yaw_trim),
+        ]
+        .into_iter()
+        .map(|(name, mode, trim)| {
+            self.resolve_joystick_axis(name, |axis| self.calibrated_axis_value(input, axis))
+                .map_or(0.0, |(_, value)| (mode.shape(value) - trim).abs())
+        })
+        .chain(
+            [&joystick.pitch_sources, &joystick.roll_sources, &joystick.lift_sources, &joystick.yaw_sources]
+                .into_iter()
+                .flatten()
+                .filter_map(|source| parse_axis_name(&source.axis))
+                .filter_map(|axis| self.calibrated_axis_value(input, axis))
+                .map(f64::abs),
+        )
+        .fold(0.0, f64::max)
+    }
+
+    // Pipeline for updates that ignore input: only the output is meaningful
+    fn output_only_pipeline(&mut self) {
+        let state = &self.state;
+        for (channel, value) in [
+            (&mut self.pipeline.pitch, state.pitch),
+            (&mut self.pipeline.roll, state.roll),
+            (&mut self.pipeline.lift, state.lift),
+            (&mut self.pipeline.yaw, state.yaw),
+        ] {
+            *channel = ChannelPipeline { output: value, ..ChannelPipeline::default() };
+        }
     }
 
     pub fn get_state(&self) -> &GimbalState {
         &self.state
     }
 
+    /// Leg positions and extensions for the current state, from the inverse kinematics
+    pub fn actuators(&self) -> Vec<kinematics::Actuator> {
+        kinematics::solve(&self.config.geometry, &self.state)
+    }
+
+    /// Where the legs may travel under the current geometry
+    pub fn leg_limits(&self) -> kinematics::LegLimits {
+        kinematics::LegLimits::new(&self.config)
+    }
+
+    pub fn last_pipeline(&self) -> &InputPipeline {
+        &self.pipeline
+    }
+
     pub fn get_config(&self) -> &Config {
         &self.config
     }
-}
\ No newline at end of file
+
+    /// New limits or geometry (e.g. another hardware profile) apply to the current state right away
+    pub fn set_config(&mut self, config: Config) {
+        debug!("config replaced");
+        self.config = config;
+        let gimbal = &self.config.gimbal;
+        self.state.pitch = clamp_to(self.state.pitch, gimbal.pitch_range());
+        self.state.roll = clamp_to(self.state.roll, gimbal.roll_range());
+        self.state.lift = self.state.lift.clamp(gimbal.lift_min, gimbal.lift_max);
+        self.state.yaw = self.state.yaw.clamp(-gimbal.max_yaw, gimbal.max_yaw);
+        let geometry = &self.config.geometry;
+        self.state.limiting_actuators = kinematics::limit_to_envelope(&self.config, &mut self.state);
+        self.state.envelope_limited =
+            geometry.envelope != EnvelopeMode::Off && !self.state.limiting_actuators.is_empty();
+    }
+}
+
+// The keyboard value a movement action drives and the direction it pushes it
+fn keyboard_channel(input: &mut InputState, action: KeyAction) -> Option<(&mut f64, f64)> {
+    match action {
+        KeyAction::PitchUp => Some((&mut input.keyboard_pitch, 1.0)),    // Pitch forward
+        KeyAction::PitchDown => Some((&mut input.keyboard_pitch, -1.0)), // Pitch back
+        KeyAction::RollLeft => Some((&mut input.keyboard_roll, -1.0)),   // Roll left
+        KeyAction::RollRight => Some((&mut input.keyboard_roll, 1.0)),   // Roll right
+        KeyAction::LiftUp => Some((&mut input.keyboard_lift, 1.0)),      // Lift up
+        KeyAction::LiftDown => Some((&mut input.keyboard_lift, -1.0)),   // Lift down
+        KeyAction::YawLeft => Some((&mut input.keyboard_yaw, -1.0)),     // Yaw left
+        KeyAction::YawRight => Some((&mut input.keyboard_yaw, 1.0)),     // Yaw right
+        _ => None,
+    }
+}
+
+// Direct mode: records a press or release of `action` and returns the direction its channel
+// should point. The newest key still down on the channel sets it, so releasing one direction
+// falls back to the other key instead of zeroing the channel
+fn held_direction(input: &mut InputState, action: KeyAction, pressed: bool) -> f64 {
+    input.pressed_keys.retain(|&held| held != action);
+    if pressed {
+        input.pressed_keys.push(action);
+    }
+    let opposite = opposite_key(action);
+    input
+        .pressed_keys
+        .iter()
+        .rev()
+        .find(|&&held| held == action || Some(held) == opposite)
+        .copied()
+        .and_then(|held| keyboard_channel(input, held).map(|(_, direction)| direction))
+        .unwrap_or(0.0)
+}
+
+// The movement key pushing the same channel the other way
+fn opposite_key(action: KeyAction) -> Option<KeyAction> {
+    match action {
+        KeyAction::PitchUp => Some(KeyAction::PitchDown),
+        KeyAction::PitchDown => Some(KeyAction::PitchUp),
+        KeyAction::RollLeft => Some(KeyAction::RollRight),
+        KeyAction::RollRight => Some(KeyAction::RollLeft),
+        KeyAction::LiftUp => Some(KeyAction::LiftDown),
+        KeyAction::LiftDown => Some(KeyAction::LiftUp),
+        KeyAction::YawLeft => Some(KeyAction::YawRight),
+        KeyAction::YawRight => Some(KeyAction::YawLeft),
+        _ => None,
+    }
+}
+
+// One channel's stick, keyboard and mouse contributions combined per `mode`, before sensitivity
+fn mix_inputs(mode: InputMixing, deadzone: f64, channel: &ChannelPipeline) -> f64 {
+    let (joystick, keyboard, mouse) = (channel.joystick, channel.keyboard, channel.mouse);
+    match mode {
+        InputMixing::Sum => joystick + keyboard + mouse,
+        InputMixing::Max => [joystick, keyboard, mouse].into_iter().fold(0.0, |a, b| if b.abs() > a.abs() { b } else { a }),
+        InputMixing::JoystickPriority if joystick.abs() > deadzone => joystick,
+        InputMixing::JoystickPriority => joystick + keyboard + mouse,
+    }
+}
+
+/// The connected gamepad a channel bound to `device` reads from: the first by name that
+/// matches. None when the channel isn't bound or no gamepad matches.
+pub fn device_owner<'a>(input: &'a InputState, device: Option<&str>) -> Option<&'a str> {
+    let device = device?;
+    input.devices.keys().filter(|name| name_matches(device, name)).min().map(String::as_str)
+}
+
+// One filter_axes step for one set of axes; axes that stopped reporting (e.g. their pad
+// disconnected) must not linger
+fn smooth(filtered: &mut HashMap<Axis, f64>, calibrated: HashMap<Axis, f64>, smoothing: f64) {
+    filtered.retain(|axis, _| calibrated.contains_key(axis));
+    for (axis, value) in calibrated {
+        filtered
+            .entry(axis)
+            .and_modify(|filtered| *filtered = *filtered * smoothing + value * (1.0 - smoothing))
+            .or_insert(value);
+    }
+}
+
+// Where a soft start begins; a zero-length ramp finishes on the first update
+fn soft_start(config: &Config) -> SoftStart {
+    if config.safety.require_center_on_start {
+        SoftStart::AwaitingCenter
+    } else {
+        SoftStart::Ramping(0.0)
+    }
+}
+
+/// Level, with lift at its configured home
+pub fn rest_state(config: &Config) -> GimbalState {
+    GimbalState {
+        lift: config.gimbal.lift_home,
+        ..GimbalState::default()
+    }
+}
+
+/// Moves `state` toward `target` at the zero ramp rate: a full deflection takes
+/// safety.zero_ramp_secs. Returns true once it is there.
+pub fn ease_toward(state: &mut GimbalState, target: &GimbalState, config: &Config, dt: f64) -> bool {
+    let fraction = dt / config.safety.zero_ramp_secs;
+    let gimbal = &config.gimbal;
+    let toward = |value: f64, target: f64, max: f64| {
+        let step = max * fraction;
+        value + (target - value).clamp(-step, step)
+    };
+    state.pitch = toward(state.pitch, target.pitch, gimbal.pitch_span());
+    state.roll = toward(state.roll, target.roll, gimbal.roll_span());
+    state.lift = toward(state.lift, target.lift, gimbal.lift_span());
+    state.yaw = toward(state.yaw, target.yaw, gimbal.max_yaw);
+    (state.pitch, state.roll, state.lift, state.yaw) == (target.pitch, target.roll, target.lift, target.yaw)
+}
+
+// What a position channel still carries from before a modifier change, in scaled input units.
+// The whole offset applies from `anchor` (the stick then) outward and fades out linearly
+// toward center, so neither the change nor the way back is a jump.
+#[derive(Debug, Clone, Copy, Default)]
+struct ModifierBlend {
+    offset: f64,
+    anchor: f64,
+}
+
+impl ModifierBlend {
+    fn value(&self, input: f64) -> f64 {
+        if self.anchor == 0.0 {
+            return 0.0;
+        }
+        self.offset * (input / self.anchor).clamp(0.0, 1.0)
+    }
+}
+
+// Saturated when the input asks for more than max: a position channel mapped beyond it, or
+// a velocity channel sitting at it while the input keeps pushing outward
+fn is_saturated(channel: &ChannelPipeline, min: f64, max: f64) -> bool {
+    channel.unclamped < min || channel.unclamped > max
+}
+
+fn clamp_to(value: f64, (min, max): (f64, f64)) -> f64 {
+    value.clamp(min, max)
+}
+
+// Position channels map the scaled input onto the range around the center pose; velocity
+// channels treat it as a rate and hold their value when the input returns to center.
+// `travel` is the distance (below, above) the center that full deflection covers; the
+// caller clamps the result to the channel's limits.
+fn channel_target(current: f64, input: f64, mode: ChannelMode, travel: (f64, f64), rate: f64, home: f64) -> f64 {
+    let max = if input < 0.0 { travel.0 } else { travel.1 };
+    match mode {
+        ChannelMode::Position => home + input * max,
+        ChannelMode::Velocity => current + input * max * rate,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // One 200 Hz update
+    const DT: f64 = 0.005;
+
+    // Axis values are f32, so they only carry about seven digits
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-6, "expected {}, got {}", expected, actual);
+    }
+
+    #[test]
+    fn centered_input_rests_at_home() {
+        let mut gimbal = GimbalController::for_test(Config::default());
+        gimbal.update(&InputState::default(), DT);
+        let state = gimbal.get_state();
+        assert_eq!((state.pitch, state.roll, state.lift, state.yaw), (0.0, 0.0, 0.0, 0.0));
+        assert!(!state.envelope_limited);
+    }
+
+    #[test]
+    fn first_update_follows_the_stick() {
+        let mut gimbal = GimbalController::for_test(Config::default());
+        gimbal.update(&InputState::default().with_axis(Axis::RightStickY, 0.5).with_axis(Axis::RightStickX, -0.25), DT);
+        assert_close(gimbal.get_state().pitch, 10.0);
+        assert_close(gimbal.get_state().roll, -5.0);
+    }
+
+    #[test]
+    fn same_input_gives_the_same_state() {
+        let input = InputState::default()
+            .with_axis(Axis::RightStickY, 0.8)
+            .with_axis(Axis::RightZ, -0.3)
+            .with_keyboard(0.0, 0.4, 0.0);
+        let run = || {
+            let mut gimbal = GimbalController::for_test(Config::default());
+            for _ in 0..10 {
+                gimbal.update(&input, DT);
+            }
+            gimbal.get_state().clone()
+        };
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn dropping_back_to_center_is_not_held() {
+        let mut gimbal = GimbalController::for_test(Config::default());
+        gimbal.update(&InputState::default().with_axis(Axis::RightStickY, 1.0), DT);
+        gimbal.update(&InputState::default().with_axis(Axis::RightStickY, 0.0), DT);
+        assert_close(gimbal.get_state().pitch, 0.0);
+    }
+
+    #[test]
+    fn restored_state_is_taken_at_once_and_held() {
+        let mut config = Config::default();
+        config.gimbal.max_yaw = 30.0;
+        let mut gimbal = GimbalController::for_test(config);
+        gimbal.set_state((5.0, -4.0, 3.0, 10.0));
+        assert!(!gimbal.is_homing());
+        for _ in 0..2 {
+            let state = gimbal.get_state();
+            assert_eq!((state.pitch, state.roll, state.lift, state.yaw), (5.0, -4.0, 3.0, 10.0));
+            gimbal.update(&InputState::default(), DT);
+        }
+    }
+
+    #[test]
+    fn invert_flags_flip_their_channel_only() {
+        let mut config = Config::default();
+        config.controls.joystick.invert_pitch = true;
+        config.controls.joystick.invert_lift = true;
+        let mut gimbal = GimbalController::for_test(config);
+        let input = InputState::default()
+            .with_axis(Axis::RightStickY, 0.4)
+            .with_axis(Axis::RightStickX, 0.4)
+            .with_axis(Axis::RightZ, 0.4);
+        gimbal.update(&input, DT);
+        assert_close(gimbal.get_state().pitch, -8.0);
+        assert_close(gimbal.get_state().roll, 8.0);
+        assert_close(gimbal.get_state().lift, -6.0);
+    }
+
+    #[test]
+    fn trim_is_removed_before_inversion() {
+        let mut config = Config::default();
+        config.controls.joystick.invert_pitch = true;
+        config.controls.joystick.pitch_trim = 0.1;
+        let mut gimbal = GimbalController::for_test(config);
+        gimbal.update(&InputState::default().with_axis(Axis::RightStickY, 0.1), DT);
+        assert_close(gimbal.get_state().pitch, 0.0);
+        gimbal.update(&InputState::default().with_axis(Axis::RightStickY, 0.5), DT);
+        assert_close(gimbal.get_state().pitch, -8.0);
+    }
+
+    #[test]
+    fn first_active_fallback_in_list_order_is_used() {
+        // LeftStickY comes before LeftStickX in the default fallback_axes
+        let mut gimbal = GimbalController::for_test(Config::default());
+        gimbal.update(&InputState::default().with_axis(Axis::LeftStickX, 0.5).with_axis(Axis::LeftStickY, 0.25), DT);
+        assert_close(gimbal.get_state().pitch, 5.0);
+
+        // A fallback resting at center is skipped for the next one
+        gimbal.update(&InputState::default().with_axis(Axis::LeftStickX, 0.5).with_axis(Axis::LeftStickY, 0.005), DT);
+        assert_close(gimbal.get_state().pitch, 10.0);
+    }
+
+    #[test]
+    fn primary_axis_wins_over_fallbacks_even_at_center() {
+        let mut gimbal = GimbalController::for_test(Config::default());
+        gimbal.update(&InputState::default().with_axis(Axis::RightStickY, 0.0).with_axis(Axis::LeftStickY, 0.7), DT);
+        assert_close(gimbal.get_state().pitch, 0.0);
+    }
+
+    #[test]
+    fn keyboard_and_stick_sum_past_full_deflection() {
+        let mut gimbal = GimbalController::for_test(Config::default());
+        gimbal.update(&InputState::default().with_axis(Axis::RightStickY, 0.8).with_keyboard(0.6, 0.0, 0.0), DT);
+        let pitch = gimbal.last_pipeline().pitch;
+        assert_close(pitch.scaled, 1.4);
+        assert_close(pitch.unclamped, 28.0);
+        assert_close(pitch.output, 20.0);
+        assert!(pitch.saturated);
+    }
+
+    #[test]
+    fn full_deflection_lands_exactly_on_the_limits() {
+        // One channel at a time: all three at full travel would leave the actuator envelope.
+        // Full roll lifts A1 75 * sin(20°) = 25.7mm, just past the default 25mm stroke.
+        let mut config = Config::default();
+        config.geometry.actuator_stroke = 30.0;
+        let mut gimbal = GimbalController::for_test(config);
+        for (axis, value, expected) in [
+            (Axis::RightStickY, 1.0, (20.0, 0.0, 0.0)),
+            (Axis::RightStickX, -1.0, (0.0, -20.0, 0.0)),
+            (Axis::RightZ, 1.0, (0.0, 0.0, 15.0)),
+        ] {
+            gimbal.update(&InputState::default().with_axis(axis, value), DT);
+            let state = gimbal.get_state();
+            assert_eq!((state.pitch, state.roll, state.lift), expected);
+            assert!(!state.envelope_limited);
+            assert!(!gimbal.last_pipeline().any_saturated());
+        }
+    }
+
+    #[test]
+    fn smoothing_approaches_a_step_geometrically() {
+        let mut config = Config::default();
+        config.controls.joystick.smoothing = 0.5;
+        let mut gimbal = GimbalController::for_test(config);
+        let dt = 1.0 / SMOOTHING_REFERENCE_HZ;
+        gimbal.update(&InputState::default().with_axis(Axis::RightStickY, 0.0), dt);
+        // Each 60 Hz frame keeps half of the remaining distance to the target
+        let step = InputState::default().with_axis(Axis::RightStickY, 1.0);
+        for frame in 1..=6 {
+            gimbal.update(&step, dt);
+            assert_close(gimbal.get_state().pitch, 20.0 * (1.0 - 0.5f64.powi(frame)));
+        }
+    }
+
+    #[test]
+    fn smoothing_rate_does_not_depend_on_the_update_rate() {
+        let mut config = Config::default();
+        config.controls.joystick.smoothing = 0.5;
+        let run = |updates_per_frame: i32| {
+            let mut gimbal = GimbalController::for_test(config.clone());
+            let dt = 1.0 / SMOOTHING_REFERENCE_HZ / updates_per_frame as f64;
+            gimbal.update(&InputState::default().with_axis(Axis::RightStickY, 0.0), dt);
+            let step = InputState::default().with_axis(Axis::RightStickY, 1.0);
+            for _ in 0..3 * updates_per_frame {
+                gimbal.update(&step, dt);
+            }
+            gimbal.get_state().pitch
+        };
+        assert_close(run(1), 17.5);
+        assert_close(run(4), 17.5);
+    }
+
+    #[test]
+    fn smoothing_leaves_the_keyboard_alone() {
+        let mut config = Config::default();
+        config.controls.joystick.smoothing = 0.9;
+        let mut gimbal = GimbalController::for_test(config);
+        gimbal.update(&InputState::default().with_keyboard(0.5, 0.0, 0.0), DT);
+        assert_close(gimbal.get_state().pitch, 10.0);
+    }
+
+    #[test]
+    fn velocity_channel_integrates_and_holds() {
+        let mut config = Config::default();
+        config.gimbal.lift_mode = ChannelMode::Velocity;
+        config.gimbal.velocity_rate = 0.5;
+        let mut gimbal = GimbalController::for_test(config);
+        // Half deflection for one second: 0.5 × 15 mm × velocity_rate 0.5 per second
+        let held = InputState::default().with_axis(Axis::RightZ, 0.5);
+        for _ in 0..200 {
+            gimbal.update(&held, DT);
+        }
+        assert_close(gimbal.get_state().lift, 3.75);
+
+        let centered = InputState::default().with_axis(Axis::RightZ, 0.0);
+        for _ in 0..200 {
+            gimbal.update(&centered, DT);
+        }
+        assert_close(gimbal.get_state().lift, 3.75);
+    }
+
+    #[test]
+    fn velocity_channel_stops_at_its_limit() {
+        let mut config = Config::default();
+        config.gimbal.lift_mode = ChannelMode::Velocity;
+        let mut gimbal = GimbalController::for_test(config);
+        let held = InputState::default().with_axis(Axis::RightZ, -1.0);
+        for _ in 0..400 {
+            gimbal.update(&held, DT);
+        }
+        assert_eq!(gimbal.get_state().lift, -15.0);
+        assert!(gimbal.last_pipeline().lift.saturated);
+    }
+
+    #[test]
+    fn held_direction_follows_the_newest_key_on_the_channel() {
+        let mut input = InputState::default();
+        assert_eq!(held_direction(&mut input, KeyAction::PitchUp, true), 1.0);
+        assert_eq!(held_direction(&mut input, KeyAction::PitchDown, true), -1.0);
+        // A key on another channel doesn't count against pitch
+        assert_eq!(held_direction(&mut input, KeyAction::RollLeft, true), -1.0);
+        assert_eq!(held_direction(&mut input, KeyAction::PitchDown, false), 1.0);
+        assert_eq!(held_direction(&mut input, KeyAction::PitchUp, false), 0.0);
+        assert_eq!(input.pressed_keys, vec![KeyAction::RollLeft]);
+    }
+
+    #[test]
+    fn releasing_s_while_w_is_held_pitches_forward_again() {
+        let mut config = Config::default();
+        config.controls.keyboard_mode = KeyboardMode::Direct;
+        let mut gimbal = GimbalController::for_test(config);
+        let mut input = InputState::default();
+        let step = gimbal.get_config().controls.keyboard_step;
+
+        gimbal.handle_keyboard(&mut input, KeyAction::PitchUp, true);
+        gimbal.update(&input, DT);
+        assert_close(input.keyboard_pitch, step);
+        assert_close(gimbal.get_state().pitch, 2.0);
+
+        gimbal.handle_keyboard(&mut input, KeyAction::PitchDown, true);
+        gimbal.update(&input, DT);
+        assert_close(input.keyboard_pitch, -step);
+        assert_close(gimbal.get_state().pitch, -2.0);
+
+        gimbal.handle_keyboard(&mut input, KeyAction::PitchDown, false);
+        gimbal.update(&input, DT);
+        assert_close(input.keyboard_pitch, step);
+        assert_close(gimbal.get_state().pitch, 2.0);
+    }
+
+    fn sources(joystick: f64, keyboard: f64, mouse: f64) -> ChannelPipeline {
+        ChannelPipeline { joystick, keyboard, mouse, ..ChannelPipeline::default() }
+    }
+
+    #[test]
+    fn sum_mixing_adds_every_source() {
+        assert_close(mix_inputs(InputMixing::Sum, 0.05, &sources(0.5, 0.3, -0.1)), 0.7);
+    }
+
+    #[test]
+    fn max_mixing_keeps_the_largest_source_with_its_sign() {
+        assert_close(mix_inputs(InputMixing::Max, 0.05, &sources(0.2, -0.6, 0.4)), -0.6);
+        assert_close(mix_inputs(InputMixing::Max, 0.05, &sources(0.0, 0.0, 0.0)), 0.0);
+    }
+
+    #[test]
+    fn joystick_priority_overrides_only_past_the_deadzone() {
+        let mode = InputMixing::JoystickPriority;
+        // Past the deadzone the stick alone drives the channel
+        assert_close(mix_inputs(mode, 0.05, &sources(0.3, 0.5, 0.2)), 0.3);
+        assert_close(mix_inputs(mode, 0.05, &sources(-0.06, 0.5, 0.0)), -0.06);
+        // Inside it (or right on it) the stick counts as at rest and everything sums
+        assert_close(mix_inputs(mode, 0.05, &sources(0.04, 0.5, 0.2)), 0.74);
+        assert_close(mix_inputs(mode, 0.05, &sources(0.05, 0.5, 0.0)), 0.55);
+    }
+
+    #[test]
+    fn joystick_priority_ignores_the_keyboard_while_the_stick_is_deflected() {
+        let mut config = Config::default();
+        config.controls.mixing = InputMixing::JoystickPriority;
+        let mut gimbal = GimbalController::for_test(config);
+        gimbal.update(&InputState::default().with_axis(Axis::RightStickY, 0.5).with_keyboard(0.2, 0.0, 0.0), DT);
+        assert_close(gimbal.get_state().pitch, 10.0);
+
+        gimbal.update(&InputState::default().with_keyboard(0.2, 0.0, 0.0), DT);
+        assert_close(gimbal.get_state().pitch, 4.0);
+    }
+
+    #[test]
+    fn each_half_of_the_stick_covers_its_own_side_of_an_uneven_range() {
+        let mut config = Config::default();
+        (config.gimbal.pitch_min, config.gimbal.pitch_max) = (Some(-10.0), Some(20.0));
+        (config.gimbal.lift_min, config.gimbal.lift_max, config.gimbal.lift_home) = (0.0, 40.0, 12.0);
+        let mut gimbal = GimbalController::for_test(config);
+        // One channel at a time, as full tilt at full lift is outside the envelope
+        for (stick, pitch) in [(-1.0, -10.0), (0.0, 0.0), (1.0, 20.0)] {
+            gimbal.update(&InputState::default().with_axis(Axis::RightStickY, stick), DT);
+            assert_close(gimbal.get_state().pitch, pitch);
+            assert_close(gimbal.get_state().lift, 12.0);
+            assert!(!gimbal.last_pipeline().any_saturated(), "pitch stick {}", stick);
+        }
+        for (stick, lift) in [(-1.0, 0.0), (0.0, 12.0), (1.0, 40.0)] {
+            gimbal.update(&InputState::default().with_axis(Axis::RightZ, stick), DT);
+            assert_close(gimbal.get_state().pitch, 0.0);
+            assert_close(gimbal.get_state().lift, lift);
+            assert!(!gimbal.last_pipeline().any_saturated(), "lift stick {}", stick);
+        }
+    }
+
+    #[test]
+    fn position_target_uses_the_travel_on_the_input_side() {
+        let target = |input| channel_target(0.0, input, ChannelMode::Position, (12.0, 28.0), 1.0, 12.0);
+        assert_eq!(target(-1.0), 0.0);
+        assert_eq!(target(0.0), 12.0);
+        assert_eq!(target(1.0), 40.0);
+        assert_eq!(target(-0.5), 6.0);
+        assert_eq!(target(0.5), 26.0);
+    }
+
+    fn lift_for(gimbal: &mut GimbalController, raw: f32) -> f64 {
+        gimbal.update(&InputState::default().with_axis(Axis::RightZ, raw), DT);
+        gimbal.get_state().lift
+    }
+
+    #[test]
+    fn bipolar_lift_axis_is_centered() {
+        let mut gimbal = GimbalController::for_test(Config::default());
+        assert_close(lift_for(&mut gimbal, -1.0), -15.0);
+        assert_close(lift_for(&mut gimbal, 0.0), 0.0);
+        assert_close(lift_for(&mut gimbal, 0.5), 7.5);
+        assert_close(lift_for(&mut gimbal, 1.0), 15.0);
+    }
+
+    #[test]
+    fn trigger_lift_axis_raises_from_home_only() {
+        // Past the 0.02 rest dead zone a trigger's pull is stretched back over 0..1
+        let half = (0.5 - 0.02) / (1.0 - 0.02) * 15.0;
+        for (mode, rest, middle) in [(AxisMode::Unipolar, 0.0, 0.5), (AxisMode::UnipolarSigned, -1.0, 0.0)] {
+            let mut config = Config::default();
+            config.controls.joystick.lift_axis_mode = mode;
+            let mut gimbal = GimbalController::for_test(config);
+            assert_close(lift_for(&mut gimbal, rest), 0.0);
+            // Resting slightly open still reads as released
+            assert_close(lift_for(&mut gimbal, rest + 0.01), 0.0);
+            assert_close(lift_for(&mut gimbal, middle), half);
+            assert_close(lift_for(&mut gimbal, 1.0), 15.0);
+        }
+    }
+
+    #[test]
+    fn coupling_mixes_pitch_into_roll_before_the_limits() {
+        let mut config = Config::default();
+        config.gimbal.coupling = [[1.0, 0.0], [-0.2, 1.0]];
+        let mut gimbal = GimbalController::for_test(config);
+        gimbal.update(&InputState::default().with_axis(Axis::RightStickY, 0.5), DT);
+        assert_close(gimbal.get_state().pitch, 10.0);
+        assert_close(gimbal.get_state().roll, -2.0);
+        assert_close(gimbal.last_pipeline().roll.scaled, 0.0);
+        assert_close(gimbal.last_pipeline().roll.coupled, -0.1);
+    }
+}
@@ -0,0 +1,252 @@
+use crate::sequence::{Channel, Pose};
+use std::net::SocketAddr;
+use tokio::sync::oneshot;
+
+// A SetTarget call waiting for the main loop, which moves the gimbal and answers it
+pub struct TargetRequest {
+    pub targets: Vec<(Channel, f64)>,
+    pub peer: Option<SocketAddr>,
+    reply: oneshot::Sender<Result<Pose, String>>,
+}
+
+impl TargetRequest {
+    // The pose actually targeted, or why the move was refused
+    pub fn reply(self, result: Result<Pose, String>) {
+        // The client may have given up already; nothing to tell it then
+        let _ = self.reply.send(result);
+    }
+}
+
+#[cfg(feature = "grpc")]
+pub use service::GrpcOutput;
+#[cfg(not(feature = "grpc"))]
+pub use unsupported::GrpcOutput;
+
+#[cfg(feature = "grpc")]
+mod service {
+    use super::TargetRequest;
+    use crate::config::GrpcOutputConfig;
+    use crate::csv_log::unix_millis;
+    use crate::gimbal::GimbalState;
+    use crate::sequence::Channel;
+    use futures_util::Stream;
+    use std::net::{SocketAddr, TcpListener};
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::mpsc::{self, Receiver, Sender};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::sync::{oneshot, watch};
+    use tokio::time::MissedTickBehavior;
+    use tonic::transport::Server;
+    use tonic::transport::server::TcpIncoming;
+    use tonic::{Request, Response, Status};
+
+    mod proto {
+        tonic::include_proto!("gimbal");
+    }
+
+    use proto::gimbal_server::{Gimbal, GimbalServer};
+
+    // Serves proto/gimbal.proto. Like WebSocketOutput the server has its own thread and
+    // single-threaded tokio runtime, and watchers read the latest state from a watch
+    // channel; SetTarget calls queue up for the main loop, as command server requests do.
+    pub struct GrpcOutput {
+        address: SocketAddr,
+        latest: watch::Sender<proto::GimbalState>,
+        requests: Receiver<TargetRequest>,
+        watchers: Arc<AtomicUsize>,
+        // Set if the server stops after it started
+        failure: Arc<Mutex<Option<String>>>,
+        sequence: u64,
+    }
+
+    impl GrpcOutput {
+        // Binds before returning so a taken port is reported at startup
+        pub fn open(config: &GrpcOutputConfig) -> Result<Self, Box<dyn std::error::Error>> {
+            let listener = TcpListener::bind(&config.address)?;
+            listener.set_nonblocking(true)?;
+            let address = listener.local_addr()?;
+            let runtime = tokio::runtime::Builder::new_current_thread().enable_io().enable_time().build()?;
+            let (latest, receiver) = watch::channel(proto::GimbalState::default());
+            let (requests_tx, requests) = mpsc::channel();
+            let watchers = Arc::new(AtomicUsize::new(0));
+            let failure = Arc::new(Mutex::new(None));
+
+            let service = Service {
+                latest: receiver,
+                interval: Duration::from_secs_f64(1.0 / config.rate_hz.max(0.1)),
+                requests: requests_tx,
+                watchers: watchers.clone(),
+            };
+            let failed = failure.clone();
+            std::thread::spawn(move || {
+                runtime.block_on(async move {
+                    let result = match tokio::net::TcpListener::from_std(listener) {
+                        Ok(listener) => match TcpIncoming::from_listener(listener, true, None) {
+                            Ok(incoming) => Server::builder()
+                                .add_service(GimbalServer::new(service))
+                                .serve_with_incoming(incoming)
+                                .await
+                                .map_err(|e| e.to_string()),
+                            Err(e) => Err(e.to_string()),
+                        },
+                        Err(e) => Err(e.to_string()),
+                    };
+                    let error = result.err().unwrap_or_else(|| "server exited".to_string());
+                    if let Ok(mut failure) = failed.lock() {
+                        *failure = Some(format!("gRPC server stopped: {}", error));
+                    }
+                })
+            });
+
+            Ok(Self {
+                address,
+                latest,
+                requests,
+                watchers,
+                failure,
+                sequence: 0,
+            })
+        }
+
+        pub fn address(&self) -> SocketAddr {
+            self.address
+        }
+
+        // Clients with a WatchState stream open
+        pub fn watchers(&self) -> usize {
+            self.watchers.load(Ordering::Relaxed)
+        }
+
+        pub fn last_error(&self) -> Option<String> {
+            self.failure.lock().ok().and_then(|failure| failure.clone())
+        }
+
+        // Every SetTarget call that arrived since the last poll, without waiting
+        pub fn poll(&self) -> Vec<TargetRequest> {
+            self.requests.try_iter().collect()
+        }
+
+        pub fn send(&mut self, state: &GimbalState, armed: bool) {
+            self.latest.send_replace(proto::GimbalState {
+                pitch: state.pitch,
+                roll: state.roll,
+                lift: state.lift,
+                yaw: state.yaw,
+                armed,
+                timestamp_ms: unix_millis() as u64,
+                seq: self.sequence,
+            });
+            self.sequence += 1;
+        }
+    }
+
+    struct Service {
+        latest: watch::Receiver<proto::GimbalState>,
+        interval: Duration,
+        requests: Sender<TargetRequest>,
+        watchers: Arc<AtomicUsize>,
+    }
+
+    // Counts one WatchState stream for as long as tonic keeps it alive
+    struct Watcher(Arc<AtomicUsize>);
+
+    impl Watcher {
+        fn new(watchers: Arc<AtomicUsize>) -> Self {
+            watchers.fetch_add(1, Ordering::Relaxed);
+            Self(watchers)
+        }
+    }
+
+    impl Drop for Watcher {
+        fn drop(&mut self) {
+            self.0.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    #[tonic::async_trait]
+    impl Gimbal for Service {
+        type WatchStateStream = Pin<Box<dyn Stream<Item = Result<proto::GimbalState, Status>> + Send>>;
+
+        // One message per tick whether or not the state changed, so a quiet gimbal still shows it is alive
+        async fn watch_state(&self, _request: Request<proto::WatchRequest>) -> Result<Response<Self::WatchStateStream>, Status> {
+            let mut ticker = tokio::time::interval(self.interval);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            let watcher = Watcher::new(self.watchers.clone());
+            let stream = futures_util::stream::unfold((self.latest.clone(), ticker, watcher), |(latest, mut ticker, watcher)| async move {
+                ticker.tick().await;
+                let state = *latest.borrow();
+                Some((Ok(state), (latest, ticker, watcher)))
+            });
+            Ok(Response::new(Box::pin(stream)))
+        }
+
+        async fn set_target(&self, request: Request<proto::Target>) -> Result<Response<proto::SetTargetReply>, Status> {
+            let peer = request.remote_addr();
+            let target = request.into_inner();
+            let targets: Vec<(Channel, f64)> = [
+                (Channel::Pitch, target.pitch),
+                (Channel::Roll, target.roll),
+                (Channel::Lift, target.lift),
+                (Channel::Yaw, target.yaw),
+            ]
+            .into_iter()
+            .filter_map(|(channel, value)| value.map(|v| (channel, v)))
+            .collect();
+            if let Some((channel, _)) = targets.iter().find(|(_, value)| !value.is_finite()) {
+                return Err(Status::invalid_argument(format!("{} is not a finite number", channel.name())));
+            }
+
+            let (reply, answer) = oneshot::channel();
+            self.requests
+                .send(TargetRequest { targets, peer, reply })
+                .map_err(|_| Status::unavailable("the gimbal is shutting down"))?;
+            match answer.await {
+                Ok(Ok((pitch, roll, lift, yaw))) => Ok(Response::new(proto::SetTargetReply { pitch, roll, lift, yaw })),
+                Ok(Err(e)) => Err(Status::failed_precondition(e)),
+                Err(_) => Err(Status::unavailable("the gimbal is shutting down")),
+            }
+        }
+    }
+}
+
+// Stands in when the grpc feature is off, so [output.grpc] can say why nothing is listening
+#[cfg(not(feature = "grpc"))]
+mod unsupported {
+    use super::TargetRequest;
+    use crate::config::GrpcOutputConfig;
+    use crate::gimbal::GimbalState;
+    use std::convert::Infallible;
+    use std::net::SocketAddr;
+
+    pub struct GrpcOutput {
+        never: Infallible,
+    }
+
+    impl GrpcOutput {
+        pub fn open(_config: &GrpcOutputConfig) -> Result<Self, Box<dyn std::error::Error>> {
+            Err("this build has no gRPC support (rebuild with --features grpc)".into())
+        }
+
+        pub fn address(&self) -> SocketAddr {
+            match self.never {}
+        }
+
+        pub fn watchers(&self) -> usize {
+            match self.never {}
+        }
+
+        pub fn last_error(&self) -> Option<String> {
+            match self.never {}
+        }
+
+        pub fn poll(&self) -> Vec<TargetRequest> {
+            match self.never {}
+        }
+
+        pub fn send(&mut self, _state: &GimbalState, _armed: bool) {
+            match self.never {}
+        }
+    }
+}
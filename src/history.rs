@@ -0,0 +1,75 @@
+// How far back the debug sparklines reach
+pub const HISTORY_SECS: f64 = 5.0;
+// A sample of exactly 0.0 straight after one further out than this is counted as a dropout;
+// a stick let go passes through smaller values on its way back to center
+const DROPOUT_FROM: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sample {
+    // None while the axis isn't reporting (unmapped, or no gamepad)
+    pub value: Option<f64>,
+    pub dropout: bool,
+}
+
+// The last `capacity` samples of one axis, one per update step, for spotting intermittent
+// dropouts. A fixed ring: pushing overwrites the oldest sample once it is full.
+pub struct AxisHistory {
+    samples: Vec<Sample>,
+    // Where the next sample goes; the oldest sample once the ring has wrapped
+    next: usize,
+    len: usize,
+}
+
+impl AxisHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: vec![Sample::default(); capacity.max(1)],
+            next: 0,
+            len: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn push(&mut self, value: Option<f64>) {
+        let last = (self.next + self.samples.len() - 1) % self.samples.len();
+        let previous = if self.len > 0 { self.samples[last].value } else { None };
+        let dropout = value == Some(0.0) && previous.is_some_and(|p| p.abs() > DROPOUT_FROM);
+        self.samples[self.next] = Sample { value, dropout };
+        self.next = (self.next + 1) % self.samples.len();
+        self.len = (self.len + 1).min(self.samples.len());
+    }
+
+    // Oldest first
+    pub fn iter(&self) -> impl Iterator<Item = &Sample> {
+        let start = (self.next + self.samples.len() - self.len) % self.samples.len();
+        let (wrapped, head) = self.samples.split_at(start);
+        head.iter().chain(wrapped).take(self.len)
+    }
+
+    pub fn dropouts(&self) -> usize {
+        self.iter().filter(|sample| sample.dropout).count()
+    }
+
+    // The history squeezed into at most `columns` samples, newest last: each column averages
+    // its run of samples and keeps any dropout in it, so narrowing the panel never hides one
+    pub fn columns(&self, columns: usize) -> Vec<Sample> {
+        let samples: Vec<&Sample> = self.iter().collect();
+        let per_column = samples.len().div_ceil(columns.max(1)).max(1);
+        // Runs are counted back from the newest sample, so the right edge is always current
+        let mut result: Vec<Sample> = samples
+            .rchunks(per_column)
+            .map(|run| {
+                let values: Vec<f64> = run.iter().filter_map(|sample| sample.value).collect();
+                Sample {
+                    value: (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64),
+                    dropout: run.iter().any(|sample| sample.dropout),
+                }
+            })
+            .collect();
+        result.reverse();
+        result
+    }
+}
@@ -0,0 +1,184 @@
+use crate::config::{JoystickConfig, VirtualAxis, VirtualGamepadConfig, VirtualSignal, parse_axis_name};
+use gilrs::{Axis, Button, EventType, GamepadId, Gilrs};
+use std::collections::VecDeque;
+use std::f64::consts::TAU;
+use std::time::{Duration, Instant, SystemTime};
+
+// How often the virtual pad reports its axes, about a real pad's rate
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(10);
+
+// Sine periods for the mapped pitch, roll and lift when no axes are configured
+const DEFAULT_PERIODS: [f64; 3] = [4.0, 6.0, 10.0];
+
+// A gamepad, whichever source it comes from. gilrs pads keep their gilrs number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PadId(usize);
+
+impl From<GamepadId> for PadId {
+    fn from(id: GamepadId) -> Self {
+        PadId(usize::from(id))
+    }
+}
+
+impl From<PadId> for usize {
+    fn from(id: PadId) -> usize {
+        id.0
+    }
+}
+
+// gilrs isn't started next to the virtual pad, so it can't hand out the same number
+const VIRTUAL_PAD: PadId = PadId(0);
+
+// The part of a gilrs event the app acts on; everything else only shows in the event pane
+#[derive(Debug, Clone, PartialEq)]
+pub enum PadEvent {
+    ButtonPressed(Button),
+    ButtonRepeated(Button),
+    ButtonReleased(Button),
+    ButtonChanged(Button, f32),
+    AxisChanged(Axis, f32),
+    Connected,
+    Disconnected,
+    Other(String),
+}
+
+pub struct InputEvent {
+    pub pad: PadId,
+    pub name: String,
+    pub event: PadEvent,
+    // The driver's raw code for the element, when there is a driver
+    pub code: Option<String>,
+    pub time: SystemTime,
+}
+
+// Where gamepad events come from: gilrs for real devices, the virtual pad for running
+// without one. App::update drains every source each update.
+pub trait InputSource {
+    // The next pending event, or None when there is nothing more for now
+    fn next_event(&mut self) -> Option<InputEvent>;
+}
+
+impl InputSource for Gilrs {
+    fn next_event(&mut self) -> Option<InputEvent> {
+        let gilrs::Event { id, event, time, .. } = Gilrs::next_event(self)?;
+        let (event, code) = match event {
+            EventType::ButtonPressed(button, code) => (PadEvent::ButtonPressed(button), Some(code)),
+            EventType::ButtonRepeated(button, code) => (PadEvent::ButtonRepeated(button), Some(code)),
+            EventType::ButtonReleased(button, code) => (PadEvent::ButtonReleased(button), Some(code)),
+            EventType::ButtonChanged(button, value, code) => (PadEvent::ButtonChanged(button, value), Some(code)),
+            EventType::AxisChanged(axis, value, code) => (PadEvent::AxisChanged(axis, value), Some(code)),
+            EventType::Connected => (PadEvent::Connected, None),
+            EventType::Disconnected => (PadEvent::Disconnected, None),
+            other => (PadEvent::Other(format!("{:?}", other)), None),
+        };
+        Some(InputEvent {
+            pad: id.into(),
+            name: self.gamepad(id).name().to_string(),
+            event,
+            code: code.map(|code| code.to_string()),
+            time,
+        })
+    }
+}
+
+// A pad that isn't there: it connects on the first poll, then reports each configured axis
+// every SAMPLE_INTERVAL, so everything downstream sees it like a real device
+pub struct VirtualPad {
+    name: String,
+    axes: Vec<(Axis, VirtualAxis)>,
+    values: Vec<f32>,
+    started: Instant,
+    last_sample: Option<Instant>,
+    pending: VecDeque<PadEvent>,
+    noise: Noise,
+}
+
+impl VirtualPad {
+    pub fn new(config: &VirtualGamepadConfig, joystick: &JoystickConfig) -> Self {
+        let axes: Vec<VirtualAxis> = if config.axes.is_empty() {
+            [&joystick.pitch_axis, &joystick.roll_axis, &joystick.lift_axis]
+                .into_iter()
+                .zip(DEFAULT_PERIODS)
+                .map(|(axis, period_secs)| VirtualAxis {
+                    axis: axis.clone(),
+                    signal: VirtualSignal::Sine,
+                    amplitude: 1.0,
+                    period_secs,
+                    offset: 0.0,
+                })
+                .collect()
+        } else {
+            config.axes.clone()
+        };
+        let axes: Vec<(Axis, VirtualAxis)> =
+            axes.into_iter().filter_map(|entry| Some((parse_axis_name(&entry.axis)?, entry))).collect();
+        Self {
+            name: config.name.clone(),
+            values: vec![f32::NAN; axes.len()],
+            axes,
+            started: Instant::now(),
+            last_sample: None,
+            pending: VecDeque::new(),
+            noise: Noise(0x2545_f491_4f6c_dd1d),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn sample(&mut self) {
+        let now = Instant::now();
+        match self.last_sample {
+            None => self.pending.push_back(PadEvent::Connected),
+            Some(at) if now.duration_since(at) < SAMPLE_INTERVAL => return,
+            Some(_) => {}
+        }
+        self.last_sample = Some(now);
+        let t = now.duration_since(self.started).as_secs_f64();
+        for ((axis, entry), last) in self.axes.iter().zip(&mut self.values) {
+            let phase = t / entry.period_secs;
+            let wave = match entry.signal {
+                VirtualSignal::Sine => (TAU * phase).sin(),
+                VirtualSignal::Step if phase.fract() < 0.5 => 1.0,
+                VirtualSignal::Step => -1.0,
+                VirtualSignal::Noise => self.noise.next_value(),
+            };
+            let value = (entry.offset + entry.amplitude * wave).clamp(-1.0, 1.0) as f32;
+            // Like a driver, only changes are reported
+            if value != *last {
+                *last = value;
+                self.pending.push_back(PadEvent::AxisChanged(*axis, value));
+            }
+        }
+    }
+}
+
+// xorshift64, so the noise is the same from run to run
+struct Noise(u64);
+
+impl Noise {
+    // Uniform in -1..1
+    fn next_value(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+    }
+}
+
+impl InputSource for VirtualPad {
+    fn next_event(&mut self) -> Option<InputEvent> {
+        if self.pending.is_empty() {
+            self.sample();
+        }
+        let event = self.pending.pop_front()?;
+        Some(InputEvent {
+            pad: VIRTUAL_PAD,
+            name: self.name.clone(),
+            event,
+            code: None,
+            time: SystemTime::now(),
+        })
+    }
+}
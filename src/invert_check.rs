@@ -0,0 +1,165 @@
+use crate::detect::DetectChannel;
+use crate::input_source::PadId;
+use gilrs::Axis;
+use std::collections::HashMap;
+
+// Distance from rest that counts as pushing the control, as in axis detection
+const MOVE_THRESHOLD: f64 = 0.3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStep {
+    Release, // Waiting for every axis to settle before the next channel
+    Push,    // Waiting for the channel's control to be pushed the positive way
+    Confirm, // Held; the user says whether the plate moved the right way
+    Review,  // Every channel answered, waiting for save or close
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    Kept,
+    Flipped,
+    Skipped,
+}
+
+// What the plate should do for each channel's positive push, and the opposite
+fn motion(channel: DetectChannel) -> (&'static str, &'static str) {
+    match channel {
+        DetectChannel::Pitch => ("pitch forward", "pitch backward"),
+        DetectChannel::Roll => ("roll right", "roll left"),
+        DetectChannel::Lift => ("rise", "sink"),
+    }
+}
+
+fn push_direction(channel: DetectChannel) -> &'static str {
+    match channel {
+        DetectChannel::Pitch => "forward",
+        DetectChannel::Roll => "right",
+        DetectChannel::Lift => "up",
+    }
+}
+
+// A guided check of the invert flags: each channel's control is pushed the positive way,
+// the sign the mapping gives it suggests an answer, and the user confirms what the plate did.
+// Unlike axis detection the mapping stays live, so the plate moves while the stick is held.
+pub struct InvertCheck {
+    pub gamepad_id: PadId,
+    pub gamepad_name: String,
+    pub message: Option<String>,
+    step: CheckStep,
+    channel: usize,
+    // Each axis' value when first seen, taken as its rest position
+    rest: HashMap<Axis, f64>,
+    // The mapped value's sign when the push was caught: positive means the right way round
+    reads_positive: bool,
+    results: Vec<(DetectChannel, Answer)>,
+}
+
+impl InvertCheck {
+    pub fn new(gamepad_id: PadId, gamepad_name: String) -> Self {
+        Self {
+            gamepad_id,
+            gamepad_name,
+            message: None,
+            step: CheckStep::Release,
+            channel: 0,
+            rest: HashMap::new(),
+            reads_positive: true,
+            results: Vec::new(),
+        }
+    }
+
+    pub fn step(&self) -> CheckStep {
+        self.step
+    }
+
+    pub fn current(&self) -> Option<DetectChannel> {
+        DetectChannel::ALL.get(self.channel).copied()
+    }
+
+    pub fn results(&self) -> &[(DetectChannel, Answer)] {
+        &self.results
+    }
+
+    pub fn prompt(&self) -> String {
+        match (self.step, self.current()) {
+            (CheckStep::Release, Some(_)) => "Let go of the controls".to_string(),
+            (CheckStep::Push, Some(channel)) => format!(
+                "Push the control for {} {} and hold it",
+                channel.name(),
+                push_direction(channel)
+            ),
+            (CheckStep::Confirm, Some(channel)) => {
+                let (expected, opposite) = motion(channel);
+                let suggestion = if self.reads_positive {
+                    format!("it reads {}: probably right", expected)
+                } else {
+                    format!("it reads {}: probably inverted", opposite)
+                };
+                format!("Does the plate {}? y/n ({})", expected, suggestion)
+            }
+            _ => "Done - Enter to save, Esc to keep the changes for this session only".to_string(),
+        }
+    }
+
+    // `axes` are the gamepad's raw values, `mapped` the axis the channel reads and `value`
+    // what the mapping makes of it, after trim and inversion
+    pub fn sample(&mut self, axes: &HashMap<Axis, f32>, mapped: Option<Axis>, value: f64) {
+        for (&axis, &raw) in axes {
+            self.rest.entry(axis).or_insert(raw as f64);
+        }
+        let Some(channel) = self.current() else { return };
+        // The axis pushed furthest from rest, if any is pushed far enough to count
+        let pushed = axes
+            .iter()
+            .map(|(&axis, &raw)| (axis, (raw as f64 - self.rest[&axis]).abs()))
+            .filter(|&(_, offset)| offset >= MOVE_THRESHOLD)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(axis, _)| axis);
+        match (self.step, pushed) {
+            (CheckStep::Release, None) => self.step = CheckStep::Push,
+            (CheckStep::Push, Some(axis)) if Some(axis) != mapped => {
+                self.message = Some(match mapped {
+                    Some(mapped) => format!(
+                        "{:?} moved, but {} reads {:?} - skip with s and run axis detection",
+                        axis,
+                        channel.name(),
+                        mapped
+                    ),
+                    None => format!("{} has no axis mapped - skip it with s", channel.name()),
+                });
+            }
+            (CheckStep::Push, Some(_)) if value != 0.0 => {
+                self.reads_positive = value > 0.0;
+                self.message = None;
+                self.step = CheckStep::Confirm;
+            }
+            _ => {}
+        }
+    }
+
+    // The user's y/n while a push is held; the channel whose invert flag should flip on a no
+    pub fn answer(&mut self, moved_right: bool) -> Option<DetectChannel> {
+        let channel = self.current().filter(|_| self.step == CheckStep::Confirm)?;
+        let answer = if moved_right { Answer::Kept } else { Answer::Flipped };
+        self.next_channel(channel, answer);
+        (answer == Answer::Flipped).then_some(channel)
+    }
+
+    // Leaves the channel as it is, e.g. with no control mapped to it
+    pub fn skip(&mut self) {
+        if let Some(channel) = self.current() {
+            self.message = None;
+            self.next_channel(channel, Answer::Skipped);
+        }
+    }
+
+    fn next_channel(&mut self, channel: DetectChannel, answer: Answer) {
+        self.results.push((channel, answer));
+        self.channel += 1;
+        self.step = if self.current().is_some() { CheckStep::Release } else { CheckStep::Review };
+    }
+
+    pub fn flipped(&self) -> usize {
+        self.results.iter().filter(|(_, answer)| *answer == Answer::Flipped).count()
+    }
+}
@@ -0,0 +1,474 @@
+use crate::config::{Config, EnvelopeMode, GeometryConfig, PlatformType};
+use crate::gimbal::GimbalState;
+
+// Scissor lifts sit at this fraction of the plate radius
+const SCISSOR_RADIUS_FACTOR: f64 = 0.75;
+// Bisection steps when scaling a pose into the envelope (1/65536 of the pose)
+const ENVELOPE_ITERATIONS: usize = 16;
+
+/// Full plate pose; x/y translation is carried through the Stewart math
+/// but not yet driven by any input
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Pose {
+    pub x: f64,
+    pub y: f64,
+    pub lift: f64,
+    pub pitch: f64,
+    pub roll: f64,
+    pub yaw: f64,
+}
+
+impl From<&GimbalState> for Pose {
+    fn from(state: &GimbalState) -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            lift: state.lift,
+            pitch: state.pitch,
+            roll: state.roll,
+            yaw: state.yaw,
+        }
+    }
+}
+
+/// Points are (x, y, height) with y pointing forward and height up
+#[derive(Debug, Clone, Copy)]
+pub struct Actuator {
+    pub base: (f64, f64, f64),
+    pub top: (f64, f64, f64),
+    /// Change from the neutral length in mm
+    pub extension: f64,
+}
+
+impl Actuator {
+    pub fn over_travel(&self, limits: &LegLimits) -> bool {
+        self.travel_excess(limits) > 0.0
+    }
+
+    /// How far past its nearest limit the leg is in mm, negative while it is inside them
+    pub fn travel_excess(&self, limits: &LegLimits) -> f64 {
+        let (low, high) = limits.extension_range(self);
+        let length = self.length();
+        let mut excess = (low - self.extension).max(self.extension - high);
+        if let Some(min_leg) = limits.min_leg {
+            excess = excess.max(min_leg - length);
+        }
+        if let Some(max_leg) = limits.max_leg {
+            excess = excess.max(length - max_leg);
+        }
+        excess
+    }
+
+    /// Straight-line distance between the base and top joints, in mm
+    pub fn length(&self) -> f64 {
+        distance(self.base, self.top)
+    }
+}
+
+/// Where a leg may go: its stroke either side of neutral (widened to wherever the lift range
+/// reaches, so every lift is reachable level), and when `[geometry]` sets them, a joint-to-joint
+/// length between min_leg and max_leg
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LegLimits {
+    pub retract: f64,
+    pub extend: f64,
+    pub min_leg: Option<f64>,
+    pub max_leg: Option<f64>,
+}
+
+impl LegLimits {
+    pub fn new(config: &Config) -> Self {
+        let (geometry, gimbal) = (&config.geometry, &config.gimbal);
+        // Extension is measured from lift 0, so an uneven lift range gives uneven ends
+        Self {
+            retract: (-geometry.actuator_stroke).min(gimbal.lift_min),
+            extend: geometry.actuator_stroke.max(gimbal.lift_max),
+            min_leg: geometry.min_leg,
+            max_leg: geometry.max_leg,
+        }
+    }
+
+    /// Lowest and highest extension for this leg, the stroke narrowed by the length limits
+    pub fn extension_range(&self, actuator: &Actuator) -> (f64, f64) {
+        let neutral = actuator.length() - actuator.extension;
+        let low = self.min_leg.map_or(self.retract, |min_leg| self.retract.max(min_leg - neutral));
+        let high = self.max_leg.map_or(self.extend, |max_leg| self.extend.min(max_leg - neutral));
+        (low, high)
+    }
+
+    /// Extension as a fraction of the travel on its side of neutral: -1 fully retracted, 1 fully extended
+    pub fn usage(&self, actuator: &Actuator) -> f64 {
+        let (low, high) = self.extension_range(actuator);
+        if actuator.extension >= 0.0 {
+            actuator.extension / high.max(f64::EPSILON)
+        } else {
+            actuator.extension / (-low).max(f64::EPSILON)
+        }
+    }
+}
+
+/// The plate plane z = a*x + b*y + c through the actuator tops as [a, b, c], a least-squares
+/// fit past three; None when the tops don't span a plane
+pub fn plate_plane(actuators: &[Actuator]) -> Option<[f64; 3]> {
+    let mut ata = [[0.0; 3]; 3];
+    let mut atb = [0.0; 3];
+    for actuator in actuators {
+        let (x, y, z) = actuator.top;
+        let row = [x, y, 1.0];
+        for i in 0..3 {
+            for j in 0..3 {
+                ata[i][j] += row[i] * row[j];
+            }
+            atb[i] += row[i] * z;
+        }
+    }
+    solve_3x3(ata, atb)
+}
+
+/// Height change of a plate point from tilt: pitch raises the +y side, roll the +x side
+pub fn tilt_offset(x: f64, y: f64, pitch: f64, roll: f64) -> f64 {
+    y * pitch.to_radians().sin() + x * roll.to_radians().sin()
+}
+
+pub fn rotate_yaw(x: f64, y: f64, yaw: f64) -> (f64, f64) {
+    let yaw = yaw.to_radians();
+    (x * yaw.cos() - y * yaw.sin(), x * yaw.sin() + y * yaw.cos())
+}
+
+pub fn solve(geometry: &GeometryConfig, state: &GimbalState) -> Vec<Actuator> {
+    let pose = Pose::from(state);
+    match geometry.platform_type {
+        PlatformType::Scissor3 => solve_scissor(geometry, &pose),
+        PlatformType::Stewart6 => solve_stewart(geometry, &pose),
+    }
+}
+
+fn solve_scissor(geometry: &GeometryConfig, pose: &Pose) -> Vec<Actuator> {
+    let radius = geometry.plate_radius * SCISSOR_RADIUS_FACTOR;
+    geometry
+        .scissor_angles
+        .iter()
+        .map(|angle| {
+            let angle = angle.to_radians();
+            let (x, y) = (radius * angle.cos(), radius * angle.sin());
+            let height = geometry.nominal_height + pose.lift + tilt_offset(x, y, pose.pitch, pose.roll);
+            let (top_x, top_y) = rotate_yaw(x, y, pose.yaw);
+            Actuator {
+                base: (x, y, geometry.base_height),
+                top: (top_x + pose.x, top_y + pose.y, height),
+                extension: height - geometry.nominal_height,
+            }
+        })
+        .collect()
+}
+
+// Moves a point from the platform frame into the world frame: yaw, then pitch
+// about x (forward edge up), then roll about y (right edge up), then translate
+fn platform_to_world(geometry: &GeometryConfig, pose: &Pose, x: f64, y: f64) -> (f64, f64, f64) {
+    let (x, y) = rotate_yaw(x, y, pose.yaw);
+
+    let pitch = pose.pitch.to_radians();
+    let (y, z) = (y * pitch.cos(), y * pitch.sin());
+
+    let roll = pose.roll.to_radians();
+    let (x, z) = (x * roll.cos() - z * roll.sin(), z * roll.cos() + x * roll.sin());
+
+    (x + pose.x, y + pose.y, z + geometry.nominal_height + pose.lift)
+}
+
+fn solve_stewart(geometry: &GeometryConfig, pose: &Pose) -> Vec<Actuator> {
+    let neutral = Pose::default();
+    geometry
+        .base_angles
+        .iter()
+        .zip(&geometry.platform_angles)
+        .map(|(base_angle, platform_angle)| {
+            let (base_angle, platform_angle) = (base_angle.to_radians(), platform_angle.to_radians());
+            let base = (
+                geometry.base_radius * base_angle.cos(),
+                geometry.base_radius * base_angle.sin(),
+                geometry.base_height,
+            );
+            let (px, py) = (
+                geometry.platform_radius * platform_angle.cos(),
+                geometry.platform_radius * platform_angle.sin(),
+            );
+            let top = platform_to_world(geometry, pose, px, py);
+            let neutral_top = platform_to_world(geometry, &neutral, px, py);
+            Actuator {
+                base,
+                top,
+                extension: distance(base, top) - distance(base, neutral_top),
+            }
+        })
+        .collect()
+}
+
+fn distance(a: (f64, f64, f64), b: (f64, f64, f64)) -> f64 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2) + (a.2 - b.2).powi(2)).sqrt()
+}
+
+/// Indices of the actuators past their stroke or length limits for this state
+pub fn limiting_actuators(config: &Config, state: &GimbalState) -> Vec<usize> {
+    let limits = LegLimits::new(config);
+    solve(&config.geometry, state)
+        .iter()
+        .enumerate()
+        .filter(|(_, actuator)| actuator.over_travel(&limits))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// The leg closest to (or furthest past) its own limit; once the envelope has pulled a pose
+/// back this is the one sitting at its limit and holding the rest of the pose back
+pub fn binding_actuator(actuators: &[Actuator], limits: &LegLimits) -> Option<usize> {
+    actuators
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.travel_excess(limits).total_cmp(&b.1.travel_excess(limits)))
+        .map(|(i, _)| i)
+}
+
+/// Pulls an unreachable pose back inside the actuator envelope according to
+/// geometry.envelope; returns the actuators that were out of stroke beforehand
+pub fn limit_to_envelope(config: &Config, state: &mut GimbalState) -> Vec<usize> {
+    let limiting = limiting_actuators(config, state);
+    if limiting.is_empty() {
+        return limiting;
+    }
+
+    match config.geometry.envelope {
+        EnvelopeMode::Off => {}
+        EnvelopeMode::Clamp => {
+            if config.geometry.platform_type == PlatformType::Scissor3 {
+                fit_clamped_scissor(config, state);
+            }
+            // The refit plate can still miss with more than three lifts
+            scale_into_envelope(config, state);
+        }
+        EnvelopeMode::Scale => scale_into_envelope(config, state),
+    }
+    limiting
+}
+
+// Largest uniform scale of the pose (toward level at lift_home) that keeps every actuator in stroke
+fn scale_into_envelope(config: &Config, state: &mut GimbalState) {
+    if limiting_actuators(config, state).is_empty() {
+        return;
+    }
+
+    let commanded = state.clone();
+    let home = config.gimbal.lift_home;
+    let scaled = |k: f64| GimbalState {
+        pitch: commanded.pitch * k,
+        roll: commanded.roll * k,
+        lift: home + (commanded.lift - home) * k,
+        yaw: commanded.yaw * k,
+        ..commanded.clone()
+    };
+    let (mut feasible, mut infeasible) = (0.0, 1.0);
+    for _ in 0..ENVELOPE_ITERATIONS {
+        let k = (feasible + infeasible) / 2.0;
+        if limiting_actuators(config, &scaled(k)).is_empty() {
+            feasible = k;
+        } else {
+            infeasible = k;
+        }
+    }
+    *state = scaled(feasible);
+}
+
+// Clamps each scissor lift to its travel and fits lift/pitch/roll to the clamped
+// heights by least squares (exact for three lifts)
+fn fit_clamped_scissor(config: &Config, state: &mut GimbalState) {
+    let limits = LegLimits::new(config);
+    // Each row is height = lift + y * sin(pitch) + x * sin(roll)
+    let rows: Vec<([f64; 3], f64)> = solve(&config.geometry, state)
+        .iter()
+        .map(|actuator| {
+            let (x, y, _) = actuator.base;
+            let (low, high) = limits.extension_range(actuator);
+            ([1.0, y, x], actuator.extension.clamp(low, high.max(low)))
+        })
+        .collect();
+
+    let mut ata = [[0.0; 3]; 3];
+    let mut atb = [0.0; 3];
+    for (row, target) in &rows {
+        for i in 0..3 {
+            for j in 0..3 {
+                ata[i][j] += row[i] * row[j];
+            }
+            atb[i] += row[i] * target;
+        }
+    }
+
+    if let Some([lift, pitch, roll]) = solve_3x3(ata, atb) {
+        state.lift = lift;
+        state.pitch = pitch.clamp(-1.0, 1.0).asin().to_degrees();
+        state.roll = roll.clamp(-1.0, 1.0).asin().to_degrees();
+    }
+}
+
+// Cramer's rule; None when the lifts don't span a plane (e.g. all in a line)
+fn solve_3x3(a: [[f64; 3]; 3], b: [f64; 3]) -> Option<[f64; 3]> {
+    let det = |m: [[f64; 3]; 3]| {
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1]) - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    };
+    let d = det(a);
+    if d.abs() < 1e-9 {
+        return None;
+    }
+    let mut solution = [0.0; 3];
+    for (column, value) in solution.iter_mut().enumerate() {
+        let mut m = a;
+        for row in 0..3 {
+            m[row][column] = b[row];
+        }
+        *value = det(m) / d;
+    }
+    Some(solution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pose(pitch: f64, roll: f64, lift: f64) -> GimbalState {
+        GimbalState { pitch, roll, lift, ..GimbalState::default() }
+    }
+
+    fn within_stroke(config: &Config, state: &GimbalState) -> bool {
+        let limits = LegLimits::new(config);
+        solve(&config.geometry, state).iter().all(|actuator| actuator.travel_excess(&limits) < 1e-9)
+    }
+
+    // The README's scissor lifts: fully collapsed at 0, 40mm up, resting at 12
+    fn collapsing_lifts() -> Config {
+        let mut config = Config::default();
+        (config.gimbal.lift_min, config.gimbal.lift_max, config.gimbal.lift_home) = (0.0, 40.0, 12.0);
+        config
+    }
+
+    #[test]
+    fn corner_poses_scale_back_along_the_commanded_direction() {
+        // Lift adds to the tilt on A1 and A2 for the first two, and on A2 alone for the third
+        let config = Config::default();
+        for (corner, expected) in
+            [(pose(20.0, 20.0, 15.0), 0.610), (pose(-20.0, -20.0, -15.0), 0.610), (pose(20.0, -20.0, 15.0), 0.494)]
+        {
+            let mut state = corner.clone();
+            let limiting = limit_to_envelope(&config, &mut state);
+            assert!(!limiting.is_empty(), "{:?} should be out of stroke", corner);
+            assert!(within_stroke(&config, &state), "{:?} scaled to {:?}", corner, state);
+
+            // One common factor for every channel, so the pose keeps its direction
+            let k = state.pitch / corner.pitch;
+            assert!((k - expected).abs() < 1e-3, "scale {}", k);
+            assert!((state.roll - corner.roll * k).abs() < 1e-9);
+            assert!((state.lift - corner.lift * k).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn scaled_corner_is_the_largest_that_fits() {
+        let config = Config::default();
+        let corner = pose(20.0, 20.0, 15.0);
+        let mut state = corner.clone();
+        limit_to_envelope(&config, &mut state);
+        let k = state.pitch / corner.pitch;
+        let further = pose(corner.pitch * (k + 0.001), corner.roll * (k + 0.001), corner.lift * (k + 0.001));
+        assert!(!limiting_actuators(&config, &further).is_empty());
+    }
+
+    #[test]
+    fn clamp_mode_fits_the_plate_to_the_clamped_lifts() {
+        let mut config = Config::default();
+        config.geometry.envelope = EnvelopeMode::Clamp;
+        for corner in [pose(20.0, 20.0, 15.0), pose(-20.0, -20.0, -15.0)] {
+            let mut state = corner.clone();
+            assert!(!limit_to_envelope(&config, &mut state).is_empty());
+            assert!(within_stroke(&config, &state), "{:?} clamped to {:?}", corner, state);
+            assert_eq!(state.pitch.signum(), corner.pitch.signum());
+            assert_eq!(state.roll.signum(), corner.roll.signum());
+        }
+    }
+
+    #[test]
+    fn leg_length_limit_binds_inside_the_angle_limits() {
+        // Legs are 45mm level; pitch 15 raises A2 and lowers A3 by the same 16.8mm, inside the
+        // stroke, but only A2 grows past max_leg
+        let mut config = Config::default();
+        config.geometry.max_leg = Some(55.0);
+        let limits = LegLimits::new(&config);
+        let commanded = pose(15.0, 0.0, 0.0);
+        let legs = solve(&config.geometry, &commanded);
+        assert!(legs.iter().all(|leg| leg.extension.abs() < config.geometry.actuator_stroke));
+        assert_eq!(limiting_actuators(&config, &commanded), vec![1]);
+        assert!((legs[1].length() - 61.8).abs() < 0.1, "A2 at {}", legs[1].length());
+        assert!((legs[1].travel_excess(&limits) - (legs[1].length() - 55.0)).abs() < 1e-9);
+
+        let mut state = commanded.clone();
+        assert_eq!(limit_to_envelope(&config, &mut state), vec![1]);
+        let legs = solve(&config.geometry, &state);
+        assert!(state.pitch > 0.0 && state.pitch < 15.0, "pitch {}", state.pitch);
+        assert!(legs[1].length() <= 55.0 + 1e-9 && legs[1].length() > 54.9, "A2 at {}", legs[1].length());
+        // A3 is as far from neutral as A2 but nowhere near a limit
+        assert_eq!(binding_actuator(&legs, &limits), Some(1));
+    }
+
+    #[test]
+    fn reachable_tilt_is_left_alone() {
+        // A3 drops 21.3mm of its 25mm stroke here
+        let config = Config::default();
+        let mut state = pose(12.0, 12.0, 0.0);
+        assert!(limit_to_envelope(&config, &mut state).is_empty());
+        assert_eq!(state, pose(12.0, 12.0, 0.0));
+    }
+
+    #[test]
+    fn both_ends_of_an_uneven_lift_range_are_reachable_level() {
+        let config = collapsing_lifts();
+        let limits = LegLimits::new(&config);
+        assert_eq!((limits.retract, limits.extend), (-25.0, 40.0));
+        for lift in [0.0, 12.0, 40.0] {
+            let mut state = pose(0.0, 0.0, lift);
+            assert!(limit_to_envelope(&config, &mut state).is_empty(), "lift {}", lift);
+            assert_eq!(state.lift, lift);
+        }
+    }
+
+    #[test]
+    fn tilt_at_the_top_of_the_range_scales_toward_lift_home() {
+        let config = collapsing_lifts();
+        let corner = pose(20.0, 0.0, 40.0);
+        let mut state = corner.clone();
+        assert!(!limit_to_envelope(&config, &mut state).is_empty());
+        assert!(within_stroke(&config, &state), "scaled to {:?}", state);
+
+        // Lift keeps the same share of its travel above home as pitch keeps of its angle
+        let k = state.pitch / corner.pitch;
+        assert!((k - 0.554).abs() < 1e-3, "scale {}", k);
+        assert!((state.lift - (12.0 + 28.0 * k)).abs() < 1e-9, "lift {}", state.lift);
+        assert!(state.lift > 25.0, "lift {} fell toward 0 instead of home", state.lift);
+    }
+
+    #[test]
+    fn tilt_at_the_bottom_of_the_range_scales_toward_lift_home() {
+        // A range that reaches below the stroke, so the low end is the one that binds
+        let mut config = Config::default();
+        (config.gimbal.lift_min, config.gimbal.lift_max, config.gimbal.lift_home) = (-30.0, 10.0, -5.0);
+        let limits = LegLimits::new(&config);
+        assert_eq!((limits.retract, limits.extend), (-30.0, 25.0));
+        assert!(limiting_actuators(&config, &pose(0.0, 0.0, -30.0)).is_empty());
+
+        let corner = pose(-20.0, 0.0, -30.0);
+        let mut state = corner.clone();
+        assert!(!limit_to_envelope(&config, &mut state).is_empty());
+        assert!(within_stroke(&config, &state), "scaled to {:?}", state);
+        let k = state.pitch / corner.pitch;
+        assert!((k - 0.526).abs() < 1e-3, "scale {}", k);
+        assert!((state.lift - (-5.0 - 25.0 * k)).abs() < 1e-9, "lift {}", state.lift);
+    }
+}
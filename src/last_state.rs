@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Bumped whenever the layout changes; files with another version are ignored
+pub const STATE_VERSION: u32 = 1;
+
+// Trims of one joystick mapping: "default" for [controls.joystick], else a profile name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedTrims {
+    pub mapping: String,
+    pub pitch: f64,
+    pub roll: f64,
+    pub lift: f64,
+    pub yaw: f64,
+}
+
+// Where the gimbal was left on the last clean exit, for app.restore_last_state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastState {
+    pub version: u32,
+    pub pitch: f64,
+    pub roll: f64,
+    pub lift: f64,
+    pub yaw: f64,
+    pub trims: SavedTrims,
+}
+
+impl LastState {
+    // Missing, unreadable, corrupt or from another version all count as no saved state
+    pub fn load(path: &Path) -> Option<Self> {
+        let state: Self = toml::from_str(&fs::read_to_string(path).ok()?).ok()?;
+        let finite = [state.pitch, state.roll, state.lift, state.yaw].iter().all(|v| v.is_finite());
+        (state.version == STATE_VERSION && finite).then_some(state)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+// Next to the config file and named after it, so each config keeps its own pose
+pub fn state_path(config_path: &Path) -> PathBuf {
+    let stem = config_path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("config");
+    config_path.with_file_name(format!("{}.state.toml", stem))
+}
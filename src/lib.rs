@@ -0,0 +1,26 @@
+//! Gimbal math and config without the TUI, for embedding the controller in other programs.
+//! The `joystick_test` binary is built on the same modules.
+//!
+//! A minimal loop: load a [`Config`](config::Config), build a
+//! [`GimbalController`](gimbal::GimbalController) from it, then on every tick fill an
+//! [`InputState`](gimbal::InputState) and call `update()` with the elapsed time; `get_state()` is
+//! the pose to command and [`kinematics::solve`] turns it into actuator lengths. Nothing here
+//! prints to the terminal; problems come back as values, e.g. the list `Config::load` returns
+//! alongside the config.
+/// Settings, loading, validation and migration of older files
+pub mod config;
+mod dropout;
+/// A model of the physical plate lagging behind the commanded pose
+pub mod dynamics;
+/// Input mapping and the gimbal state it drives
+pub mod gimbal;
+/// Leg lengths for a pose and the actuator envelope
+pub mod kinematics;
+/// Streaming the state over UDP and WebSocket
+pub mod output;
+/// Flattening the 3D model onto the canvas
+pub mod projection;
+/// Scripted motion sequences
+pub mod sequence;
+/// Colors and styles for the UI
+pub mod theme;
@@ -0,0 +1,319 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{self, Write as _};
+use std::fs::{File, OpenOptions};
+use std::io::{LineWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tracing::field::{Field, Visit};
+use tracing::level_filters::LevelFilter;
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Metadata, Subscriber};
+
+// Target of the per-update input lines that [debug] log_input_values turns on. They only go
+// to the file: at the update rate they would push everything else out of the pane.
+pub const INPUT_VALUES: &str = "input_values";
+
+// The binary's own target, left off the lines to keep the pane readable
+const APP_TARGET: &str = "joystick_test";
+
+// What the runtime key steps through
+pub const LEVELS: [LevelFilter; 3] = [LevelFilter::INFO, LevelFilter::DEBUG, LevelFilter::TRACE];
+
+// RUST_LOG style: a default level and/or target=level pairs, comma separated, e.g.
+// "debug" or "info,joystick_test::gimbal=trace". A target matches itself and its submodules.
+#[derive(Default)]
+pub struct LogFilter {
+    pub default: Option<LevelFilter>,
+    targets: Vec<(String, LevelFilter)>,
+}
+
+impl LogFilter {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut filter = LogFilter::default();
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            let parse_level = |level: &str| level.parse::<LevelFilter>().map_err(|_| format!("'{}' is not a log level", level));
+            match directive.split_once('=') {
+                Some((target, level)) => filter.targets.push((target.trim().to_string(), parse_level(level.trim())?)),
+                None => filter.default = Some(parse_level(directive)?),
+            }
+        }
+        // Longest target first, so the most specific one decides
+        filter.targets.sort_by_key(|(target, _)| std::cmp::Reverse(target.len()));
+        Ok(filter)
+    }
+
+    // Turns a target on at `level` unless the spec already says something about it
+    pub fn enable(&mut self, target: &str, level: LevelFilter) {
+        if !self.targets.iter().any(|(t, _)| t == target) {
+            self.targets.push((target.to_string(), level));
+            self.targets.sort_by_key(|(target, _)| std::cmp::Reverse(target.len()));
+        }
+    }
+
+    fn level_for(&self, target: &str) -> Option<LevelFilter> {
+        self.targets
+            .iter()
+            .find(|(t, _)| target == t || target.strip_prefix(t.as_str()).is_some_and(|rest| rest.starts_with("::")))
+            .map(|(_, level)| *level)
+    }
+}
+
+fn encode(level: LevelFilter) -> u8 {
+    match level.into_level() {
+        None => 0,
+        Some(Level::ERROR) => 1,
+        Some(Level::WARN) => 2,
+        Some(Level::INFO) => 3,
+        Some(Level::DEBUG) => 4,
+        Some(Level::TRACE) => 5,
+    }
+}
+
+fn decode(value: u8) -> LevelFilter {
+    match value {
+        0 => LevelFilter::OFF,
+        1 => LevelFilter::ERROR,
+        2 => LevelFilter::WARN,
+        3 => LevelFilter::INFO,
+        4 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
+}
+
+// State the subscriber and the app's handle share
+struct Shared {
+    filter: LogFilter,
+    // Level for targets the filter doesn't name; the runtime key changes it
+    level: AtomicU8,
+    path: PathBuf,
+    file: Mutex<Option<LineWriter<File>>>,
+    file_open: AtomicBool,
+    // Lines for the debug log pane, collected until the app takes them
+    pane: Mutex<Vec<String>>,
+}
+
+impl Shared {
+    fn level(&self) -> LevelFilter {
+        decode(self.level.load(Ordering::Relaxed))
+    }
+
+    // The pane always shows info and up, and more once the level is raised
+    fn to_pane(&self, metadata: &Metadata<'_>) -> bool {
+        *metadata.level() <= self.level().max(LevelFilter::INFO) && metadata.target() != INPUT_VALUES
+    }
+
+    // Input values are only written when named, not whenever the level is raised to debug
+    fn to_file(&self, metadata: &Metadata<'_>) -> bool {
+        let level = match self.filter.level_for(metadata.target()) {
+            Some(level) => level,
+            None if metadata.target() == INPUT_VALUES => LevelFilter::OFF,
+            None => self.level(),
+        };
+        self.file_open.load(Ordering::Relaxed) && *metadata.level() <= level
+    }
+}
+
+thread_local! {
+    // Spans entered on this thread, innermost last
+    static SPAN_STACK: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+}
+
+// Collects an event's message and any other fields as " key=value"
+struct Fields(String);
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.0, "{:?}", value);
+        } else {
+            let _ = write!(self.0, " {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.0.push_str(value);
+        } else {
+            let _ = write!(self.0, " {}={}", field.name(), value);
+        }
+    }
+}
+
+// The one tracing subscriber: every event goes out as the same line to the debug log pane
+// and, once opened, the log file, so the terminal the TUI draws on is never written to
+struct Logger {
+    shared: Arc<Shared>,
+    started: Instant,
+    // Open spans by id: name and reference count
+    spans: Mutex<HashMap<u64, (&'static str, usize)>>,
+    next_span: AtomicU64,
+}
+
+impl Logger {
+    fn span_path(&self) -> String {
+        let spans = self.spans.lock().map(|spans| {
+            SPAN_STACK.with(|stack| {
+                stack.borrow().iter().filter_map(|id| spans.get(id).map(|(name, _)| *name)).collect::<Vec<_>>().join(">")
+            })
+        });
+        spans.unwrap_or_default()
+    }
+}
+
+impl Subscriber for Logger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        self.shared.to_pane(metadata) || self.shared.to_file(metadata)
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        let named = self.shared.filter.targets.iter().map(|(_, level)| *level);
+        Some(named.fold(self.shared.level().max(LevelFilter::INFO), LevelFilter::max))
+    }
+
+    fn new_span(&self, attributes: &Attributes<'_>) -> Id {
+        let id = self.next_span.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut spans) = self.spans.lock() {
+            spans.insert(id, (attributes.metadata().name(), 1));
+        }
+        Id::from_u64(id)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let metadata = event.metadata();
+        let mut fields = Fields(String::new());
+        event.record(&mut fields);
+        let mut prefix = self.span_path();
+        if metadata.target() != APP_TARGET {
+            if !prefix.is_empty() {
+                prefix.push(' ');
+            }
+            prefix.push_str(metadata.target());
+        }
+        if !prefix.is_empty() {
+            prefix.push_str(": ");
+        }
+        let line = format!(
+            "[{:>8.3}s] {:<5} {}{}",
+            self.started.elapsed().as_secs_f64(),
+            metadata.level(),
+            prefix,
+            fields.0
+        );
+        if self.shared.to_file(metadata)
+            && let Ok(mut file) = self.shared.file.lock()
+            && let Some(file) = file.as_mut()
+        {
+            // A failed write can't be reported anywhere useful; the line is lost
+            let _ = writeln!(file, "{}", line);
+        }
+        if self.shared.to_pane(metadata)
+            && let Ok(mut pane) = self.shared.pane.lock()
+        {
+            pane.push(line);
+        }
+    }
+
+    fn enter(&self, span: &Id) {
+        SPAN_STACK.with(|stack| stack.borrow_mut().push(span.into_u64()));
+    }
+
+    fn exit(&self, span: &Id) {
+        SPAN_STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            if let Some(index) = stack.iter().rposition(|&id| id == span.into_u64()) {
+                stack.remove(index);
+            }
+        });
+    }
+
+    fn clone_span(&self, span: &Id) -> Id {
+        if let Ok(mut spans) = self.spans.lock()
+            && let Some((_, count)) = spans.get_mut(&span.into_u64())
+        {
+            *count += 1;
+        }
+        span.clone()
+    }
+
+    fn try_close(&self, span: Id) -> bool {
+        let Ok(mut spans) = self.spans.lock() else { return false };
+        let Some((_, count)) = spans.get_mut(&span.into_u64()) else { return false };
+        *count -= 1;
+        if *count == 0 {
+            spans.remove(&span.into_u64());
+            return true;
+        }
+        false
+    }
+}
+
+// The app's side of the logger: takes the pane's lines, changes the level and opens the file
+pub struct LogHandle {
+    shared: Arc<Shared>,
+}
+
+impl LogHandle {
+    // Sets the logger as the global subscriber. `level` is where the runtime key starts and what
+    // the file records for targets the filter doesn't name.
+    pub fn install(filter: LogFilter, level: LevelFilter, path: &Path, started: Instant) -> Self {
+        let shared = Arc::new(Shared {
+            filter,
+            level: AtomicU8::new(encode(level)),
+            path: path.to_path_buf(),
+            file: Mutex::new(None),
+            file_open: AtomicBool::new(false),
+            pane: Mutex::new(Vec::new()),
+        });
+        let logger = Logger {
+            shared: Arc::clone(&shared),
+            started,
+            spans: Mutex::new(HashMap::new()),
+            next_span: AtomicU64::new(1),
+        };
+        // Only fails if a subscriber is already set, and then that one keeps logging
+        let _ = tracing::subscriber::set_global_default(logger);
+        Self { shared }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.shared.path
+    }
+
+    pub fn file_open(&self) -> bool {
+        self.shared.file_open.load(Ordering::Relaxed)
+    }
+
+    // Appends to the file from here on; does nothing if it is already open
+    pub fn open_file(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.file_open() {
+            return Ok(());
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&self.shared.path)?;
+        *self.shared.file.lock().map_err(|_| "log file lock poisoned")? = Some(LineWriter::new(file));
+        self.shared.file_open.store(true, Ordering::Relaxed);
+        tracing::callsite::rebuild_interest_cache();
+        Ok(())
+    }
+
+    pub fn level(&self) -> LevelFilter {
+        self.shared.level()
+    }
+
+    pub fn set_level(&self, level: LevelFilter) {
+        self.shared.level.store(encode(level), Ordering::Relaxed);
+        // Callsites remember whether they were enabled; make them ask again
+        tracing::callsite::rebuild_interest_cache();
+    }
+
+    // Lines for the pane since the last call, oldest first
+    pub fn take_lines(&self) -> Vec<String> {
+        self.shared.pane.lock().map(|mut pane| std::mem::take(&mut *pane)).unwrap_or_default()
+    }
+}
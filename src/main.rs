@@ -1,28 +1,93 @@
-mod config;
-mod gimbal;
+mod axis_buttons;
+mod calibration;
+mod cli;
+mod command;
+mod detect;
+mod csv_log;
+mod debounce;
+mod event_log;
+mod feedback;
+mod input_source;
+mod logging;
+mod invert_check;
+mod grpc;
+mod history;
+mod last_state;
+mod mavlink;
+mod mqtt;
+mod osc;
+mod paint;
+mod server;
+mod settings;
+mod simulate;
+mod status;
+mod svg;
+mod timing;
 
-use config::Config;
-use gimbal::{GimbalController, InputState};
-use gilrs::{Gilrs, Event, Axis, Button};
+// The gimbal math lives in the library; imported here so the modules above can keep
+// reaching it as crate::config, crate::gimbal and so on
+use joystick_test::{config, dynamics, gimbal, kinematics, output, projection, sequence, theme};
+
+use axis_buttons::{Side, VirtualButton};
+use calibration::{CalibrationStep, CalibrationWizard};
+use cli::Args;
+use config::{CONFIG_VERSION, Migration, ActuatorFormat, AxisMode, ButtonAction, ChannelMode, Config, TRIM_STEP, EstopBehavior, GeometryConfig, InputMixing, KeyAction, LiftControl, PlatformType, axis_key, parse_axis_name, parse_button_name};
+use csv_log::{AxisSample, CsvLog, DEFAULT_CSV_LOG, unix_millis};
+use debounce::Debouncer;
+use detect::{AxisDetector, DetectChannel, DetectStep};
+use dynamics::SimulatedState;
+use event_log::{EventLog, EventRecord};
+use feedback::{FeedbackInput, pose_error};
+use input_source::{InputEvent, InputSource, PadEvent, PadId, VirtualPad};
+use logging::{INPUT_VALUES, LEVELS, LogFilter, LogHandle};
+use last_state::{LastState, SavedTrims};
+use grpc::GrpcOutput;
+use history::{AxisHistory, HISTORY_SECS};
+use invert_check::{Answer, CheckStep, InvertCheck};
+use gimbal::{ArmState, ChannelPipeline, GimbalController, GimbalState, InputState, SoftStart, device_owner, ease_toward, rest_state};
+use kinematics::{Actuator, LegLimits};
+use mavlink::MavlinkOutput;
+use mqtt::{LinkState, MqttOutput};
+use osc::OscOutput;
+use output::{UdpOutput, WebSocketOutput};
+use paint::{Pen, Resolution, Scene, StaticLayer};
+use projection::Projection;
+use sequence::{Channel, Sequence, SequencePlayer, append_run_log};
+use server::{Client, CommandServer, ServerCommand, ServerEvent};
+use settings::{Kind, Outcome, Row, SettingsEditor, format_number};
+use simulate::SimulationPattern;
+use status::{Severity, StatusBar};
+use theme::{Role, Theme};
+use timing::{FrameStats, Summary};
+use gilrs::{Gilrs, Axis, Button};
+use gilrs::ff::{BaseEffect, BaseEffectType, EffectBuilder, Repeat, Replay, Ticks};
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
+    style::Style,
+    symbols::{border, Marker},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, List, ListItem},
+    widgets::{Block, Borders, Clear, Gauge, Paragraph, List, ListItem, Sparkline, SparklineBar},
     widgets::canvas::Canvas,
     Frame, Terminal,
 };
 use crossterm::{
-    event::{self, Event as CrosstermEvent, KeyCode, KeyEventKind},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event as CrosstermEvent, KeyCode, KeyEventKind,
+        MouseButton, MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use std::{
-    collections::HashMap,
+    cell::{Cell, RefCell},
+    collections::{HashMap, VecDeque},
     io::stdout,
+    net::SocketAddr,
+    path::{Path, PathBuf},
     time::{Duration, Instant},
 };
+use tracing::level_filters::LevelFilter;
 
 #[derive(Default)]
 struct GamepadState {
@@ -30,871 +95,4963 @@ struct GamepadState {
     connected: bool,
     axes: HashMap<Axis, f32>,
     buttons: HashMap<Button, bool>,
+    // Raw and settled state of each mapped button, for [controls] button_debounce_ms
+    debouncers: HashMap<Button, Debouncer>,
     last_activity: Option<Instant>,
 }
 
+// Where the idle return stands: counting down, slewing home, or home and waiting for input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IdleReturn {
+    Watching,
+    Returning,
+    Done,
+}
+
+// How often to retry gilrs initialization when joystick control is enabled but failed
+const GILRS_RETRY_INTERVAL: Duration = Duration::from_secs(3);
+// Gamepads listed before the panel starts cutting rows off
+const MAX_GAMEPAD_ROWS: usize = 4;
+// Shortest update step and redraw interval, so a huge rate can't turn the loop into a busy spin
+const MIN_LOOP_MS: u64 = 4;
+// Updates run to catch up after a stall; anything beyond is dropped rather than replayed
+const MAX_CATCH_UP_STEPS: u32 = 50;
+// The debug axis history: three channels of a label and a sparkline row, plus borders
+const HISTORY_PANEL_HEIGHT: u16 = 8;
+// Redraw this often even when nothing was marked changed, for clocks like "idle 12s"
+const IDLE_REDRAW: Duration = Duration::from_secs(1);
+// Without key release events, a gap longer than the initial key-repeat delay means a held
+// key (re-arm, output enable) was let go
+const HELD_KEY_GAP: Duration = Duration::from_millis(750);
+// How long the measured pose may stay off before the feedback warning shows
+const FEEDBACK_ERROR_HOLD: Duration = Duration::from_secs(1);
+// Limit rumble: pulse length, strength and the quiet time before the next one
+const RUMBLE_DURATION_MS: u32 = 150;
+const RUMBLE_MAGNITUDE: u16 = 40_000;
+const RUMBLE_COOLDOWN: Duration = Duration::from_secs(1);
+// Stick deflection (axis units from the trimmed center) that counts as someone at the controls,
+// and how long before the idle return the status bar starts counting down
+const IDLE_DEFLECTION: f64 = 0.05;
+const IDLE_WARNING_SECS: f64 = 5.0;
+// Lines kept in the debug log buffer
+const DEBUG_LOG_CAPACITY: usize = 200;
+// Borders for app.ascii_mode, where box-drawing characters may not render
+const ASCII_BORDER: border::Set = border::Set {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    vertical_left: "|",
+    vertical_right: "|",
+    horizontal_top: "-",
+    horizontal_bottom: "-",
+};
+
 struct App {
     config: Config,
+    config_path: PathBuf,
+    // --config, and the profiles/ files next to it that keys 1-9 switch to
+    startup_config_path: PathBuf,
+    config_profiles: Vec<PathBuf>,
     gimbal_controller: GimbalController,
     input_state: InputState,
-    gilrs: Gilrs,
-    gamepads: HashMap<gilrs::GamepadId, GamepadState>,
+    // Plate pose lagging behind the commanded state when dynamics.simulate_dynamics is on
+    simulated: SimulatedState,
+    gilrs: Option<Gilrs>,
+    gilrs_error: Option<String>,
+    last_gilrs_attempt: Instant,
+    no_gamepad: bool,
+    // Synthetic input pattern from --simulate, replacing the gamepad entirely
+    simulation: Option<SimulationPattern>,
+    // Synthetic gamepad from --virtual-gamepad, standing in for gilrs
+    virtual_pad: Option<VirtualPad>,
+    gamepads: HashMap<PadId, GamepadState>,
+    // Pad shown in the debug input panel when there are too many to show side by side
+    debug_gamepad: usize,
+    last_active_gamepad: Option<PadId>,
+    // Whether any channel was saturated last tick, when the last rumble fired and the
+    // effect itself (dropping it stops the motors)
+    was_saturated: bool,
+    last_rumble: Option<Instant>,
+    rumble: Option<gilrs::ff::Effect>,
+    calibration: Option<CalibrationWizard>,
+    // Axis auto-detect mode started with the detect_axes key
+    detection: Option<AxisDetector>,
+    // Guided invert flag check started with the check_inversion key
+    invert_check: Option<InvertCheck>,
+    status: StatusBar,
+    debug_log: VecDeque<String>,
+    // The tracing subscriber's handle: feeds debug_log and owns the log file and level
+    logging: LogHandle,
+    started: Instant,
+    last_update: Instant,
+    // Time not yet covered by a fixed update step
+    step_accumulator: Duration,
+    // Something on screen changed since the last draw
+    needs_redraw: bool,
+    udp_output: Option<UdpOutput>,
+    udp_error: Option<String>,
+    mavlink_output: Option<MavlinkOutput>,
+    mavlink_error: Option<String>,
+    osc_output: Option<OscOutput>,
+    osc_error: Option<String>,
+    websocket_output: Option<WebSocketOutput>,
+    websocket_error: Option<String>,
+    grpc_output: Option<GrpcOutput>,
+    grpc_error: Option<String>,
+    mqtt_output: Option<MqttOutput>,
+    mqtt_error: Option<String>,
+    command_server: Option<CommandServer>,
+    server_error: Option<String>,
+    feedback_input: Option<FeedbackInput>,
+    feedback_error: Option<String>,
+    // When the measured pose first drifted past feedback.error_threshold, while it stays there
+    feedback_off_since: Option<Instant>,
+    // Connected command server clients, and those that asked for STREAM on
+    server_clients: Vec<SocketAddr>,
+    stream_clients: Vec<Client>,
+    csv_log: Option<CsvLog>,
+    // Last rendered gimbal canvas, so mouse drags can be mapped onto it
+    canvas_area: Cell<Rect>,
+    // Static part of the gimbal drawing, and the shapes the last canvas paint drew
+    static_layer: RefCell<Option<StaticLayer>>,
+    canvas_draws: Cell<usize>,
+    // View the gimbal canvas is drawn in, cycled with the cycle_projection key
+    projection: Projection,
+    // Roll/pitch tilt lines and their angle labels, from app.tilt_lines
+    tilt_lines: bool,
+    // The solver view replaces the gimbal or debug view while this is on
+    show_solver: bool,
+    // Colors from [theme], or none at all when NO_COLOR is set
+    theme: Theme,
+    // Gimbal updates are skipped and the last state is held and drawn
+    paused: bool,
+    // Last operator input, for [homing] idle_timeout_secs
+    last_input: Instant,
+    idle: IdleReturn,
+    // Running motion sequence and when it started (Unix ms), and the file the
+    // run_sequence key loads (--sequence, else [sequence] file)
+    sequence: Option<(SequencePlayer, u128)>,
+    sequence_path: Option<PathBuf>,
+    // Text typed after the command key, and why the last attempt to run it failed
+    command_line: Option<String>,
+    command_error: Option<String>,
+    // Drag start cell and the mouse pitch/roll at that moment
+    mouse_drag: Option<((u16, u16), (f64, f64))>,
+    // Mapping profile in use (None = [controls.joystick]) and a manual pick overriding the auto-match
+    active_profile: Option<usize>,
+    manual_profile: Option<Option<usize>>,
+    // [[hardware]] profile in use; None = [gimbal] + [geometry]
+    active_hardware: Option<usize>,
+    // Set by the first quit key press when app.confirm_quit is on
+    pending_quit: bool,
+    // When the re-arm key was first and last seen during the current hold
+    rearm_hold: Option<(Instant, Instant)>,
+    // When the output enable key was last seen down
+    enable_key_seen: Option<Instant>,
+    // What the outputs send instead of the gimbal state while the enable switch is released,
+    // and while they ease back onto the gimbal after it is held again
+    output_pose: Option<GimbalState>,
+    // Scroll offset of the help overlay while it is open
+    help_scroll: Option<u16>,
+    // Furthest the overlay could scroll at the last draw, so scrolling stops at the end
+    help_max_scroll: Cell<u16>,
+    // The settings editor while it is open
+    settings: Option<SettingsEditor>,
+    // Loop timing, always recorded; the overlay is toggled with the toggle_timing key
+    timing: FrameStats,
+    show_timing: bool,
+    // Source axis of pitch, roll and lift at every update step, for the debug sparklines
+    axis_history: [AxisHistory; 3],
+    // Raw gilrs events, shown in place of the debug log while show_event_log is on
+    event_log: EventLog,
+    // One per [[controls.axis_buttons]] entry, resized when a profile or reload changes them
+    axis_button_states: Vec<VirtualButton>,
+    show_event_log: bool,
+    // Rows the event pane showed at the last draw, for paging
+    event_log_rows: Cell<usize>,
     running: bool,
     debug_mode: bool,
 }
 
 impl App {
-    fn new() -> Result<Self, Box<dyn std::error::Error>> {
-        let config = Config::load_or_create("config.toml")?;
+    fn new(args: &Args) -> Result<Self, Box<dyn std::error::Error>> {
+        let creating = !args.config_path.exists();
+        let (config, config_problems) = Config::load_or_create(&args.config_path, args.strict_config)?;
+        if creating {
+            println!("Created default config file at {}", args.config_path.display());
+        }
         let gimbal_controller = GimbalController::new(config.clone());
-        let gilrs = Gilrs::new().map_err(|e| format!("Failed to initialize gilrs: {}", e))?;
-        
-        Ok(App {
-            debug_mode: config.debug.enabled,
+        // The virtual pad stands in for gilrs rather than joining it
+        let no_gamepad = args.no_gamepad || args.simulate.is_some() || args.virtual_gamepad;
+        let virtual_pad = args.virtual_gamepad.then(|| VirtualPad::new(&config.virtual_gamepad, &config.controls.joystick));
+        let config_status_secs = config.app.status_message_secs.max(0.5);
+
+        // RUST_LOG wins over [debug] log_level; a bad one is reported once the status bar exists
+        let started = Instant::now();
+        let (log_filter, log_filter_error) = match std::env::var("RUST_LOG") {
+            Ok(spec) => match LogFilter::parse(&spec) {
+                Ok(filter) => (Some(filter), None),
+                Err(e) => (None, Some(e)),
+            },
+            Err(_) => (None, None),
+        };
+        let log_from_env = log_filter.is_some();
+        let mut filter = log_filter.unwrap_or_default();
+        if config.debug.log_input_values {
+            filter.enable(INPUT_VALUES, LevelFilter::DEBUG);
+        }
+        let log_level = filter.default.unwrap_or_else(|| config.debug.log_level.parse().unwrap_or(LevelFilter::OFF));
+        let logging = LogHandle::install(filter, log_level, Path::new(&config.debug.log_file), started);
+
+        let mut app = App {
+            debug_mode: config.debug.enabled || args.force_debug,
+            projection: config.app.view,
+            tilt_lines: config.app.tilt_lines,
+            show_solver: false,
+            theme: Theme::new(&config.theme, std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty())),
             config,
+            config_path: args.config_path.clone(),
+            startup_config_path: args.config_path.clone(),
+            config_profiles: Vec::new(),
             gimbal_controller,
             input_state: InputState::default(),
-            gilrs,
+            simulated: SimulatedState::default(),
+            gilrs: None,
+            gilrs_error: None,
+            last_gilrs_attempt: Instant::now(),
+            no_gamepad,
+            simulation: args.simulate,
+            virtual_pad,
             gamepads: HashMap::new(),
+            debug_gamepad: 0,
+            last_active_gamepad: None,
+            was_saturated: false,
+            last_rumble: None,
+            rumble: None,
+            calibration: None,
+            detection: None,
+            invert_check: None,
+            status: StatusBar::new(Duration::from_secs_f64(config_status_secs)),
+            debug_log: VecDeque::new(),
+            logging,
+            started,
+            last_update: Instant::now(),
+            step_accumulator: Duration::ZERO,
+            needs_redraw: true,
+            udp_output: None,
+            udp_error: None,
+            mavlink_output: None,
+            mavlink_error: None,
+            osc_output: None,
+            osc_error: None,
+            websocket_output: None,
+            websocket_error: None,
+            grpc_output: None,
+            grpc_error: None,
+            mqtt_output: None,
+            mqtt_error: None,
+            command_server: None,
+            server_error: None,
+            feedback_input: None,
+            feedback_error: None,
+            feedback_off_since: None,
+            server_clients: Vec::new(),
+            stream_clients: Vec::new(),
+            csv_log: None,
+            canvas_area: Cell::new(Rect::default()),
+            static_layer: RefCell::new(None),
+            canvas_draws: Cell::new(0),
+            mouse_drag: None,
+            active_profile: None,
+            manual_profile: None,
+            active_hardware: None,
+            pending_quit: false,
+            rearm_hold: None,
+            enable_key_seen: None,
+            output_pose: None,
+            help_scroll: None,
+            help_max_scroll: Cell::new(0),
+            settings: None,
+            timing: FrameStats::new(),
+            show_timing: false,
+            axis_history: std::array::from_fn(|_| AxisHistory::new(0)),
+            event_log: EventLog::new(),
+            axis_button_states: Vec::new(),
+            show_event_log: false,
+            event_log_rows: Cell::new(0),
+            paused: false,
+            last_input: Instant::now(),
+            idle: IdleReturn::Watching,
+            sequence: None,
+            sequence_path: None,
+            command_line: None,
+            command_error: None,
             running: true,
-        })
+        };
+
+        if let Some(e) = log_filter_error {
+            eprintln!("RUST_LOG: {}", e);
+            app.pin_status("log_file", Severity::Error, format!("RUST_LOG ignored: {}", e));
+        }
+        // The file is only opened when something asked for logging
+        if (log_from_env || app.config.debug.log_input_values || log_level != LevelFilter::OFF)
+            && let Err(message) = app.open_log_file()
+        {
+            eprintln!("{}", message);
+        }
+        if log_level > LevelFilter::INFO {
+            app.show_log_level();
+        }
+
+        // Printed before the TUI starts, so they are still on screen after quitting
+        if let Some(migration) = &app.config.migration {
+            let (severity, message) = migration_message(migration, &args.config_path);
+            eprintln!("{}", message);
+            app.notify(severity, message);
+        }
+        for problem in config_problems {
+            eprintln!("Config: {} (using the default)", problem);
+            app.notify(Severity::Warn, format!("Config: {} (using the default)", problem));
+        }
+
+        if let Some(name) = args.hardware.as_ref().or(app.config.app.hardware_profile.as_ref()) {
+            let index = app
+                .config
+                .hardware_index(name)
+                .ok_or_else(|| format!("Unknown hardware profile '{}'", name))?;
+            app.active_hardware = Some(index);
+            app.apply_mapping();
+        }
+
+        for conflict in app.config.controls.keyboard_bindings.conflicts() {
+            app.notify(Severity::Warn, conflict);
+        }
+
+        let profile_dir = args.config_path.parent().unwrap_or(Path::new("")).join(config::PROFILE_DIR);
+        app.config_profiles = config::config_profiles(&profile_dir);
+        if app.config_profiles.len() > config::MAX_CONFIG_PROFILES {
+            let skipped = app.config_profiles.split_off(config::MAX_CONFIG_PROFILES);
+            app.notify(
+                Severity::Warn,
+                format!("Only the first {} profiles get a key; {} more ignored", config::MAX_CONFIG_PROFILES, skipped.len()),
+            );
+        }
+        app.warn_profile_key_conflicts();
+
+        if app.config.output.udp.enabled {
+            match UdpOutput::open(&app.config.output.udp) {
+                Ok(output) => app.udp_output = Some(output),
+                Err(e) => {
+                    let error = format!("Failed to open UDP output: {}", e);
+                    app.pin_status("udp", Severity::Error, error.clone());
+                    app.udp_error = Some(error);
+                }
+            }
+        }
+
+        if app.config.output.mavlink.enabled {
+            match MavlinkOutput::open(&app.config.output.mavlink) {
+                Ok(output) => app.mavlink_output = Some(output),
+                Err(e) => {
+                    let error = format!("Failed to open MAVLink output: {}", e);
+                    app.pin_status("mavlink", Severity::Error, error.clone());
+                    app.mavlink_error = Some(error);
+                }
+            }
+        }
+
+        if app.config.output.osc.enabled {
+            match OscOutput::open(&app.config.output.osc) {
+                Ok(output) => app.osc_output = Some(output),
+                Err(e) => {
+                    let error = format!("Failed to open OSC output: {}", e);
+                    app.pin_status("osc", Severity::Error, error.clone());
+                    app.osc_error = Some(error);
+                }
+            }
+        }
+
+        if app.config.output.websocket.enabled {
+            match WebSocketOutput::open(&app.config.output.websocket) {
+                Ok(output) => app.websocket_output = Some(output),
+                Err(e) => {
+                    let error = format!("Failed to start WebSocket output on {}: {}", app.config.output.websocket.address, e);
+                    app.pin_status("websocket", Severity::Error, error.clone());
+                    app.websocket_error = Some(error);
+                }
+            }
+        }
+
+        if app.config.output.grpc.enabled {
+            match GrpcOutput::open(&app.config.output.grpc) {
+                Ok(output) => {
+                    app.log(Severity::Info, &format!("gRPC server listening on {}", output.address()));
+                    app.grpc_output = Some(output);
+                }
+                Err(e) => {
+                    let error = format!("Failed to start gRPC output on {}: {}", app.config.output.grpc.address, e);
+                    app.pin_status("grpc", Severity::Error, error.clone());
+                    app.grpc_error = Some(error);
+                }
+            }
+        }
+
+        if app.config.output.mqtt.enabled {
+            match MqttOutput::open(&app.config.output.mqtt) {
+                Ok(output) => {
+                    app.log(Severity::Info, &format!("MQTT publishing to {} under {}/", output.broker(), output.prefix()));
+                    app.mqtt_output = Some(output);
+                }
+                Err(e) => {
+                    let error = format!("Failed to start MQTT output for {}: {}", app.config.output.mqtt.broker, e);
+                    app.pin_status("mqtt", Severity::Error, error.clone());
+                    app.mqtt_error = Some(error);
+                }
+            }
+        }
+
+        if app.config.server.enabled {
+            match CommandServer::start(&app.config.server.address) {
+                Ok(server) => {
+                    app.log(Severity::Info, &format!("Command server listening on {}", server.address()));
+                    app.command_server = Some(server);
+                }
+                Err(e) => {
+                    let error = format!("Failed to start command server on {}: {}", app.config.server.address, e);
+                    app.pin_status("server", Severity::Error, error.clone());
+                    app.server_error = Some(error);
+                }
+            }
+        }
+
+        if app.config.feedback.enabled {
+            match FeedbackInput::open(&app.config.feedback) {
+                Ok(input) => {
+                    let address = input.address().map_or(app.config.feedback.address.clone(), |a| a.to_string());
+                    app.log(Severity::Info, &format!("Listening for feedback on UDP {}", address));
+                    app.feedback_input = Some(input);
+                }
+                Err(e) => {
+                    let error = format!("Failed to listen for feedback on {}: {}", app.config.feedback.address, e);
+                    app.pin_status("feedback", Severity::Error, error.clone());
+                    app.feedback_error = Some(error);
+                }
+            }
+        }
+
+        if let Some(pattern) = app.config.debug.csv_log.clone() {
+            app.start_csv_log(&pattern);
+        }
+
+        if app.config.app.restore_last_state || app.config.app.restore_last_pose {
+            app.restore_last_state();
+        }
+
+        if let Some(pad) = &app.virtual_pad {
+            let text = format!("Virtual gamepad '{}' (no hardware)", pad.name());
+            app.pin_status("virtual_pad", Severity::Info, text);
+        }
+
+        if let Some(pattern) = app.simulation {
+            app.pin_status("simulate", Severity::Info, format!("Simulating '{}' input (no hardware)", pattern.name()));
+            if !app.config.controls.joystick.enabled {
+                app.notify(Severity::Warn, "Simulated input drives the joystick axes, but joystick control is disabled");
+            }
+        }
+
+        // A failed init is not fatal: keyboard control keeps working and the UI shows why
+        if !no_gamepad {
+            app.init_gilrs();
+        }
+
+        // A sequence named on the command line has to load, like the config
+        app.sequence_path = args.sequence.clone().or_else(|| app.config.sequence.file.as_ref().map(PathBuf::from));
+        if let Some(path) = &args.sequence {
+            let sequence = Sequence::load(path).map_err(|e| format!("Failed to load sequence {}: {}", path.display(), e))?;
+            app.run_sequence(sequence);
+        }
+
+        Ok(app)
+    }
+
+    // A missing or unusable state file just means starting from rest, without a word
+    // restore_last_state picks up exactly where the hardware was left; restore_last_pose
+    // (for hardware that re-homes at power-up) eases there from rest like a typed target
+    fn restore_last_state(&mut self) {
+        let Some(saved) = LastState::load(&last_state::state_path(&self.config_path)) else { return };
+        if !self.config.app.restore_last_state {
+            let targets = [
+                (Channel::Pitch, saved.pitch),
+                (Channel::Roll, saved.roll),
+                (Channel::Lift, saved.lift),
+                (Channel::Yaw, saved.yaw),
+            ];
+            if self.move_to(&targets, "the restored pose").is_ok() {
+                self.log(Severity::Info, "Easing into pose from previous session");
+            }
+            return;
+        }
+        let trims = &saved.trims;
+        let mapping = if trims.mapping == "default" {
+            Some(&mut self.config.controls.joystick)
+        } else {
+            self.config.profiles.iter_mut().find(|profile| profile.name == trims.mapping).map(|profile| &mut profile.joystick)
+        };
+        if let Some(joystick) = mapping {
+            joystick.pitch_trim = trims.pitch.clamp(-1.0, 1.0);
+            joystick.roll_trim = trims.roll.clamp(-1.0, 1.0);
+            joystick.lift_trim = trims.lift.clamp(-1.0, 1.0);
+            joystick.yaw_trim = trims.yaw.clamp(-1.0, 1.0);
+        }
+        self.apply_mapping();
+        self.gimbal_controller.set_state((saved.pitch, saved.roll, saved.lift, saved.yaw));
+        self.notify(Severity::Info, "Restored pose from previous session");
+    }
+
+    fn save_last_state(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let state = self.gimbal_controller.get_state();
+        let joystick = &self.gimbal_controller.get_config().controls.joystick;
+        let saved = LastState {
+            version: last_state::STATE_VERSION,
+            pitch: state.pitch,
+            roll: state.roll,
+            lift: state.lift,
+            yaw: state.yaw,
+            trims: SavedTrims {
+                mapping: self.profile_name(self.active_profile).to_string(),
+                pitch: joystick.pitch_trim,
+                roll: joystick.roll_trim,
+                lift: joystick.lift_trim,
+                yaw: joystick.yaw_trim,
+            },
+        };
+        saved.save(&last_state::state_path(&self.config_path))
+    }
+
+    fn start_csv_log(&mut self, pattern: &str) {
+        match CsvLog::create(pattern) {
+            Ok(log) => {
+                self.notify(Severity::Info, format!("Recording to {}", log.path().display()));
+                self.csv_log = Some(log);
+            }
+            Err(e) => self.notify(Severity::Error, format!("Failed to start CSV log {}: {}", pattern, e)),
+        }
+    }
+
+    fn stop_csv_log(&mut self) {
+        if let Some(log) = self.csv_log.take() {
+            let path = log.path().display().to_string();
+            let rows = log.rows();
+            match log.finish() {
+                Ok(()) => self.notify(Severity::Info, format!("Stopped recording {} ({} rows)", path, rows)),
+                Err(e) => self.notify(Severity::Error, format!("Failed to flush {}: {}", path, e)),
+            }
+        }
+    }
+
+    // Writes the simulated stick position onto the mapped axes, as a gamepad would
+    fn inject_simulation(&mut self) {
+        let Some(pattern) = self.simulation else { return };
+        let (pitch, roll, lift) = pattern.sample(self.started.elapsed().as_secs_f64());
+        let joystick = &self.gimbal_controller.get_config().controls.joystick;
+        for (name, value) in [(&joystick.pitch_axis, pitch), (&joystick.roll_axis, roll), (&joystick.lift_axis, lift)] {
+            if let Some(axis) = parse_axis_name(name) {
+                self.input_state.axes.insert(axis, value as f32);
+            }
+        }
+    }
+
+    fn record_csv_row(&mut self) {
+        let Some(log) = self.csv_log.as_mut() else { return };
+        let joystick = &self.gimbal_controller.get_config().controls.joystick;
+        let raw = |name: &str| parse_axis_name(name).and_then(|axis| self.input_state.axes.get(&axis).copied());
+        let sample = AxisSample {
+            pitch: raw(&joystick.pitch_axis),
+            roll: raw(&joystick.roll_axis),
+            lift: raw(&joystick.lift_axis),
+        };
+        if let Err(e) = log.write_row(&sample, &self.input_state, self.gimbal_controller.get_state()) {
+            let path = log.path().display().to_string();
+            self.csv_log = None;
+            self.notify(Severity::Error, format!("CSV log {} stopped: {}", path, e));
+        }
+    }
+
+    // The next event from any source: the virtual pad first, then gilrs
+    fn next_input_event(&mut self) -> Option<InputEvent> {
+        let sources: [Option<&mut dyn InputSource>; 2] = [
+            self.virtual_pad.as_mut().map(|pad| pad as &mut dyn InputSource),
+            self.gilrs.as_mut().map(|gilrs| gilrs as &mut dyn InputSource),
+        ];
+        sources.into_iter().flatten().find_map(|source| source.next_event())
+    }
+
+    fn init_gilrs(&mut self) {
+        self.last_gilrs_attempt = Instant::now();
+        match Gilrs::new() {
+            Ok(gilrs) => {
+                self.gilrs = Some(gilrs);
+                self.gilrs_error = None;
+            }
+            Err(e) => {
+                self.gilrs_error = Some(format!("Failed to initialize gilrs: {}", e));
+            }
+        }
+    }
+
+    // Status bar notice when the gamepad subsystem is not running
+    fn gamepad_status(&self) -> Option<(Severity, String)> {
+        if self.gilrs.is_some() || self.simulation.is_some() || self.virtual_pad.is_some() {
+            return None;
+        }
+        if self.no_gamepad {
+            return Some((Severity::Warn, "gamepad subsystem unavailable (--no-gamepad)".to_string()));
+        }
+        let error = self.gilrs_error.as_deref().unwrap_or("not initialized");
+        if self.config.controls.joystick.enabled {
+            Some((Severity::Error, format!("{} - retrying", error)))
+        } else {
+            Some((Severity::Warn, format!("gamepad subsystem unavailable: {}", error)))
+        }
+    }
+
+    // Transient message in the status bar, also kept in the debug log
+    fn notify(&mut self, severity: Severity, text: impl Into<String>) {
+        let text = text.into();
+        self.log(severity, &text);
+        self.status.push(severity, text);
+    }
+
+    // Long-lived condition that stays in the status bar until unpinned
+    fn pin_status(&mut self, key: &str, severity: Severity, text: String) {
+        if self.status.pin(key, severity, text.clone()) {
+            self.log(severity, &text);
+        }
+    }
+
+    fn unpin_status(&mut self, key: &str) {
+        if self.status.unpin(key) {
+            self.log(Severity::Info, &format!("cleared: {}", key));
+        }
+    }
+
+    // Goes through tracing like everything else, so the pane and the log file get the same line
+    fn log(&mut self, severity: Severity, text: &str) {
+        match severity {
+            Severity::Info => tracing::info!("{}", text),
+            Severity::Warn => tracing::warn!("{}", text),
+            Severity::Error => tracing::error!("{}", text),
+        }
+        self.take_log_lines();
+    }
+
+    // Moves what the subscriber collected for the pane into the debug log
+    fn take_log_lines(&mut self) {
+        let lines = self.logging.take_lines();
+        if lines.is_empty() {
+            return;
+        }
+        self.needs_redraw = true;
+        for line in lines {
+            if self.debug_log.len() == DEBUG_LOG_CAPACITY {
+                self.debug_log.pop_front();
+            }
+            self.debug_log.push_back(line);
+        }
     }
 
     fn update(&mut self) {
+        // Keep retrying gilrs so fixing permissions or plugging in a device recovers without a restart
+        if self.gilrs.is_none()
+            && !self.no_gamepad
+            && self.config.controls.joystick.enabled
+            && self.last_gilrs_attempt.elapsed() >= GILRS_RETRY_INTERVAL
+        {
+            self.init_gilrs();
+        }
+
+        // Connection changes and button presses are handled after the loop, once the gamepad borrow ends
+        let mut connection_changes = Vec::new();
+        let mut estop_from = None;
+        let mut button_presses = Vec::new();
+        let mut buttons_pressed = false;
+        let debounce = Duration::from_millis(self.config.controls.button_debounce_ms);
+
         // Process gamepad events
-        while let Some(Event { id, event, .. }) = self.gilrs.next_event() {
+        let input_span = tracing::debug_span!("input").entered();
+        while let Some(input) = self.next_input_event() {
+            self.timing.gamepad_event(Instant::now());
+            self.needs_redraw = true;
+            // Everything is logged, including event types the match below ignores
+            let age = input.time.elapsed().unwrap_or_default();
+            let at = self.started.elapsed().saturating_sub(age).as_secs_f64();
+            // Events are drained in batches, so bounces are timed by when they happened
+            let happened = Instant::now().checked_sub(age).unwrap_or_else(Instant::now);
+            self.event_log.push(EventRecord::new(at, &input), self.config.debug.event_axis_threshold as f32);
+            let InputEvent { pad: id, name, event, .. } = input;
             let gamepad_state = self.gamepads.entry(id).or_insert_with(|| GamepadState {
-                name: self.gilrs.gamepad(id).name().to_string(),
+                name: name.clone(),
                 connected: true,
                 axes: HashMap::new(),
                 buttons: HashMap::new(),
+                debouncers: HashMap::new(),
                 last_activity: Some(Instant::now()),
             });
 
-            gamepad_state.last_activity = Some(Instant::now());
+            // Only real input counts as activity, not connection changes
+            if !matches!(event, PadEvent::Connected | PadEvent::Disconnected) {
+                gamepad_state.last_activity = Some(Instant::now());
+                if !self.input_state.devices.contains_key(&gamepad_state.name) {
+                    self.input_state.devices.insert(gamepad_state.name.clone(), HashMap::new());
+                }
+            }
 
             match event {
-                gilrs::EventType::ButtonPressed(button, _) => {
+                PadEvent::ButtonPressed(button) => {
+                    buttons_pressed = true;
                     gamepad_state.buttons.insert(button, true);
                     self.input_state.buttons.insert(button, true);
+                    // The e-stop isn't debounced: a bounce can only stop it again
+                    if self.config.safety.estop_button.as_deref().and_then(parse_button_name) == Some(button) {
+                        estop_from = Some(format!("{:?} on '{}'", button, gamepad_state.name));
+                    } else if let Some(action) = self.config.controls.button_action(button) {
+                        // Actions fire once per settled press, never while held; with a debounce
+                        // that is after the loop below, once the press has held long enough
+                        let debouncer = gamepad_state.debouncers.entry(button).or_default();
+                        if debouncer.set(true, happened, debounce) {
+                            tracing::debug!("{:?} on '{}' bounced, {:?} waits to settle", button, gamepad_state.name, action);
+                        }
+                        if debouncer.poll(happened, debounce) {
+                            button_presses.push(action);
+                        }
+                    }
                 },
-                gilrs::EventType::ButtonReleased(button, _) => {
+                PadEvent::ButtonReleased(button) => {
                     gamepad_state.buttons.insert(button, false);
                     self.input_state.buttons.insert(button, false);
+                    if let Some(debouncer) = gamepad_state.debouncers.get_mut(&button) {
+                        if debouncer.set(false, happened, debounce) {
+                            tracing::debug!("{:?} on '{}' bounced", button, gamepad_state.name);
+                        }
+                        debouncer.poll(happened, debounce);
+                    }
                 },
-                gilrs::EventType::AxisChanged(axis, value, _) => {
+                PadEvent::AxisChanged(axis, value) => {
                     gamepad_state.axes.insert(axis, value);
                     self.input_state.axes.insert(axis, value);
+                    if let Some(axes) = self.input_state.devices.get_mut(&gamepad_state.name) {
+                        axes.insert(axis, value);
+                    }
+                    if self.input_state.active_gamepad.as_deref() != Some(gamepad_state.name.as_str()) {
+                        self.input_state.active_gamepad = Some(gamepad_state.name.clone());
+                    }
+                    self.last_active_gamepad = Some(id);
                 },
-                gilrs::EventType::Connected => {
+                PadEvent::Connected => {
+                    // A reconnecting pad starts from a clean slate; fresh events repopulate it
+                    for axis in gamepad_state.axes.drain().map(|(axis, _)| axis) {
+                        self.input_state.axes.remove(&axis);
+                    }
+                    for button in gamepad_state.buttons.drain().map(|(button, _)| button) {
+                        self.input_state.buttons.remove(&button);
+                    }
+                    gamepad_state.debouncers.clear();
                     gamepad_state.connected = true;
-                    gamepad_state.name = self.gilrs.gamepad(id).name().to_string();
+                    gamepad_state.name = name;
+                    self.input_state.devices.insert(gamepad_state.name.clone(), HashMap::new());
+                    connection_changes.push((id, gamepad_state.name.clone(), true));
                 },
-                gilrs::EventType::Disconnected => {
+                PadEvent::Disconnected => {
+                    // Drop its last readings so a lost pad can't hold the plate tilted
+                    for axis in gamepad_state.axes.drain().map(|(axis, _)| axis) {
+                        self.input_state.axes.remove(&axis);
+                    }
+                    for button in gamepad_state.buttons.drain().map(|(button, _)| button) {
+                        self.input_state.buttons.remove(&button);
+                    }
+                    gamepad_state.debouncers.clear();
+                    self.input_state.devices.remove(&gamepad_state.name);
                     gamepad_state.connected = false;
+                    connection_changes.push((id, gamepad_state.name.clone(), false));
                 },
                 _ => {}
             }
         }
 
-        // Update gimbal with current input
-        self.gimbal_controller.update(&self.input_state);
-    }
-
-    fn handle_key(&mut self, key: KeyCode) {
-        match key {
-            KeyCode::Char('q') | KeyCode::Esc => {
-                self.running = false;
-            }
-            KeyCode::Char('t') => {
-                self.debug_mode = !self.debug_mode;
+        // Debounced presses that have now held for the whole debounce time, whether or not any
+        // events came in this update
+        let now = Instant::now();
+        for gamepad_state in self.gamepads.values_mut() {
+            for (&button, debouncer) in &mut gamepad_state.debouncers {
+                if debouncer.poll(now, debounce)
+                    && let Some(action) = self.config.controls.button_action(button)
+                {
+                    button_presses.push(action);
+                }
             }
-            KeyCode::Char('r') => {
-                self.gimbal_controller.reset();
-                self.input_state.keyboard_pitch = 0.0;
-                self.input_state.keyboard_roll = 0.0;
-                self.input_state.keyboard_lift = 0.0;
+        }
+
+        // Axis buttons read the merged axes, so a lost pad releases them like its real buttons.
+        // A stepping D-pad is one of them; its previous value is what makes a click one step.
+        let axis_buttons = self.config.controls.axis_button_entries();
+        self.axis_button_states.resize(axis_buttons.len(), VirtualButton::default());
+        for (entry, state) in axis_buttons.iter().zip(&mut self.axis_button_states) {
+            let value = parse_axis_name(&entry.axis).and_then(|axis| self.input_state.axes.get(&axis)).copied().unwrap_or(0.0);
+            let action = match state.update(value as f64) {
+                Some(Side::Positive) => entry.positive,
+                Some(Side::Negative) => entry.negative,
+                None => None,
+            };
+            if let Some(action) = action {
+                buttons_pressed = true;
+                button_presses.push(action);
             }
-            KeyCode::Char(c) => {
-                self.gimbal_controller.handle_keyboard(&mut self.input_state, c, true);
+        }
+
+        if buttons_pressed {
+            self.note_input();
+        }
+        if let Some(source) = estop_from {
+            self.trigger_estop(&source);
+        }
+        for action in button_presses {
+            self.run_button_action(action);
+        }
+
+        for (id, name, connected) in connection_changes {
+            let key = format!("gamepad-{}", usize::from(id));
+            if connected {
+                self.unpin_status(&key);
+                self.notify(Severity::Info, format!("Controller '{}' connected", name));
+            } else {
+                self.pin_status(&key, Severity::Warn, format!("Controller '{}' disconnected", name));
             }
-            _ => {}
         }
-    }
 
-    fn draw(&self, frame: &mut Frame) {
-        if self.debug_mode {
-            self.draw_debug_view(frame);
+        // Channels bound to a gamepad that isn't there follow the active one instead
+        let joystick = &self.gimbal_controller.get_config().controls.joystick;
+        let missing: Vec<String> = joystick
+            .channel_devices()
+            .filter(|(_, device)| joystick.enabled && device_owner(&self.input_state, Some(device)).is_none())
+            .map(|(channel, device)| format!("{} ('{}')", channel, device))
+            .collect();
+        if missing.is_empty() {
+            self.unpin_status("devices");
         } else {
-            self.draw_gimbal_view(frame);
+            let text = format!("No gamepad for {}; using the active gamepad", missing.join(", "));
+            self.pin_status("devices", Severity::Warn, text);
         }
-    }
 
-    fn draw_debug_view(&self, frame: &mut Frame) {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3),     // Header
-                Constraint::Min(10),       // Debug info
-                Constraint::Min(15),       // Gimbal (smaller)
-            ])
-            .split(frame.area());
+        match self.gamepad_status() {
+            Some((severity, text)) => self.pin_status("gamepad", severity, text),
+            None => self.unpin_status("gamepad"),
+        }
 
-        // Header
-        let header = Paragraph::new("🔧 DEBUG MODE - Press 't' to toggle, 'q' to quit, 'r' to reset")
-            .block(Block::default().borders(Borders::ALL))
-            .style(Style::default().fg(Color::Yellow));
-        frame.render_widget(header, chunks[0]);
+        self.refresh_profile();
 
-        // Debug info split
-        let debug_chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Percentage(50),  // Axes
-                Constraint::Percentage(50),  // Config & State
-            ])
-            .split(chunks[1]);
+        if let Some(wizard) = self.calibration.as_mut()
+            && let Some(gamepad) = self.gamepads.get(&wizard.gamepad_id)
+        {
+            wizard.sample(&gamepad.axes);
+        }
+        if let Some(detector) = self.detection.as_mut()
+            && let Some(gamepad) = self.gamepads.get(&detector.gamepad_id)
+        {
+            detector.sample(&gamepad.axes);
+        }
+        if let Some(check) = self.invert_check.as_mut()
+            && let Some(gamepad) = self.gamepads.get(&check.gamepad_id)
+            && let Some(channel) = check.current()
+        {
+            let joystick = &self.gimbal_controller.get_config().controls.joystick;
+            let pipeline = self.gimbal_controller.last_pipeline();
+            let (axis_name, device, value) = match channel {
+                DetectChannel::Pitch => (&joystick.pitch_axis, &joystick.pitch_device, pipeline.pitch.joystick),
+                DetectChannel::Roll => (&joystick.roll_axis, &joystick.roll_device, pipeline.roll.joystick),
+                DetectChannel::Lift => (&joystick.lift_axis, &joystick.lift_device, pipeline.lift.joystick),
+            };
+            let owner = device_owner(&self.input_state, device.as_deref());
+            let mapped = self.gimbal_controller.resolved_axis(&self.input_state, axis_name, owner);
+            check.sample(&gamepad.axes, mapped, value);
+        }
+        drop(input_span);
 
-        self.draw_debug_axes(frame, debug_chunks[0]);
-        self.draw_debug_state(frame, debug_chunks[1]);
-        
-        // Smaller gimbal view
-        self.draw_gimbal_visualization(frame, chunks[2]);
-    }
+        self.poll_command_server();
+        self.poll_grpc();
+        self.poll_mqtt();
+        self.poll_feedback();
+        self.inject_simulation();
 
-    fn draw_debug_axes(&self, frame: &mut Frame, area: Rect) {
-        let mut items = vec![
-            ListItem::new(Line::from(Span::styled("=== ACTIVE AXES ===", Style::default().fg(Color::Cyan)))),
-        ];
+        // Advance the gimbal in fixed steps, however unevenly update() itself is called
+        let now = Instant::now();
+        self.step_accumulator += now.duration_since(self.last_update);
+        self.last_update = now;
+        let step = self.step_interval();
+        let shown_before = (self.gimbal_controller.get_state().clone(), self.displayed_state());
+        let mut steps = 0;
+        while self.step_accumulator >= step {
+            if steps == MAX_CATCH_UP_STEPS {
+                self.step_accumulator = Duration::ZERO;
+                break;
+            }
+            self.step_accumulator -= step;
+            self.step(step.as_secs_f64());
+            // Stamped with the moment the step stands for, so a catch-up burst isn't counted as a spike
+            self.timing.record_step(now - self.step_accumulator);
+            steps += 1;
+        }
+        // Dialogs show live readings and countdowns, the tilt warning flashes and the soft
+        // start shows its progress, so they redraw at the full rate
+        let state = self.gimbal_controller.get_state();
+        let tilt_warning = self.gimbal_controller.get_config().gimbal.tilt_warning(state.pitch, state.roll).is_some();
+        let soft_starting = matches!(self.gimbal_controller.soft_start(), SoftStart::Ramping(_));
+        if (state.clone(), self.displayed_state()) != shown_before
+            || self.calibration.is_some()
+            || self.detection.is_some()
+            || self.invert_check.is_some()
+            || tilt_warning
+            || soft_starting
+        {
+            self.needs_redraw = true;
+        }
+        self.check_idle();
 
-        // Show all axes with values
-        let mut axes_vec: Vec<_> = self.input_state.axes.iter().collect();
-        axes_vec.sort_by_key(|(axis, _)| format!("{:?}", axis));
+        let outputs_span = tracing::debug_span!("outputs").entered();
+        let armed = self.gimbal_controller.arm_state() == ArmState::Armed && self.output_enabled();
+        let state = &self.output_state();
+        let actuators = || {
+            let legs = kinematics::solve(&self.gimbal_controller.get_config().geometry, state);
+            output::actuator_commands(&self.config.output.actuators, &legs)
+        };
+        if let Some(output) = self.websocket_output.as_mut() {
+            output.send(state, armed, actuators());
+        }
+        if let Some(output) = self.mqtt_output.as_mut() {
+            output.send(state, armed, actuators());
+        }
+        if let Some(output) = self.udp_output.as_mut() {
+            output.send(state, armed, actuators());
+            match output.last_error.clone() {
+                Some(error) => self.pin_status("udp", Severity::Error, error),
+                None => self.unpin_status("udp"),
+            }
+        }
+        self.stream_state();
+        self.check_feedback(state);
+        if let Some(output) = self.mavlink_output.as_mut() {
+            output.send(state);
+            match output.last_error.clone() {
+                Some(error) => self.pin_status("mavlink", Severity::Error, error),
+                None => self.unpin_status("mavlink"),
+            }
+        }
+        if let Some(output) = self.osc_output.as_mut() {
+            output.send(&self.gimbal_controller.get_config().gimbal, state);
+            match output.last_error.clone() {
+                Some(error) => self.pin_status("osc", Severity::Error, error),
+                None => self.unpin_status("osc"),
+            }
+        }
+        if let Some(output) = self.grpc_output.as_mut() {
+            output.send(state, armed);
+            if let Some(error) = output.last_error() {
+                self.pin_status("grpc", Severity::Error, error);
+            }
+        }
+        drop(outputs_span);
+        match self.enable_label() {
+            Some(label) if !self.output_enabled() => {
+                self.pin_status("output", Severity::Warn, format!("Output disabled: hold {} to enable", label))
+            }
+            _ => self.unpin_status("output"),
+        }
+        // An unreachable broker is retried in the background; say so until it is back
+        match self.mqtt_output.as_ref().map(MqttOutput::link) {
+            Some(LinkState::Down { error, .. }) => self.pin_status("mqtt", Severity::Warn, format!("MQTT broker unreachable: {}", error)),
+            Some(_) => self.unpin_status("mqtt"),
+            None => {}
+        }
 
-        for (axis, &value) in axes_vec {
-            let color = if value.abs() > 0.1 {
-                Color::Green
-            } else if value.abs() > 0.01 {
-                Color::Yellow
+        if self.status.tick() {
+            self.needs_redraw = true;
+        }
+        // Events the library logged on its own since the last update
+        self.take_log_lines();
+    }
+
+    // One fixed update of the gimbal from the current input
+    fn step(&mut self, dt: f64) {
+        // Paused: events are still drained, but the state is held. Detecting axes holds it
+        // too, so waggling every control doesn't swing the plate
+        if !self.paused && self.detection.is_none() {
+            if let Some((player, _)) = self.sequence.as_mut() {
+                let target = player.advance(dt, self.gimbal_controller.home_pose());
+                self.gimbal_controller.update_scripted(&self.input_state, target, dt);
+                self.check_sequence();
             } else {
-                Color::Gray
-            };
+                self.gimbal_controller.integrate_keyboard(&mut self.input_state, dt);
+                self.gimbal_controller.update(&self.input_state, dt);
+                self.log_input_values();
+                self.finish_homing();
+            }
+            self.rumble_on_limit();
+        }
+        if self.config.dynamics.simulate_dynamics {
+            let geometry = &self.gimbal_controller.get_config().geometry;
+            self.simulated.update(&self.config.dynamics, geometry, self.gimbal_controller.get_state(), dt);
+        }
+        self.gate_output(dt);
+        self.record_csv_row();
+        self.record_axis_history();
+    }
 
-            items.push(ListItem::new(Line::from(Span::styled(
-                format!("{:?}: {:.3}", axis, value),
-                Style::default().fg(color),
-            ))));
+    // The dead-man's switch is held, or there is none ([output] enable_button/enable_key)
+    fn output_enabled(&self) -> bool {
+        let output = &self.config.output;
+        if output.enable_button.is_none() && output.enable_key.is_none() {
+            return true;
         }
+        let button = output.enable_button.as_deref().and_then(parse_button_name);
+        let button_held = button.is_some_and(|button| self.input_state.buttons.get(&button) == Some(&true));
+        let key_held = output.enable_key.is_some() && self.enable_key_seen.is_some_and(|seen| seen.elapsed() < HELD_KEY_GAP);
+        button_held || key_held
+    }
 
-        if self.config.debug.show_button_states && !self.input_state.buttons.is_empty() {
-            items.push(ListItem::new(Line::from(Span::styled("=== BUTTONS ===", Style::default().fg(Color::Cyan)))));
-            for (button, &pressed) in &self.input_state.buttons {
-                if pressed {
-                    items.push(ListItem::new(Line::from(Span::styled(
-                        format!("{:?}: PRESSED", button),
-                        Style::default().fg(Color::Red),
-                    ))));
-                }
+    // "RightTrigger", "'y'" or both, for the status bar; None without a switch
+    fn enable_label(&self) -> Option<String> {
+        let output = &self.config.output;
+        let labels: Vec<String> = output
+            .enable_button
+            .iter()
+            .cloned()
+            .chain(output.enable_key.map(|key| format!("'{}'", key_label(key))))
+            .collect();
+        (!labels.is_empty()).then(|| labels.join(" or "))
+    }
+
+    // What the outputs send: the gimbal state, unless the enable switch has them held
+    fn output_state(&self) -> GimbalState {
+        self.output_pose.clone().unwrap_or_else(|| self.gimbal_controller.get_state().clone())
+    }
+
+    // Releasing the switch freezes the outputs (on_release = "hold") or ramps them to rest
+    // ("zero") while the plate on screen keeps following input. Holding it again eases them
+    // back onto the gimbal at the same rate, so the hardware never jumps to catch up.
+    fn gate_output(&mut self, dt: f64) {
+        let enabled = self.output_enabled();
+        let state = self.gimbal_controller.get_state().clone();
+        let Some(pose) = self.output_pose.as_mut() else {
+            if !enabled {
+                self.output_pose = Some(state);
+            }
+            return;
+        };
+        let config = self.gimbal_controller.get_config();
+        if enabled {
+            if ease_toward(pose, &state, config, dt) {
+                self.output_pose = None;
             }
+        } else if self.config.output.on_release == EstopBehavior::Zero {
+            ease_toward(pose, &rest_state(config), config, dt);
         }
+    }
 
-        let list = List::new(items)
-            .block(Block::default().borders(Borders::ALL).title("Input Debug"));
-        frame.render_widget(list, area);
+    // The raw value of the axis each of pitch, roll and lift reads from right now. The
+    // ring holds HISTORY_SECS at the current update rate and starts over if that changes.
+    fn record_axis_history(&mut self) {
+        let capacity = (HISTORY_SECS * self.config.app.update_rate_hz).round() as usize;
+        let joystick = &self.gimbal_controller.get_config().controls.joystick;
+        let values = [
+            (&joystick.pitch_axis, &joystick.pitch_device),
+            (&joystick.roll_axis, &joystick.roll_device),
+            (&joystick.lift_axis, &joystick.lift_device),
+        ]
+        .map(|(axis_name, device)| joystick.enabled.then(|| self.channel_raw(axis_name, device)).flatten());
+        for (history, value) in self.axis_history.iter_mut().zip(values) {
+            if history.capacity() != capacity {
+                *history = AxisHistory::new(capacity);
+            }
+            history.push(value);
+        }
     }
 
-    fn draw_debug_state(&self, frame: &mut Frame, area: Rect) {
-        let state = self.gimbal_controller.get_state();
-        let config = self.gimbal_controller.get_config();
+    // As the gamepad reported it, from the channel's own device when it is bound to one
+    fn channel_raw(&self, axis_name: &str, device: &Option<String>) -> Option<f64> {
+        let owner = device_owner(&self.input_state, device.as_deref());
+        let axis = self.gimbal_controller.resolved_axis(&self.input_state, axis_name, owner)?;
+        let axes = owner.and_then(|name| self.input_state.devices.get(name)).unwrap_or(&self.input_state.axes);
+        axes.get(&axis).map(|&value| value as f64)
+    }
 
-        let items = vec![
-            ListItem::new(Line::from(Span::styled("=== GIMBAL STATE ===", Style::default().fg(Color::Cyan)))),
-            ListItem::new(Line::from(format!("Pitch: {:.1}° (max: ±{:.1}°)", state.pitch, config.gimbal.max_pitch))),
-            ListItem::new(Line::from(format!("Roll:  {:.1}° (max: ±{:.1}°)", state.roll, config.gimbal.max_roll))),
-            ListItem::new(Line::from(format!("Lift:  {:.1}mm (max: ±{:.1}mm)", state.lift, config.gimbal.max_lift))),
-            ListItem::new(Line::from("")),
-            ListItem::new(Line::from(Span::styled("=== CONFIG ===", Style::default().fg(Color::Cyan)))),
-            ListItem::new(Line::from(format!("Pitch Axis: {}", config.controls.joystick.pitch_axis))),
-            ListItem::new(Line::from(format!("Roll Axis:  {}", config.controls.joystick.roll_axis))),
-            ListItem::new(Line::from(format!("Lift Axis:  {}", config.controls.joystick.lift_axis))),
-            ListItem::new(Line::from("")),
-            ListItem::new(Line::from(Span::styled("=== KEYBOARD ===", Style::default().fg(Color::Cyan)))),
-            ListItem::new(Line::from(format!("WASD: Pitch/Roll, RF: Lift"))),
-            ListItem::new(Line::from(format!("Step: {:.3}", config.controls.keyboard_step))),
-        ];
+    // Read from the config on every pass, so a changed rate applies straight away
+    fn step_interval(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.config.app.update_rate_hz).max(Duration::from_millis(MIN_LOOP_MS))
+    }
 
-        let list = List::new(items)
-            .block(Block::default().borders(Borders::ALL).title("State & Config"));
-        frame.render_widget(list, area);
+    fn render_interval(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.config.app.render_rate_hz).max(Duration::from_millis(MIN_LOOP_MS))
     }
 
-    fn draw_gimbal_view(&self, frame: &mut Frame) {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3),
-                Constraint::Min(0),
+    fn until_next_step(&self) -> Duration {
+        self.step_interval().saturating_sub(self.step_accumulator + self.last_update.elapsed())
+    }
+
+    fn set_paused(&mut self, paused: bool) {
+        if self.paused == paused {
+            return;
+        }
+        self.paused = paused;
+        if paused {
+            let key = key_label(self.config.controls.keyboard_bindings.pause);
+            self.pin_status("paused", Severity::Warn, format!("PAUSED - press {} to resume", key));
+        } else {
+            // Keys pressed or mouse drags made while paused must not land all at once
+            self.clear_manual_input();
+            self.unpin_status("paused");
+        }
+    }
+
+    // Restarts the idle countdown, and hands control back if the idle return is under way
+    fn note_input(&mut self) {
+        self.last_input = Instant::now();
+        self.unpin_status("idle");
+        if self.idle == IdleReturn::Returning && self.gimbal_controller.is_homing() {
+            self.gimbal_controller.stop_homing();
+            self.unpin_status("homing");
+            self.notify(Severity::Info, "Idle return cancelled");
+        }
+        self.idle = IdleReturn::Watching;
+    }
+
+    // [homing] idle_timeout_secs: homes the plate once nobody has touched anything for that
+    // long, counting down in the status bar first. Runs once per idle spell.
+    fn check_idle(&mut self) {
+        let timeout = self.config.homing.idle_timeout_secs;
+        if timeout <= 0.0 {
+            return;
+        }
+        if self.gimbal_controller.stick_deflection(&self.input_state) > IDLE_DEFLECTION {
+            self.note_input();
+            return;
+        }
+        match self.idle {
+            IdleReturn::Returning if !self.gimbal_controller.is_homing() => self.idle = IdleReturn::Done,
+            IdleReturn::Returning | IdleReturn::Done => return,
+            IdleReturn::Watching => {}
+        }
+        // Holding still on purpose isn't walking away; the countdown starts once these end
+        let busy = self.paused
+            || self.sequence.is_some()
+            || self.calibration.is_some()
+            || self.detection.is_some()
+            || self.invert_check.is_some()
+            || self.command_line.is_some()
+            || self.gimbal_controller.is_homing()
+            || self.gimbal_controller.arm_state() == ArmState::Stopped;
+        if busy {
+            self.last_input = Instant::now();
+            self.unpin_status("idle");
+            return;
+        }
+
+        let remaining = timeout - self.last_input.elapsed().as_secs_f64();
+        if remaining > IDLE_WARNING_SECS {
+            self.unpin_status("idle");
+        } else if remaining > 0.0 {
+            let text = format!("No input - returning home in {:.0}s", remaining.ceil());
+            self.pin_status("idle", Severity::Warn, text);
+        } else {
+            self.unpin_status("idle");
+            self.idle = IdleReturn::Done;
+            let state = self.gimbal_controller.get_state();
+            let home = self.gimbal_controller.home_pose();
+            let at_home = [(state.pitch, home.0), (state.roll, home.1), (state.lift, home.2), (state.yaw, home.3)]
+                .iter()
+                .all(|(value, target)| (value - target).abs() < 0.01);
+            if !at_home {
+                self.log(Severity::Info, &format!("No input for {:.0}s, returning home", timeout));
+                self.start_homing();
+                if self.gimbal_controller.is_homing() {
+                    self.idle = IdleReturn::Returning;
+                }
+            }
+        }
+    }
+
+    // Slews to the configured home pose; stick and keyboard input resume once it arrives
+    fn start_homing(&mut self) {
+        if !self.gimbal_controller.home() {
+            return;
+        }
+        self.clear_manual_input();
+        let (pitch, roll, lift, yaw) = self.gimbal_controller.home_pose();
+        let text = format!(
+            "HOMING to pitch {:+.1} roll {:+.1} lift {:+.1} yaw {:+.1}",
+            pitch, roll, lift, yaw
+        );
+        self.pin_status("homing", Severity::Warn, text);
+    }
+
+    // One line per update on the input_values target, taken from the controller's pipeline.
+    // [debug] log_input_values turns the target on; RUST_LOG=input_values=debug does too.
+    fn log_input_values(&self) {
+        let pipeline = self.gimbal_controller.last_pipeline();
+        let input = |channel: ChannelPipeline| channel.joystick + channel.keyboard + channel.mouse;
+        tracing::debug!(
+            target: INPUT_VALUES,
+            "Input: pitch={:.3}, roll={:.3}, lift={:.3}, yaw={:.3} -> State: pitch={:.1}°, roll={:.1}°, lift={:.1}mm, yaw={:.1}°",
+            input(pipeline.pitch),
+            input(pipeline.roll),
+            input(pipeline.lift),
+            input(pipeline.yaw),
+            pipeline.pitch.output,
+            pipeline.roll.output,
+            pipeline.lift.output,
+            pipeline.yaw.output
+        );
+    }
+
+    // Tracing output goes to [debug] log_file, never the terminal the TUI draws on. It is
+    // appended to from the first time something asks for it until the app exits. A failure
+    // is pinned to the status bar and handed back, for the caller to print before the TUI starts.
+    fn open_log_file(&mut self) -> Result<(), String> {
+        if self.logging.file_open() {
+            return Ok(());
+        }
+        let path = self.logging.path().display().to_string();
+        match self.logging.open_file() {
+            Ok(()) => {
+                self.log(Severity::Info, &format!("Logging to {}", path));
+                Ok(())
+            }
+            Err(e) => {
+                let message = format!("Failed to open log file {}: {}", path, e);
+                self.pin_status("log_file", Severity::Error, message.clone());
+                Err(message)
+            }
+        }
+    }
+
+    // Info -> debug -> trace -> info; raising it opens the log file
+    fn cycle_log_level(&mut self) {
+        let current = self.logging.level();
+        let next = match LEVELS.iter().position(|&level| level == current) {
+            Some(index) => LEVELS[(index + 1) % LEVELS.len()],
+            None => LevelFilter::DEBUG,
+        };
+        self.logging.set_level(next);
+        if next > LevelFilter::INFO {
+            // Already in the status bar on failure
+            let _ = self.open_log_file();
+            self.show_log_level();
+        } else {
+            self.unpin_status("log_level");
+            self.notify(Severity::Info, format!("Log level {}", next));
+        }
+    }
+
+    // Stays in the status bar while the level is raised, since debug and trace fill the file fast
+    fn show_log_level(&mut self) {
+        let text = format!("Log level {} -> {}", self.logging.level(), self.logging.path().display());
+        self.pin_status("log_level", Severity::Info, text);
+    }
+
+    // Clears the HOMING status once the controller has arrived or an e-stop cut it short
+    fn finish_homing(&mut self) {
+        if self.gimbal_controller.is_homing() || !self.status.unpin("homing") {
+            return;
+        }
+        if self.gimbal_controller.arm_state() == ArmState::Stopped {
+            self.notify(Severity::Warn, "Homing interrupted by e-stop");
+        } else if self.gimbal_controller.setpoint().is_some() {
+            self.notify(Severity::Info, "Target pose reached");
+        } else {
+            self.notify(Severity::Info, "Home pose reached");
+        }
+    }
+
+    // Keys typed while the line is open are text, the e-stop key included (it is Space by
+    // default); the gamepad estop_button still stops
+    fn handle_command_key(&mut self, key: KeyCode) {
+        let Some(line) = self.command_line.as_mut() else { return };
+        match key {
+            KeyCode::Esc => {
+                self.command_line = None;
+                self.command_error = None;
+            }
+            KeyCode::Enter => {
+                let line = line.clone();
+                self.run_command(&line);
+            }
+            KeyCode::Backspace => {
+                line.pop();
+            }
+            KeyCode::Char(c) => line.push(c),
+            _ => {}
+        }
+    }
+
+    // Slews to the typed values; channels not named keep their current center. The line
+    // stays open with the error when it doesn't parse.
+    fn run_command(&mut self, line: &str) {
+        let targets = match command::parse(line) {
+            Ok(targets) => targets,
+            Err(e) => {
+                self.command_error = Some(e);
+                return;
+            }
+        };
+        self.command_line = None;
+        self.command_error = None;
+        if targets.is_empty() {
+            return;
+        }
+
+        if let Err(e) = self.move_to(&targets, "a typed target") {
+            self.notify(Severity::Warn, format!("Target ignored: {}", e));
+        }
+    }
+
+    // Slews to a pose like homing does; channels not in `targets` keep the current target
+    fn move_to(&mut self, targets: &[(Channel, f64)], source: &str) -> Result<(), String> {
+        let mut pose = self.gimbal_controller.center_pose();
+        for &(channel, value) in targets {
+            *channel.of(&mut pose) = value;
+        }
+        if !self.gimbal_controller.set_setpoint(pose) {
+            return Err("the e-stop is engaged".to_string());
+        }
+        self.finish_sequence(&format!("aborted by {}", source));
+        self.clear_manual_input();
+        let target = self.gimbal_controller.center_pose();
+        let clamped: Vec<&str> = targets
+            .iter()
+            .filter(|&&(channel, value)| channel.value(target) != value)
+            .map(|(channel, _)| channel.name())
+            .collect();
+        let mut text = format!(
+            "MOVING to pitch {:+.1} roll {:+.1} lift {:+.1} yaw {:+.1}",
+            target.0, target.1, target.2, target.3
+        );
+        if !clamped.is_empty() {
+            text.push_str(&format!(" ({} clamped to the limits)", clamped.join(", ")));
+        }
+        self.pin_status("homing", Severity::Warn, text);
+        Ok(())
+    }
+
+    fn poll_feedback(&mut self) {
+        let Some(input) = self.feedback_input.as_mut() else { return };
+        let received = input.poses;
+        let problems = input.poll();
+        if input.poses != received {
+            self.needs_redraw = true;
+        }
+        for problem in problems {
+            self.log(Severity::Warn, &format!("feedback: {}", problem));
+        }
+    }
+
+    // The pose the hardware last reported, while it is recent enough to trust
+    fn measured_state(&self) -> Option<&GimbalState> {
+        let timeout = Duration::from_secs_f64(self.config.feedback.timeout_secs);
+        self.feedback_input.as_ref()?.latest(timeout)
+    }
+
+    // Warns once the hardware has trailed the command by more than the threshold for a
+    // second, naming the channels that are off; a brief lag while moving is expected
+    fn check_feedback(&mut self, commanded: &GimbalState) {
+        if self.feedback_input.is_none() {
+            return;
+        }
+        let threshold = self.config.feedback.error_threshold;
+        let yaw = self.gimbal_controller.get_config().gimbal.yaw_enabled();
+        let off: Vec<&str> = self
+            .measured_state()
+            .map(|measured| pose_error(commanded, measured))
+            .into_iter()
+            .flatten()
+            .filter(|&(name, error)| error.abs() > threshold && (yaw || name != "Yaw"))
+            .map(|(name, _)| name)
+            .collect();
+        if off.is_empty() {
+            self.feedback_off_since = None;
+            self.unpin_status("feedback");
+            return;
+        }
+        let since = *self.feedback_off_since.get_or_insert_with(Instant::now);
+        if since.elapsed() > FEEDBACK_ERROR_HOLD {
+            let text = format!("Hardware off by more than {} on {}", threshold, off.join(", "));
+            self.pin_status("feedback", Severity::Warn, text);
+        }
+    }
+
+    fn poll_command_server(&mut self) {
+        let Some(server) = self.command_server.as_ref() else { return };
+        for event in server.poll() {
+            match event {
+                ServerEvent::Connected(peer) => {
+                    self.log(Severity::Info, &format!("Command client {} connected", peer));
+                    self.server_clients.push(peer);
+                }
+                ServerEvent::Disconnected(peer) => {
+                    self.log(Severity::Info, &format!("Command client {} disconnected", peer));
+                    self.server_clients.retain(|&p| p != peer);
+                    self.stream_clients.retain(|client| client.peer() != peer);
+                }
+                ServerEvent::Request { command, client } => {
+                    self.note_input();
+                    let source = format!("command client {}", client.peer());
+                    let reply = match self.run_server_command(command, &source, Some(&client)) {
+                        Ok(reply) => reply,
+                        Err(e) => format!("ERR {}", e),
+                    };
+                    client.send(reply);
+                }
+            }
+        }
+    }
+
+    // SetTarget calls go through the same path as the command server's SET
+    fn poll_grpc(&mut self) {
+        let Some(output) = self.grpc_output.as_ref() else { return };
+        for request in output.poll() {
+            self.note_input();
+            let source = match request.peer {
+                Some(peer) => format!("gRPC client {}", peer),
+                None => "gRPC client".to_string(),
+            };
+            let result = self.move_to(&request.targets, &source).map(|()| self.gimbal_controller.center_pose());
+            request.reply(result);
+        }
+    }
+
+    // Commands published to <prefix>/command, answered on <prefix>/reply
+    fn poll_mqtt(&mut self) {
+        let Some(commands) = self.mqtt_output.as_ref().map(MqttOutput::poll) else { return };
+        for command in commands {
+            self.note_input();
+            let reply = match self.run_server_command(command, "MQTT", None) {
+                Ok(reply) => reply,
+                Err(e) => format!("ERR {}", e),
+            };
+            if let Some(output) = self.mqtt_output.as_mut() {
+                output.reply(reply);
+            }
+        }
+    }
+
+    // The reply line for one command; remote commands obey the e-stop like keys do. Only
+    // command server clients can stream, MQTT gets the state on its own topic.
+    fn run_server_command(&mut self, command: ServerCommand, source: &str, client: Option<&Client>) -> Result<String, String> {
+        let stopped = self.gimbal_controller.arm_state() == ArmState::Stopped;
+        match command {
+            ServerCommand::Set(targets) => self.move_to(&targets, source)?,
+            ServerCommand::Get => return Ok(format!("OK {}", self.state_line())),
+            ServerCommand::Reset if stopped => return Err("the e-stop is engaged".to_string()),
+            ServerCommand::Reset => {
+                self.finish_sequence(&format!("aborted by {}", source));
+                self.gimbal_controller.reset();
+                self.clear_manual_input();
+                self.notify(Severity::Info, format!("Gimbal reset by {}", source));
+            }
+            ServerCommand::Preset(name) => {
+                if let Some(preset) = self.config.presets.get(&name) {
+                    let targets: Vec<(Channel, f64)> = [
+                        (Channel::Pitch, preset.pitch),
+                        (Channel::Roll, preset.roll),
+                        (Channel::Lift, preset.lift),
+                        (Channel::Yaw, preset.yaw),
+                    ]
+                    .into_iter()
+                    .filter_map(|(channel, value)| value.map(|v| (channel, v)))
+                    .collect();
+                    self.move_to(&targets, source)?;
+                } else if name.eq_ignore_ascii_case("home") {
+                    if stopped {
+                        return Err("the e-stop is engaged".to_string());
+                    }
+                    self.finish_sequence(&format!("aborted by {}", source));
+                    self.start_homing();
+                } else {
+                    return Err(format!("unknown preset '{}'", name));
+                }
+            }
+            ServerCommand::Estop => self.trigger_estop(source),
+            ServerCommand::Stream(on) => {
+                let Some(client) = client else { return Err("STREAM needs a command server connection".to_string()) };
+                self.stream_clients.retain(|c| c.peer() != client.peer());
+                if on {
+                    self.stream_clients.push(client.clone());
+                }
+            }
+        }
+        Ok("OK".to_string())
+    }
+
+    // "pitch roll lift yaw armed|stopped", as GET answers and STREAM pushes it
+    fn state_line(&self) -> String {
+        let state = self.gimbal_controller.get_state();
+        let arm = match self.gimbal_controller.arm_state() {
+            ArmState::Armed => "armed",
+            ArmState::Stopped => "stopped",
+        };
+        format!("{:.3} {:.3} {:.3} {:.3} {}", state.pitch, state.roll, state.lift, state.yaw, arm)
+    }
+
+    fn stream_state(&mut self) {
+        if self.stream_clients.is_empty() {
+            return;
+        }
+        let line = format!("STATE {}", self.state_line());
+        self.stream_clients.retain(|client| client.send(line.clone()));
+    }
+
+    // Loads the sequence file fresh each time, so edits apply without a restart
+    fn start_sequence(&mut self) {
+        let Some(path) = self.sequence_path.clone() else {
+            self.notify(Severity::Warn, "No sequence file: set [sequence] file or pass --sequence");
+            return;
+        };
+        match Sequence::load(&path) {
+            Ok(sequence) => self.run_sequence(sequence),
+            Err(e) => self.notify(Severity::Error, format!("Failed to load sequence {}: {}", path.display(), e)),
+        }
+    }
+
+    // Manual input is locked out until the run completes or is aborted
+    fn run_sequence(&mut self, sequence: Sequence) {
+        let state = self.gimbal_controller.get_state();
+        let pose = (state.pitch, state.roll, state.lift, state.yaw);
+        let started = unix_millis();
+        let abort = if self.config.sequence.abort_threshold > 0.0 { "Esc or a stick movement" } else { "Esc" };
+        self.clear_manual_input();
+        self.unpin_status("homing");
+        self.log(Severity::Info, &format!("Sequence '{}' started at {} (Unix ms)", sequence.name, started));
+        let text = format!("SEQUENCE '{}' ({:.1}s) - {} aborts", sequence.name, sequence.duration(), abort);
+        self.pin_status("sequence", Severity::Warn, text);
+        self.sequence = Some((SequencePlayer::new(sequence, pose), started));
+    }
+
+    fn check_sequence(&mut self) {
+        let Some((player, _)) = &self.sequence else { return };
+        if player.is_finished() {
+            self.finish_sequence("completed");
+            return;
+        }
+        // --simulate and --virtual-gamepad move the sticks themselves, so only a real stick can abort
+        let threshold = self.config.sequence.abort_threshold;
+        if threshold > 0.0
+            && self.simulation.is_none()
+            && self.virtual_pad.is_none()
+            && self.gimbal_controller.stick_deflection(&self.input_state) >= threshold
+        {
+            self.finish_sequence("aborted by stick movement");
+        }
+    }
+
+    // Ends the run and records its start/end times, for lining up with external captures
+    fn finish_sequence(&mut self, outcome: &str) {
+        let Some((player, started)) = self.sequence.take() else { return };
+        let ended = unix_millis();
+        self.unpin_status("sequence");
+        self.clear_manual_input();
+        let severity = if outcome == "completed" { Severity::Info } else { Severity::Warn };
+        let text = format!("Sequence '{}' {} ({} - {} Unix ms)", player.name(), outcome, started, ended);
+        self.notify(severity, text);
+
+        let Some(path) = self.config.sequence.run_log.clone() else { return };
+        if let Err(e) = append_run_log(&path, player.name(), started, ended, outcome) {
+            self.notify(Severity::Error, format!("Failed to write sequence run log {}: {}", path, e));
+        }
+    }
+
+    // Rumbles once when a channel first saturates, then stays quiet for RUMBLE_COOLDOWN
+    fn rumble_on_limit(&mut self) {
+        let saturated = self.gimbal_controller.last_pipeline().any_saturated();
+        let rising = saturated && !self.was_saturated;
+        self.was_saturated = saturated;
+        if !rising
+            || !self.config.controls.rumble_on_limit
+            || self.last_rumble.is_some_and(|at| at.elapsed() < RUMBLE_COOLDOWN)
+        {
+            return;
+        }
+        let (Some(gilrs), Some(pad)) = (self.gilrs.as_mut(), self.last_active_gamepad) else {
+            return;
+        };
+        // Only gilrs pads have motors
+        let Some(id) = gilrs.gamepads().map(|(id, _)| id).find(|&id| PadId::from(id) == pad) else {
+            return;
+        };
+        if !gilrs.connected_gamepad(id).is_some_and(|gamepad| gamepad.is_ff_supported()) {
+            return;
+        }
+
+        let duration = Ticks::from_ms(RUMBLE_DURATION_MS);
+        let effect = EffectBuilder::new()
+            .add_effect(BaseEffect {
+                kind: BaseEffectType::Strong { magnitude: RUMBLE_MAGNITUDE },
+                scheduling: Replay { play_for: duration, ..Default::default() },
+                envelope: Default::default(),
+            })
+            .repeat(Repeat::For(duration))
+            .gamepads(&[id])
+            .finish(gilrs)
+            .and_then(|effect| effect.play().map(|()| effect));
+        self.last_rumble = Some(Instant::now());
+        match effect {
+            Ok(effect) => self.rumble = Some(effect),
+            Err(e) => self.log(Severity::Warn, &format!("Rumble failed: {}", e)),
+        }
+    }
+
+    // The pad that last moved an axis, or any connected one
+    fn wizard_gamepad(&self) -> Option<(PadId, String)> {
+        let id = self
+            .last_active_gamepad
+            .or_else(|| self.gamepads.iter().find(|(_, g)| g.connected).map(|(&id, _)| id))?;
+        self.gamepads.get(&id).map(|gamepad| (id, gamepad.name.clone()))
+    }
+
+    fn start_calibration(&mut self) {
+        if let Some((id, name)) = self.wizard_gamepad() {
+            self.calibration = Some(CalibrationWizard::new(id, name));
+        }
+    }
+
+    fn start_detection(&mut self) {
+        match self.wizard_gamepad() {
+            Some((id, name)) => {
+                self.clear_manual_input();
+                self.detection = Some(AxisDetector::new(id, name));
+            }
+            None => self.notify(Severity::Warn, "No gamepad to detect axes on"),
+        }
+    }
+
+    fn start_invert_check(&mut self) {
+        match self.wizard_gamepad() {
+            Some((id, name)) => {
+                self.clear_manual_input();
+                self.invert_check = Some(InvertCheck::new(id, name));
+            }
+            None => self.notify(Severity::Warn, "No gamepad to check inversion on"),
+        }
+    }
+
+    // A "no" flips the channel's invert flag on the active mapping straight away, so the
+    // plate turns round while the stick is still held
+    fn handle_invert_check_key(&mut self, key: KeyCode) {
+        let Some(mut check) = self.invert_check.take() else {
+            return;
+        };
+
+        match key {
+            KeyCode::Esc => {
+                let message = match check.flipped() {
+                    0 => "Inversion check closed".to_string(),
+                    flipped => format!(
+                        "Inversion check closed; {} flag(s) changed for this session (press '{}' to save)",
+                        flipped,
+                        key_label(self.config.controls.keyboard_bindings.save_config)
+                    ),
+                };
+                self.notify(Severity::Info, message);
+                return;
+            }
+            KeyCode::Char('y' | 'Y') => {
+                check.answer(true);
+            }
+            KeyCode::Char('n' | 'N') => {
+                let action = match check.answer(false) {
+                    Some(DetectChannel::Pitch) => Some(KeyAction::InvertPitch),
+                    Some(DetectChannel::Roll) => Some(KeyAction::InvertRoll),
+                    Some(DetectChannel::Lift) => Some(KeyAction::InvertLift),
+                    None => None,
+                };
+                if let Some(action) = action {
+                    self.toggle_invert(action);
+                }
+            }
+            KeyCode::Char('s' | 'S') => check.skip(),
+            KeyCode::Enter if check.step() == CheckStep::Review => match self.save_config() {
+                Ok(()) => {
+                    self.notify(Severity::Info, format!("Saved inversion check for '{}'", check.gamepad_name));
+                    return;
+                }
+                Err(e) => {
+                    let error = format!("Failed to save inversion check: {}", e);
+                    self.notify(Severity::Error, error.clone());
+                    check.message = Some(error);
+                }
+            },
+            _ => {}
+        }
+
+        self.invert_check = Some(check);
+    }
+
+    fn handle_detection_key(&mut self, key: KeyCode) {
+        let Some(mut detector) = self.detection.take() else {
+            return;
+        };
+
+        match key {
+            KeyCode::Esc => {
+                self.notify(Severity::Info, "Axis detection cancelled");
+                return;
+            }
+            KeyCode::Backspace => detector.redo(),
+            KeyCode::Enter if detector.step() == DetectStep::Review => {
+                // Results go to whichever mapping is active; a trim measured on the old axis
+                // means nothing on the new one
+                let joystick = match self.active_profile.and_then(|i| self.config.profiles.get_mut(i)) {
+                    Some(profile) => &mut profile.joystick,
+                    None => &mut self.config.controls.joystick,
+                };
+                for &(channel, detected) in detector.results() {
+                    let (axis, invert, trim) = match channel {
+                        DetectChannel::Pitch => {
+                            (&mut joystick.pitch_axis, &mut joystick.invert_pitch, &mut joystick.pitch_trim)
+                        }
+                        DetectChannel::Roll => {
+                            (&mut joystick.roll_axis, &mut joystick.invert_roll, &mut joystick.roll_trim)
+                        }
+                        DetectChannel::Lift => {
+                            (&mut joystick.lift_axis, &mut joystick.invert_lift, &mut joystick.lift_trim)
+                        }
+                    };
+                    let name = axis_key(detected.axis);
+                    if *axis != name {
+                        *trim = 0.0;
+                    }
+                    *axis = name;
+                    *invert = detected.invert;
+                }
+                match self.save_config() {
+                    Ok(()) => {
+                        self.notify(Severity::Info, format!("Saved detected axes for '{}'", detector.gamepad_name));
+                        return;
+                    }
+                    Err(e) => {
+                        let error = format!("Failed to save detected axes: {}", e);
+                        self.notify(Severity::Error, error.clone());
+                        detector.message = Some(error);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        self.detection = Some(detector);
+    }
+
+    // Axes the wizard lists for its gamepad, in display order
+    fn calibration_axes(&self, wizard: &CalibrationWizard) -> Vec<Axis> {
+        let mut axes: Vec<Axis> = self
+            .gamepads
+            .get(&wizard.gamepad_id)
+            .map(|g| g.axes.keys().copied().collect())
+            .unwrap_or_default();
+        axes.sort_by_key(|axis| axis_key(*axis));
+        axes
+    }
+
+    fn handle_calibration_key(&mut self, key: KeyCode) {
+        let Some(mut wizard) = self.calibration.take() else {
+            return;
+        };
+        let axes = self.calibration_axes(&wizard);
+
+        match key {
+            KeyCode::Esc => return,
+            KeyCode::Enter if wizard.step() == CalibrationStep::Review => {
+                let entry = self.config.calibration.entry(wizard.gamepad_name.clone()).or_default();
+                for (axis, calibration) in wizard.results() {
+                    entry.insert(axis_key(axis), calibration);
+                }
+                match self.save_config() {
+                    Ok(()) => {
+                        self.notify(Severity::Info, format!("Saved calibration for '{}'", wizard.gamepad_name));
+                        return;
+                    }
+                    Err(e) => {
+                        let error = format!("Failed to save calibration: {}", e);
+                        self.notify(Severity::Error, error.clone());
+                        wizard.message = Some(error);
+                    }
+                }
+            }
+            KeyCode::Enter => wizard.advance(),
+            KeyCode::Up => wizard.selected = wizard.selected.saturating_sub(1),
+            KeyCode::Down if wizard.selected + 1 < axes.len() => wizard.selected += 1,
+            KeyCode::Char('x') | KeyCode::Delete => {
+                if let Some(&axis) = axes.get(wizard.selected) {
+                    wizard.discard(axis);
+                    if let Some(stored) = self.config.calibration.get_mut(&wizard.gamepad_name) {
+                        stored.remove(&axis_key(axis));
+                    }
+                    match self.save_config() {
+                        Ok(()) => self.notify(Severity::Info, format!("Cleared calibration for {}", axis_key(axis))),
+                        Err(e) => {
+                            let error = format!("Failed to save calibration: {}", e);
+                            self.notify(Severity::Error, error.clone());
+                            wizard.message = Some(error);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        self.calibration = Some(wizard);
+    }
+
+    // Writes the config to disk and hands it to the gimbal controller
+    fn save_config(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.apply_mapping();
+        self.config.save(&self.config_path)
+    }
+
+    // The controller runs on the config with the active mapping and hardware profiles swapped in
+    fn apply_mapping(&mut self) {
+        let mut config = self.config.clone();
+        if let Some(profile) = self.active_profile.and_then(|i| self.config.profiles.get(i)) {
+            config.controls.joystick = profile.joystick.clone();
+        }
+        if let Some(hardware) = self.active_hardware.and_then(|i| self.config.hardware.get(i)) {
+            config.gimbal = hardware.gimbal.clone();
+            config.geometry = hardware.geometry.clone();
+        }
+        self.gimbal_controller.set_config(config);
+    }
+
+    // What the settings editor works on: the config with the active hardware profile's
+    // [gimbal] in place of the base one, so edits land where they take effect
+    fn settings_config(&self) -> Config {
+        let mut config = self.config.clone();
+        if let Some(hardware) = self.active_hardware.and_then(|i| self.config.hardware.get(i)) {
+            config.gimbal = hardware.gimbal.clone();
+        }
+        config
+    }
+
+    // The running config with an edited settings config folded back in
+    fn with_settings(&self, edited: Config) -> Config {
+        let mut config = edited;
+        if let Some(i) = self.active_hardware.filter(|&i| i < config.hardware.len()) {
+            config.hardware[i].gimbal = std::mem::replace(&mut config.gimbal, self.config.gimbal.clone());
+        }
+        config
+    }
+
+    // Flips one invert flag on whichever mapping is active; saving keeps it
+    fn toggle_invert(&mut self, action: KeyAction) {
+        let joystick = match self.active_profile.and_then(|i| self.config.profiles.get_mut(i)) {
+            Some(profile) => &mut profile.joystick,
+            None => &mut self.config.controls.joystick,
+        };
+        // An absolute lever has no invert; the key flips which end is up instead
+        if action == KeyAction::InvertLift && joystick.lift_control == LiftControl::Absolute {
+            joystick.lift_reversed = !joystick.lift_reversed;
+            let message = format!(
+                "Lift lever {} (press '{}' to save)",
+                if joystick.lift_reversed { "reversed" } else { "not reversed" },
+                key_label(self.config.controls.keyboard_bindings.save_config)
+            );
+            self.apply_mapping();
+            self.notify(Severity::Info, message);
+            return;
+        }
+        let (channel, invert) = match action {
+            KeyAction::InvertPitch => ("Pitch", &mut joystick.invert_pitch),
+            KeyAction::InvertRoll => ("Roll", &mut joystick.invert_roll),
+            KeyAction::InvertLift => ("Lift", &mut joystick.invert_lift),
+            _ => return,
+        };
+        *invert = !*invert;
+        let message = format!(
+            "{} axis {} (press '{}' to save)",
+            channel,
+            if *invert { "inverted" } else { "not inverted" },
+            key_label(self.config.controls.keyboard_bindings.save_config)
+        );
+        self.apply_mapping();
+        self.notify(Severity::Info, message);
+    }
+
+    // Moves one channel's output by TRIM_STEP on whichever mapping is active. A trim is
+    // subtracted from the raw axis before inversion, so its sign follows the invert flag.
+    fn nudge_trim(&mut self, channel: Channel, direction: f64) {
+        let joystick = match self.active_profile.and_then(|i| self.config.profiles.get_mut(i)) {
+            Some(profile) => &mut profile.joystick,
+            None => &mut self.config.controls.joystick,
+        };
+        let (trim, inverted) = match channel {
+            Channel::Pitch => (&mut joystick.pitch_trim, joystick.invert_pitch),
+            Channel::Roll => (&mut joystick.roll_trim, joystick.invert_roll),
+            _ => return,
+        };
+        let sign = if inverted { 1.0 } else { -1.0 };
+        *trim = (*trim + sign * direction * TRIM_STEP).clamp(-1.0, 1.0);
+        let message = format!(
+            "{} trim {:+.3} (press '{}' to save)",
+            channel.name(),
+            *trim,
+            key_label(self.config.controls.keyboard_bindings.save_config)
+        );
+        self.apply_mapping();
+        self.notify(Severity::Info, message);
+    }
+
+    // For a stick mapped the wrong way round; inversions stay with their channel
+    fn swap_pitch_roll(&mut self) {
+        let joystick = match self.active_profile.and_then(|i| self.config.profiles.get_mut(i)) {
+            Some(profile) => &mut profile.joystick,
+            None => &mut self.config.controls.joystick,
+        };
+        std::mem::swap(&mut joystick.pitch_axis, &mut joystick.roll_axis);
+        std::mem::swap(&mut joystick.pitch_trim, &mut joystick.roll_trim);
+        let message = format!(
+            "Pitch now on {}, roll on {} (press '{}' to save)",
+            joystick.pitch_axis,
+            joystick.roll_axis,
+            key_label(self.config.controls.keyboard_bindings.save_config)
+        );
+        self.apply_mapping();
+        self.notify(Severity::Info, message);
+    }
+
+    fn hardware_name(&self) -> &str {
+        self.active_hardware
+            .and_then(|i| self.config.hardware.get(i))
+            .map_or("default", |hardware| hardware.name.as_str())
+    }
+
+    // default -> each [[hardware]] profile -> back to default
+    fn cycle_hardware(&mut self) {
+        self.active_hardware = match self.active_hardware {
+            None if !self.config.hardware.is_empty() => Some(0),
+            Some(i) if i + 1 < self.config.hardware.len() => Some(i + 1),
+            _ => None,
+        };
+        self.apply_mapping();
+        let gimbal = &self.gimbal_controller.get_config().gimbal;
+        let message = format!(
+            "Hardware profile '{}' (pitch {}°, roll {}°, lift {:.0}..{:.0}mm)",
+            self.hardware_name(),
+            range_label(gimbal.pitch_range(), 0),
+            range_label(gimbal.roll_range(), 0),
+            gimbal.lift_min,
+            gimbal.lift_max
+        );
+        self.notify(Severity::Info, message);
+    }
+
+    fn profile_name(&self, index: Option<usize>) -> &str {
+        index
+            .and_then(|i| self.config.profiles.get(i))
+            .map_or("default", |profile| profile.name.as_str())
+    }
+
+    // Profile auto-matched to the gamepad currently driving the gimbal
+    fn auto_profile(&self) -> Option<usize> {
+        self.last_active_gamepad
+            .and_then(|id| self.gamepads.get(&id))
+            .and_then(|gamepad| self.config.profile_for(&gamepad.name))
+    }
+
+    fn refresh_profile(&mut self) {
+        let wanted = self.manual_profile.unwrap_or_else(|| self.auto_profile());
+        if wanted != self.active_profile {
+            self.active_profile = wanted;
+            self.apply_mapping();
+            self.notify(Severity::Info, format!("Using mapping profile '{}'", self.profile_name(wanted)));
+        }
+    }
+
+    // The running config's file name without .toml
+    fn config_profile_name(&self) -> String {
+        self.config_path.file_stem().map_or_else(|| self.config_path.display().to_string(), |stem| stem.to_string_lossy().into_owned())
+    }
+
+    // " (name)" for the headers, or nothing without config profiles
+    fn config_profile_tag(&self) -> String {
+        if self.config_profiles.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", self.config_profile_name())
+        }
+    }
+
+    // Digit keys bound to an action keep it, which leaves that profile without a key
+    fn warn_profile_key_conflicts(&mut self) {
+        let bindings = &self.config.controls.keyboard_bindings;
+        let conflicts: Vec<String> = (0..self.config_profiles.len())
+            .filter_map(|slot| {
+                let key = char::from_digit(slot as u32 + 1, 10)?;
+                let action = bindings.action_for(key)?;
+                Some(format!("key '{}' is bound to {}, so profile {} has no key", key, action.name(), self.config_profile_label(slot)))
+            })
+            .collect();
+        for conflict in conflicts {
+            self.notify(Severity::Warn, conflict);
+        }
+    }
+
+    fn config_profile_label(&self, slot: usize) -> String {
+        self.config_profiles[slot].file_stem().map_or_else(String::new, |stem| format!("'{}'", stem.to_string_lossy()))
+    }
+
+    // Loads profiles/<file> in place of the running config; its key again goes back to --config.
+    // Everything read per update follows at once, but outputs, the command server and the CSV
+    // log stay as they were started.
+    fn switch_config_profile(&mut self, slot: usize) {
+        let Some(profile) = self.config_profiles.get(slot) else {
+            self.notify(Severity::Warn, format!("No profile on key '{}'", slot + 1));
+            return;
+        };
+        let path = if self.config_path == *profile { self.startup_config_path.clone() } else { profile.clone() };
+        let (config, problems) = match Config::load(&path, false) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                // TOML errors quote the offending line below the message; the status bar has one line
+                let reason: Vec<String> = e.to_string().lines().filter(|line| !line.contains('|')).map(str::to_string).collect();
+                self.notify(Severity::Error, format!("Failed to load profile {}: {}", path.display(), reason.join(": ")));
+                return;
+            }
+        };
+        if let Some(migration) = &config.migration {
+            let (severity, message) = migration_message(migration, &path);
+            self.notify(severity, message);
+        }
+        for problem in problems {
+            self.notify(Severity::Warn, format!("Config: {} (using the default)", problem));
+        }
+
+        self.finish_sequence("aborted by a profile switch");
+        self.clear_manual_input();
+        self.config = config;
+        self.config_path = path;
+        self.theme = Theme::new(&self.config.theme, std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()));
+        self.active_profile = None;
+        self.manual_profile = None;
+        self.active_hardware = self.config.app.hardware_profile.as_ref().and_then(|name| self.config.hardware_index(name));
+        self.apply_mapping();
+        self.refresh_profile();
+        self.needs_redraw = true;
+        for conflict in self.config.controls.keyboard_bindings.conflicts() {
+            self.notify(Severity::Warn, conflict);
+        }
+        self.warn_profile_key_conflicts();
+        self.notify(Severity::Info, format!("Profile '{}' ({})", self.config_profile_name(), self.config_path.display()));
+    }
+
+    // auto -> default -> each profile -> back to auto
+    fn cycle_profile(&mut self) {
+        self.manual_profile = match self.manual_profile {
+            None => Some(None),
+            Some(None) if !self.config.profiles.is_empty() => Some(Some(0)),
+            Some(Some(i)) if i + 1 < self.config.profiles.len() => Some(Some(i + 1)),
+            Some(_) => None,
+        };
+        if self.manual_profile.is_none() {
+            self.notify(Severity::Info, "Mapping profile: automatic");
+        }
+        self.refresh_profile();
+    }
+
+    fn handle_key(&mut self, key: KeyCode) {
+        self.note_input();
+        if self.command_line.is_some() {
+            self.handle_command_key(key);
+            return;
+        }
+
+        // The output enable key only ever enables, in every mode
+        if self.config.output.enable_key.map(KeyCode::Char) == Some(key) {
+            self.enable_key_seen = Some(Instant::now());
+            return;
+        }
+
+        // The stop key works everywhere else, including dialogs and overlays
+        let action = self.key_action(key);
+        if action == Some(KeyAction::Estop) {
+            self.trigger_estop("keyboard");
+            return;
+        }
+        if action == Some(KeyAction::Rearm) && self.gimbal_controller.arm_state() == ArmState::Stopped {
+            self.hold_rearm();
+            return;
+        }
+
+        if self.calibration.is_some() {
+            self.handle_calibration_key(key);
+            return;
+        }
+
+        if self.detection.is_some() {
+            self.handle_detection_key(key);
+            return;
+        }
+
+        if self.invert_check.is_some() {
+            self.handle_invert_check_key(key);
+            return;
+        }
+
+        if self.help_scroll.is_some() {
+            self.handle_help_key(key);
+            return;
+        }
+
+        if self.settings.is_some() {
+            self.handle_settings_key(key);
+            return;
+        }
+
+        if key == KeyCode::Esc && self.sequence.is_some() {
+            self.finish_sequence("aborted by Esc");
+            return;
+        }
+
+        let bindings = &self.config.controls.keyboard_bindings;
+        let is_quit = match key {
+            KeyCode::Esc => true,
+            KeyCode::Char(c) => bindings.action_for(c) == Some(KeyAction::Quit),
+            _ => false,
+        };
+
+        // While a quit is pending, a second quit key exits and anything else cancels
+        if self.pending_quit {
+            self.pending_quit = false;
+            self.unpin_status("quit");
+            if is_quit {
+                self.running = false;
+            } else {
+                self.notify(Severity::Info, "Quit cancelled");
+            }
+            return;
+        }
+
+        if is_quit {
+            if self.config.app.confirm_quit {
+                self.pending_quit = true;
+                let prompt = format!("Press {} again to quit / any other key to cancel", bindings.quit);
+                self.pin_status("quit", Severity::Warn, prompt);
+            } else {
+                self.running = false;
+            }
+            return;
+        }
+
+        if key == KeyCode::F(1) {
+            self.help_scroll = Some(0);
+            return;
+        }
+
+        if self.debug_mode && self.show_event_log && self.handle_event_log_key(key) {
+            return;
+        }
+
+        // Unbound digits pick a config profile
+        if action.is_none()
+            && let KeyCode::Char(c) = key
+            && let Some(digit @ 1..=9) = c.to_digit(10)
+            && !self.config_profiles.is_empty()
+        {
+            self.switch_config_profile(digit as usize - 1);
+            return;
+        }
+
+        let Some(action) = action else { return };
+        if !self.run_action(action) && self.sequence.is_none() {
+            self.gimbal_controller.handle_keyboard(&mut self.input_state, action, true);
+        }
+    }
+
+    // The bound action for a key; with arrow_keys on, the arrows also tilt like w/s/a/d
+    fn key_action(&self, key: KeyCode) -> Option<KeyAction> {
+        let bindings = &self.config.controls.keyboard_bindings;
+        match key {
+            KeyCode::Char(c) => bindings.action_for(c),
+            KeyCode::Up if bindings.arrow_keys => Some(KeyAction::PitchUp),
+            KeyCode::Down if bindings.arrow_keys => Some(KeyAction::PitchDown),
+            KeyCode::Left if bindings.arrow_keys => Some(KeyAction::RollLeft),
+            KeyCode::Right if bindings.arrow_keys => Some(KeyAction::RollRight),
+            _ => None,
+        }
+    }
+
+    // App actions shared by key bindings and [controls.button_actions]. Returns false for
+    // movement keys and actions that don't apply right now, so the caller can treat the key
+    // as movement.
+    fn run_action(&mut self, action: KeyAction) -> bool {
+        // While stopped only actions that can't move the gimbal do anything
+        if self.gimbal_controller.arm_state() == ArmState::Stopped
+            && !matches!(
+                action,
+                KeyAction::Help
+                    | KeyAction::ToggleDebug
+                    | KeyAction::SaveConfig
+                    | KeyAction::ToggleCsvLog
+                    | KeyAction::PrevGamepad
+                    | KeyAction::NextGamepad
+                    | KeyAction::ToggleTiming
+                    | KeyAction::ToggleEventLog
+                    | KeyAction::CycleProjection
+                    | KeyAction::ToggleTiltLines
+                    | KeyAction::ToggleSolver
+                    | KeyAction::CycleView
+                    | KeyAction::ExportFrame
+                    | KeyAction::CycleLogLevel
+            )
+        {
+            return true;
+        }
+        // Anything else that moves the gimbal ends a running sequence first
+        if matches!(action, KeyAction::Reset | KeyAction::Home) {
+            self.finish_sequence(&format!("aborted by {}", action.name()));
+        }
+        match action {
+            KeyAction::Help => {
+                self.help_scroll = Some(0);
+            }
+            KeyAction::ToggleDebug => {
+                self.debug_mode = !self.debug_mode;
+                self.show_solver = false;
+            }
+            KeyAction::ToggleTiming => {
+                self.show_timing = !self.show_timing;
+            }
+            // The pane lives in the debug view, so opening it from the normal view switches there
+            KeyAction::ToggleEventLog => {
+                self.show_event_log = !self.show_event_log || !self.debug_mode;
+                self.debug_mode = true;
+            }
+            KeyAction::CycleProjection => {
+                self.projection = self.projection.next();
+            }
+            KeyAction::ToggleTiltLines => {
+                self.tilt_lines = !self.tilt_lines;
+            }
+            KeyAction::ToggleSolver => {
+                self.show_solver = !self.show_solver;
+            }
+            // Gimbal -> debug -> solver -> gimbal
+            KeyAction::CycleView => {
+                (self.debug_mode, self.show_solver) = match (self.debug_mode, self.show_solver) {
+                    (_, true) => (false, false),
+                    (false, false) => (true, false),
+                    (true, false) => (false, true),
+                };
+            }
+            KeyAction::Settings => {
+                self.clear_manual_input();
+                self.settings = Some(SettingsEditor::new(&self.settings_config()));
+            }
+            KeyAction::Calibrate if self.debug_mode => {
+                self.start_calibration();
+            }
+            KeyAction::DetectAxes => {
+                self.start_detection();
+            }
+            KeyAction::CheckInversion => {
+                self.start_invert_check();
+            }
+            KeyAction::Command => {
+                self.clear_manual_input();
+                self.command_line = Some(String::new());
+            }
+            KeyAction::RunSequence => {
+                if self.sequence.is_some() {
+                    self.finish_sequence("aborted");
+                } else {
+                    self.start_sequence();
+                }
+            }
+            KeyAction::CaptureCenter => {
+                self.gimbal_controller.capture_center(&self.input_state);
+                // Trims belong to whichever mapping is active
+                let captured = self.gimbal_controller.get_config().controls.joystick.clone();
+                match self.active_profile.and_then(|i| self.config.profiles.get_mut(i)) {
+                    Some(profile) => profile.joystick = captured,
+                    None => self.config.controls.joystick = captured,
+                }
+                let joystick = &self.gimbal_controller.get_config().controls.joystick;
+                let message = format!(
+                    "Captured trims: pitch {:+.3} roll {:+.3} lift {:+.3} yaw {:+.3}",
+                    joystick.pitch_trim, joystick.roll_trim, joystick.lift_trim, joystick.yaw_trim
+                );
+                self.notify(Severity::Info, message);
+            }
+            KeyAction::SaveConfig => match self.save_config() {
+                Ok(()) => self.notify(Severity::Info, format!("Saved config to {}", self.config_path.display())),
+                Err(e) => self.notify(Severity::Error, format!("Failed to save config: {}", e)),
+            },
+            KeyAction::CycleProfile => {
+                self.cycle_profile();
+            }
+            KeyAction::CycleHardware => {
+                self.cycle_hardware();
+            }
+            KeyAction::InvertPitch | KeyAction::InvertRoll | KeyAction::InvertLift => {
+                self.toggle_invert(action);
+            }
+            KeyAction::SwapPitchRoll => {
+                self.swap_pitch_roll();
+            }
+            KeyAction::PrevGamepad if self.debug_mode && !self.gamepads.is_empty() => {
+                let count = self.gamepads.len();
+                self.debug_gamepad = (self.debug_gamepad % count + count - 1) % count;
+            }
+            KeyAction::NextGamepad if self.debug_mode && !self.gamepads.is_empty() => {
+                self.debug_gamepad = (self.debug_gamepad + 1) % self.gamepads.len();
+            }
+            KeyAction::ToggleCsvLog => {
+                if self.csv_log.is_some() {
+                    self.stop_csv_log();
+                } else {
+                    let pattern = self.config.debug.csv_log.clone().unwrap_or_else(|| DEFAULT_CSV_LOG.to_string());
+                    self.start_csv_log(&pattern);
+                }
+            }
+            KeyAction::Reset => {
+                self.gimbal_controller.reset();
+                self.clear_manual_input();
+                self.notify(Severity::Info, "Gimbal reset");
+            }
+            KeyAction::Home => {
+                self.start_homing();
+            }
+            KeyAction::Pause => {
+                self.set_paused(!self.paused);
+            }
+            KeyAction::CycleLogLevel => {
+                self.cycle_log_level();
+            }
+            KeyAction::ExportFrame => {
+                let path = PathBuf::from(svg::DEFAULT_SNAPSHOT.replace("{timestamp}", &unix_millis().to_string()));
+                match self.export_frame(&path) {
+                    Ok(()) => self.notify(Severity::Info, format!("Saved snapshot to {}", path.display())),
+                    Err(e) => self.notify(Severity::Error, format!("Failed to save snapshot: {}", e)),
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    // Dialogs own the keyboard, not the pad: buttons do nothing during calibration, axis
+    // detection, the inversion check or settings editing, and the help button only closes
+    // the help overlay
+    fn run_button_action(&mut self, action: ButtonAction) {
+        if self.calibration.is_some() || self.detection.is_some() || self.invert_check.is_some() || self.settings.is_some() {
+            return;
+        }
+        if self.help_scroll.is_some() {
+            if action == ButtonAction::Help {
+                self.help_scroll = None;
+            }
+            return;
+        }
+        match (action.key_action(), action.trim()) {
+            // Movement actions aren't run_action's; they step the channel like a key tap
+            (Some(key_action), _) => {
+                if !self.run_action(key_action) && self.sequence.is_none() {
+                    self.gimbal_controller.step_channel(&mut self.input_state, key_action);
+                }
+            }
+            // Trims shift the output, so like any other move they wait out an e-stop
+            (None, Some((channel, direction))) if self.gimbal_controller.arm_state() != ArmState::Stopped => {
+                self.nudge_trim(channel, direction);
+            }
+            (None, _) => {}
+        }
+    }
+
+    // The overlay swallows every key so movement keys can't tilt the platform behind it
+    fn handle_help_key(&mut self, key: KeyCode) {
+        let Some(scroll) = self.help_scroll else { return };
+        let is_help = match key {
+            KeyCode::Char(c) => self.config.controls.keyboard_bindings.action_for(c) == Some(KeyAction::Help),
+            _ => false,
+        };
+        self.help_scroll = match key {
+            KeyCode::Esc | KeyCode::F(1) => None,
+            _ if is_help => None,
+            KeyCode::Up => Some(scroll.saturating_sub(1)),
+            KeyCode::Down => Some(scroll.saturating_add(1)),
+            KeyCode::PageUp => Some(scroll.saturating_sub(10)),
+            KeyCode::PageDown => Some(scroll.saturating_add(10)),
+            KeyCode::Home => Some(0),
+            _ => Some(scroll),
+        }
+        .map(|scroll| scroll.min(self.help_max_scroll.get()));
+    }
+
+    // Like the help overlay, the editor swallows every key. Changes apply as they are made;
+    // one that breaks a rule across fields (lift_min above lift_max, say) is refused.
+    fn handle_settings_key(&mut self, key: KeyCode) {
+        let Some(mut editor) = self.settings.take() else { return };
+        let is_settings = editor.entry.is_none()
+            && matches!(key, KeyCode::Char(c) if self.config.controls.keyboard_bindings.action_for(c) == Some(KeyAction::Settings));
+        let outcome = if is_settings { Outcome::Close } else { editor.handle_key(key, &self.settings_config()) };
+        let save_key = key_label(self.config.controls.keyboard_bindings.save_config);
+        match outcome {
+            Outcome::None => {}
+            Outcome::Change(edited) => {
+                let config = self.with_settings(*edited);
+                let before = self.config.validate().err().unwrap_or_default();
+                let after = config.validate().err().unwrap_or_default();
+                match after.into_iter().find(|problem| !before.contains(problem)) {
+                    Some(problem) => editor.refuse(problem),
+                    None => {
+                        self.config = config;
+                        self.apply_mapping();
+                    }
+                }
+            }
+            Outcome::Save => match self.save_config() {
+                Ok(()) => {
+                    editor.saved = self.settings_config();
+                    editor.message = Some((false, format!("Saved to {}", self.config_path.display())));
+                }
+                Err(e) => editor.refuse(format!("Failed to save config: {}", e)),
+            },
+            Outcome::Revert => {
+                let reverted = editor.reverted(&self.settings_config());
+                self.config = self.with_settings(reverted);
+                self.apply_mapping();
+                editor.message = Some((false, "Reverted to the saved values".to_string()));
+            }
+            Outcome::Close => {
+                let changed = editor.changed_count(&self.settings_config());
+                if changed > 0 {
+                    self.notify(
+                        Severity::Warn,
+                        format!("{} setting(s) changed but not saved (press '{}' to save)", changed, save_key),
+                    );
+                }
+                return;
+            }
+        }
+        self.settings = Some(editor);
+    }
+
+    // Paging, pause and filters for the event pane; other keys keep their usual meaning
+    fn handle_event_log_key(&mut self, key: KeyCode) -> bool {
+        let rows = self.event_log_rows.get();
+        match key {
+            KeyCode::PageUp => self.event_log.scroll_by(rows.max(1) as isize, rows),
+            KeyCode::PageDown => self.event_log.scroll_by(-(rows.max(1) as isize), rows),
+            KeyCode::Home => self.event_log.scroll_to_oldest(rows),
+            KeyCode::End => self.event_log.scroll_to_newest(),
+            KeyCode::F(2) => self.event_log.toggle_pause(),
+            KeyCode::F(3) => {
+                let mut ids: Vec<_> = self.gamepads.keys().copied().collect();
+                ids.sort_by_key(|id| usize::from(*id));
+                self.event_log.cycle_gamepad(&ids);
+            }
+            KeyCode::F(4) => self.event_log.cycle_kind(),
+            _ => return false,
+        }
+        true
+    }
+
+    // Key repeat from terminals that report it; only movement keys react
+    fn handle_key_repeat(&mut self, key: KeyCode) {
+        if self.config.output.enable_key.map(KeyCode::Char) == Some(key) {
+            self.enable_key_seen = Some(Instant::now());
+            return;
+        }
+        let Some(action) = self.key_action(key) else { return };
+        if self.gimbal_controller.arm_state() == ArmState::Stopped {
+            if action == KeyAction::Rearm {
+                self.hold_rearm();
+            }
+            return;
+        }
+        if self.help_scroll.is_none()
+            && self.settings.is_none()
+            && self.calibration.is_none()
+            && self.detection.is_none()
+            && self.invert_check.is_none()
+            && self.sequence.is_none()
+            && self.command_line.is_none()
+        {
+            self.gimbal_controller.handle_keyboard(&mut self.input_state, action, true);
+        }
+    }
+
+    fn handle_key_release(&mut self, key: KeyCode) {
+        if self.config.output.enable_key.map(KeyCode::Char) == Some(key) {
+            self.enable_key_seen = None;
+            return;
+        }
+        let Some(action) = self.key_action(key) else { return };
+        if action == KeyAction::Rearm {
+            self.rearm_hold = None;
+        }
+        self.gimbal_controller.handle_keyboard(&mut self.input_state, action, false);
+    }
+
+    fn trigger_estop(&mut self, source: &str) {
+        if !self.gimbal_controller.estop() {
+            return;
+        }
+        // The stop has to act on the output, so it ends a pause and any sequence
+        self.set_paused(false);
+        self.finish_sequence("aborted by e-stop");
+        // Nothing typed or dragged before the stop may apply once re-armed
+        self.clear_manual_input();
+        self.rearm_hold = None;
+        let behavior = match self.config.safety.estop_behavior {
+            EstopBehavior::Hold => "holding position",
+            EstopBehavior::Zero => "returning to level",
+        };
+        self.notify(Severity::Error, format!("EMERGENCY STOP from {} ({})", source, behavior));
+        let prompt = format!(
+            "E-STOP - hold {} for {:.1}s to re-arm",
+            key_label(self.config.controls.keyboard_bindings.rearm),
+            self.config.safety.rearm_hold_secs
+        );
+        self.pin_status("estop", Severity::Error, prompt);
+    }
+
+    // Called for every press or repeat of the re-arm key; re-arms once it has been held long enough
+    fn hold_rearm(&mut self) {
+        let now = Instant::now();
+        let started = match self.rearm_hold {
+            Some((started, last)) if now.duration_since(last) < HELD_KEY_GAP => started,
+            _ => now,
+        };
+        self.rearm_hold = Some((started, now));
+
+        if now.duration_since(started).as_secs_f64() >= self.config.safety.rearm_hold_secs {
+            self.rearm_hold = None;
+            if self.gimbal_controller.rearm() {
+                self.unpin_status("estop");
+                self.notify(Severity::Warn, "Re-armed: input is live again");
+            }
+        }
+    }
+
+    // Header badge while input is still easing in after launch, reset or re-arm
+    fn soft_start_label(&self) -> Option<String> {
+        match self.gimbal_controller.soft_start() {
+            SoftStart::AwaitingCenter => Some(" CENTER STICK TO START".to_string()),
+            SoftStart::Ramping(elapsed) => {
+                const CELLS: usize = 5;
+                let progress = (elapsed / self.config.safety.soft_start_secs).clamp(0.0, 1.0);
+                let filled = ((progress * CELLS as f64).round() as usize).min(CELLS);
+                let (fill, empty) = if self.config.app.ascii_mode { ("#", "-") } else { ("█", "·") };
+                Some(format!(" SOFT START {}{}", fill.repeat(filled), empty.repeat(CELLS - filled)))
+            }
+            SoftStart::Done => None,
+        }
+    }
+
+    // Fraction of the re-arm hold completed, while the key is still held
+    fn rearm_progress(&self) -> Option<f64> {
+        let (started, last) = self.rearm_hold?;
+        (last.elapsed() < HELD_KEY_GAP)
+            .then(|| (started.elapsed().as_secs_f64() / self.config.safety.rearm_hold_secs).min(1.0))
+    }
+
+    fn clear_manual_input(&mut self) {
+        self.input_state.keyboard_pitch = 0.0;
+        self.input_state.keyboard_roll = 0.0;
+        self.input_state.keyboard_lift = 0.0;
+        self.input_state.keyboard_yaw = 0.0;
+        self.input_state.held_keys.clear();
+        self.input_state.pressed_keys.clear();
+        self.input_state.mouse_pitch = 0.0;
+        self.input_state.mouse_roll = 0.0;
+        self.input_state.mouse_lift = 0.0;
+        self.mouse_drag = None;
+    }
+
+    // Dragging maps displacement from the drag origin onto roll (horizontal) and pitch
+    // (vertical), a half-canvas drag being full deflection; the wheel steps lift
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        self.note_input();
+        if !self.config.controls.mouse_enabled
+            || self.calibration.is_some()
+            || self.detection.is_some()
+            || self.invert_check.is_some()
+            || self.sequence.is_some()
+            || self.help_scroll.is_some()
+            || self.settings.is_some()
+            || self.gimbal_controller.arm_state() == ArmState::Stopped
+        {
+            return;
+        }
+
+        let area = self.canvas_area.get();
+        let position = ratatui::layout::Position::new(mouse.column, mouse.row);
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) if area.contains(position) => {
+                let held = (self.input_state.mouse_pitch, self.input_state.mouse_roll);
+                self.mouse_drag = Some(((mouse.column, mouse.row), held));
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                if let Some(((column, row), (pitch, roll))) = self.mouse_drag {
+                    let half_width = (area.width as f64 / 2.0).max(1.0);
+                    let half_height = (area.height as f64 / 2.0).max(1.0);
+                    let dx = mouse.column as f64 - column as f64;
+                    let dy = mouse.row as f64 - row as f64;
+                    self.input_state.mouse_roll = (roll + dx / half_width).clamp(-1.0, 1.0);
+                    self.input_state.mouse_pitch = (pitch - dy / half_height).clamp(-1.0, 1.0);
+                }
+            }
+            MouseEventKind::Up(MouseButton::Left) => {
+                let was_dragging = self.mouse_drag.take().is_some();
+                if was_dragging && self.config.controls.mouse_spring_return {
+                    self.input_state.mouse_pitch = 0.0;
+                    self.input_state.mouse_roll = 0.0;
+                }
+            }
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollDown if area.contains(position) => {
+                let step = self.config.controls.keyboard_step;
+                let step = if mouse.kind == MouseEventKind::ScrollUp { step } else { -step };
+                self.input_state.mouse_lift = (self.input_state.mouse_lift + step).clamp(-1.0, 1.0);
+            }
+            _ => {}
+        }
+    }
+
+    fn glyph(&self, unicode: &'static str, ascii: &'static str) -> &'static str {
+        if self.config.app.ascii_mode { ascii } else { unicode }
+    }
+
+    fn block(&self) -> Block<'static> {
+        let block = Block::default().borders(Borders::ALL);
+        if self.config.app.ascii_mode { block.border_set(ASCII_BORDER) } else { block }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let area = frame.area();
+        // Below [app] min_width x min_height the panels get too thin to read, so a message is shown instead
+        let (min_width, min_height) = (self.config.app.min_width, self.config.app.min_height);
+        if area.width < min_width || area.height < min_height {
+            // Nothing is on screen to drag
+            self.canvas_area.set(Rect::default());
+            let message = format!(
+                "Terminal too small ({}x{}, need at least {}x{})",
+                area.width, area.height, min_width, min_height
+            );
+            let y = area.y + area.height / 2;
+            let message_area = Rect::new(area.x, y, area.width, 1.min(area.height));
+            frame.render_widget(
+                Paragraph::new(message)
+                    .alignment(ratatui::layout::Alignment::Center)
+                    .style(self.theme.style(Role::Warning)),
+                message_area,
+            );
+            return;
+        }
+
+        let gamepad_rows = self.gamepads.len().clamp(1, MAX_GAMEPAD_ROWS) as u16;
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(gamepad_rows + 2),  // Gamepads
+                Constraint::Length(1),                 // Status bar
+            ])
+            .split(frame.area());
+
+        if self.show_solver {
+            self.draw_solver_view(frame, chunks[0]);
+        } else if self.debug_mode {
+            self.draw_debug_view(frame, chunks[0]);
+        } else {
+            self.draw_gimbal_view(frame, chunks[0]);
+        }
+        self.draw_gamepads(frame, chunks[1]);
+        match &self.command_line {
+            Some(line) => self.draw_command_line(frame, chunks[2], line),
+            None => self.draw_status_bar(frame, chunks[2]),
+        }
+
+        if self.gimbal_controller.arm_state() == ArmState::Stopped {
+            self.draw_estop_banner(frame, chunks[0]);
+        } else if self.paused {
+            self.draw_pause_banner(frame, chunks[0]);
+        }
+
+        if self.show_timing {
+            self.draw_timing(frame, chunks[0]);
+        }
+
+        if let Some((player, _)) = &self.sequence {
+            self.draw_sequence(frame, chunks[0], player);
+        }
+
+        if let Some(detector) = &self.detection {
+            self.draw_detection(frame, chunks[0], detector);
+        }
+
+        if let Some(check) = &self.invert_check {
+            self.draw_invert_check(frame, chunks[0], check);
+        }
+
+        if let Some(editor) = &self.settings {
+            self.draw_settings(frame, editor);
+        }
+
+        if let Some(scroll) = self.help_scroll {
+            self.draw_help(frame, scroll);
+        }
+    }
+
+    fn help_lines(&self) -> Vec<Line<'static>> {
+        let heading = |text: &str| Line::from(Span::styled(text.to_string(), self.theme.style(Role::Header)));
+        let config = self.gimbal_controller.get_config();
+        let mut lines = vec![heading("=== KEYBOARD ===")];
+        for (action, key) in config.controls.keyboard_bindings.entries() {
+            lines.push(Line::from(format!("  {:<6} {}", key_label(key), action.name())));
+        }
+        lines.push(Line::from(format!("  {:<6} {}", "Esc", "quit (closes this help while open)")));
+        lines.push(Line::from(format!("  {:<6} {}", "F1", "help")));
+        lines.push(Line::from(format!(
+            "  {:<6} {}",
+            "F2-F4", "event log: pause, filter by gamepad, filter by kind (PgUp/PgDn/Home/End scroll)"
+        )));
+        if config.controls.keyboard_bindings.arrow_keys {
+            lines.push(Line::from(format!("  {:<6} {}", "Arrows", "pitch (up/down) and roll (left/right)")));
+        }
+        lines.push(Line::from(format!(
+            "  Keyboard mode: {:?}, step {:.3}",
+            config.controls.keyboard_mode, config.controls.keyboard_step
+        )));
+
+        if config.controls.mouse_enabled {
+            lines.push(Line::from(""));
+            lines.push(heading("=== MOUSE ==="));
+            lines.push(Line::from("  Drag in the canvas: pitch (vertical) / roll (horizontal)"));
+            lines.push(Line::from("  Scroll wheel: lift"));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(heading(&format!("=== JOYSTICK ({}) ===", self.profile_name(self.active_profile))));
+        let joystick = &config.controls.joystick;
+        for (channel, axis_name, invert, device) in [
+            ("pitch", &joystick.pitch_axis, joystick.invert_pitch, &joystick.pitch_device),
+            ("roll", &joystick.roll_axis, joystick.invert_roll, &joystick.roll_device),
+            ("lift", &joystick.lift_axis, joystick.invert_lift, &joystick.lift_device),
+            ("yaw", &joystick.yaw_axis, joystick.invert_yaw, &joystick.yaw_device),
+        ] {
+            let owner = device_owner(&self.input_state, device.as_deref());
+            let resolved = match self.gimbal_controller.resolved_axis(&self.input_state, axis_name, owner) {
+                Some(axis) if axis_key(axis) == *axis_name => "live".to_string(),
+                Some(axis) => format!("via fallback {}", axis_key(axis)),
+                None => "no input yet".to_string(),
+            };
+            let source = match (device, owner) {
+                (Some(_), Some(name)) => format!(" on '{}'", name),
+                (Some(device), None) => format!(" ('{}' missing, active pad)", device),
+                (None, _) => String::new(),
+            };
+            lines.push(Line::from(format!(
+                "  {:<6} {:<14} {:<9} {}{}",
+                channel,
+                axis_name,
+                if invert { "inverted" } else { "" },
+                resolved,
+                source
+            )));
+        }
+        if !joystick.fallback_axes.is_empty() {
+            lines.push(Line::from(format!("  Fallbacks: {}", joystick.fallback_axes.join(", "))));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(heading("=== GAMEPAD BUTTONS ==="));
+        if let Some(button) = &self.config.safety.estop_button {
+            lines.push(Line::from(format!("  {:<14} estop", button)));
+        }
+        let mut button_actions: Vec<_> = self.config.controls.button_actions.iter().collect();
+        button_actions.sort_by_key(|(button, _)| *button);
+        for (button, action) in button_actions {
+            lines.push(Line::from(format!("  {:<14} {}", button, action.name())));
+        }
+        let axis_buttons = self.config.controls.axis_button_entries();
+        for entry in &axis_buttons {
+            let side = |action: Option<ButtonAction>| action.map_or("-", ButtonAction::name);
+            lines.push(Line::from(format!("  {:<14} + {}, - {}", entry.axis, side(entry.positive), side(entry.negative))));
+        }
+        let mut modifiers: Vec<_> = self.config.controls.modifiers.iter().collect();
+        modifiers.sort_by_key(|(button, _)| *button);
+        for (button, factor) in modifiers {
+            lines.push(Line::from(format!("  {:<14} hold: sensitivity {}{}", button, self.glyph("×", "x"), factor)));
+        }
+        if self.config.safety.estop_button.is_none()
+            && self.config.controls.button_actions.is_empty()
+            && axis_buttons.is_empty()
+            && self.config.controls.modifiers.is_empty()
+        {
+            lines.push(Line::from("  No button actions are mapped"));
+        }
+
+        if !self.config_profiles.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(heading("=== PROFILES ==="));
+            for (slot, path) in self.config_profiles.iter().enumerate() {
+                let active = if *path == self.config_path { " (active, again for the startup config)" } else { "" };
+                lines.push(Line::from(format!("  {:<6} {}{}", slot + 1, path.display(), active)));
+            }
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            if self.config.app.ascii_mode {
+                "Up/Down PgUp/PgDn scroll - Esc or ? to close"
+            } else {
+                "↑/↓ PgUp/PgDn scroll · Esc or ? to close"
+            },
+            self.theme.style(Role::Muted),
+        )));
+        lines
+    }
+
+    fn draw_help(&self, frame: &mut Frame, scroll: u16) {
+        let area = frame.area();
+        let width = (area.width * 7 / 10).max(40).min(area.width);
+        let height = (area.height * 8 / 10).max(10).min(area.height);
+        let popup = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+
+        let lines = self.help_lines();
+        // Stop scrolling once the last line is on screen
+        let max_scroll = (lines.len() as u16).saturating_sub(height.saturating_sub(2));
+        self.help_max_scroll.set(max_scroll);
+        let help = Paragraph::new(lines)
+            .scroll((scroll.min(max_scroll), 0))
+            .block(self.block().title("Help"));
+        frame.render_widget(Clear, popup);
+        frame.render_widget(help, popup);
+    }
+
+    fn draw_settings(&self, frame: &mut Frame, editor: &SettingsEditor) {
+        let area = frame.area();
+        let width = (area.width * 7 / 10).max(40).min(area.width);
+        let height = (area.height * 8 / 10).max(10).min(area.height);
+        let popup = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+
+        let config = self.settings_config();
+        let rows = editor.rows();
+        let selected_style = self.theme.style(Role::Text).add_modifier(ratatui::style::Modifier::REVERSED);
+        let mut lines: Vec<Line> = rows
+            .iter()
+            .enumerate()
+            .map(|(index, row)| {
+                let selected = index == editor.selected;
+                match *row {
+                    Row::Section(section) => {
+                        let marker = if editor.is_collapsed(section) { self.glyph("▸", ">") } else { self.glyph("▾", "v") };
+                        let style = if selected { selected_style } else { self.theme.style(Role::Header) };
+                        Line::from(Span::styled(format!("{} [{}]", marker, section), style))
+                    }
+                    Row::Field(index) => {
+                        let field = &editor.fields[index];
+                        let value = match &editor.entry {
+                            Some(text) if selected => format!("{}_", text),
+                            _ => field.display(&config),
+                        };
+                        let changed = if editor.changed(field, &config) { "*" } else { " " };
+                        let name = Span::styled(
+                            format!("  {}{:<24} {:<10}", changed, field.name, value),
+                            if selected { selected_style } else { self.theme.style(Role::Text) },
+                        );
+                        let note = match &field.kind {
+                            Kind::Number { min, max, step, .. } => {
+                                format!(" [{}..{}]", format_number(*min, *step), format_number(*max, *step))
+                            }
+                            Kind::Flag { .. } => " [true/false]".to_string(),
+                            Kind::Fixed { why, .. } => format!(" (read-only: {})", why),
+                        };
+                        Line::from(vec![name, Span::styled(note, self.theme.style(Role::Muted))])
+                    }
+                }
+            })
+            .collect();
+
+        // Keep the selection in view, with the footer's two lines below the list
+        let visible = height.saturating_sub(4) as usize;
+        let first = editor.selected.saturating_sub(visible.saturating_sub(1));
+        lines.drain(..first.min(lines.len()));
+        lines.truncate(visible);
+        while lines.len() < visible {
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(""));
+        lines.push(match &editor.message {
+            Some((true, message)) => Line::from(Span::styled(message.clone(), self.theme.style(Role::Error))),
+            Some((false, message)) => Line::from(Span::styled(message.clone(), self.theme.style(Role::Ok))),
+            None if editor.entry.is_some() => Line::from(Span::styled(
+                "Type a value: Enter applies, Esc cancels",
+                self.theme.style(Role::Muted),
+            )),
+            None => Line::from(Span::styled(
+                "↑↓ select  ←→ fold  +/- step  Enter edit  s save  r revert  Esc close",
+                self.theme.style(Role::Muted),
+            )),
+        });
+
+        let changed = editor.changed_count(&config);
+        let title = if changed > 0 { format!("Settings ({} unsaved)", changed) } else { "Settings".to_string() };
+        let settings = Paragraph::new(lines).block(self.block().title(title));
+        frame.render_widget(Clear, popup);
+        frame.render_widget(settings, popup);
+    }
+
+    fn draw_gamepads(&self, frame: &mut Frame, area: Rect) {
+        let mut gamepads: Vec<_> = self.gamepads.iter().collect();
+        gamepads.sort_by_key(|(id, _)| usize::from(**id));
+
+        let mut items: Vec<ListItem> = gamepads
+            .into_iter()
+            .map(|(id, gamepad)| {
+                let since = gamepad
+                    .last_activity
+                    .map_or("never".to_string(), |at| format!("{:.1}s ago", at.elapsed().as_secs_f64()));
+                // The driving pad shows the profile actually in use, others what they would match
+                let profile = if self.last_active_gamepad == Some(*id) {
+                    let source = if self.manual_profile.is_some() { "manual" } else { "auto" };
+                    format!("{} ({})", self.profile_name(self.active_profile), source)
+                } else {
+                    self.profile_name(self.config.profile_for(&gamepad.name)).to_string()
+                };
+                let line = format!(
+                    "{} {:<32} {:<14} profile {:<20} last input {}",
+                    if self.last_active_gamepad == Some(*id) { self.glyph("▶", ">") } else { " " },
+                    gamepad.name,
+                    if gamepad.connected {
+                        format!("{} connected", self.glyph("●", "*"))
+                    } else {
+                        format!("{} disconnected", self.glyph("○", "o"))
+                    },
+                    profile,
+                    since
+                );
+                let role = if gamepad.connected { Role::Ok } else { Role::Muted };
+                ListItem::new(Line::from(Span::styled(line, self.theme.style(role))))
+            })
+            .collect();
+        if items.is_empty() {
+            items.push(ListItem::new(Line::from(Span::styled(
+                "No gamepads seen yet",
+                self.theme.style(Role::Muted),
+            ))));
+        }
+
+        let list = List::new(items).block(self.block().title("Gamepads"));
+        frame.render_widget(list, area);
+    }
+
+    fn draw_estop_banner(&self, frame: &mut Frame, area: Rect) {
+        let width = 56.min(area.width);
+        let height = 6.min(area.height);
+        let banner = Rect::new(area.x + (area.width - width) / 2, area.y + area.height.saturating_sub(height) / 3, width, height);
+
+        // Alternate between the theme's two alarm styles twice a second
+        let flash = (self.started.elapsed().as_millis() / 500).is_multiple_of(2);
+        let style = self.theme.alarm(flash);
+        let behavior = match self.config.safety.estop_behavior {
+            EstopBehavior::Hold => "Output held - input ignored",
+            EstopBehavior::Zero => "Returning to level - input ignored",
+        };
+        let rearm = key_label(self.config.controls.keyboard_bindings.rearm);
+        let hint = match self.rearm_progress() {
+            Some(progress) => format!("Re-arming... {:.0}%", progress * 100.0),
+            None => format!("Hold {} for {:.1}s to re-arm", rearm, self.config.safety.rearm_hold_secs),
+        };
+        let lines = vec![
+            Line::from(Span::styled("EMERGENCY STOP", style.add_modifier(ratatui::style::Modifier::BOLD))),
+            Line::from(behavior),
+            Line::from(""),
+            Line::from(hint),
+        ];
+
+        let paragraph = Paragraph::new(lines)
+            .alignment(ratatui::layout::Alignment::Center)
+            .style(style)
+            .block(self.block().border_style(style));
+        frame.render_widget(Clear, banner);
+        frame.render_widget(paragraph, banner);
+    }
+
+    // Quieter than the e-stop banner: a steady box near the top of the view
+    fn draw_pause_banner(&self, frame: &mut Frame, area: Rect) {
+        let width = 40.min(area.width);
+        let height = 4.min(area.height);
+        let banner = Rect::new(area.x + (area.width - width) / 2, area.y + 4.min(area.height - height), width, height);
+        let style = self.theme.style(Role::Warning);
+        let lines = vec![
+            Line::from(Span::styled("PAUSED", style.add_modifier(ratatui::style::Modifier::BOLD))),
+            Line::from(format!("Holding pose - press {} to resume", key_label(self.config.controls.keyboard_bindings.pause))),
+        ];
+        let paragraph = Paragraph::new(lines)
+            .alignment(ratatui::layout::Alignment::Center)
+            .block(self.block().border_style(style));
+        frame.render_widget(Clear, banner);
+        frame.render_widget(paragraph, banner);
+    }
+
+    // Step and progress of a running sequence, along the bottom of the main area
+    fn draw_sequence(&self, frame: &mut Frame, area: Rect, player: &SequencePlayer) {
+        let width = 64.min(area.width);
+        let height = 4.min(area.height);
+        // One row up, so the canvas keeps its bottom border
+        let panel = Rect::new(area.x + (area.width - width) / 2, area.y + area.height.saturating_sub(height + 1), width, height);
+        let block = self.block().title(format!("Sequence - {}", player.name()));
+        let inner = block.inner(panel);
+        frame.render_widget(Clear, panel);
+        frame.render_widget(block, panel);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Length(1)])
+            .split(inner);
+        let (step, count) = player.position();
+        let step_text = format!(
+            "Step {}/{}: {} ({:.1}s left)",
+            step,
+            count,
+            player.step_label(),
+            player.step_remaining()
+        );
+        frame.render_widget(Paragraph::new(step_text).style(self.theme.style(Role::Text)), rows[0]);
+        let gauge = Gauge::default()
+            .gauge_style(self.theme.style(Role::Warning))
+            .ratio(player.progress())
+            .label(format!("{:.0}% - {:.1}s remaining", player.progress() * 100.0, player.remaining()));
+        frame.render_widget(gauge, rows[1]);
+    }
+
+    // Small overlay in the top-right corner; figures are over the last few seconds
+    fn draw_timing(&self, frame: &mut Frame, area: Rect) {
+        let width = 36.min(area.width);
+        let height = 9.min(area.height);
+        let corner = Rect::new(area.x + area.width - width, area.y, width, height);
+
+        let now = Instant::now();
+        let range = |summary: Option<Summary>, unit: &str, precision: usize| match summary {
+            Some(s) => format!(
+                "{:>6.p$}{} ({:.p$}-{:.p$})",
+                s.avg, unit, s.min, s.max, p = precision
+            ),
+            None => "     -".to_string(),
+        };
+        let rate = |rate: Option<f64>, limit: f64| match rate {
+            Some(rate) => format!("{:>6.1}/s (of {:.0})", rate, limit),
+            None => "     -".to_string(),
+        };
+        let lines = vec![
+            // Frames are skipped while nothing changes, so FPS sits well below the limit at rest
+            Line::from(format!("FPS      {}", rate(self.timing.fps(now), self.config.app.render_rate_hz))),
+            Line::from(format!("Steps    {}", rate(self.timing.step_rate(now), self.config.app.update_rate_hz))),
+            Line::from(format!("Draw     {}", range(self.timing.draw_time(now), "ms", 2))),
+            Line::from(format!("Update   {}", range(self.timing.update_time(now), "ms", 2))),
+            Line::from(format!("Events   {}", range(self.timing.events_per_tick(now), "/t", 1))),
+            Line::from(format!("Latency  {}", range(self.timing.input_latency(now), "ms", 1))),
+            Line::from(format!("Canvas   {:>6} shapes", self.canvas_draws.get())),
+        ];
+
+        let paragraph = Paragraph::new(lines).block(self.block().title("Timing avg (min-max)"));
+        frame.render_widget(Clear, corner);
+        frame.render_widget(paragraph, corner);
+    }
+
+    fn draw_status_bar(&self, frame: &mut Frame, area: Rect) {
+        let mut spans = Vec::new();
+        if let Some(message) = self.status.current() {
+            spans.push(Span::styled(message.text.clone(), self.theme.style(severity_role(message.severity))));
+        }
+        for message in self.status.pinned() {
+            if !spans.is_empty() {
+                spans.push(Span::styled(self.glyph(" │ ", " | "), self.theme.style(Role::Muted)));
+            }
+            spans.push(Span::styled(message.text.clone(), self.theme.style(severity_role(message.severity))));
+        }
+        frame.render_widget(Paragraph::new(Line::from(spans)), area);
+    }
+
+    // Replaces the status bar while typing, with the last error after the cursor
+    fn draw_command_line(&self, frame: &mut Frame, area: Rect, line: &str) {
+        let mut spans = vec![
+            Span::styled(format!("{}{}", key_label(self.config.controls.keyboard_bindings.command), line), self.theme.style(Role::Text)),
+            Span::styled(self.glyph("█", "_"), self.theme.style(Role::Muted)),
+        ];
+        match &self.command_error {
+            Some(error) => spans.push(Span::styled(format!("  {}", error), self.theme.style(Role::Error))),
+            None => spans.push(Span::styled(
+                format!("  {} - Enter to move, Esc to cancel", command::USAGE),
+                self.theme.style(Role::Muted),
+            )),
+        }
+        frame.render_widget(Paragraph::new(Line::from(spans)), area);
+    }
+
+    fn draw_debug_view(&self, frame: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),     // Header
+                Constraint::Min(10),       // Debug info
+                Constraint::Min(15),       // Gimbal (smaller)
+            ])
+            .split(area);
+
+        // Header
+        let bindings = &self.config.controls.keyboard_bindings;
+        let header_text = format!(
+            "{}DEBUG MODE{} [{}] - Press '{}' to toggle, '{}' to calibrate, '{}' to quit, '{}' to reset",
+            self.glyph("🔧 ", ""),
+            self.config_profile_tag(),
+            self.hardware_name(),
+            bindings.toggle_debug,
+            bindings.calibrate,
+            bindings.quit,
+            bindings.reset
+        ) + &self.soft_start_label().unwrap_or_default();
+        let header = Paragraph::new(header_text)
+            .block(self.block())
+            .style(self.theme.style(Role::Warning));
+        frame.render_widget(header, chunks[0]);
+
+        // Debug info split
+        let debug_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(30),  // Axes
+                Constraint::Percentage(40),  // Config & State
+                Constraint::Percentage(30),  // Log
+            ])
+            .split(chunks[1]);
+
+        if let Some(wizard) = &self.calibration {
+            self.draw_calibration(frame, chunks[1], wizard);
+        } else {
+            // The history goes under the axes when there is room for both
+            if self.config.controls.joystick.enabled && debug_chunks[0].height >= HISTORY_PANEL_HEIGHT + 6 {
+                let axes_chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Min(6), Constraint::Length(HISTORY_PANEL_HEIGHT)])
+                    .split(debug_chunks[0]);
+                self.draw_debug_axes(frame, axes_chunks[0]);
+                self.draw_axis_history(frame, axes_chunks[1]);
+            } else {
+                self.draw_debug_axes(frame, debug_chunks[0]);
+            }
+            self.draw_debug_state(frame, debug_chunks[1]);
+            if self.show_event_log {
+                self.draw_event_log(frame, debug_chunks[2]);
+            } else {
+                self.draw_debug_log(frame, debug_chunks[2]);
+            }
+        }
+        
+        // Smaller gimbal view
+        self.draw_gimbal_visualization(frame, chunks[2]);
+    }
+
+    // A label row and a sparkline row per channel. Each bar is one sample, or the average
+    // of several when the panel is narrower than the history; the middle height is 0.0 and
+    // a dropout is a full-height bar in the error color.
+    // Read-only check of the inverse kinematics for the current pose: the plate plane, every
+    // leg's length and share of its stroke, and whether the pose is reachable as commanded
+    fn draw_solver_view(&self, frame: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)])
+            .split(area);
+
+        let state = self.gimbal_controller.get_state();
+        let config = self.gimbal_controller.get_config();
+        let geometry = &config.geometry;
+        let header_text = format!(
+            "{}SOLVER{} - Pitch: {:.2}° Roll: {:.2}° Lift: {:.2}mm Yaw: {:.2}° | '{}' closes",
+            self.glyph("📐 ", ""),
+            self.config_profile_tag(),
+            state.pitch,
+            state.roll,
+            state.lift,
+            state.yaw,
+            self.config.controls.keyboard_bindings.toggle_solver
+        );
+        let header = Paragraph::new(header_text).block(self.block()).style(self.theme.style(Role::Header));
+        frame.render_widget(header, chunks[0]);
+
+        let heading = |text: &str| ListItem::new(Line::from(Span::styled(text.to_string(), self.theme.style(Role::Header))));
+        let actuators = self.gimbal_controller.actuators();
+        let limits = self.gimbal_controller.leg_limits();
+        let mut items = vec![heading("=== PLATE PLANE ===")];
+        match kinematics::plate_plane(&actuators) {
+            Some([a, b, c]) => {
+                // z = a*x + b*y + c has the upward normal (-a, -b, 1)
+                let length = (a * a + b * b + 1.0).sqrt();
+                let normal = (-a / length, -b / length, 1.0 / length);
+                items.push(ListItem::new(Line::from(format!("z = {:+.4}·x {:+.4}·y {:+.2} mm", a, b, c))));
+                items.push(ListItem::new(Line::from(format!(
+                    "Normal: ({:+.4}, {:+.4}, {:+.4}), {:.2}° from vertical",
+                    normal.0,
+                    normal.1,
+                    normal.2,
+                    normal.2.acos().to_degrees()
+                ))));
+            }
+            None => items.push(ListItem::new(Line::from(Span::styled(
+                "The joints don't span a plane; check the actuator angles",
+                self.theme.style(Role::Error),
+            )))),
+        }
+
+        items.push(ListItem::new(Line::from("")));
+        let lengths = match (limits.min_leg, limits.max_leg) {
+            (None, None) => String::new(),
+            (min_leg, max_leg) => format!(
+                ", length {}..{}mm",
+                min_leg.map_or("-".to_string(), |l| format!("{:.1}", l)),
+                max_leg.map_or("-".to_string(), |l| format!("{:.1}", l))
+            ),
+        };
+        items.push(heading(&format!(
+            "=== LEGS ({}, stroke {:+.1}..{:+.1}mm{}) ===",
+            actuators.len(),
+            limits.retract,
+            limits.extend,
+            lengths
+        )));
+        for (i, actuator) in actuators.iter().enumerate() {
+            let alarm = actuator.over_travel(&limits);
+            items.push(ListItem::new(Line::from(Span::styled(
+                format!(
+                    "A{:<3} length {:7.2}mm  neutral {:7.2}mm  extension {:+7.2}mm",
+                    i + 1,
+                    actuator.length(),
+                    actuator.length() - actuator.extension,
+                    actuator.extension
+                ),
+                self.theme.style(if alarm { Role::Error } else { Role::Text }),
+            ))));
+            items.push(ListItem::new(self.centered_bar(
+                "     ".to_string(),
+                limits.usage(actuator),
+                format!(" {:+4.0}%", limits.usage(actuator) * 100.0),
+                leg_role(actuator.extension, alarm),
+                chunks[1].width.saturating_sub(2),
+            )));
+        }
+        let usage = |actuator: &kinematics::Actuator| limits.usage(actuator) * 100.0;
+        let by_usage = |a: &(usize, &kinematics::Actuator), b: &(usize, &kinematics::Actuator)| usage(a.1).total_cmp(&usage(b.1));
+        if let (Some((low, lowest)), Some((high, highest))) =
+            (actuators.iter().enumerate().min_by(by_usage), actuators.iter().enumerate().max_by(by_usage))
+        {
+            items.push(ListItem::new(Line::from(format!(
+                "Usage: min {:+.0}% (A{})  max {:+.0}% (A{})",
+                usage(lowest),
+                low + 1,
+                usage(highest),
+                high + 1
+            ))));
+        }
+
+        items.push(ListItem::new(Line::from("")));
+        items.push(heading("=== VERDICT ==="));
+        let over_travel: Vec<String> = actuators
+            .iter()
+            .enumerate()
+            .filter(|(_, actuator)| actuator.over_travel(&limits))
+            .map(|(i, actuator)| format!("A{} by {:.1}mm", i + 1, actuator.travel_excess(&limits)))
+            .collect();
+        let pipeline = self.gimbal_controller.last_pipeline();
+        let saturated: Vec<&str> = [("pitch", pipeline.pitch), ("roll", pipeline.roll), ("lift", pipeline.lift), ("yaw", pipeline.yaw)]
+            .into_iter()
+            .filter(|(_, channel)| channel.saturated)
+            .map(|(name, _)| name)
+            .collect();
+        let verdict = if !over_travel.is_empty() {
+            (format!("{}INFEASIBLE: over travel {}", self.glyph("✖ ", "X "), over_travel.join(", ")), Role::Error)
+        } else if state.envelope_limited {
+            let limiting: Vec<String> = state.limiting_actuators.iter().map(|i| format!("A{}", i + 1)).collect();
+            (
+                format!(
+                    "{}LIMITED: the commanded pose needed {} past the stroke and was pulled back ({})",
+                    self.glyph("⚠ ", "! "),
+                    limiting.join(" "),
+                    format!("{:?}", geometry.envelope).to_lowercase()
+                ),
+                Role::Warning,
+            )
+        } else {
+            (format!("{}FEASIBLE: every leg within its stroke", self.glyph("✔ ", "OK ")), Role::Ok)
+        };
+        items.push(ListItem::new(Line::from(Span::styled(
+            verdict.0,
+            self.theme.style(verdict.1).add_modifier(ratatui::style::Modifier::BOLD),
+        ))));
+        if !saturated.is_empty() {
+            items.push(ListItem::new(Line::from(Span::styled(
+                format!("Input asks for more than the channel limit on {}", saturated.join(", ")),
+                self.theme.style(Role::Warning),
+            ))));
+        }
+
+        frame.render_widget(List::new(items).block(self.block().title("Inverse Kinematics (dry run)")), chunks[1]);
+    }
+
+    fn draw_axis_history(&self, frame: &mut Frame, area: Rect) {
+        let block = self.block().title(format!("Axis History ({:.0}s)", HISTORY_SECS));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(2); 3])
+            .split(inner);
+        let joystick = &self.gimbal_controller.get_config().controls.joystick;
+        let channels = [
+            ("pitch", &joystick.pitch_axis, &joystick.pitch_device),
+            ("roll", &joystick.roll_axis, &joystick.roll_device),
+            ("lift", &joystick.lift_axis, &joystick.lift_device),
+        ];
+        for (((channel, axis_name, device), history), row) in channels.into_iter().zip(&self.axis_history).zip(rows.iter()) {
+            let latest = history.iter().last().and_then(|sample| sample.value);
+            let dropouts = history.dropouts();
+            let mut spans = vec![Span::raw(format!(
+                "{:<5} {} {}",
+                channel,
+                axis_name,
+                latest.map_or("-".to_string(), |value| format!("{:+.3}", value))
+            ))];
+            if dropouts > 0 {
+                spans.push(Span::styled(format!("  {} dropout(s)", dropouts), self.theme.style(Role::Error)));
+            }
+            // Session total of the gaps the dropout filter held over, on the axis the channel reads
+            let owner = device_owner(&self.input_state, device.as_deref());
+            let held = self
+                .gimbal_controller
+                .resolved_axis(&self.input_state, axis_name, owner)
+                .map_or(0, |axis| self.gimbal_controller.dropouts(owner, axis));
+            if held > 0 {
+                spans.push(Span::styled(format!("  {} held", held), self.theme.style(Role::Warning)));
+            }
+            frame.render_widget(Paragraph::new(Line::from(spans)), Rect { height: 1, ..*row });
+
+            // Right-aligned, so the newest sample is always at the right edge
+            let columns = history.columns(row.width as usize);
+            let padding = (row.width as usize).saturating_sub(columns.len());
+            let bars: Vec<SparklineBar> = std::iter::repeat_n(SparklineBar::from(None), padding)
+                .chain(columns.into_iter().map(|sample| match sample.value {
+                    _ if sample.dropout => SparklineBar::from(100).style(self.theme.style(Role::Error)),
+                    Some(value) => SparklineBar::from(((value.clamp(-1.0, 1.0) + 1.0) * 50.0).round() as u64),
+                    None => SparklineBar::from(None),
+                }))
+                .collect();
+            let sparkline = Sparkline::default().data(bars).max(100).style(self.theme.style(Role::Secondary));
+            frame.render_widget(sparkline, Rect { y: row.y + 1, height: row.height.saturating_sub(1), ..*row });
+        }
+    }
+
+    fn draw_debug_axes(&self, frame: &mut Frame, area: Rect) {
+        let mut ids: Vec<_> = self.gamepads.keys().copied().collect();
+        ids.sort_by_key(|id| usize::from(*id));
+
+        // No devices (--simulate, --no-gamepad or nothing plugged in yet): show the merged input
+        if ids.is_empty() {
+            let mut items = vec![
+                ListItem::new(Line::from(Span::styled("=== ACTIVE AXES ===", self.theme.style(Role::Header)))),
+            ];
+            items.extend(self.axis_items(&self.input_state.axes, &self.input_state.buttons, true, area.width.saturating_sub(2)));
+            let list = List::new(items)
+                .block(self.block().title("Input Debug"));
+            frame.render_widget(list, area);
+            return;
+        }
+
+        // One panel each for up to two pads, otherwise the selected pad with [ / ] to switch
+        if ids.len() <= 2 {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(vec![Constraint::Ratio(1, ids.len() as u32); ids.len()])
+                .split(area);
+            for (id, chunk) in ids.iter().zip(chunks.iter()) {
+                self.draw_gamepad_panel(frame, *chunk, *id, None);
+            }
+        } else {
+            let selected = self.debug_gamepad % ids.len();
+            self.draw_gamepad_panel(frame, area, ids[selected], Some((selected, ids.len())));
+        }
+    }
+
+    fn draw_gamepad_panel(&self, frame: &mut Frame, area: Rect, id: PadId, page: Option<(usize, usize)>) {
+        let Some(gamepad) = self.gamepads.get(&id) else { return };
+        let since = gamepad
+            .last_activity
+            .map_or("never".to_string(), |at| format!("{:.1}s ago", at.elapsed().as_secs_f64()));
+        let separator = self.glyph(" · ", " - ");
+        let mut title = format!(
+            "{}{}{}{}{}",
+            gamepad.name,
+            separator,
+            if gamepad.connected { "connected" } else { "disconnected" },
+            separator,
+            since
+        );
+        // Channels bound to this pad with a `*_device` setting
+        let joystick = &self.gimbal_controller.get_config().controls.joystick;
+        let owned: Vec<&str> = joystick
+            .channel_devices()
+            .filter(|(_, device)| device_owner(&self.input_state, Some(device)) == Some(gamepad.name.as_str()))
+            .map(|(channel, _)| channel)
+            .collect();
+        if !owned.is_empty() {
+            title = format!("{}{}drives {}", title, separator, owned.join("/"));
+        }
+        if let Some((index, count)) = page {
+            let bindings = &self.config.controls.keyboard_bindings;
+            title = format!("[{}/{}] {} ({}/{})", index + 1, count, title, bindings.prev_gamepad, bindings.next_gamepad);
+        }
+
+        // Smoothing only applies to the pad driving the gimbal
+        let driving = self.last_active_gamepad == Some(id);
+        let items = self.axis_items(&gamepad.axes, &gamepad.buttons, driving, area.width.saturating_sub(2));
+        let role = if gamepad.connected { Role::Text } else { Role::Muted };
+        let list = List::new(items).block(
+            self.block()
+                .border_style(self.theme.style(if driving { Role::Header } else { role }))
+                .title(Span::styled(title, self.theme.style(role))),
+        );
+        frame.render_widget(list, area);
+    }
+
+    fn axis_items(
+        &self,
+        axes: &HashMap<Axis, f32>,
+        buttons: &HashMap<Button, bool>,
+        show_filter: bool,
+        width: u16,
+    ) -> Vec<ListItem<'static>> {
+        let mut items = Vec::new();
+        let mut axes_vec: Vec<_> = axes.iter().collect();
+        axes_vec.sort_by_key(|(axis, _)| format!("{:?}", axis));
+
+        // With smoothing on, show the calibrated value next to what the filter passed on
+        let smoothing = show_filter && self.gimbal_controller.get_config().controls.joystick.smoothing > 0.0;
+        let bars = self.config.debug.axis_bars;
+        for (axis, &value) in axes_vec {
+            let role = if value.abs() > 0.1 {
+                Role::Ok
+            } else if value.abs() > 0.01 {
+                Role::Warning
+            } else {
+                Role::Secondary
+            };
+
+            let filter = if smoothing
+                && let Some(calibrated) = self.gimbal_controller.calibrated_axis_value(&self.input_state, *axis)
+                && let Some(filtered) = self.gimbal_controller.filtered_axis_value(*axis)
+            {
+                Some(format!("  cal {:+.3} {} filt {:+.3}", calibrated, self.glyph("→", "->"), filtered))
+            } else {
+                None
+            };
+
+            if bars {
+                items.push(ListItem::new(self.axis_bar(*axis, value, role, width)));
+                if let Some(filter) = filter {
+                    items.push(ListItem::new(Line::from(Span::styled(filter, self.theme.style(Role::Muted)))));
+                }
+            } else {
+                let mut spans = vec![Span::styled(format!("{:?}: {:.3}", axis, value), self.theme.style(role))];
+                if let Some(filter) = filter {
+                    spans.push(Span::styled(filter, self.theme.style(Role::Muted)));
+                }
+                items.push(ListItem::new(Line::from(spans)));
+            }
+        }
+
+        if self.config.debug.show_button_states {
+            if bars {
+                items.extend(self.button_grid(buttons, width).into_iter().map(ListItem::new));
+            } else {
+                let mut pressed: Vec<_> = buttons.iter().filter(|(_, pressed)| **pressed).map(|(button, _)| button).collect();
+                pressed.sort_by_key(|button| format!("{:?}", button));
+                if !pressed.is_empty() {
+                    items.push(ListItem::new(Line::from(Span::styled("=== BUTTONS ===", self.theme.style(Role::Header)))));
+                }
+                for button in pressed {
+                    items.push(ListItem::new(Line::from(Span::styled(
+                        format!("{:?}: PRESSED", button),
+                        self.theme.style(Role::Highlight),
+                    ))));
+                }
+            }
+        }
+        items
+    }
+
+    // `LeftStickX  ----|###    +0.327`: zero in the middle, filled toward the value's side
+    fn axis_bar(&self, axis: Axis, value: f32, role: Role, width: u16) -> Line<'static> {
+        self.centered_bar(format!("{:<12}", format!("{:?}", axis)), f64::from(value), format!(" {:+.3}", value), role, width)
+    }
+
+    // `label`, a bar with zero in the middle filled toward the side `value` (-1..1) is on,
+    // then `number`; just the label and number when `width` leaves no room for a bar
+    fn centered_bar(&self, label: String, value: f64, number: String, role: Role, width: u16) -> Line<'static> {
+        let bar_width = (width as usize).saturating_sub(label.chars().count() + number.chars().count());
+        // Too narrow for a bar worth reading: fall back to the number alone
+        if bar_width < 5 {
+            return Line::from(Span::styled(format!("{}:{}", label.trim_end(), number), self.theme.style(role)));
+        }
+
+        let half = (bar_width - 1) / 2;
+        let filled = ((value.abs().min(1.0) * half as f64).round() as usize).min(half);
+        let (fill, empty, center) = if self.config.app.ascii_mode { ("#", "-", "|") } else { ("█", "·", "│") };
+        let (left, right) = if value < 0.0 {
+            (format!("{}{}", empty.repeat(half - filled), fill.repeat(filled)), empty.repeat(half))
+        } else {
+            (empty.repeat(half), format!("{}{}", fill.repeat(filled), empty.repeat(half - filled)))
+        };
+        let (left_role, right_role) = if value < 0.0 { (role, Role::Muted) } else { (Role::Muted, role) };
+
+        Line::from(vec![
+            Span::raw(label),
+            Span::styled(left, self.theme.style(left_role)),
+            Span::styled(center, self.theme.style(Role::Text)),
+            Span::styled(right, self.theme.style(right_role)),
+            Span::styled(number, self.theme.style(role)),
+        ])
+    }
+
+    // Every button the pad has reported, as filled (pressed) or empty cells packed into rows
+    fn button_grid(&self, buttons: &HashMap<Button, bool>, width: u16) -> Vec<Line<'static>> {
+        if buttons.is_empty() {
+            return Vec::new();
+        }
+        let mut sorted: Vec<_> = buttons.iter().collect();
+        sorted.sort_by_key(|(button, _)| format!("{:?}", button));
+
+        let (pressed_mark, released_mark) = if self.config.app.ascii_mode { ("#", ".") } else { ("■", "□") };
+        let cells: Vec<(String, bool)> = sorted
+            .into_iter()
+            .map(|(button, &pressed)| {
+                let mark = if pressed { pressed_mark } else { released_mark };
+                (format!("{} {:?}", mark, button), pressed)
+            })
+            .collect();
+        let cell_width = cells.iter().map(|(text, _)| text.chars().count()).max().unwrap_or(0) + 1;
+        let columns = (width as usize / cell_width).max(1);
+
+        let mut lines = vec![Line::from(Span::styled("=== BUTTONS ===", self.theme.style(Role::Header)))];
+        for row in cells.chunks(columns) {
+            let spans: Vec<Span> = row
+                .iter()
+                .map(|(text, pressed)| {
+                    let role = if *pressed { Role::Highlight } else { Role::Muted };
+                    Span::styled(format!("{:<width$}", text, width = cell_width), self.theme.style(role))
+                })
+                .collect();
+            lines.push(Line::from(spans));
+        }
+        lines
+    }
+
+    fn draw_debug_log(&self, frame: &mut Frame, area: Rect) {
+        // Newest entries at the bottom, trimmed to what fits
+        let visible = area.height.saturating_sub(2) as usize;
+        let items: Vec<ListItem> = self
+            .debug_log
+            .iter()
+            .skip(self.debug_log.len().saturating_sub(visible))
+            .map(|line| ListItem::new(Line::from(line.as_str())))
+            .collect();
+
+        let list = List::new(items)
+            .block(self.block().title("Log"));
+        frame.render_widget(list, area);
+    }
+
+    fn draw_event_log(&self, frame: &mut Frame, area: Rect) {
+        let rows = area.height.saturating_sub(2) as usize;
+        self.event_log_rows.set(rows);
+        let log = &self.event_log;
+        let items: Vec<ListItem> = log
+            .visible(rows)
+            .into_iter()
+            .map(|record| ListItem::new(Line::from(record.line())))
+            .collect();
+
+        // Kept short: the pane is a third of the width
+        let gamepad = log.gamepad_filter.map_or("all".to_string(), |id| format!("#{}", usize::from(id)));
+        let kind = log.kind_filter.map_or("all", |kind| kind.label());
+        let mut title = format!("Events {}/{}", gamepad, kind);
+        if log.hidden > 0 {
+            title.push_str(&format!(" ({} hidden)", log.hidden));
+        }
+        if log.scroll() > 0 {
+            title.push_str(&format!(" +{} newer", log.scroll()));
+        }
+        let style = if log.paused {
+            title.push_str(&format!(" PAUSED, {} skipped", log.skipped));
+            self.theme.style(Role::Warning)
+        } else {
+            self.theme.style(Role::Text)
+        };
+
+        let list = List::new(items).block(self.block().title(Span::styled(title, style)));
+        frame.render_widget(list, area);
+    }
+
+    fn draw_debug_state(&self, frame: &mut Frame, area: Rect) {
+        let state = self.gimbal_controller.get_state();
+        let config = self.gimbal_controller.get_config();
+
+        let mut items = vec![
+            ListItem::new(Line::from(Span::styled("=== GIMBAL STATE ===", self.theme.style(Role::Header)))),
+            ListItem::new(Line::from(format!("Pitch: {:.1}° (limits: {}°)", state.pitch, range_label(config.gimbal.pitch_range(), 1)))),
+            ListItem::new(Line::from(format!("Roll:  {:.1}° (limits: {}°)", state.roll, range_label(config.gimbal.roll_range(), 1)))),
+            ListItem::new(Line::from(format!(
+                "Lift:  {:.1}mm (range: {:.1}..{:.1}mm, home {:.1}mm)",
+                state.lift, config.gimbal.lift_min, config.gimbal.lift_max, config.gimbal.lift_home
+            ))),
+            ListItem::new(Line::from(if config.gimbal.yaw_enabled() {
+                format!("Yaw:   {:.1}° (max: ±{:.1}°)", state.yaw, config.gimbal.max_yaw)
+            } else {
+                "Yaw:   disabled (max_yaw = 0)".to_string()
+            })),
+        ];
+        if let Some(threshold) = config.gimbal.warn_tilt_threshold {
+            let tilt = state.pitch.hypot(state.roll);
+            let style = if tilt > threshold { self.theme.style(Role::Error) } else { self.theme.style(Role::Text) };
+            items.push(ListItem::new(Line::from(Span::styled(format!("Tilt:  {:.1}° (warn above {:.1}°)", tilt, threshold), style))));
+        }
+        if self.config.debug.show_fps {
+            let fps = match (self.timing.current_fps(), self.timing.frame_ms()) {
+                (Some(fps), Some(ms)) => format!("{:.1} ({:.1}ms/frame)", fps, ms),
+                _ => "-".to_string(),
+            };
+            let update = self.timing.average_update_ms().map_or("-".to_string(), |ms| format!("{:.2}ms", ms));
+            items.push(ListItem::new(Line::from(format!("FPS:   {}, update {}", fps, update))));
+        }
+        items.extend([
+            ListItem::new(Line::from("")),
+            ListItem::new(Line::from(Span::styled(
+                match config.controls.mixing {
+                    InputMixing::Sum => "=== PIPELINE ===",
+                    InputMixing::Max => "=== PIPELINE (mixing: max) ===",
+                    InputMixing::JoystickPriority => "=== PIPELINE (mixing: joystick_priority) ===",
+                },
+                self.theme.style(Role::Header),
+            ))),
+            ListItem::new(Line::from(Span::styled(
+                format!(
+                    "{:<6} {:>7} {:>7} {:>7} {:>7} {:>7} {:>8}",
+                    "", "raw", "stick", "key", "mouse", self.glyph("×sens", "*sens"), "final"
+                ),
+                self.theme.style(Role::Muted),
+            ))),
+        ]);
+
+        let pipeline = self.gimbal_controller.last_pipeline();
+        let joystick = &config.controls.joystick;
+        let mut channels = vec![
+            ("Pitch", &pipeline.pitch, &joystick.pitch_axis, &joystick.pitch_sources, &joystick.pitch_device),
+            ("Roll", &pipeline.roll, &joystick.roll_axis, &joystick.roll_sources, &joystick.roll_device),
+            ("Lift", &pipeline.lift, &joystick.lift_axis, &joystick.lift_sources, &joystick.lift_device),
+        ];
+        if config.gimbal.yaw_enabled() {
+            channels.push(("Yaw", &pipeline.yaw, &joystick.yaw_axis, &joystick.yaw_sources, &joystick.yaw_device));
+        }
+        for (name, channel, axis, sources, device) in channels {
+            let raw = channel.raw.map_or("-".to_string(), |raw| format!("{:+.3}", raw));
+            // Red while the input asks for more than the channel's limit
+            let style = if channel.saturated { self.theme.style(Role::Error) } else { Style::default() };
+            items.push(ListItem::new(Line::from(Span::styled(
+                format!(
+                    "{:<6} {:>7} {:>+7.3} {:>+7.3} {:>+7.3} {:>+7.3} {:>+8.2}",
+                    name, raw, channel.joystick, channel.keyboard, channel.mouse, channel.scaled, channel.output
+                ),
+                style,
+            ))));
+            // Under ×sens, what the coupling matrix made of it
+            if config.gimbal.coupling_enabled() && matches!(name, "Pitch" | "Roll") {
+                items.push(ListItem::new(Line::from(Span::styled(
+                    format!("  {:<36} {:>+7.3}", "coupled", channel.coupled),
+                    self.theme.style(Role::Muted),
+                ))));
+            }
+            // With extra sources bound, break the stick column down per axis
+            if sources.is_empty() {
+                continue;
+            }
+            let mut parts = vec![(axis.clone(), channel.joystick - channel.sources)];
+            let owner = device_owner(&self.input_state, device.as_deref());
+            for (source, value) in self.gimbal_controller.source_contributions(sources, owner) {
+                parts.push((format!("{}{}{:.2}", source.axis, self.glyph("×", "*"), source.gain), value));
+            }
+            for (label, value) in parts {
+                items.push(ListItem::new(Line::from(Span::styled(
+                    format!("  {:<20} {:>+7.3}", label, value),
+                    self.theme.style(Role::Muted),
+                ))));
+            }
+        }
+
+        items.extend([
+            ListItem::new(Line::from("")),
+            ListItem::new(Line::from(Span::styled("=== CONFIG ===", self.theme.style(Role::Header)))),
+            ListItem::new(Line::from(format!("File: {}", self.config_path.display()))),
+            ListItem::new(Line::from(format!(
+                "Profile: {} ({})",
+                self.profile_name(self.active_profile),
+                if self.manual_profile.is_some() { "manual" } else { "auto" }
+            ))),
+            ListItem::new(Line::from(format!(
+                "Hardware: {} ({} defined, '{}' cycles)",
+                self.hardware_name(),
+                self.config.hardware.len() + 1,
+                self.config.controls.keyboard_bindings.cycle_hardware
+            ))),
+            ListItem::new(Line::from(format!("Pitch Axis: {}", config.controls.joystick.pitch_axis))),
+            ListItem::new(Line::from(format!(
+                "Roll Axis:  {} ('{}' swaps with pitch)",
+                config.controls.joystick.roll_axis, config.controls.keyboard_bindings.swap_pitch_roll
+            ))),
+            ListItem::new(Line::from(format!(
+                "Lift Axis:  {}{}",
+                config.controls.joystick.lift_axis,
+                match config.controls.joystick.lift_axis_mode {
+                    AxisMode::Bipolar => "",
+                    AxisMode::Unipolar => " (trigger 0..1)",
+                    AxisMode::UnipolarSigned => " (trigger -1..1)",
+                }
+            ))),
+            ListItem::new(Line::from(format!("Yaw Axis:   {}", config.controls.joystick.yaw_axis))),
+            ListItem::new(Line::from({
+                let gimbal = &config.gimbal;
+                let mode = |mode: ChannelMode| match mode {
+                    ChannelMode::Position => "position",
+                    ChannelMode::Velocity => "velocity",
+                };
+                let lift = match (self.gimbal_controller.absolute_lift(), config.controls.joystick.lift_reversed) {
+                    (true, false) => "absolute",
+                    (true, true) => "absolute, reversed",
+                    (false, _) => mode(gimbal.lift_mode),
+                };
+                format!("Modes: P {} R {} L {} Y {}", mode(gimbal.pitch_mode), mode(gimbal.roll_mode), lift, mode(gimbal.yaw_mode))
+            })),
+            ListItem::new(Line::from(format!(
+                "Trim: P {:+.3} R {:+.3} L {:+.3} Y {:+.3}",
+                config.controls.joystick.pitch_trim,
+                config.controls.joystick.roll_trim,
+                config.controls.joystick.lift_trim,
+                config.controls.joystick.yaw_trim,
+            ))),
+            ListItem::new(Line::from({
+                let joystick = &config.controls.joystick;
+                let b = &config.controls.keyboard_bindings;
+                let state = |invert: bool| if invert { "on" } else { "off" };
+                format!(
+                    "Invert: P {} R {} L {} Y {} ('{}'/'{}'/'{}' toggle)",
+                    state(joystick.invert_pitch),
+                    state(joystick.invert_roll),
+                    state(joystick.invert_lift),
+                    state(joystick.invert_yaw),
+                    b.invert_pitch,
+                    b.invert_roll,
+                    b.invert_lift
+                )
+            })),
+            ListItem::new(Line::from(format!("Smoothing:  {:.2}", config.controls.joystick.smoothing))),
+            ListItem::new(Line::from("")),
+            ListItem::new(Line::from(Span::styled("=== KEYBOARD ===", self.theme.style(Role::Header)))),
+            ListItem::new(Line::from({
+                let b = &config.controls.keyboard_bindings;
+                format!(
+                    "Pitch {}/{}  Roll {}/{}  Lift {}/{}  Yaw {}/{}",
+                    b.pitch_up, b.pitch_down, b.roll_left, b.roll_right,
+                    b.lift_up, b.lift_down, b.yaw_left, b.yaw_right
+                )
+            })),
+            ListItem::new(Line::from({
+                let b = &config.controls.keyboard_bindings;
+                format!(
+                    "Reset {}  Home {}  Center {}  Save {}  CSV {}  Calibrate {}  Profile {}",
+                    b.reset, b.home, b.capture_center, b.save_config, b.toggle_csv_log, b.calibrate, b.cycle_profile
+                )
+            })),
+            ListItem::new(Line::from({
+                let b = &config.controls.keyboard_bindings;
+                format!("E-stop {}  Re-arm {} (hold)", key_label(b.estop), key_label(b.rearm))
+            })),
+            ListItem::new(Line::from(format!("Step: {:.3} ({:?})", config.controls.keyboard_step, config.controls.keyboard_mode))),
+            ListItem::new(Line::from(format!(
+                "Value: P {:+.3} R {:+.3} L {:+.3} Y {:+.3}",
+                self.input_state.keyboard_pitch,
+                self.input_state.keyboard_roll,
+                self.input_state.keyboard_lift,
+                self.input_state.keyboard_yaw,
+            ))),
+        ]);
+
+        items.push(ListItem::new(Line::from("")));
+        items.push(ListItem::new(Line::from(Span::styled("=== ACTUATORS ===", self.theme.style(Role::Header)))));
+        let limits = self.gimbal_controller.leg_limits();
+        let actuators = self.gimbal_controller.actuators();
+        let commands = output::actuator_commands(&self.config.output.actuators, &actuators);
+        let binding = if state.envelope_limited { kinematics::binding_actuator(&actuators, &limits) } else { None };
+        for (i, actuator) in actuators.iter().enumerate() {
+            let (label, role) = if actuator.over_travel(&limits) {
+                (" OVER TRAVEL", Role::Error)
+            } else if binding == Some(i) {
+                (" LIMIT (binding)", Role::Error)
+            } else if state.envelope_limited && state.limiting_actuators.contains(&i) {
+                (" LIMIT", Role::Error)
+            } else {
+                ("", Role::Text)
+            };
+            let marker = match self.leg_marker(actuator.extension) {
+                Some(marker) => format!("{} ", marker),
+                None => String::new(),
+            };
+            let command = match &commands {
+                Some(commands) if commands.format == ActuatorFormat::Steps => format!(" {:+} steps", commands.values[i]),
+                Some(commands) => format!(" {}{}", commands.values[i], self.glyph("µs", "us")),
+                None => String::new(),
+            };
+            let (low, high) = limits.extension_range(actuator);
+            items.push(ListItem::new(Line::from(Span::styled(
+                format!("{}A{}: {:+.1}mm (travel {:+.0}..{:+.0}mm){}{}", marker, i + 1, actuator.extension, low, high, command, label),
+                self.theme.style(role),
+            ))));
+        }
+        // Each leg's extension across its travel, colored like the legs on the canvas
+        for (i, actuator) in actuators.iter().enumerate() {
+            let alarm = actuator.over_travel(&limits) || (state.envelope_limited && state.limiting_actuators.contains(&i));
+            items.push(ListItem::new(self.centered_bar(
+                format!("A{:<3}", i + 1),
+                limits.usage(actuator),
+                format!(" {:+6.1}mm", actuator.extension),
+                leg_role(actuator.extension, alarm),
+                area.width.saturating_sub(2),
+            )));
+        }
+        if let Some(i) = binding {
+            items.push(ListItem::new(Line::from(Span::styled(
+                format!("Envelope ({}): A{} at its stroke holds the pose back", format!("{:?}", config.geometry.envelope).to_lowercase(), i + 1),
+                self.theme.style(Role::Warning),
+            ))));
+        }
+
+        if self.config.dynamics.simulate_dynamics {
+            let simulated = self.simulated.pose(state);
+            items.push(ListItem::new(Line::from("")));
+            items.push(ListItem::new(Line::from(Span::styled("=== DYNAMICS ===", self.theme.style(Role::Header)))));
+            items.push(ListItem::new(Line::from(Span::styled(
+                format!("{:<6} {:>8} {:>8} {:>8}", "", "cmd", "sim", "lag"),
+                self.theme.style(Role::Muted),
+            ))));
+            for (name, commanded, actual) in [
+                ("Pitch", state.pitch, simulated.pitch),
+                ("Roll", state.roll, simulated.roll),
+                ("Lift", state.lift, simulated.lift),
+                ("Yaw", state.yaw, simulated.yaw),
+            ] {
+                let lag = commanded - actual;
+                let role = if lag.abs() > 0.5 { Role::Warning } else { Role::Text };
+                items.push(ListItem::new(Line::from(Span::styled(
+                    format!("{:<6} {:>+8.2} {:>+8.2} {:>+8.2}", name, commanded, actual, lag),
+                    self.theme.style(role),
+                ))));
+            }
+            let max_leg_speed = self.config.dynamics.max_leg_speed;
+            if max_leg_speed > 0.0 {
+                let (text, role) = if self.simulated.leg_limited() {
+                    (format!("Legs at {:.0} mm/s: move slowed", max_leg_speed), Role::Warning)
+                } else {
+                    (format!("Legs under {:.0} mm/s", max_leg_speed), Role::Muted)
+                };
+                items.push(ListItem::new(Line::from(Span::styled(text, self.theme.style(role)))));
+            }
+        }
+
+        let output_config = &self.config.output;
+        if output_config.udp.enabled || output_config.mavlink.enabled || output_config.websocket.enabled || output_config.osc.enabled || output_config.grpc.enabled || output_config.mqtt.enabled {
+            items.push(ListItem::new(Line::from("")));
+            items.push(ListItem::new(Line::from(Span::styled("=== OUTPUT ===", self.theme.style(Role::Header)))));
+        }
+        if output_config.udp.enabled {
+            match &self.udp_output {
+                Some(output) => {
+                    items.push(ListItem::new(Line::from(format!(
+                        "UDP {} {} @ {:.0}Hz, sent {}",
+                        self.glyph("→", "->"),
+                        output.target(), output_config.udp.rate_hz, output.packets_sent
+                    ))));
+                    if let Some(error) = &output.last_error {
+                        items.push(ListItem::new(Line::from(Span::styled(error.clone(), self.theme.style(Role::Error)))));
+                    }
+                }
+                None => {
+                    let error = self.udp_error.as_deref().unwrap_or("UDP output not open");
+                    items.push(ListItem::new(Line::from(Span::styled(error.to_string(), self.theme.style(Role::Error)))));
+                }
+            }
+        }
+        if output_config.websocket.enabled {
+            match &self.websocket_output {
+                Some(output) => items.push(ListItem::new(Line::from(format!(
+                    "WebSocket ws://{}: {} client(s)",
+                    output.address(),
+                    output.clients()
+                )))),
+                None => {
+                    let error = self.websocket_error.as_deref().unwrap_or("WebSocket output not running");
+                    items.push(ListItem::new(Line::from(Span::styled(error.to_string(), self.theme.style(Role::Error)))));
+                }
+            }
+        }
+        if output_config.grpc.enabled {
+            match &self.grpc_output {
+                Some(output) => {
+                    items.push(ListItem::new(Line::from(format!(
+                        "gRPC {} @ {:.0}Hz: {} watching",
+                        output.address(),
+                        output_config.grpc.rate_hz,
+                        output.watchers()
+                    ))));
+                    if let Some(error) = output.last_error() {
+                        items.push(ListItem::new(Line::from(Span::styled(error, self.theme.style(Role::Error)))));
+                    }
+                }
+                None => {
+                    let error = self.grpc_error.as_deref().unwrap_or("gRPC output not running");
+                    items.push(ListItem::new(Line::from(Span::styled(error.to_string(), self.theme.style(Role::Error)))));
+                }
+            }
+        }
+        if output_config.mqtt.enabled {
+            match &self.mqtt_output {
+                Some(output) => {
+                    let (sent, dropped) = output.counts();
+                    let dropped = if dropped > 0 { format!(", dropped {}", dropped) } else { String::new() };
+                    items.push(ListItem::new(Line::from(format!(
+                        "MQTT {} {}/ @ {:.0}Hz, sent {}{}",
+                        output.broker(),
+                        output.prefix(),
+                        output_config.mqtt.rate_hz,
+                        sent,
+                        dropped
+                    ))));
+                    let (text, role) = match output.link() {
+                        LinkState::Connecting => ("  connecting...".to_string(), Role::Muted),
+                        LinkState::Connected => ("  connected".to_string(), Role::Ok),
+                        LinkState::Down { error, retry_secs } => (format!("  down: {} (backing off {:.1}s)", error, retry_secs), Role::Warning),
+                    };
+                    items.push(ListItem::new(Line::from(Span::styled(text, self.theme.style(role)))));
+                }
+                None => {
+                    let error = self.mqtt_error.as_deref().unwrap_or("MQTT output not running");
+                    items.push(ListItem::new(Line::from(Span::styled(error.to_string(), self.theme.style(Role::Error)))));
+                }
+            }
+        }
+        if output_config.mavlink.enabled {
+            match &self.mavlink_output {
+                Some(output) => {
+                    items.push(ListItem::new(Line::from(format!(
+                        "MAVLink {} {} @ {:.0}Hz, sent {}",
+                        self.glyph("→", "->"),
+                        output.target(), output_config.mavlink.rate_hz, output.packets_sent
+                    ))));
+                    if let Some(error) = &output.last_error {
+                        items.push(ListItem::new(Line::from(Span::styled(error.clone(), self.theme.style(Role::Error)))));
+                    }
+                }
+                None => {
+                    let error = self.mavlink_error.as_deref().unwrap_or("MAVLink output not open");
+                    items.push(ListItem::new(Line::from(Span::styled(error.to_string(), self.theme.style(Role::Error)))));
+                }
+            }
+        }
+        if output_config.osc.enabled {
+            match &self.osc_output {
+                Some(output) => {
+                    items.push(ListItem::new(Line::from(format!(
+                        "OSC {} {}{} @ {:.0}Hz, sent {}",
+                        self.glyph("→", "->"),
+                        output.target(), output_config.osc.base_path, output_config.osc.rate_hz, output.packets_sent
+                    ))));
+                    if let Some(error) = &output.last_error {
+                        items.push(ListItem::new(Line::from(Span::styled(error.clone(), self.theme.style(Role::Error)))));
+                    }
+                }
+                None => {
+                    let error = self.osc_error.as_deref().unwrap_or("OSC output not open");
+                    items.push(ListItem::new(Line::from(Span::styled(error.to_string(), self.theme.style(Role::Error)))));
+                }
+            }
+        }
+
+        if self.config.server.enabled {
+            items.push(ListItem::new(Line::from("")));
+            items.push(ListItem::new(Line::from(Span::styled("=== COMMAND SERVER ===", self.theme.style(Role::Header)))));
+            match &self.command_server {
+                Some(server) => items.push(ListItem::new(Line::from(format!(
+                    "TCP {}: {} client(s), {} streaming",
+                    server.address(),
+                    self.server_clients.len(),
+                    self.stream_clients.len()
+                )))),
+                None => {
+                    let error = self.server_error.as_deref().unwrap_or("Command server not running");
+                    items.push(ListItem::new(Line::from(Span::styled(error.to_string(), self.theme.style(Role::Error)))));
+                }
+            }
+        }
+
+        if self.config.feedback.enabled {
+            items.push(ListItem::new(Line::from("")));
+            items.push(ListItem::new(Line::from(Span::styled("=== FEEDBACK ===", self.theme.style(Role::Header)))));
+            match (&self.feedback_input, self.measured_state()) {
+                (Some(input), Some(measured)) => {
+                    items.push(ListItem::new(Line::from(format!(
+                        "UDP {}: {} poses, {} bad lines",
+                        self.config.feedback.address, input.poses, input.errors
+                    ))));
+                    items.push(ListItem::new(Line::from(Span::styled(
+                        format!("{:<6} {:>9} {:>9} {:>8}", "", "commanded", "measured", "error"),
+                        self.theme.style(Role::Muted),
+                    ))));
+                    let commanded = self.output_state();
+                    let threshold = self.config.feedback.error_threshold;
+                    let measured_values = [measured.pitch, measured.roll, measured.lift, measured.yaw];
+                    let commanded_values = [commanded.pitch, commanded.roll, commanded.lift, commanded.yaw];
+                    for (i, (name, error)) in pose_error(&commanded, measured).into_iter().enumerate() {
+                        if name == "Yaw" && !config.gimbal.yaw_enabled() {
+                            continue;
+                        }
+                        let style = if error.abs() > threshold { self.theme.style(Role::Warning) } else { Style::default() };
+                        items.push(ListItem::new(Line::from(Span::styled(
+                            format!("{:<6} {:>+9.2} {:>+9.2} {:>+8.2}", name, commanded_values[i], measured_values[i], error),
+                            style,
+                        ))));
+                    }
+                }
+                (Some(input), None) => items.push(ListItem::new(Line::from(Span::styled(
+                    format!("UDP {}: no recent pose ({} bad lines)", self.config.feedback.address, input.errors),
+                    self.theme.style(Role::Muted),
+                )))),
+                (None, _) => {
+                    let error = self.feedback_error.as_deref().unwrap_or("Feedback input not open");
+                    items.push(ListItem::new(Line::from(Span::styled(error.to_string(), self.theme.style(Role::Error)))));
+                }
+            }
+        }
+
+        if let Some(log) = &self.csv_log {
+            items.push(ListItem::new(Line::from("")));
+            items.push(ListItem::new(Line::from(Span::styled("=== RECORDING ===", self.theme.style(Role::Header)))));
+            items.push(ListItem::new(Line::from(format!("CSV {} {} ({} rows)", self.glyph("→", "->"), log.path().display(), log.rows()))));
+        }
+
+        for conflict in config.controls.keyboard_bindings.conflicts() {
+            items.push(ListItem::new(Line::from(Span::styled(
+                format!("{}{}", self.glyph("⚠ ", "! "), conflict),
+                self.theme.style(Role::Warning),
+            ))));
+        }
+
+        let list = List::new(items)
+            .block(self.block().title("State & Config"));
+        frame.render_widget(list, area);
+    }
+
+    fn draw_calibration(&self, frame: &mut Frame, area: Rect, wizard: &CalibrationWizard) {
+        let mut items = vec![
+            ListItem::new(Line::from(Span::styled(wizard.prompt(), self.theme.style(Role::Warning)))),
+        ];
+        if let Some(message) = &wizard.message {
+            items.push(ListItem::new(Line::from(Span::styled(message.clone(), self.theme.style(Role::Error)))));
+        }
+        items.push(ListItem::new(Line::from("")));
+        items.push(ListItem::new(Line::from(Span::styled(
+            format!("  {:<12} {:>8} {:>11} {:>7} {:>7} {:>7}", "Axis", "Raw", "Calibrated", "Min", "Center", "Max"),
+            self.theme.style(Role::Header),
+        ))));
+
+        let pending = wizard.results();
+        let gamepad = self.gamepads.get(&wizard.gamepad_id);
+        for (i, axis) in self.calibration_axes(wizard).into_iter().enumerate() {
+            let raw = gamepad.and_then(|g| g.axes.get(&axis)).copied().unwrap_or(0.0) as f64;
+            // Show the new calibration once recorded, otherwise whatever is stored
+            let calibration = pending
+                .get(&axis)
+                .filter(|_| wizard.step() == CalibrationStep::Review)
+                .or_else(|| self.config.axis_calibration(Some(&wizard.gamepad_name), axis));
+            let row = match calibration {
+                Some(c) => format!(
+                    "{} {:<12} {:>8.3} {:>11.3} {:>7.3} {:>7.3} {:>7.3}",
+                    if i == wizard.selected { ">" } else { " " },
+                    axis_key(axis), raw, c.normalize(raw), c.min, c.center, c.max
+                ),
+                None => format!(
+                    "{} {:<12} {:>8.3} {:>11} {:>7} {:>7} {:>7}",
+                    if i == wizard.selected { ">" } else { " " },
+                    axis_key(axis), raw, "-", "-", "-", "-"
+                ),
+            };
+            let role = if i == wizard.selected { Role::Text } else { Role::Secondary };
+            items.push(ListItem::new(Line::from(Span::styled(row, self.theme.style(role)))));
+        }
+
+        items.push(ListItem::new(Line::from("")));
+        items.push(ListItem::new(Line::from(Span::styled(
+            if self.config.app.ascii_mode {
+                "Enter: next/save  Up/Down: select axis  x: clear axis calibration  Esc: cancel"
+            } else {
+                "Enter: next/save  ↑/↓: select axis  x: clear axis calibration  Esc: cancel"
+            },
+            self.theme.style(Role::Muted),
+        ))));
+
+        let list = List::new(items)
+            .block(self.block().title(format!("Calibration - {}", wizard.gamepad_name)));
+        frame.render_widget(list, area);
+    }
+
+    // Centered dialog, drawn over either view
+    fn draw_detection(&self, frame: &mut Frame, area: Rect, detector: &AxisDetector) {
+        let width = 72.min(area.width);
+        let height = 12.min(area.height);
+        let popup = Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height);
+
+        let mut items = vec![
+            ListItem::new(Line::from(Span::styled(detector.prompt(), self.theme.style(Role::Warning)))),
+        ];
+        if let Some(message) = &detector.message {
+            items.push(ListItem::new(Line::from(Span::styled(message.clone(), self.theme.style(Role::Error)))));
+        }
+        items.push(ListItem::new(Line::from("")));
+        for channel in DetectChannel::ALL {
+            let row = match detector.results().iter().find(|(c, _)| *c == channel) {
+                Some((_, detected)) => format!(
+                    "  {:<6} {:<12} {}",
+                    channel.name(),
+                    axis_key(detected.axis),
+                    if detected.invert { "inverted" } else { "" }
+                ),
+                None => format!("  {:<6} {:<12}", channel.name(), "-"),
+            };
+            items.push(ListItem::new(Line::from(Span::styled(row, self.theme.style(Role::Text)))));
+        }
+        items.push(ListItem::new(Line::from("")));
+        items.push(ListItem::new(Line::from(Span::styled(
+            "Enter: save  Backspace: redo last channel  Esc: cancel",
+            self.theme.style(Role::Muted),
+        ))));
+
+        let list = List::new(items)
+            .block(self.block().title(format!("Detect Axes - {}", detector.gamepad_name)));
+        frame.render_widget(Clear, popup);
+        frame.render_widget(list, popup);
+    }
+
+    fn draw_invert_check(&self, frame: &mut Frame, area: Rect, check: &InvertCheck) {
+        let width = 72.min(area.width);
+        let height = 12.min(area.height);
+        let popup = Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height);
+
+        let mut items = vec![
+            ListItem::new(Line::from(Span::styled(check.prompt(), self.theme.style(Role::Warning)))),
+        ];
+        if let Some(message) = &check.message {
+            items.push(ListItem::new(Line::from(Span::styled(message.clone(), self.theme.style(Role::Error)))));
+        }
+        items.push(ListItem::new(Line::from("")));
+        let joystick = &self.gimbal_controller.get_config().controls.joystick;
+        for channel in DetectChannel::ALL {
+            let inverted = match channel {
+                DetectChannel::Pitch => joystick.invert_pitch,
+                DetectChannel::Roll => joystick.invert_roll,
+                DetectChannel::Lift => joystick.invert_lift,
+            };
+            let answer = match check.results().iter().find(|(c, _)| *c == channel) {
+                Some((_, Answer::Kept)) => "right way round",
+                Some((_, Answer::Flipped)) => "flipped",
+                Some((_, Answer::Skipped)) => "skipped",
+                None => "-",
+            };
+            let row = format!("  {:<6} {:<16} {}", channel.name(), answer, if inverted { "inverted" } else { "" });
+            items.push(ListItem::new(Line::from(Span::styled(row, self.theme.style(Role::Text)))));
+        }
+        items.push(ListItem::new(Line::from("")));
+        items.push(ListItem::new(Line::from(Span::styled(
+            "y/n: answer  s: skip channel  Enter: save  Esc: close",
+            self.theme.style(Role::Muted),
+        ))));
+
+        let list = List::new(items)
+            .block(self.block().title(format!("Check Inversion - {}", check.gamepad_name)));
+        frame.render_widget(Clear, popup);
+        frame.render_widget(list, popup);
+    }
+
+    fn draw_gimbal_view(&self, frame: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(0),
             ])
-            .split(frame.area());
+            .split(area);
 
         // Header
         let state = self.gimbal_controller.get_state();
+        let config = self.gimbal_controller.get_config();
+        let bindings = &self.config.controls.keyboard_bindings;
+        let yaw_text = if config.gimbal.yaw_enabled() {
+            format!(" Yaw: {:.1}°", state.yaw)
+        } else {
+            String::new()
+        };
+        // The hardware profile name only shows once there is more than one to pick from
+        let hardware = if self.config.hardware.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", self.hardware_name())
+        };
         let header_text = format!(
-            "🎮 EPL Gimbal Controller - Pitch: {:.1}° Roll: {:.1}° Lift: {:.1}mm | 't' debug, 'r' reset, 'q' quit",
-            state.pitch, state.roll, state.lift
+            "{}EPL Gimbal Controller{}{} - Pitch: {:.1}° Roll: {:.1}° Lift: {:.1}mm [{:.0}..{:.0}]{} | '{}' debug, '{}' reset, '{}' quit",
+            self.glyph("🎮 ", ""),
+            self.config_profile_tag(),
+            hardware,
+            state.pitch, state.roll, state.lift, config.gimbal.lift_min, config.gimbal.lift_max, yaw_text, bindings.toggle_debug, bindings.reset, bindings.quit
         );
-        let header = Paragraph::new(header_text)
-            .block(Block::default().borders(Borders::ALL))
-            .style(Style::default().fg(Color::Cyan));
+        let over_travel: Vec<String> = self
+            .gimbal_controller
+            .actuators()
+            .iter()
+            .enumerate()
+            .filter(|(_, a)| a.over_travel(&self.gimbal_controller.leg_limits()))
+            .map(|(i, _)| format!("A{}", i + 1))
+            .collect();
+        let mut spans = vec![Span::styled(header_text, self.theme.style(Role::Header))];
+        // Only with a dead-man's switch configured; first, so a narrow terminal still shows it
+        if self.enable_label().is_some() {
+            let (label, role) = match (self.output_enabled(), self.output_pose.is_some()) {
+                (true, false) => ("OUTPUT LIVE", Role::Ok),
+                (true, true) => ("OUTPUT EASING IN", Role::Warning),
+                (false, _) => ("OUTPUT DISABLED", Role::Warning),
+            };
+            spans.insert(0, Span::styled(label, self.theme.style(role).add_modifier(ratatui::style::Modifier::BOLD)));
+            spans.insert(1, Span::raw(" "));
+        }
+        if !over_travel.is_empty() {
+            spans.push(Span::styled(
+                format!(" {}OVER TRAVEL {}", self.glyph("⚠ ", "! "), over_travel.join(" ")),
+                self.theme.style(Role::Error),
+            ));
+        }
+        if self.gimbal_controller.is_homing() {
+            let label = if self.gimbal_controller.setpoint().is_some() { " MOVING" } else { " HOMING" };
+            spans.push(Span::styled(label, self.theme.style(Role::Warning).add_modifier(ratatui::style::Modifier::BOLD)));
+        }
+        let modifier = self.gimbal_controller.sensitivity_modifier();
+        if modifier != 1.0 {
+            let label = if modifier < 1.0 { "PRECISION" } else { "BOOST" };
+            spans.push(Span::styled(
+                format!(" {}{} {}", self.glyph("×", "x"), modifier, label),
+                self.theme.style(Role::Warning).add_modifier(ratatui::style::Modifier::BOLD),
+            ));
+        }
+        if let Some(label) = self.soft_start_label() {
+            spans.push(Span::styled(label, self.theme.style(Role::Warning).add_modifier(ratatui::style::Modifier::BOLD)));
+        }
+        if self.sequence.is_some() {
+            spans.push(Span::styled(" SEQUENCE", self.theme.style(Role::Warning).add_modifier(ratatui::style::Modifier::BOLD)));
+        }
+        // Before the hard limits: the plate still has travel, but the mechanism is losing authority
+        if let Some(tilt) = config.gimbal.tilt_warning(state.pitch, state.roll) {
+            let flash = (self.started.elapsed().as_millis() / 500).is_multiple_of(2);
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(format!("{}TILT {:.1}°", self.glyph("⚠ ", "! "), tilt), self.theme.alarm(flash)));
+        }
+        if state.envelope_limited {
+            let limiting: Vec<String> = state.limiting_actuators.iter().map(|i| format!("A{}", i + 1)).collect();
+            spans.push(Span::styled(
+                format!(" {}LIMIT {}", self.glyph("⛔ ", "! "), limiting.join(" ")),
+                self.theme.style(Role::Error),
+            ));
+        }
+        let header = Paragraph::new(Line::from(spans)).block(self.block());
         frame.render_widget(header, chunks[0]);
 
         self.draw_gimbal_visualization(frame, chunks[1]);
     }
 
+    // What the canvas draws: the simulated plate with dynamics on, otherwise the commanded pose
+    fn displayed_state(&self) -> GimbalState {
+        let commanded = self.gimbal_controller.get_state();
+        if self.config.dynamics.simulate_dynamics {
+            self.simulated.pose(commanded)
+        } else {
+            commanded.clone()
+        }
+    }
+
     fn draw_gimbal_visualization(&self, frame: &mut Frame, area: Rect) {
-        let state = self.gimbal_controller.get_state();
-        
+        self.canvas_area.set(area);
+        let displayed = self.displayed_state();
+        let geometry = &self.gimbal_controller.get_config().geometry;
+        let projection = self.projection;
+        let theme = &self.theme;
+        let title = self.canvas_title(geometry, projection);
+
+        // Cells are about twice as tall as wide
+        let aspect = area.width.saturating_sub(2) as f64 / (area.height.saturating_sub(2) as f64 * 2.0).max(1.0);
+        let (x_half, y_half) = paint::fit_bounds(projection, geometry.plate_radius, aspect);
+
+        // Braille shows as boxes on terminals without full Unicode fonts
+        let marker = if self.config.app.ascii_mode { Marker::Dot } else { Marker::Braille };
+        let resolution = Resolution::new(
+            area.width.saturating_sub(2),
+            area.height.saturating_sub(2),
+            x_half * 2.0,
+            y_half * 2.0,
+            marker,
+        );
+        let pen = Pen::new(resolution);
+        if self.static_layer.borrow().as_ref().is_none_or(|layer| !layer.is_current(geometry, resolution, projection)) {
+            *self.static_layer.borrow_mut() = Some(StaticLayer::build(geometry, resolution, projection, theme));
+        }
+        let static_layer = self.static_layer.borrow();
+        let static_layer = static_layer.as_ref().expect("built above");
+        let scene = self.paint_scene(&displayed, projection);
         let gimbal_canvas = Canvas::default()
-            .block(Block::default().borders(Borders::ALL)
-                .title("🎯 EPL Parallel Plate Gimbal - Isometric View (3 Scissor Lifts)"))
+            .marker(marker)
+            .block(self.block().title(title))
             .paint(|ctx| {
-                // Use the processed gimbal state values instead of raw input
-                let pitch_angle = state.pitch;  // Already processed by gimbal controller
-                let roll_angle = state.roll;    // Already processed by gimbal controller
-                let base_lift = state.lift;     // Already processed by gimbal controller
-
-                // Platform dimensions - optimized for clear visualization (more squat design)
-                let platform_radius = 100.0;  
-                let base_height = -30.0;  // Raised base height for more squat appearance
-                let nominal_height = 15.0 + base_lift;  // Lower nominal height for closer plates
-
-                // Improved isometric projection helper function
-                let to_isometric = |x: f64, y: f64, z: f64| -> (f64, f64) {
-                    // Standard isometric projection with proper orientation
-                    let iso_x = (x - z) * 0.866;  // cos(30°) ≈ 0.866
-                    let iso_y = (x + z) * 0.5 + y;  // sin(30°) = 0.5
-                    (iso_x, iso_y)
-                };
+                // Base plate, its rings and the coordinate frame come prebuilt
+                pen.batch(ctx, &static_layer.segments);
+                pen.render(ctx, &scene, theme);
+                self.canvas_draws.set(pen.draws());
+            })
+            .x_bounds([-x_half, x_half])
+            .y_bounds([-y_half, y_half]);
+        frame.render_widget(gimbal_canvas, area);
+    }
 
-                // Draw base platform (lower circular plate) - more prominent like real gimbal
-                let base_points = 32;  // High resolution circle
-                for i in 0..base_points {
-                    let angle1 = i as f64 * 2.0 * std::f64::consts::PI / base_points as f64;
-                    let angle2 = (i + 1) as f64 * 2.0 * std::f64::consts::PI / base_points as f64;
-                    
-                    let x1_3d = platform_radius * angle1.cos();
-                    let y1_3d = platform_radius * angle1.sin();
-                    let x2_3d = platform_radius * angle2.cos();
-                    let y2_3d = platform_radius * angle2.sin();
-                    
-                    let (x1, y1) = to_isometric(x1_3d, base_height, y1_3d);
-                    let (x2, y2) = to_isometric(x2_3d, base_height, y2_3d);
-                    
-                    // Draw thick circular base platform edge
-                    for thickness in [-2.0, -1.0, 0.0, 1.0, 2.0] {
-                        ctx.draw(&ratatui::widgets::canvas::Line {
-                            x1: x1 + thickness, y1, x2: x2 + thickness, y2,
-                            color: Color::Gray,
-                        });
-                    }
+    fn canvas_title(&self, geometry: &GeometryConfig, projection: Projection) -> String {
+        match geometry.platform_type {
+            PlatformType::Scissor3 => format!(
+                "{}EPL Parallel Plate Gimbal - {} View ({} Scissor Lifts)",
+                self.glyph("🎯 ", ""),
+                projection.name(),
+                geometry.scissor_angles.len()
+            ),
+            PlatformType::Stewart6 => format!(
+                "{}Stewart Platform - {} View ({} Legs)",
+                self.glyph("🎯 ", ""),
+                projection.name(),
+                geometry.base_angles.len()
+            ),
+        }
+    }
+
+    // Renders the current pose through the canvas drawing code into an SVG file
+    fn export_frame(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if !path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("svg")) {
+            return Err("only .svg snapshots are supported".into());
+        }
+        let geometry = &self.gimbal_controller.get_config().geometry;
+        let displayed = self.displayed_state();
+        let projection = self.projection;
+        let mut scene = paint::static_scene(geometry, projection, &self.theme);
+        scene.append(self.paint_scene(&displayed, projection));
+        let bounds = paint::fit_bounds(projection, geometry.plate_radius, svg::ASPECT);
+        let readout = format!(
+            "Pitch: {:.1}°  Roll: {:.1}°  Lift: {:.1}mm  Yaw: {:.1}°",
+            displayed.pitch, displayed.roll, displayed.lift, displayed.yaw
+        );
+        let title = self.canvas_title(geometry, projection);
+        std::fs::write(path, svg::render(&scene, &self.theme, bounds, title.trim_start_matches("🎯 "), &readout))?;
+        Ok(())
+    }
+
+    // The moving parts of the gimbal drawing in canvas coordinates, for the terminal canvas
+    // and the SVG export alike; the base plate comes from paint::static_scene
+    fn paint_scene(&self, state: &GimbalState, projection: Projection) -> Scene {
+        let geometry = &self.gimbal_controller.get_config().geometry;
+        let actuators = kinematics::solve(geometry, state);
+        let limits = self.gimbal_controller.leg_limits();
+        let project = |x, y, z| projection.project(x, y, z);
+        let theme = &self.theme;
+        // Outline of the commanded plate, drawn over the simulated one so the lag is visible
+        let commanded_tops: Vec<(f64, f64, f64)> = if self.config.dynamics.simulate_dynamics {
+            self.gimbal_controller.actuators().iter().map(|a| a.top).collect()
+        } else {
+            Vec::new()
+        };
+        let mut scene = Scene::default();
+
+        // Use the processed gimbal state values instead of raw input
+        let pitch_angle = state.pitch;  // Already processed by gimbal controller
+        let roll_angle = state.roll;    // Already processed by gimbal controller
+        let base_lift = state.lift;     // Absolute travel; nominal_height is the plate at lift 0
+        let yaw_rad = state.yaw.to_radians();
+
+        // Platform dimensions from [geometry]; defaults give a squat, easy-to-read layout
+        let platform_radius = geometry.plate_radius;
+        let base_height = geometry.base_height;
+        let nominal_height = geometry.nominal_height + base_lift;
+
+        let avg_height = if geometry.platform_type == PlatformType::Stewart6 {
+            self.paint_stewart(&mut scene, projection, &actuators, &limits, &state.limiting_actuators);
+            nominal_height
+        } else {
+            // EPL Gimbal: scissor lifts at the configured angles (0°, 120°, 240° by default)
+            let mut upper_plate_points = Vec::new();
+
+            // One scissor lift per solved actuator, so the count follows geometry.scissor_angles
+            for (i, actuator) in actuators.iter().enumerate() {
+                // 3D position on base platform
+                let (base_x_3d, base_y_3d, _) = actuator.base;
+                let angle_rad = base_y_3d.atan2(base_x_3d);
+
+                // Leg height from the plate plane (tilt, lift and yaw are solved in kinematics)
+                let (top_x_3d, top_y_3d, scissor_height_3d) = actuator.top;
+                let (upper_x, upper_y) = project(top_x_3d, scissor_height_3d, top_y_3d);
+                upper_plate_points.push((upper_x, upper_y, scissor_height_3d));
+
+                // Determine scissor lift color based on extension
+                let extension = scissor_height_3d - nominal_height;
+                // Beyond actuator stroke, or holding the envelope
+                let alarm = actuator.over_travel(&limits) || state.limiting_actuators.contains(&i);
+                let lift_color = theme.color(leg_role(extension, alarm));
+
+                // Draw realistic large diamond-shaped scissor mechanism - spans nearly entire base plate
+                // Much larger - nearly touching neighbouring lifts; narrower as the count grows
+                let scissor_width = platform_radius * 1.2 * 3.0 / actuators.len() as f64;
+                let mid_height_3d = (base_height + scissor_height_3d) / 2.0;
+
+                // Calculate diamond pattern endpoints - single points at tips like real hardware
+                let diamond_half_width = scissor_width * 0.5;
+
+                // Diamond tips - single attachment points (not scaffold)
+                let (bottom_tip_x, bottom_tip_y) = project(base_x_3d, base_height, base_y_3d);
+                let (top_tip_x, top_tip_y) = (upper_x, upper_y);
+
+                // Middle diamond points (wider diamond when extended, narrower when compressed)
+                let compression_factor = (scissor_height_3d - nominal_height) / nominal_height;
+                let current_width = diamond_half_width * (1.0 - compression_factor * 0.3);
+
+                // Calculate proper orientation for diamond scissor lift based on angle
+                let perpendicular_angle = angle_rad + std::f64::consts::PI / 2.0;
+
+                // Diamond points oriented perpendicular to radius for proper scissors orientation
+                let diamond_offset_x = current_width * perpendicular_angle.cos();
+                let diamond_offset_z = current_width * perpendicular_angle.sin();
+
+                let (mid_left_x, mid_left_y) = project(base_x_3d - diamond_offset_x, mid_height_3d, base_y_3d - diamond_offset_z);
+                let (mid_right_x, mid_right_y) = project(base_x_3d + diamond_offset_x, mid_height_3d, base_y_3d + diamond_offset_z);
+
+                // Draw the diamond-shaped scissor mechanism (4 main struts forming diamond) - much thicker
+                for (from, to) in [
+                    ((bottom_tip_x, bottom_tip_y), (mid_left_x, mid_left_y)),
+                    ((bottom_tip_x, bottom_tip_y), (mid_right_x, mid_right_y)),
+                    ((mid_left_x, mid_left_y), (top_tip_x, top_tip_y)),
+                    ((mid_right_x, mid_right_y), (top_tip_x, top_tip_y)),
+                ] {
+                    scene.thick_line(from, to, 6.0, lift_color);
                 }
 
-                // Draw inner circular rings on base platform for depth
-                for ring_factor in [0.7, 0.5, 0.3] {
-                    let ring_radius = platform_radius * ring_factor;
-                    for i in 0..24 {
-                        let angle1 = i as f64 * 2.0 * std::f64::consts::PI / 24.0;
-                        let angle2 = (i + 1) as f64 * 2.0 * std::f64::consts::PI / 24.0;
-                        
-                        let x1_3d = ring_radius * angle1.cos();
-                        let y1_3d = ring_radius * angle1.sin();
-                        let x2_3d = ring_radius * angle2.cos();
-                        let y2_3d = ring_radius * angle2.sin();
-                        
-                        let (x1, y1) = to_isometric(x1_3d, base_height, y1_3d);
-                        let (x2, y2) = to_isometric(x2_3d, base_height, y2_3d);
-                        
-                        ctx.draw(&ratatui::widgets::canvas::Line {
-                            x1, y1, x2, y2,
-                            color: Color::DarkGray,
-                        });
-                    }
+                // Draw horizontal worm gear shaft running through center of diamond (perpendicular to lift) - thicker
+                let worm_start_x = base_x_3d - diamond_offset_x * 0.8;
+                let worm_start_z = base_y_3d - diamond_offset_z * 0.8;
+                let worm_end_x = base_x_3d + diamond_offset_x * 0.8;
+                let worm_end_z = base_y_3d + diamond_offset_z * 0.8;
+
+                let (worm_start_iso_x, worm_start_iso_y) = project(worm_start_x, mid_height_3d, worm_start_z);
+                let (worm_end_iso_x, worm_end_iso_y) = project(worm_end_x, mid_height_3d, worm_end_z);
+
+                scene.thick_line((worm_start_iso_x, worm_start_iso_y), (worm_end_iso_x, worm_end_iso_y), 5.0, theme.color(Role::WormGear));
+
+                // Draw threaded pattern on worm gear shaft
+                let thread_segments = 8;
+                for i in 0..thread_segments {
+                    let t = i as f64 / thread_segments as f64;
+                    let thread_x = worm_start_x + (worm_end_x - worm_start_x) * t;
+                    let thread_z = worm_start_z + (worm_end_z - worm_start_z) * t;
+                    let thread_offset = (i % 2) as f64 * 2.0 - 1.0; // Alternating offset for threads
+
+                    let (thread_iso_x, thread_iso_y) = project(thread_x, mid_height_3d + thread_offset, thread_z);
+                    scene.circle((thread_iso_x, thread_iso_y), 1.0, theme.color(Role::Secondary));
+                }
+
+                // Draw diamond pivot points where struts meet (ball bearings) - larger
+                for pivot in [(mid_left_x, mid_left_y), (mid_right_x, mid_right_y)] {
+                    scene.circle(pivot, 4.5, theme.color(Role::Bearing));
                 }
 
-                // EPL Gimbal: Three scissor lifts at 0°, 120°, 240° (triangular configuration)
-                let scissor_positions: [(f64, f64); 3] = [
-                    (0.0, platform_radius * 0.75),     // Front (0°)
-                    (120.0, platform_radius * 0.75),   // Back-right (120°)
-                    (240.0, platform_radius * 0.75),   // Back-left (240°)
+                // Draw square stepper motor mounted on the moving scissor assembly (moves with lift)
+                let motor_3d_x = base_x_3d + diamond_offset_x * 1.2;
+                let motor_3d_z = base_y_3d + diamond_offset_z * 1.2;
+                let (motor_x, motor_y) = project(motor_3d_x, mid_height_3d, motor_3d_z);
+
+                // Draw square motor housing (stepper motors are square, not circular)
+                let motor_size = 8.0;  // Half-size for square motor
+                let motor_corners = [
+                    (-motor_size, -motor_size),
+                    (motor_size, -motor_size),
+                    (motor_size, motor_size),
+                    (-motor_size, motor_size),
                 ];
 
-                let mut upper_plate_points = Vec::new();
-
-                for (i, (angle_deg, radius)) in scissor_positions.iter().enumerate() {
-                    let angle_rad = angle_deg.to_radians();
-                    
-                    // 3D position on base platform
-                    let base_x_3d = radius * angle_rad.cos();
-                    let base_y_3d = radius * angle_rad.sin();
-                    
-                    // Calculate scissor extension based on desired tilt angles
-                    // More realistic gimbal mechanics - each actuator controls plate tilt
-                    let pitch_effect = (base_y_3d / platform_radius) * pitch_angle.to_radians() * platform_radius * 0.5;
-                    let roll_effect = (base_x_3d / platform_radius) * roll_angle.to_radians() * platform_radius * 0.5;
-                    
-                    // Final height for this scissor lift
-                    let scissor_height_3d = nominal_height + pitch_effect + roll_effect;
-                    
-                    // Store upper plate connection point
-                    let (upper_x, upper_y) = to_isometric(base_x_3d, scissor_height_3d, base_y_3d);
-                    upper_plate_points.push((upper_x, upper_y, scissor_height_3d));
-                    
-                    // Determine scissor lift color based on extension
-                    let extension = scissor_height_3d - nominal_height;
-                    let lift_color = if extension > 3.0 {
-                        Color::LightGreen  // Extended
-                    } else if extension < -3.0 {
-                        Color::LightRed    // Retracted
-                    } else {
-                        Color::Yellow      // Neutral
-                    };
-                    
-                    // Draw realistic large diamond-shaped scissor mechanism - spans nearly entire base plate
-                    let scissor_width = platform_radius * 1.2;  // Much larger - nearly touching other lifts
-                    let mid_height_3d = (base_height + scissor_height_3d) / 2.0;
-                    
-                    // Calculate diamond pattern endpoints - single points at tips like real hardware
-                    let diamond_half_width = scissor_width * 0.5;
-                    
-                    // Diamond tips - single attachment points (not scaffold)
-                    let (bottom_tip_x, bottom_tip_y) = to_isometric(base_x_3d, base_height, base_y_3d);
-                    let (top_tip_x, top_tip_y) = to_isometric(base_x_3d, scissor_height_3d, base_y_3d);
-                    
-                    // Middle diamond points (wider diamond when extended, narrower when compressed)
-                    let compression_factor = (scissor_height_3d - nominal_height) / nominal_height;
-                    let current_width = diamond_half_width * (1.0 - compression_factor * 0.3);
-                    
-                    // Calculate proper orientation for diamond scissor lift based on angle
-                    let perpendicular_angle = angle_rad + std::f64::consts::PI / 2.0;
-                    
-                    // Diamond points oriented perpendicular to radius for proper scissors orientation
-                    let diamond_offset_x = current_width * perpendicular_angle.cos();
-                    let diamond_offset_z = current_width * perpendicular_angle.sin();
-                    
-                    let (mid_left_x, mid_left_y) = to_isometric(base_x_3d - diamond_offset_x, mid_height_3d, base_y_3d - diamond_offset_z);
-                    let (mid_right_x, mid_right_y) = to_isometric(base_x_3d + diamond_offset_x, mid_height_3d, base_y_3d + diamond_offset_z);
-                    
-                    // Draw the diamond-shaped scissor mechanism (4 main struts forming diamond) - much thicker
-                    for thickness in [-3.0, -2.5, -2.0, -1.5, -1.0, -0.5, 0.0, 0.5, 1.0, 1.5, 2.0, 2.5, 3.0] {
-                        // Four main diamond struts
-                        // Bottom tip to left middle
-                        ctx.draw(&ratatui::widgets::canvas::Line {
-                            x1: bottom_tip_x + thickness,
-                            y1: bottom_tip_y,
-                            x2: mid_left_x + thickness,
-                            y2: mid_left_y,
-                            color: lift_color,
-                        });
-                        
-                        // Bottom tip to right middle  
-                        ctx.draw(&ratatui::widgets::canvas::Line {
-                            x1: bottom_tip_x + thickness,
-                            y1: bottom_tip_y,
-                            x2: mid_right_x + thickness,
-                            y2: mid_right_y,
-                            color: lift_color,
-                        });
-                        
-                        // Left middle to top tip
-                        ctx.draw(&ratatui::widgets::canvas::Line {
-                            x1: mid_left_x + thickness,
-                            y1: mid_left_y,
-                            x2: top_tip_x + thickness,
-                            y2: top_tip_y,
-                            color: lift_color,
-                        });
-                        
-                        // Right middle to top tip
-                        ctx.draw(&ratatui::widgets::canvas::Line {
-                            x1: mid_right_x + thickness,
-                            y1: mid_right_y,
-                            x2: top_tip_x + thickness,
-                            y2: top_tip_y,
-                            color: lift_color,
-                        });
-                    }
-                    
-                    // Draw horizontal worm gear shaft running through center of diamond (perpendicular to lift) - thicker
-                    let worm_start_x = base_x_3d - diamond_offset_x * 0.8;
-                    let worm_start_z = base_y_3d - diamond_offset_z * 0.8;
-                    let worm_end_x = base_x_3d + diamond_offset_x * 0.8;
-                    let worm_end_z = base_y_3d + diamond_offset_z * 0.8;
-                    
-                    let (worm_start_iso_x, worm_start_iso_y) = to_isometric(worm_start_x, mid_height_3d, worm_start_z);
-                    let (worm_end_iso_x, worm_end_iso_y) = to_isometric(worm_end_x, mid_height_3d, worm_end_z);
-                    
-                    for thickness in [-2.5, -2.0, -1.5, -1.0, -0.5, 0.0, 0.5, 1.0, 1.5, 2.0, 2.5] {
-                        ctx.draw(&ratatui::widgets::canvas::Line {
-                            x1: worm_start_iso_x + thickness,
-                            y1: worm_start_iso_y,
-                            x2: worm_end_iso_x + thickness,
-                            y2: worm_end_iso_y,
-                            color: Color::DarkGray,
-                        });
-                    }
-                    
-                    // Draw threaded pattern on worm gear shaft
-                    let thread_segments = 8;
-                    for i in 0..thread_segments {
-                        let t = i as f64 / thread_segments as f64;
-                        let thread_x = worm_start_x + (worm_end_x - worm_start_x) * t;
-                        let thread_z = worm_start_z + (worm_end_z - worm_start_z) * t;
-                        let thread_offset = (i % 2) as f64 * 2.0 - 1.0; // Alternating offset for threads
-                        
-                        let (thread_iso_x, thread_iso_y) = to_isometric(thread_x, mid_height_3d + thread_offset, thread_z);
-                        ctx.draw(&ratatui::widgets::canvas::Circle {
-                            x: thread_iso_x,
-                            y: thread_iso_y,
-                            radius: 1.0,
-                            color: Color::Gray,
-                        });
-                    }
-                    
-                    // Draw diamond pivot points where struts meet (ball bearings) - larger
-                    for (px, py, color, radius) in [
-                        (mid_left_x, mid_left_y, Color::White, 4.5),
-                        (mid_right_x, mid_right_y, Color::White, 4.5),
-                    ] {
-                        ctx.draw(&ratatui::widgets::canvas::Circle {
-                            x: px,
-                            y: py,
-                            radius,
-                            color,
-                        });
-                    }
-                    
-                    // Draw square stepper motor mounted on the moving scissor assembly (moves with lift)
-                    let motor_3d_x = base_x_3d + diamond_offset_x * 1.2;
-                    let motor_3d_z = base_y_3d + diamond_offset_z * 1.2;
-                    let (motor_x, motor_y) = to_isometric(motor_3d_x, mid_height_3d, motor_3d_z);
-                    
-                    // Draw square motor housing (stepper motors are square, not circular)
-                    let motor_size = 8.0;  // Half-size for square motor
-                    let motor_corners = [
-                        (-motor_size, -motor_size),
-                        (motor_size, -motor_size),
-                        (motor_size, motor_size),
-                        (-motor_size, motor_size),
-                    ];
-                    
-                    // Draw square motor body
-                    for i in 0..4 {
-                        let (x1, y1) = motor_corners[i];
-                        let (x2, y2) = motor_corners[(i + 1) % 4];
-                        
-                        for thickness in [-2.0, -1.5, -1.0, -0.5, 0.0, 0.5, 1.0, 1.5, 2.0] {
-                            ctx.draw(&ratatui::widgets::canvas::Line {
-                                x1: motor_x + x1 + thickness,
-                                y1: motor_y + y1,
-                                x2: motor_x + x2 + thickness,
-                                y2: motor_y + y2,
-                                color: Color::Blue,
-                            });
-                        }
-                    }
-                    
-                    // Draw square motor housing outline
-                    let housing_size = motor_size + 2.0;
-                    let housing_corners = [
-                        (-housing_size, -housing_size),
-                        (housing_size, -housing_size),
-                        (housing_size, housing_size),
-                        (-housing_size, housing_size),
-                    ];
-                    
-                    for i in 0..4 {
-                        let (x1, y1) = housing_corners[i];
-                        let (x2, y2) = housing_corners[(i + 1) % 4];
-                        
-                        ctx.draw(&ratatui::widgets::canvas::Line {
-                            x1: motor_x + x1,
-                            y1: motor_y + y1,
-                            x2: motor_x + x2,
-                            y2: motor_y + y2,
-                            color: Color::DarkGray,
-                        });
-                    }
-                    
-                    // Draw motor connection to worm gear (horizontal drive shaft) - thicker
-                    for thickness in [-2.0, -1.5, -1.0, -0.5, 0.0, 0.5, 1.0, 1.5, 2.0] {
-                        ctx.draw(&ratatui::widgets::canvas::Line {
-                            x1: motor_x + thickness,
-                            y1: motor_y,
-                            x2: (worm_start_iso_x + worm_end_iso_x) / 2.0 + thickness,
-                            y2: (worm_start_iso_y + worm_end_iso_y) / 2.0,
-                            color: Color::DarkGray,
-                        });
-                    }
-                    
-                    // Draw mounting brackets for motor (attached to scissor assembly) - thicker
-                    let bracket_size = 6.0;  // Larger brackets for bigger motor
-                    for bracket_offset in [-bracket_size, bracket_size] {
-                        let bracket_3d_x = motor_3d_x + bracket_offset * perpendicular_angle.cos();
-                        let bracket_3d_z = motor_3d_z + bracket_offset * perpendicular_angle.sin();
-                        let (bracket_x, bracket_y) = to_isometric(bracket_3d_x, mid_height_3d, bracket_3d_z);
-                        
-                        for thickness in [-1.5, -1.0, -0.5, 0.0, 0.5, 1.0, 1.5] {
-                            ctx.draw(&ratatui::widgets::canvas::Line {
-                                x1: motor_x + thickness,
-                                y1: motor_y,
-                                x2: bracket_x + thickness,
-                                y2: bracket_y,
-                                color: Color::DarkGray,
-                            });
-                        }
-                    }
-                    
-                    // Draw connection points - single attachment points like real hardware (larger)
-                    // Bottom tip connection (fixed to base)
-                    ctx.draw(&ratatui::widgets::canvas::Circle {
-                        x: bottom_tip_x,
-                        y: bottom_tip_y,
-                        radius: 4.5,
-                        color: Color::Gray,
-                    });
-                    
-                    // Top tip connection (ball bearing to upper plate)
-                    ctx.draw(&ratatui::widgets::canvas::Circle {
-                        x: top_tip_x,
-                        y: top_tip_y,
-                        radius: 5.5,
-                        color: Color::LightBlue,
-                    });
-                    
-                    // Draw enhanced ball bearing detail at the top connection - larger
-                    // Main ball bearing housing
-                    ctx.draw(&ratatui::widgets::canvas::Circle {
-                        x: top_tip_x,
-                        y: top_tip_y,
-                        radius: 7.0,
-                        color: Color::White,
-                    });
-                    // Inner bearing race
-                    ctx.draw(&ratatui::widgets::canvas::Circle {
-                        x: top_tip_x,
-                        y: top_tip_y,
-                        radius: 3.5,
-                        color: Color::Gray,
-                    });
-                    
-                    // Label the actuators
-                    let _label = match i {
-                        0 => "A1",
-                        1 => "A2", 
-                        2 => "A3",
-                        _ => "",
-                    };
+                // Draw square motor body
+                for i in 0..4 {
+                    let (x1, y1) = motor_corners[i];
+                    let (x2, y2) = motor_corners[(i + 1) % 4];
+
+                    scene.thick_line((motor_x + x1, motor_y + y1), (motor_x + x2, motor_y + y2), 4.0, theme.color(Role::Motor));
+                }
+
+                // Draw square motor housing outline
+                let housing_size = motor_size + 2.0;
+                let housing_corners = [
+                    (-housing_size, -housing_size),
+                    (housing_size, -housing_size),
+                    (housing_size, housing_size),
+                    (-housing_size, housing_size),
+                ];
+
+                for i in 0..4 {
+                    let (x1, y1) = housing_corners[i];
+                    let (x2, y2) = housing_corners[(i + 1) % 4];
+
+                    scene.line((motor_x + x1, motor_y + y1), (motor_x + x2, motor_y + y2), theme.color(Role::BaseDetail));
+                }
+
+                // Draw motor connection to worm gear (horizontal drive shaft) - thicker
+                scene.thick_line((motor_x, motor_y), ((worm_start_iso_x + worm_end_iso_x) / 2.0, (worm_start_iso_y + worm_end_iso_y) / 2.0), 4.0, theme.color(Role::BaseDetail));
+
+                // Draw mounting brackets for motor (attached to scissor assembly) - thicker
+                let bracket_size = 6.0;  // Larger brackets for bigger motor
+                for bracket_offset in [-bracket_size, bracket_size] {
+                    let bracket_3d_x = motor_3d_x + bracket_offset * perpendicular_angle.cos();
+                    let bracket_3d_z = motor_3d_z + bracket_offset * perpendicular_angle.sin();
+                    let (bracket_x, bracket_y) = project(bracket_3d_x, mid_height_3d, bracket_3d_z);
+
+                    scene.thick_line((motor_x, motor_y), (bracket_x, bracket_y), 3.0, theme.color(Role::BaseDetail));
                 }
 
-                // Draw upper platform (circular plate like the real gimbal)
-                // First, calculate the average height and tilt of the upper plate
-                let avg_height = upper_plate_points.iter().map(|(_, _, h)| h).sum::<f64>() / upper_plate_points.len() as f64;
-                
-                // Draw the main circular upper plate
-                let upper_points = 32;
-                for i in 0..upper_points {
-                    let angle1 = i as f64 * 2.0 * std::f64::consts::PI / upper_points as f64;
-                    let angle2 = (i + 1) as f64 * 2.0 * std::f64::consts::PI / upper_points as f64;
-                    
-                    // Calculate height variation due to tilt
-                    let x1_3d = platform_radius * 0.9 * angle1.cos();
-                    let y1_3d = platform_radius * 0.9 * angle1.sin();
-                    let x2_3d = platform_radius * 0.9 * angle2.cos();
-                    let y2_3d = platform_radius * 0.9 * angle2.sin();
-                    
-                    // Apply tilt effects to height
+                // Draw connection points - single attachment points like real hardware (larger)
+                // Bottom tip connection (fixed to base)
+                scene.circle((bottom_tip_x, bottom_tip_y), 4.5, theme.color(Role::BasePlate));
+
+                // Top tip connection (ball bearing to upper plate)
+                scene.circle((top_tip_x, top_tip_y), 5.5, theme.color(Role::Joint));
+
+                // Draw enhanced ball bearing detail at the top connection - larger
+                // Main ball bearing housing
+                scene.circle((top_tip_x, top_tip_y), 7.0, theme.color(Role::Bearing));
+                // Inner bearing race
+                scene.circle((top_tip_x, top_tip_y), 3.5, theme.color(Role::Secondary));
+
+                // Label the actuators
+                let _label = match i {
+                    0 => "A1",
+                    1 => "A2", 
+                    2 => "A3",
+                    _ => "",
+                };
+                if let Some(marker) = self.leg_marker(extension) {
+                    scene.label((top_tip_x, top_tip_y), marker, leg_role(extension, alarm));
+                }
+            }
+
+            // Draw upper platform (circular plate like the real gimbal)
+            // First, calculate the average height and tilt of the upper plate
+            let avg_height = upper_plate_points.iter().map(|(_, _, h)| h).sum::<f64>() / upper_plate_points.len() as f64;
+
+            // Draw the main circular upper plate
+            let upper_points = 32;
+            for i in 0..upper_points {
+                let angle1 = i as f64 * 2.0 * std::f64::consts::PI / upper_points as f64;
+                let angle2 = (i + 1) as f64 * 2.0 * std::f64::consts::PI / upper_points as f64;
+
+                // Calculate height variation due to tilt
+                let x1_3d = platform_radius * 0.9 * angle1.cos();
+                let y1_3d = platform_radius * 0.9 * angle1.sin();
+                let x2_3d = platform_radius * 0.9 * angle2.cos();
+                let y2_3d = platform_radius * 0.9 * angle2.sin();
+
+                // Apply tilt effects to height, halved so the ring stays readable at steep angles
+                let h1 = avg_height + 0.5 * kinematics::tilt_offset(x1_3d, y1_3d, pitch_angle, roll_angle);
+                let h2 = avg_height + 0.5 * kinematics::tilt_offset(x2_3d, y2_3d, pitch_angle, roll_angle);
+
+                let (x1, y1) = project(x1_3d, h1, y1_3d);
+                let (x2, y2) = project(x2_3d, h2, y2_3d);
+
+                // Draw the upper plate edge with varying brightness based on height
+                let avg_edge_height = (h1 + h2) / 2.0;
+                let brightness = ((avg_edge_height - (nominal_height - 5.0)) / 15.0).clamp(0.0, 1.0);
+
+                let line_color = if brightness > 0.8 {
+                    theme.color(Role::UpperPlate)
+                } else if brightness > 0.5 {
+                    theme.color(Role::Secondary)
+                } else {
+                    theme.color(Role::Muted)
+                };
+
+                // Draw thick upper plate edge
+                scene.thick_line((x1, y1), (x2, y2), 3.0, line_color);
+            }
+
+            // Draw inner rings on upper plate for structural detail
+            for ring_factor in [0.7, 0.5] {
+                let ring_radius = platform_radius * 0.9 * ring_factor;
+                for i in 0..24 {
+                    let angle1 = i as f64 * 2.0 * std::f64::consts::PI / 24.0;
+                    let angle2 = (i + 1) as f64 * 2.0 * std::f64::consts::PI / 24.0;
+
+                    let x1_3d = ring_radius * angle1.cos();
+                    let y1_3d = ring_radius * angle1.sin();
+                    let x2_3d = ring_radius * angle2.cos();
+                    let y2_3d = ring_radius * angle2.sin();
+
+                    // Apply same tilt effects
                     let pitch_effect1 = (y1_3d / platform_radius) * pitch_angle.to_radians() * platform_radius * 0.5;
                     let roll_effect1 = (x1_3d / platform_radius) * roll_angle.to_radians() * platform_radius * 0.5;
                     let h1 = avg_height + pitch_effect1 + roll_effect1;
-                    
+
                     let pitch_effect2 = (y2_3d / platform_radius) * pitch_angle.to_radians() * platform_radius * 0.5;
                     let roll_effect2 = (x2_3d / platform_radius) * roll_angle.to_radians() * platform_radius * 0.5;
                     let h2 = avg_height + pitch_effect2 + roll_effect2;
-                    
-                    let (x1, y1) = to_isometric(x1_3d, h1, y1_3d);
-                    let (x2, y2) = to_isometric(x2_3d, h2, y2_3d);
-                    
-                    // Draw the upper plate edge with varying brightness based on height
-                    let avg_edge_height = (h1 + h2) / 2.0;
-                    let brightness = ((avg_edge_height - (nominal_height - 5.0)) / 15.0).clamp(0.0, 1.0);
-                    
-                    let line_color = if brightness > 0.8 {
-                        Color::White
-                    } else if brightness > 0.5 {
-                        Color::Gray
-                    } else {
-                        Color::DarkGray
-                    };
-                    
-                    // Draw thick upper plate edge
-                    for thickness in [-1.5, -1.0, -0.5, 0.0, 0.5, 1.0, 1.5] {
-                        ctx.draw(&ratatui::widgets::canvas::Line {
-                            x1: x1 + thickness, y1, x2: x2 + thickness, y2,
-                            color: line_color,
-                        });
-                    }
-                }
-                
-                // Draw connection lines from scissor tops to upper plate edge
-                for (upper_x, upper_y, _h) in &upper_plate_points {
-                    ctx.draw(&ratatui::widgets::canvas::Circle {
-                        x: *upper_x,
-                        y: *upper_y,
-                        radius: 4.0,
-                        color: Color::LightBlue,
-                    });
-                }
-                
-                // Draw inner rings on upper plate for structural detail
-                for ring_factor in [0.7, 0.5] {
-                    let ring_radius = platform_radius * 0.9 * ring_factor;
-                    for i in 0..24 {
-                        let angle1 = i as f64 * 2.0 * std::f64::consts::PI / 24.0;
-                        let angle2 = (i + 1) as f64 * 2.0 * std::f64::consts::PI / 24.0;
-                        
-                        let x1_3d = ring_radius * angle1.cos();
-                        let y1_3d = ring_radius * angle1.sin();
-                        let x2_3d = ring_radius * angle2.cos();
-                        let y2_3d = ring_radius * angle2.sin();
-                        
-                        // Apply same tilt effects
-                        let pitch_effect1 = (y1_3d / platform_radius) * pitch_angle.to_radians() * platform_radius * 0.5;
-                        let roll_effect1 = (x1_3d / platform_radius) * roll_angle.to_radians() * platform_radius * 0.5;
-                        let h1 = avg_height + pitch_effect1 + roll_effect1;
-                        
-                        let pitch_effect2 = (y2_3d / platform_radius) * pitch_angle.to_radians() * platform_radius * 0.5;
-                        let roll_effect2 = (x2_3d / platform_radius) * roll_angle.to_radians() * platform_radius * 0.5;
-                        let h2 = avg_height + pitch_effect2 + roll_effect2;
-                        
-                        let (x1, y1) = to_isometric(x1_3d, h1, y1_3d);
-                        let (x2, y2) = to_isometric(x2_3d, h2, y2_3d);
-                        
-                        ctx.draw(&ratatui::widgets::canvas::Line {
-                            x1, y1, x2, y2,
-                            color: Color::DarkGray,
-                        });
-                    }
-                }
 
-                // Draw center payload mount on upper plate (adjusted for squat design)
-                let center_height = avg_height + 
-                    (pitch_angle.to_radians() * 0.0) +  // Center doesn't move much for small tilts
-                    (roll_angle.to_radians() * 0.0);
-                    
-                // Main payload mounting ring
-                let ring_points = 16;
-                let mount_radius = 10.0;  // Slightly smaller for better proportions
-                for i in 0..ring_points {
-                    let angle1 = i as f64 * 2.0 * std::f64::consts::PI / ring_points as f64;
-                    let angle2 = (i + 1) as f64 * 2.0 * std::f64::consts::PI / ring_points as f64;
-                    
-                    let x1_3d = mount_radius * angle1.cos();
-                    let y1_3d = mount_radius * angle1.sin();
-                    let x2_3d = mount_radius * angle2.cos();
-                    let y2_3d = mount_radius * angle2.sin();
-                    
-                    let (x1, y1) = to_isometric(x1_3d, center_height + 2.0, y1_3d);  // Reduced height
-                    let (x2, y2) = to_isometric(x2_3d, center_height + 2.0, y2_3d);
-                    
-                    ctx.draw(&ratatui::widgets::canvas::Line {
-                        x1, y1, x2, y2,
-                        color: Color::LightCyan,
-                    });
-                }
-                
-                // Inner mounting ring
-                let inner_radius = 6.0;  // Proportionally smaller
-                for i in 0..ring_points {
-                    let angle1 = i as f64 * 2.0 * std::f64::consts::PI / ring_points as f64;
-                    let angle2 = (i + 1) as f64 * 2.0 * std::f64::consts::PI / ring_points as f64;
-                    
-                    let x1_3d = inner_radius * angle1.cos();
-                    let y1_3d = inner_radius * angle1.sin();
-                    let x2_3d = inner_radius * angle2.cos();
-                    let y2_3d = inner_radius * angle2.sin();
-                    
-                    let (x1, y1) = to_isometric(x1_3d, center_height + 2.0, y1_3d);
-                    let (x2, y2) = to_isometric(x2_3d, center_height + 2.0, y2_3d);
-                    
-                    ctx.draw(&ratatui::widgets::canvas::Line {
-                        x1, y1, x2, y2,
-                        color: Color::Cyan,
-                    });
-                }
-                
-                // Draw payload mounting bolt holes (3 bolts at 120° spacing)
-                let bolt_radius = 8.0;  // Proportionally smaller
-                for i in 0..3 {
-                    let angle = i as f64 * 2.0 * std::f64::consts::PI / 3.0; // 120° spacing
-                    let x_3d = bolt_radius * angle.cos();
-                    let y_3d = bolt_radius * angle.sin();
-                    let (bolt_x, bolt_y) = to_isometric(x_3d, center_height + 2.0, y_3d);
-                    
-                    ctx.draw(&ratatui::widgets::canvas::Circle {
-                        x: bolt_x,
-                        y: bolt_y,
-                        radius: 1.5,  // Smaller bolt holes
-                        color: Color::DarkGray,
-                    });
-                }
-
-                // Draw tilt visualization lines
-                let tilt_line_length = platform_radius * 0.6;
-                
-                // Roll tilt line (left-right axis)
-                let roll_tilt_height = roll_angle.to_radians() * tilt_line_length * 0.4;
-                let (tilt_left_x, tilt_left_y) = to_isometric(-tilt_line_length, center_height - roll_tilt_height, 0.0);
-                let (tilt_right_x, tilt_right_y) = to_isometric(tilt_line_length, center_height + roll_tilt_height, 0.0);
-                
-                for thickness in [-1.5, -1.0, -0.5, 0.0, 0.5, 1.0, 1.5] {
-                    ctx.draw(&ratatui::widgets::canvas::Line {
-                        x1: tilt_left_x + thickness,
-                        y1: tilt_left_y,
-                        x2: tilt_right_x + thickness,
-                        y2: tilt_right_y,
-                        color: Color::Magenta,
-                    });
-                }
-                
-                // Pitch tilt line (forward-back axis)
-                let pitch_tilt_height = pitch_angle.to_radians() * tilt_line_length * 0.4;
-                let (tilt_front_x, tilt_front_y) = to_isometric(0.0, center_height - pitch_tilt_height, -tilt_line_length);
-                let (tilt_back_x, tilt_back_y) = to_isometric(0.0, center_height + pitch_tilt_height, tilt_line_length);
-                
-                for thickness in [-1.5, -1.0, -0.5, 0.0, 0.5, 1.0, 1.5] {
-                    ctx.draw(&ratatui::widgets::canvas::Line {
-                        x1: tilt_front_x + thickness,
-                        y1: tilt_front_y,
-                        x2: tilt_back_x + thickness,
-                        y2: tilt_back_y,
-                        color: Color::Cyan,
-                    });
-                }
-
-                // Draw coordinate system reference
-                let coord_origin_3d = (-130.0, -70.0, 0.0);
-                let (coord_x, coord_y) = to_isometric(coord_origin_3d.0, coord_origin_3d.1, coord_origin_3d.2);
-                
-                // X-axis (Roll) - Red
-                let (x_end_x, x_end_y) = to_isometric(coord_origin_3d.0 + 25.0, coord_origin_3d.1, coord_origin_3d.2);
-                for thickness in [-1.0, 0.0, 1.0] {
-                    ctx.draw(&ratatui::widgets::canvas::Line {
-                        x1: coord_x + thickness, y1: coord_y, x2: x_end_x + thickness, y2: x_end_y,
-                        color: Color::Red,
-                    });
-                }
-                
-                // Y-axis (Height) - Green  
-                let (y_end_x, y_end_y) = to_isometric(coord_origin_3d.0, coord_origin_3d.1 + 25.0, coord_origin_3d.2);
-                for thickness in [-1.0, 0.0, 1.0] {
-                    ctx.draw(&ratatui::widgets::canvas::Line {
-                        x1: coord_x + thickness, y1: coord_y, x2: y_end_x + thickness, y2: y_end_y,
-                        color: Color::Green,
-                    });
-                }
-                
-                // Z-axis (Pitch) - Blue
-                let (z_end_x, z_end_y) = to_isometric(coord_origin_3d.0, coord_origin_3d.1, coord_origin_3d.2 + 25.0);
-                for thickness in [-1.0, 0.0, 1.0] {
-                    ctx.draw(&ratatui::widgets::canvas::Line {
-                        x1: coord_x + thickness, y1: coord_y, x2: z_end_x + thickness, y2: z_end_y,
-                        color: Color::Blue,
-                    });
-                }
-
-                // Status indicators
-                let tilt_magnitude = (pitch_angle.powi(2) + roll_angle.powi(2)).sqrt();
-                if tilt_magnitude > 1.0 {
-                    // Tilt warning indicator
-                    let (warning_x, warning_y) = to_isometric(110.0, 70.0, 15.0);
-                    ctx.draw(&ratatui::widgets::canvas::Circle {
-                        x: warning_x,
-                        y: warning_y,
-                        radius: 6.0,
-                        color: Color::Red,
-                    });
-                    
-                    // Draw angle magnitude as visual bar
-                    let bar_length = (tilt_magnitude * 2.0).min(25.0);
-                    let (bar_start_x, bar_start_y) = to_isometric(110.0 - bar_length / 2.0, 60.0, 15.0);
-                    let (bar_end_x, bar_end_y) = to_isometric(110.0 + bar_length / 2.0, 60.0, 15.0);
-                    for thickness in [-1.0, 0.0, 1.0] {
-                        ctx.draw(&ratatui::widgets::canvas::Line {
-                            x1: bar_start_x + thickness,
-                            y1: bar_start_y,
-                            x2: bar_end_x + thickness,
-                            y2: bar_end_y,
-                            color: Color::Red,
-                        });
-                    }
-                }
-                
-                if base_lift.abs() > 1.0 {
-                    // Height change indicator
-                    let (height_ind_x, height_ind_y) = to_isometric(110.0, 45.0, 0.0);
-                    ctx.draw(&ratatui::widgets::canvas::Circle {
-                        x: height_ind_x,
-                        y: height_ind_y,
-                        radius: 6.0,
-                        color: if base_lift > 0.0 { Color::LightGreen } else { Color::LightRed },
-                    });
-                    
-                    // Draw height as visual bar
-                    let height_bar = (base_lift.abs() * 1.5).min(20.0);
-                    let bar_end_height = if base_lift > 0.0 { 45.0 + height_bar } else { 45.0 - height_bar };
-                    let (height_bar_end_x, height_bar_end_y) = to_isometric(110.0, bar_end_height, 0.0);
-                    
-                    for thickness in [-1.0, 0.0, 1.0] {
-                        ctx.draw(&ratatui::widgets::canvas::Line {
-                            x1: height_ind_x + thickness,
-                            y1: height_ind_y,
-                            x2: height_bar_end_x + thickness,
-                            y2: height_bar_end_y,
-                            color: if base_lift > 0.0 { Color::LightGreen } else { Color::LightRed },
-                        });
-                    }
-                }
-                
-                // Draw real-time angle readouts as position indicators
-                if tilt_magnitude > 0.3 {
-                    let angle_indicator_radius = platform_radius * 1.1;
-                    
-                    // Roll angle indicator
-                    let (roll_ind_x, roll_ind_y) = to_isometric(roll_angle * 2.5, angle_indicator_radius, 0.0);
-                    ctx.draw(&ratatui::widgets::canvas::Circle {
-                        x: roll_ind_x,
-                        y: roll_ind_y,
-                        radius: 3.0,
-                        color: Color::Magenta,
-                    });
-                    
-                    // Pitch angle indicator  
-                    let (pitch_ind_x, pitch_ind_y) = to_isometric(0.0, angle_indicator_radius, pitch_angle * 2.5);
-                    ctx.draw(&ratatui::widgets::canvas::Circle {
-                        x: pitch_ind_x,
-                        y: pitch_ind_y,
-                        radius: 3.0,
-                        color: Color::Cyan,
-                    });
+                    let (x1, y1) = project(x1_3d, h1, y1_3d);
+                    let (x2, y2) = project(x2_3d, h2, y2_3d);
+
+                    scene.line((x1, y1), (x2, y2), theme.color(Role::BaseDetail));
                 }
-            })
-            .x_bounds([-180.0, 180.0])  // Optimized bounds for better view
-            .y_bounds([-100.0, 100.0]);
-        frame.render_widget(gimbal_canvas, area);
+            }
+
+            // Joints where the scissor tops meet the upper plate
+            for &(x, y, _) in &upper_plate_points {
+                scene.circle((x, y), 4.0, theme.color(Role::Joint));
+            }
+            avg_height
+        };
+
+        // Draw center payload mount on upper plate (adjusted for squat design)
+        let center_height = avg_height + 
+            (pitch_angle.to_radians() * 0.0) +  // Center doesn't move much for small tilts
+            (roll_angle.to_radians() * 0.0);
+
+        // Main payload mounting ring
+        let ring_points = 16;
+        let mount_radius = 10.0;  // Slightly smaller for better proportions
+        for i in 0..ring_points {
+            let angle1 = i as f64 * 2.0 * std::f64::consts::PI / ring_points as f64 + yaw_rad;
+            let angle2 = (i + 1) as f64 * 2.0 * std::f64::consts::PI / ring_points as f64 + yaw_rad;
+
+            let x1_3d = mount_radius * angle1.cos();
+            let y1_3d = mount_radius * angle1.sin();
+            let x2_3d = mount_radius * angle2.cos();
+            let y2_3d = mount_radius * angle2.sin();
+
+            let (x1, y1) = project(x1_3d, center_height + 2.0, y1_3d);  // Reduced height
+            let (x2, y2) = project(x2_3d, center_height + 2.0, y2_3d);
+
+            scene.line((x1, y1), (x2, y2), theme.color(Role::Payload));
+        }
+
+        // Inner mounting ring
+        let inner_radius = 6.0;  // Proportionally smaller
+        for i in 0..ring_points {
+            let angle1 = i as f64 * 2.0 * std::f64::consts::PI / ring_points as f64 + yaw_rad;
+            let angle2 = (i + 1) as f64 * 2.0 * std::f64::consts::PI / ring_points as f64 + yaw_rad;
+
+            let x1_3d = inner_radius * angle1.cos();
+            let y1_3d = inner_radius * angle1.sin();
+            let x2_3d = inner_radius * angle2.cos();
+            let y2_3d = inner_radius * angle2.sin();
+
+            let (x1, y1) = project(x1_3d, center_height + 2.0, y1_3d);
+            let (x2, y2) = project(x2_3d, center_height + 2.0, y2_3d);
+
+            scene.line((x1, y1), (x2, y2), theme.color(Role::Payload));
+        }
+
+        // Draw payload mounting bolt holes (3 bolts at 120° spacing)
+        let bolt_radius = 8.0;  // Proportionally smaller
+        for i in 0..3 {
+            let angle = i as f64 * 2.0 * std::f64::consts::PI / 3.0 + yaw_rad; // 120° spacing
+            let x_3d = bolt_radius * angle.cos();
+            let y_3d = bolt_radius * angle.sin();
+            let (bolt_x, bolt_y) = project(x_3d, center_height + 2.0, y_3d);
+
+            scene.circle((bolt_x, bolt_y), 1.5, theme.color(Role::Muted));
+        }
+
+        // Draw tilt visualization lines, each labeled at its raised end with the live angle
+        if self.tilt_lines {
+            let tilt_line_length = platform_radius * 0.6;
+
+            // Roll tilt line (left-right axis)
+            let roll_tilt_height = roll_angle.to_radians() * tilt_line_length * 0.4;
+            let (tilt_left_x, tilt_left_y) = project(-tilt_line_length, center_height - roll_tilt_height, 0.0);
+            let (tilt_right_x, tilt_right_y) = project(tilt_line_length, center_height + roll_tilt_height, 0.0);
+
+            scene.thick_line((tilt_left_x, tilt_left_y), (tilt_right_x, tilt_right_y), 3.0, theme.color(Role::TiltLineRoll));
+
+            // Pitch tilt line (forward-back axis)
+            let pitch_tilt_height = pitch_angle.to_radians() * tilt_line_length * 0.4;
+            let (tilt_front_x, tilt_front_y) = project(0.0, center_height - pitch_tilt_height, -tilt_line_length);
+            let (tilt_back_x, tilt_back_y) = project(0.0, center_height + pitch_tilt_height, tilt_line_length);
+
+            scene.thick_line((tilt_front_x, tilt_front_y), (tilt_back_x, tilt_back_y), 3.0, theme.color(Role::TiltLinePitch));
+
+            let (roll_label_x, roll_label_y) = project(tilt_line_length * 1.15, center_height + roll_tilt_height, 0.0);
+            scene.label((roll_label_x, roll_label_y), &format!("R {:+.1}°", roll_angle), Role::TiltLineRoll);
+            let (pitch_label_x, pitch_label_y) = project(0.0, center_height + pitch_tilt_height, tilt_line_length * 1.15);
+            scene.label((pitch_label_x, pitch_label_y), &format!("P {:+.1}°", pitch_angle), Role::TiltLinePitch);
+        }
+
+        // Status indicators
+        let tilt_magnitude = (pitch_angle.powi(2) + roll_angle.powi(2)).sqrt();
+        if tilt_magnitude > 1.0 {
+            // Tilt warning indicator
+            let (warning_x, warning_y) = project(110.0, 70.0, 15.0);
+            scene.circle((warning_x, warning_y), 6.0, theme.color(Role::Error));
+
+            // Draw angle magnitude as visual bar
+            let bar_length = (tilt_magnitude * 2.0).min(25.0);
+            let (bar_start_x, bar_start_y) = project(110.0 - bar_length / 2.0, 60.0, 15.0);
+            let (bar_end_x, bar_end_y) = project(110.0 + bar_length / 2.0, 60.0, 15.0);
+            scene.thick_line((bar_start_x, bar_start_y), (bar_end_x, bar_end_y), 2.0, theme.color(Role::Error));
+        }
+
+        if base_lift.abs() > 1.0 {
+            // Height change indicator
+            let (height_ind_x, height_ind_y) = project(110.0, 45.0, 0.0);
+            scene.circle((height_ind_x, height_ind_y), 6.0, if base_lift > 0.0 { theme.color(Role::ActuatorExtended) } else { theme.color(Role::ActuatorRetracted) });
+
+            // Draw height as visual bar
+            let height_bar = (base_lift.abs() * 1.5).min(20.0);
+            let bar_end_height = if base_lift > 0.0 { 45.0 + height_bar } else { 45.0 - height_bar };
+            let (height_bar_end_x, height_bar_end_y) = project(110.0, bar_end_height, 0.0);
+
+            scene.thick_line((height_ind_x, height_ind_y), (height_bar_end_x, height_bar_end_y), 2.0, if base_lift > 0.0 { theme.color(Role::ActuatorExtended) } else { theme.color(Role::ActuatorRetracted) });
+        }
+
+        // Draw real-time angle readouts as position indicators
+        if tilt_magnitude > 0.3 {
+            let angle_indicator_radius = platform_radius * 1.1;
+
+            // Roll angle indicator
+            let (roll_ind_x, roll_ind_y) = project(roll_angle * 2.5, angle_indicator_radius, 0.0);
+            scene.circle((roll_ind_x, roll_ind_y), 3.0, theme.color(Role::TiltLineRoll));
+
+            // Pitch angle indicator  
+            let (pitch_ind_x, pitch_ind_y) = project(0.0, angle_indicator_radius, pitch_angle * 2.5);
+            scene.circle((pitch_ind_x, pitch_ind_y), 3.0, theme.color(Role::TiltLinePitch));
+        }
+
+        if projection == Projection::Side {
+            let left = platform_radius * 1.25;
+            self.paint_extension_bars(&mut scene, &actuators, &limits, &state.limiting_actuators, left);
+        }
+
+        // Where the hardware reports the plate to be, as a ghost outline in its own color
+        let measured_tops: Vec<(f64, f64, f64)> = match self.measured_state() {
+            Some(measured) => kinematics::solve(geometry, measured).iter().map(|a| a.top).collect(),
+            None => Vec::new(),
+        };
+        for (tops, role) in [(&commanded_tops, Role::UpperPlate), (&measured_tops, Role::Feedback)] {
+            for (i, &(x1, y1, z1)) in tops.iter().enumerate() {
+                let (x2, y2, z2) = tops[(i + 1) % tops.len()];
+                let (x1, y1) = project(x1, z1, y1);
+                let (x2, y2) = project(x2, z2, y2);
+                scene.line((x1, y1), (x2, y2), theme.color(role));
+            }
+        }
+        scene
+    }
+
+    // Six straight legs between base and platform joints, with the platform drawn
+    // as the polygon through its joints so it tilts exactly as the kinematics say
+    fn paint_stewart(
+        &self,
+        scene: &mut Scene,
+        projection: Projection,
+        actuators: &[Actuator],
+        limits: &LegLimits,
+        limiting: &[usize],
+    ) {
+        let theme = &self.theme;
+        let project = |x, y, z| projection.project(x, y, z);
+        for (i, actuator) in actuators.iter().enumerate() {
+            let (base_x, base_y) = project(actuator.base.0, actuator.base.2, actuator.base.1);
+            let (top_x, top_y) = project(actuator.top.0, actuator.top.2, actuator.top.1);
+
+            let role = leg_role(actuator.extension, actuator.over_travel(limits) || limiting.contains(&i));
+            scene.thick_line((base_x, base_y), (top_x, top_y), 3.0, theme.color(role));
+            if let Some(marker) = self.leg_marker(actuator.extension) {
+                scene.label((top_x, top_y), marker, role);
+            }
+
+            // Universal joint at the base, ball joint at the platform
+            scene.circle((base_x, base_y), 4.0, theme.color(Role::BasePlate));
+            scene.circle((top_x, top_y), 4.0, theme.color(Role::Joint));
+        }
+
+        // Platform outline through the joints in angular order
+        let mut tops: Vec<_> = actuators.iter().map(|a| a.top).collect();
+        tops.sort_by(|a, b| a.1.atan2(a.0).total_cmp(&b.1.atan2(b.0)));
+        for (i, top) in tops.iter().enumerate() {
+            let next = tops[(i + 1) % tops.len()];
+            let (x1, y1) = project(top.0, top.2, top.1);
+            let (x2, y2) = project(next.0, next.2, next.1);
+            scene.thick_line((x1, y1), (x2, y2), 2.0, theme.color(Role::UpperPlate));
+        }
+    }
+
+    // Side view: one bar per actuator from neutral to its extension, over a track
+    // spanning the leg's travel, in a row starting at `left`
+    fn paint_extension_bars(
+        &self,
+        scene: &mut Scene,
+        actuators: &[Actuator],
+        limits: &LegLimits,
+        limiting: &[usize],
+        left: f64,
+    ) {
+        const SPACING: f64 = 10.0;
+        let theme = &self.theme;
+        for (i, actuator) in actuators.iter().enumerate() {
+            let x = left + i as f64 * SPACING;
+            let (low, high) = limits.extension_range(actuator);
+            scene.line((x, low), (x, high), theme.color(Role::BaseDetail));
+        }
+        for (i, actuator) in actuators.iter().enumerate() {
+            let x = left + i as f64 * SPACING;
+            let (low, high) = limits.extension_range(actuator);
+            let top = actuator.extension.clamp(low.min(0.0) * 1.2, high.max(0.0) * 1.2);
+            let role = leg_role(actuator.extension, actuator.over_travel(limits) || limiting.contains(&i));
+            scene.thick_line((x, 0.0), (x, top), 3.0, theme.color(role));
+        }
+    }
+
+    // ▲/▼ for extended/retracted legs when the theme asks for markers; none near neutral
+    fn leg_marker(&self, extension: f64) -> Option<&'static str> {
+        if !self.theme.markers() {
+            None
+        } else if extension > 3.0 {
+            Some(self.glyph("▲", "^"))
+        } else if extension < -3.0 {
+            Some(self.glyph("▼", "v"))
+        } else {
+            None
+        }
+    }
+}
+
+// Legs past their stroke or holding back the envelope (`alarm`) are errors; otherwise by extension
+fn leg_role(extension: f64, alarm: bool) -> Role {
+    if alarm {
+        Role::Error
+    } else if extension > 3.0 {
+        Role::ActuatorExtended
+    } else if extension < -3.0 {
+        Role::ActuatorRetracted
+    } else {
+        Role::ActuatorNeutral
+    }
+}
+
+fn severity_role(severity: Severity) -> Role {
+    match severity {
+        Severity::Info => Role::Header,
+        Severity::Warn => Role::Warning,
+        Severity::Error => Role::Error,
+    }
+}
+
+fn migration_message(migration: &Migration, path: &Path) -> (Severity, String) {
+    match &migration.save_error {
+        None => (
+            Severity::Info,
+            format!("Config {} upgraded from version {} to {}", path.display(), migration.from, CONFIG_VERSION),
+        ),
+        Some(e) => (
+            Severity::Warn,
+            format!("Config upgraded from version {} to {} but not saved to {}: {}", migration.from, CONFIG_VERSION, path.display(), e),
+        ),
+    }
+}
+
+// Bindings as shown to the user; a space would otherwise be invisible
+fn key_label(key: char) -> String {
+    if key == ' ' { "Space".to_string() } else { key.to_string() }
+}
+
+// "±20.0" for a symmetric range, "-10.0..+20.0" for pitch_min/pitch_max style travel
+fn range_label((min, max): (f64, f64), decimals: usize) -> String {
+    if min == -max {
+        format!("±{:.*}", decimals, max)
+    } else {
+        format!("{:.*}..{:+.*}", decimals, min, decimals, max)
     }
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = match Args::parse() {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
+        }
+    };
+    if args.help {
+        println!("{}", Args::usage());
+        return Ok(());
+    }
+
+    // Create the app before taking over the terminal so config errors print normally
+    let mut app = match App::new(&args) {
+        Ok(app) => app,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    println!("Config loaded from {}. Debug mode: {}", app.config_path.display(), app.debug_mode);
+    if let Some(path) = &args.export_frame {
+        if let Err(e) = app.export_frame(path) {
+            eprintln!("Failed to export {}: {}", path.display(), e);
+            std::process::exit(1);
+        }
+        println!("Saved snapshot to {}", path.display());
+        return Ok(());
+    }
+
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = stdout();
@@ -902,48 +5059,81 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // Create app
-    let mut app = App::new()?;
-    println!("Config loaded. Debug mode: {}", app.debug_mode);
+    // Mouse capture is only taken when mouse mode is on, so the terminal keeps normal selection otherwise
+    let mouse_capture = app.config.controls.mouse_enabled;
+    if mouse_capture {
+        execute!(terminal.backend_mut(), EnableMouseCapture)?;
+    }
 
     // Main loop
-    let tick_rate = Duration::from_millis(16); // ~60 FPS
-    let mut last_tick = Instant::now();
+    // Input is handled after every batch of terminal events and at least once per update step,
+    // so gamepad events don't wait for a frame. Redraws are throttled to render_rate_hz and
+    // skipped while nothing changes. Both rates are read on every pass.
+    // None forces a redraw on the next pass
+    let mut last_draw: Option<Instant> = None;
 
     while app.running {
-        let timeout = tick_rate
-            .checked_sub(last_tick.elapsed())
-            .unwrap_or_else(|| Duration::from_secs(0));
+        let redraw_after = if app.needs_redraw { app.render_interval() } else { IDLE_REDRAW };
+        let until_draw = last_draw.map_or(Duration::ZERO, |at| redraw_after.saturating_sub(at.elapsed()));
+        let mut timeout = app.until_next_step().min(until_draw);
 
-        if crossterm::event::poll(timeout)? {
-            if let CrosstermEvent::Key(key) = event::read()? {
-                match key.kind {
+        // Drain everything already queued before updating, not one event per pass
+        while crossterm::event::poll(timeout)? {
+            timeout = Duration::ZERO;
+            app.timing.count_event();
+            app.needs_redraw = true;
+            match event::read()? {
+                CrosstermEvent::Key(key) => match key.kind {
                     KeyEventKind::Press => {
                         app.handle_key(key.code);
                     }
+                    KeyEventKind::Repeat => {
+                        // Held movement keys only; app actions fire once per press
+                        app.handle_key_repeat(key.code);
+                    }
                     KeyEventKind::Release => {
-                        // Handle key release for WASD movement
-                        if let KeyCode::Char(c) = key.code {
-                            app.gimbal_controller.handle_keyboard(&mut app.input_state, c, false);
-                        }
+                        // Movement keys (letters or arrows) and the re-arm hold
+                        app.handle_key_release(key.code);
                     }
-                    _ => {}
+                },
+                CrosstermEvent::Mouse(mouse) => app.handle_mouse(mouse),
+                // Clear so nothing from the old size survives; the next draw re-fits the layout
+                CrosstermEvent::Resize(_, _) => {
+                    terminal.clear()?;
+                    last_draw = None;
                 }
+                _ => {}
             }
         }
 
-        if last_tick.elapsed() >= tick_rate {
-            app.update();
-            last_tick = Instant::now();
-        }
+        // update() runs as many fixed steps as the time since the last call covers
+        let update_started = Instant::now();
+        app.update();
+        app.timing.record_update(update_started);
 
-        terminal.draw(|f| app.draw(f))?;
+        let redraw_after = if app.needs_redraw { app.render_interval() } else { IDLE_REDRAW };
+        if last_draw.is_none_or(|at| at.elapsed() >= redraw_after) {
+            let draw_started = Instant::now();
+            terminal.draw(|f| app.draw(f))?;
+            app.timing.record_draw(draw_started);
+            app.needs_redraw = false;
+            last_draw = Some(Instant::now());
+        }
     }
 
     // Restore terminal
+    if mouse_capture {
+        execute!(terminal.backend_mut(), DisableMouseCapture)?;
+    }
     disable_raw_mode()?;
     execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
     terminal.show_cursor()?;
 
+    if (app.config.app.restore_last_state || app.config.app.restore_last_pose)
+        && let Err(e) = app.save_last_state()
+    {
+        eprintln!("Failed to save the gimbal state: {}", e);
+    }
+
     Ok(())
 }
\ No newline at end of file
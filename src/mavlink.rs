@@ -0,0 +1,223 @@
+use crate::config::{MavlinkMessage, MavlinkOutputConfig};
+use crate::gimbal::GimbalState;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+
+const STX_V2: u8 = 0xFD;
+
+// Message IDs and the CRC_EXTRA seed each one's checksum ends with, from common.xml
+const GIMBAL_DEVICE_SET_ATTITUDE: (u32, u8) = (284, 99);
+const COMMAND_LONG: (u32, u8) = (76, 152);
+
+// GIMBAL_DEVICE_FLAGS_ROLL_LOCK | GIMBAL_DEVICE_FLAGS_PITCH_LOCK: roll and pitch are held
+// relative to the horizon, yaw follows the vehicle
+const ATTITUDE_FLAGS: u16 = 4 | 8;
+const MAV_CMD_DO_MOUNT_CONTROL: u16 = 205;
+const MAV_MOUNT_MODE_MAVLINK_TARGETING: f32 = 2.0;
+
+// Attitude quaternion (w, x, y, z) from angles in degrees, applied yaw, then pitch, then roll
+// (aerospace Z-Y-X order), as MAVLink's attitude fields expect
+pub fn euler_to_quaternion(roll: f64, pitch: f64, yaw: f64) -> [f32; 4] {
+    let (sr, cr) = (roll.to_radians() / 2.0).sin_cos();
+    let (sp, cp) = (pitch.to_radians() / 2.0).sin_cos();
+    let (sy, cy) = (yaw.to_radians() / 2.0).sin_cos();
+    [
+        (cr * cp * cy + sr * sp * sy) as f32,
+        (sr * cp * cy - cr * sp * sy) as f32,
+        (cr * sp * cy + sr * cp * sy) as f32,
+        (cr * cp * sy - sr * sp * cy) as f32,
+    ]
+}
+
+// CRC-16/MCRF4XX (the "X.25" checksum MAVLink uses), folded in one byte at a time
+fn crc_accumulate(crc: u16, byte: u8) -> u16 {
+    let mut tmp = byte ^ (crc & 0xFF) as u8;
+    tmp ^= tmp << 4;
+    let tmp = tmp as u16;
+    (crc >> 8) ^ (tmp << 8) ^ (tmp << 3) ^ (tmp >> 4)
+}
+
+// A complete MAVLink 2 frame: header, payload with trailing zeros trimmed, checksum
+fn encode_frame(seq: u8, system_id: u8, component_id: u8, (msg_id, crc_extra): (u32, u8), payload: &[u8]) -> Vec<u8> {
+    // MAVLink 2 drops trailing zero bytes but always keeps the first one
+    let len = payload.iter().rposition(|&b| b != 0).map_or(1, |i| i + 1);
+    let mut frame = Vec::with_capacity(12 + len);
+    frame.extend_from_slice(&[STX_V2, len as u8, 0, 0, seq, system_id, component_id]);
+    frame.extend_from_slice(&msg_id.to_le_bytes()[..3]);
+    frame.extend_from_slice(&payload[..len]);
+    let crc = frame[1..].iter().chain([crc_extra].iter()).fold(0xFFFF, |crc, &b| crc_accumulate(crc, b));
+    frame.extend_from_slice(&crc.to_le_bytes());
+    frame
+}
+
+// Fields go on the wire largest type first, not in the order common.xml lists them
+fn attitude_payload(config: &MavlinkOutputConfig, state: &GimbalState) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(32);
+    for q in euler_to_quaternion(state.roll, state.pitch, state.yaw) {
+        payload.extend_from_slice(&q.to_le_bytes());
+    }
+    // Angular velocities: NaN means "not set", leaving the gimbal to pick its own rate
+    for _ in 0..3 {
+        payload.extend_from_slice(&f32::NAN.to_le_bytes());
+    }
+    payload.extend_from_slice(&ATTITUDE_FLAGS.to_le_bytes());
+    payload.extend_from_slice(&[config.target_system, config.target_component]);
+    payload
+}
+
+fn mount_control_payload(config: &MavlinkOutputConfig, state: &GimbalState) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(33);
+    // param1..param7: pitch, roll, yaw in degrees, three reserved, then the mount mode
+    let params = [state.pitch as f32, state.roll as f32, state.yaw as f32, 0.0, 0.0, 0.0, MAV_MOUNT_MODE_MAVLINK_TARGETING];
+    for param in params {
+        payload.extend_from_slice(&param.to_le_bytes());
+    }
+    payload.extend_from_slice(&MAV_CMD_DO_MOUNT_CONTROL.to_le_bytes());
+    payload.extend_from_slice(&[config.target_system, config.target_component, 0]);
+    payload
+}
+
+// "udpout:host:port" (or a bare "host:port") sends to that address; anything else is refused
+fn parse_connection(connection: &str) -> Result<SocketAddr, Box<dyn std::error::Error>> {
+    let address = match connection.split_once(':') {
+        Some(("udpout", address)) => address,
+        Some(("udp" | "udpin", _)) => return Err(format!("'{}': listening connections are not supported, use udpout:<host>:<port>", connection).into()),
+        Some(("serial" | "tcp" | "tcpout", _)) => return Err(format!("'{}': only udpout:<host>:<port> is supported; bridge serial links with e.g. mavproxy", connection).into()),
+        _ if connection.starts_with('/') || connection.starts_with("COM") => {
+            return Err(format!("'{}': serial ports are not supported; bridge them to UDP with e.g. mavproxy", connection).into());
+        }
+        _ => connection,
+    };
+    Ok(address
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| format!("Could not resolve {}", address))?)
+}
+
+// Sends the gimbal attitude as MAVLink 2 messages at a fixed rate, like UdpOutput
+pub struct MavlinkOutput {
+    socket: UdpSocket,
+    target: SocketAddr,
+    config: MavlinkOutputConfig,
+    interval: Duration,
+    last_send: Option<Instant>,
+    sequence: u8,
+    pub packets_sent: u64,
+    pub last_error: Option<String>,
+}
+
+impl MavlinkOutput {
+    pub fn open(config: &MavlinkOutputConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let target = parse_connection(&config.connection)?;
+        let bind_address = if target.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+        let socket = UdpSocket::bind(bind_address)?;
+        socket.set_nonblocking(true)?;
+
+        Ok(Self {
+            socket,
+            target,
+            config: config.clone(),
+            interval: Duration::from_secs_f64(1.0 / config.rate_hz.max(0.1)),
+            last_send: None,
+            sequence: 0,
+            packets_sent: 0,
+            last_error: None,
+        })
+    }
+
+    pub fn target(&self) -> SocketAddr {
+        self.target
+    }
+
+    pub fn send(&mut self, state: &GimbalState) {
+        if self.last_send.is_some_and(|last| last.elapsed() < self.interval) {
+            return;
+        }
+        self.last_send = Some(Instant::now());
+
+        let (message, payload) = match self.config.message {
+            MavlinkMessage::GimbalDeviceSetAttitude => (GIMBAL_DEVICE_SET_ATTITUDE, attitude_payload(&self.config, state)),
+            MavlinkMessage::MountControl => (COMMAND_LONG, mount_control_payload(&self.config, state)),
+        };
+        let frame = encode_frame(self.sequence, self.config.system_id, self.config.component_id, message, &payload);
+        // The MAVLink sequence number is a single byte and wraps
+        self.sequence = self.sequence.wrapping_add(1);
+
+        match self.socket.send_to(&frame, self.target) {
+            Ok(_) => {
+                self.packets_sent += 1;
+                self.last_error = None;
+            }
+            Err(e) => self.last_error = Some(format!("MAVLink send failed: {}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Quaternions go out as f32
+    fn assert_quaternion(actual: [f32; 4], expected: [f64; 4]) {
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((*a as f64 - e).abs() < 1e-6, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    // Hamilton product, for building the expected rotation one axis at a time
+    fn multiply(a: [f64; 4], b: [f64; 4]) -> [f64; 4] {
+        [
+            a[0] * b[0] - a[1] * b[1] - a[2] * b[2] - a[3] * b[3],
+            a[0] * b[1] + a[1] * b[0] + a[2] * b[3] - a[3] * b[2],
+            a[0] * b[2] - a[1] * b[3] + a[2] * b[0] + a[3] * b[1],
+            a[0] * b[3] + a[1] * b[2] - a[2] * b[1] + a[3] * b[0],
+        ]
+    }
+
+    fn about(axis: usize, degrees: f64) -> [f64; 4] {
+        let (s, c) = (degrees.to_radians() / 2.0).sin_cos();
+        let mut q = [c, 0.0, 0.0, 0.0];
+        q[axis] = s;
+        q
+    }
+
+    #[test]
+    fn level_is_the_identity_quaternion() {
+        assert_quaternion(euler_to_quaternion(0.0, 0.0, 0.0), [1.0, 0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn quarter_turns_rotate_about_their_own_axis() {
+        let half = std::f64::consts::FRAC_1_SQRT_2;
+        assert_quaternion(euler_to_quaternion(0.0, 90.0, 0.0), [half, 0.0, half, 0.0]);
+        assert_quaternion(euler_to_quaternion(0.0, -90.0, 0.0), [half, 0.0, -half, 0.0]);
+        assert_quaternion(euler_to_quaternion(0.0, 0.0, 90.0), [half, 0.0, 0.0, half]);
+        assert_quaternion(euler_to_quaternion(90.0, 0.0, 0.0), [half, half, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn mixed_angles_apply_yaw_then_pitch_then_roll() {
+        let expected = multiply(multiply(about(3, 10.0), about(2, 20.0)), about(1, 30.0));
+        assert_quaternion(euler_to_quaternion(30.0, 20.0, 10.0), expected);
+    }
+
+    #[test]
+    fn checksum_matches_the_crc16_mcrf4xx_check_value() {
+        let crc = b"123456789".iter().fold(0xFFFF, |crc, &b| crc_accumulate(crc, b));
+        assert_eq!(crc, 0x6F91);
+    }
+
+    #[test]
+    fn frame_trims_trailing_zeros_and_ends_with_the_checksum() {
+        let frame = encode_frame(7, 1, 154, GIMBAL_DEVICE_SET_ATTITUDE, &[5, 0, 6, 0, 0]);
+        assert_eq!(&frame[..13], &[STX_V2, 3, 0, 0, 7, 1, 154, 28, 1, 0, 5, 0, 6]);
+        // Everything after the start byte, then the message's CRC_EXTRA
+        let crc = frame[1..13].iter().chain([99].iter()).fold(0xFFFF, |crc, &b| crc_accumulate(crc, b));
+        assert_eq!(&frame[13..], &crc.to_le_bytes());
+
+        // An all-zero payload still keeps its first byte
+        let frame = encode_frame(0, 1, 1, COMMAND_LONG, &[0, 0, 0]);
+        assert_eq!(frame[1], 1);
+        assert_eq!(frame.len(), 10 + 1 + 2);
+    }
+}
@@ -0,0 +1,239 @@
+#[cfg(feature = "mqtt")]
+pub use client::MqttOutput;
+#[cfg(not(feature = "mqtt"))]
+pub use unsupported::MqttOutput;
+
+// Where the background connection stands, for the debug panel. Only the real client
+// builds one, hence the allow for builds without it.
+#[cfg_attr(not(feature = "mqtt"), allow(dead_code))]
+#[derive(Debug, Clone)]
+pub enum LinkState {
+    Connecting,
+    Connected,
+    // The broker refused or dropped us; the next attempt is `retry_secs` after the failure
+    Down { error: String, retry_secs: f64 },
+}
+
+#[cfg(feature = "mqtt")]
+mod client {
+    use super::LinkState;
+    use crate::config::MqttOutputConfig;
+    use crate::gimbal::GimbalState;
+    use crate::output::{ActuatorCommands, StatePacket};
+    use crate::server::{self, ServerCommand};
+    use rumqttc::{Client, Event, LastWill, MqttOptions, Packet, QoS};
+    use std::sync::mpsc::{self, Receiver};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    // Requests queued for the connection thread; past this, publishes are dropped, not waited on
+    const QUEUE: usize = 64;
+    const MIN_BACKOFF: Duration = Duration::from_millis(500);
+    const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+    // Publishes to <prefix>/state at a fixed rate and keeps a retained online/offline flag on
+    // <prefix>/status, with a last will so a crash or lost link still reads offline. Lines on
+    // <prefix>/command are command server commands, answered on <prefix>/reply. The
+    // connection runs on its own thread and reconnects with backoff; the main loop only ever
+    // queues messages, so a dead broker can't stall the UI.
+    pub struct MqttOutput {
+        client: Client,
+        broker: String,
+        prefix: String,
+        qos: QoS,
+        interval: Duration,
+        last_send: Option<Instant>,
+        link: Arc<Mutex<LinkState>>,
+        commands: Receiver<ServerCommand>,
+        sequence: u64,
+        sent: u64,
+        // Publishes refused because the queue to the connection thread was full
+        dropped: u64,
+    }
+
+    impl MqttOutput {
+        pub fn open(config: &MqttOutputConfig) -> Result<Self, Box<dyn std::error::Error>> {
+            let (host, port) = broker_address(&config.broker)?;
+            let prefix = config.topic_prefix.trim_end_matches('/').to_string();
+            let qos = match config.qos {
+                0 => QoS::AtMostOnce,
+                1 => QoS::AtLeastOnce,
+                _ => QoS::ExactlyOnce,
+            };
+            let mut options = MqttOptions::new(&config.client_id, &host, port);
+            options.set_keep_alive(Duration::from_secs(5));
+            options.set_last_will(LastWill::new(format!("{}/status", prefix), "offline", qos, true));
+            let (client, mut connection) = Client::new(options, QUEUE);
+
+            let link = Arc::new(Mutex::new(LinkState::Connecting));
+            let (commands_tx, commands) = mpsc::channel();
+            let (thread_client, thread_link, thread_prefix) = (client.clone(), link.clone(), prefix.clone());
+            thread::spawn(move || {
+                let set_link = |state: LinkState| {
+                    if let Ok(mut link) = thread_link.lock() {
+                        *link = state;
+                    }
+                };
+                let command_topic = format!("{}/command", thread_prefix);
+                let mut backoff = MIN_BACKOFF;
+                for notification in connection.iter() {
+                    match notification {
+                        Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                            backoff = MIN_BACKOFF;
+                            set_link(LinkState::Connected);
+                            // A fresh session each time, so subscribe again on every connect
+                            let _ = thread_client.try_subscribe(command_topic.as_str(), qos);
+                            let _ = thread_client.try_publish(format!("{}/status", thread_prefix), qos, true, "online");
+                        }
+                        Ok(Event::Incoming(Packet::Publish(publish))) if publish.topic == command_topic => {
+                            let line = String::from_utf8_lossy(&publish.payload);
+                            match server::parse(&line) {
+                                Ok(command) => {
+                                    if commands_tx.send(command).is_err() {
+                                        break;
+                                    }
+                                }
+                                Err(e) => {
+                                    let _ = thread_client.try_publish(format!("{}/reply", thread_prefix), qos, false, format!("ERR {}", e));
+                                }
+                            }
+                        }
+                        Ok(_) => {}
+                        Err(e) => {
+                            set_link(LinkState::Down {
+                                error: e.to_string(),
+                                retry_secs: backoff.as_secs_f64(),
+                            });
+                            thread::sleep(backoff);
+                            backoff = (backoff * 2).min(MAX_BACKOFF);
+                            set_link(LinkState::Connecting);
+                        }
+                    }
+                }
+            });
+
+            Ok(Self {
+                client,
+                broker: format!("{}:{}", host, port),
+                prefix,
+                qos,
+                interval: Duration::from_secs_f64(1.0 / config.rate_hz.max(0.1)),
+                last_send: None,
+                link,
+                commands,
+                sequence: 0,
+                sent: 0,
+                dropped: 0,
+            })
+        }
+
+        pub fn broker(&self) -> &str {
+            &self.broker
+        }
+
+        pub fn prefix(&self) -> &str {
+            &self.prefix
+        }
+
+        // Messages handed to the connection thread, and ones dropped because it was behind
+        pub fn counts(&self) -> (u64, u64) {
+            (self.sent, self.dropped)
+        }
+
+        pub fn link(&self) -> LinkState {
+            self.link.lock().map(|link| link.clone()).unwrap_or(LinkState::Connecting)
+        }
+
+        // Every command that arrived since the last poll, without waiting
+        pub fn poll(&self) -> Vec<ServerCommand> {
+            self.commands.try_iter().collect()
+        }
+
+        pub fn reply(&mut self, line: String) {
+            self.publish("reply", false, line.into_bytes());
+        }
+
+        // Nothing is queued while the broker is away, so reconnecting doesn't replay stale poses
+        pub fn send(&mut self, state: &GimbalState, armed: bool, actuators: Option<ActuatorCommands>) {
+            if !matches!(self.link(), LinkState::Connected) || self.last_send.is_some_and(|last| last.elapsed() < self.interval) {
+                return;
+            }
+            self.last_send = Some(Instant::now());
+            let packet = StatePacket::new(state, armed, actuators, self.sequence);
+            self.sequence += 1;
+            if let Ok(json) = serde_json::to_vec(&packet) {
+                self.publish("state", false, json);
+            }
+        }
+
+        fn publish(&mut self, topic: &str, retain: bool, payload: Vec<u8>) {
+            match self.client.try_publish(format!("{}/{}", self.prefix, topic), self.qos, retain, payload) {
+                Ok(()) => self.sent += 1,
+                Err(_) => self.dropped += 1,
+            }
+        }
+    }
+
+    // "mqtt://host:port", "tcp://host:port" or plain "host[:port]"; the port defaults to 1883
+    fn broker_address(broker: &str) -> Result<(String, u16), String> {
+        let address = broker.strip_prefix("mqtt://").or_else(|| broker.strip_prefix("tcp://")).unwrap_or(broker);
+        let address = address.trim_end_matches('/');
+        match address.rsplit_once(':') {
+            Some((host, port)) => {
+                let port = port.parse().map_err(|_| format!("'{}' is not a port number", port))?;
+                Ok((host.to_string(), port))
+            }
+            None => Ok((address.to_string(), 1883)),
+        }
+    }
+}
+
+// Stands in when the mqtt feature is off, so [output.mqtt] can say why nothing is published
+#[cfg(not(feature = "mqtt"))]
+mod unsupported {
+    use super::LinkState;
+    use crate::config::MqttOutputConfig;
+    use crate::gimbal::GimbalState;
+    use crate::output::ActuatorCommands;
+    use crate::server::ServerCommand;
+    use std::convert::Infallible;
+
+    pub struct MqttOutput {
+        never: Infallible,
+    }
+
+    impl MqttOutput {
+        pub fn open(_config: &MqttOutputConfig) -> Result<Self, Box<dyn std::error::Error>> {
+            Err("this build has no MQTT support (rebuild with --features mqtt)".into())
+        }
+
+        pub fn broker(&self) -> &str {
+            match self.never {}
+        }
+
+        pub fn prefix(&self) -> &str {
+            match self.never {}
+        }
+
+        pub fn counts(&self) -> (u64, u64) {
+            match self.never {}
+        }
+
+        pub fn link(&self) -> LinkState {
+            match self.never {}
+        }
+
+        pub fn poll(&self) -> Vec<ServerCommand> {
+            match self.never {}
+        }
+
+        pub fn reply(&mut self, _line: String) {
+            match self.never {}
+        }
+
+        pub fn send(&mut self, _state: &GimbalState, _armed: bool, _actuators: Option<ActuatorCommands>) {
+            match self.never {}
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use crate::config::{GimbalConfig, OscOutputConfig};
+use crate::gimbal::GimbalState;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+
+// OSC strings end in at least one NUL and are padded with more to a multiple of 4 bytes
+fn push_padded(packet: &mut Vec<u8>, text: &str) {
+    packet.extend_from_slice(text.as_bytes());
+    let padding = 4 - text.len() % 4;
+    packet.extend(std::iter::repeat_n(0, padding));
+}
+
+// A message with a single float32 argument: address pattern, type tag ",f", big-endian value
+fn encode_float(address: &str, value: f32) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(address.len() + 12);
+    push_padded(&mut packet, address);
+    push_padded(&mut packet, ",f");
+    packet.extend_from_slice(&value.to_be_bytes());
+    packet
+}
+
+// Each channel's value, in degrees (mm for lift) or as a fraction of its limit
+fn channel_values(config: &OscOutputConfig, gimbal: &GimbalConfig, state: &GimbalState) -> [(&'static str, f64); 4] {
+    let scale = |value: f64, limit: f64| if config.normalize && limit > 0.0 { value / limit } else { value };
+    // Against whichever end of an uneven range the value is heading for
+    let toward_end = |value: f64, (min, max): (f64, f64)| scale(value, if value < 0.0 { -min } else { max });
+    // Lift is normalized around its home, against whichever end it is heading for
+    let lift_end = if state.lift < gimbal.lift_home { gimbal.lift_home - gimbal.lift_min } else { gimbal.lift_max - gimbal.lift_home };
+    let lift = if config.normalize { scale(state.lift - gimbal.lift_home, lift_end) } else { state.lift };
+    [
+        ("pitch", toward_end(state.pitch, gimbal.pitch_range())),
+        ("roll", toward_end(state.roll, gimbal.roll_range())),
+        ("lift", lift),
+        ("yaw", scale(state.yaw, gimbal.max_yaw)),
+    ]
+}
+
+// Sends each channel as its own OSC message over UDP at a fixed rate, like UdpOutput
+pub struct OscOutput {
+    socket: UdpSocket,
+    target: SocketAddr,
+    config: OscOutputConfig,
+    interval: Duration,
+    last_send: Option<Instant>,
+    pub packets_sent: u64,
+    pub last_error: Option<String>,
+}
+
+impl OscOutput {
+    pub fn open(config: &OscOutputConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let target = (config.address.as_str(), config.port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| format!("Could not resolve {}", config.address))?;
+        let bind_address = if target.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+        let socket = UdpSocket::bind(bind_address)?;
+        socket.set_nonblocking(true)?;
+
+        Ok(Self {
+            socket,
+            target,
+            config: config.clone(),
+            interval: Duration::from_secs_f64(1.0 / config.rate_hz.max(0.1)),
+            last_send: None,
+            packets_sent: 0,
+            last_error: None,
+        })
+    }
+
+    pub fn target(&self) -> SocketAddr {
+        self.target
+    }
+
+    pub fn send(&mut self, gimbal: &GimbalConfig, state: &GimbalState) {
+        if self.last_send.is_some_and(|last| last.elapsed() < self.interval) {
+            return;
+        }
+        self.last_send = Some(Instant::now());
+
+        let base_path = self.config.base_path.trim_end_matches('/');
+        for (channel, value) in channel_values(&self.config, gimbal, state) {
+            let packet = encode_float(&format!("{}/{}", base_path, channel), value as f32);
+            if let Err(e) = self.socket.send_to(&packet, self.target) {
+                // The rest would fail the same way; try again next interval
+                self.last_error = Some(format!("OSC send failed: {}", e));
+                return;
+            }
+            self.packets_sent += 1;
+        }
+        self.last_error = None;
+    }
+}
@@ -0,0 +1,265 @@
+use crate::config::{ActuatorFormat, ActuatorOutputConfig, UdpOutputConfig, WebSocketOutputConfig};
+use crate::gimbal::GimbalState;
+use crate::kinematics::Actuator;
+use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
+use std::net::{SocketAddr, TcpListener, ToSocketAddrs, UdpSocket};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::watch;
+use tokio_tungstenite::tungstenite::Message;
+
+/// Shared by the UDP, WebSocket and MQTT outputs, so all carry the same JSON
+#[derive(Debug, Serialize)]
+pub struct StatePacket {
+    pitch: f64,
+    roll: f64,
+    lift: f64,
+    yaw: f64,
+    // False while the emergency stop is engaged
+    armed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    actuators: Option<ActuatorCommands>,
+    timestamp_ms: u128, // Wall clock so the receiver can correlate and spot gaps
+    seq: u64,
+}
+
+impl StatePacket {
+    /// Stamped with the current wall clock time; `seq` counts packets per output
+    pub fn new(state: &GimbalState, armed: bool, actuators: Option<ActuatorCommands>, seq: u64) -> Self {
+        Self {
+            pitch: state.pitch,
+            roll: state.roll,
+            lift: state.lift,
+            yaw: state.yaw,
+            armed,
+            actuators,
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis())
+                .unwrap_or(0),
+            seq,
+        }
+    }
+}
+
+/// One command per leg, in actuator order (A1, A2, ...)
+#[derive(Debug, Clone, Serialize)]
+pub struct ActuatorCommands {
+    /// What the values are: step counts or servo pulse widths in µs
+    pub format: ActuatorFormat,
+    /// One per leg
+    pub values: Vec<i64>,
+}
+
+/// Converts leg extensions into step counts or servo pulse widths; None when the format is off
+pub fn actuator_commands(config: &ActuatorOutputConfig, actuators: &[Actuator]) -> Option<ActuatorCommands> {
+    let values = match config.format {
+        ActuatorFormat::None => return None,
+        ActuatorFormat::Steps => actuators
+            .iter()
+            .map(|a| (a.extension * config.steps_per_mm).round() as i64)
+            .collect(),
+        ActuatorFormat::Servo => actuators.iter().map(|a| servo_us(config, a.extension)).collect(),
+    };
+    Some(ActuatorCommands { format: config.format, values })
+}
+
+// Neutral is the middle of min_us..max_us and ±mm_range/2 the ends; beyond that it clamps
+fn servo_us(config: &ActuatorOutputConfig, extension: f64) -> i64 {
+    let fraction = (extension / config.mm_range + 0.5).clamp(0.0, 1.0);
+    (config.min_us + fraction * (config.max_us - config.min_us)).round() as i64
+}
+
+/// Sends the gimbal state as JSON datagrams at a fixed rate, independent of the draw loop
+pub struct UdpOutput {
+    socket: UdpSocket,
+    target: SocketAddr,
+    interval: Duration,
+    last_send: Option<Instant>,
+    sequence: u64,
+    /// Datagrams sent since opening
+    pub packets_sent: u64,
+    /// Why the latest send failed; cleared by the next one that succeeds
+    pub last_error: Option<String>,
+}
+
+impl UdpOutput {
+    /// Resolves the target address and binds a non-blocking socket for it
+    pub fn open(config: &UdpOutputConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let target = config
+            .address
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| format!("Could not resolve {}", config.address))?;
+        let bind_address = if target.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+        let socket = UdpSocket::bind(bind_address)?;
+        // Never let a slow network stall the UI thread
+        socket.set_nonblocking(true)?;
+
+        Ok(Self {
+            socket,
+            target,
+            interval: Duration::from_secs_f64(1.0 / config.rate_hz.max(0.1)),
+            last_send: None,
+            sequence: 0,
+            packets_sent: 0,
+            last_error: None,
+        })
+    }
+
+    /// Where the datagrams go
+    pub fn target(&self) -> SocketAddr {
+        self.target
+    }
+
+    /// Sends one packet unless the last one went out less than an interval ago; a failure
+    /// is kept in `last_error` rather than returned
+    pub fn send(&mut self, state: &GimbalState, armed: bool, actuators: Option<ActuatorCommands>) {
+        if self.last_send.is_some_and(|last| last.elapsed() < self.interval) {
+            return;
+        }
+        self.last_send = Some(Instant::now());
+
+        let packet = StatePacket::new(state, armed, actuators, self.sequence);
+        self.sequence += 1;
+
+        let result = serde_json::to_vec(&packet)
+            .map_err(|e| e.to_string())
+            .and_then(|payload| self.socket.send_to(&payload, self.target).map_err(|e| e.to_string()));
+        match result {
+            Ok(_) => {
+                self.packets_sent += 1;
+                self.last_error = None;
+            }
+            Err(e) => self.last_error = Some(format!("UDP send failed: {}", e)),
+        }
+    }
+}
+
+/// Serves the gimbal state to browsers over WebSocket. The server runs on its own thread
+/// with a single-threaded tokio runtime; the UI thread only replaces the latest JSON in a
+/// watch channel, so it never waits on the network and slow clients just skip states.
+pub struct WebSocketOutput {
+    address: SocketAddr,
+    latest: watch::Sender<String>,
+    clients: Arc<AtomicUsize>,
+    sequence: u64,
+}
+
+impl WebSocketOutput {
+    /// Binds before returning so a taken port is reported at startup
+    pub fn open(config: &WebSocketOutputConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let listener = TcpListener::bind(&config.address)?;
+        listener.set_nonblocking(true)?;
+        let address = listener.local_addr()?;
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_io().build()?;
+        let (latest, receiver) = watch::channel(String::new());
+        let clients = Arc::new(AtomicUsize::new(0));
+
+        let counter = clients.clone();
+        std::thread::spawn(move || {
+            runtime.block_on(async move {
+                let Ok(listener) = tokio::net::TcpListener::from_std(listener) else { return };
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::spawn(serve_websocket(stream, receiver.clone(), counter.clone()));
+                }
+            })
+        });
+
+        Ok(Self { address, latest, clients, sequence: 0 })
+    }
+
+    /// The bound address, with the actual port when the config asked for port 0
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Browsers connected right now
+    pub fn clients(&self) -> usize {
+        self.clients.load(Ordering::Relaxed)
+    }
+
+    /// Replaces the state the clients are sent; never blocks
+    pub fn send(&mut self, state: &GimbalState, armed: bool, actuators: Option<ActuatorCommands>) {
+        let packet = StatePacket::new(state, armed, actuators, self.sequence);
+        self.sequence += 1;
+        if let Ok(json) = serde_json::to_string(&packet) {
+            self.latest.send_replace(json);
+        }
+    }
+}
+
+// Pushes every new state to one client until it closes or a send fails; what it sends is ignored
+async fn serve_websocket(stream: tokio::net::TcpStream, mut latest: watch::Receiver<String>, clients: Arc<AtomicUsize>) {
+    let Ok(mut socket) = tokio_tungstenite::accept_async(stream).await else { return };
+    clients.fetch_add(1, Ordering::Relaxed);
+    loop {
+        tokio::select! {
+            changed = latest.changed() => {
+                let json = latest.borrow_and_update().clone();
+                if changed.is_err() || socket.send(Message::text(json)).await.is_err() {
+                    break;
+                }
+            }
+            message = socket.next() => {
+                if !matches!(message, Some(Ok(message)) if !message.is_close()) {
+                    break;
+                }
+            }
+        }
+    }
+    clients.fetch_sub(1, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Only the extension matters for the commands
+    fn legs(extensions: &[f64]) -> Vec<Actuator> {
+        extensions
+            .iter()
+            .map(|&extension| Actuator { base: (0.0, 0.0, 0.0), top: (0.0, 0.0, 0.0), extension })
+            .collect()
+    }
+
+    fn config(format: ActuatorFormat) -> ActuatorOutputConfig {
+        ActuatorOutputConfig { format, ..ActuatorOutputConfig::default() }
+    }
+
+    #[test]
+    fn no_format_sends_no_commands() {
+        assert!(actuator_commands(&config(ActuatorFormat::None), &legs(&[1.0])).is_none());
+    }
+
+    #[test]
+    fn steps_count_from_the_neutral_length() {
+        // 100 steps per mm; -25 and +25 are the default stroke's ends
+        let commands = actuator_commands(&config(ActuatorFormat::Steps), &legs(&[-25.0, 0.0, 25.0, 0.126])).unwrap();
+        assert_eq!(commands.format, ActuatorFormat::Steps);
+        assert_eq!(commands.values, vec![-2500, 0, 2500, 13]);
+    }
+
+    #[test]
+    fn servo_pulses_span_min_to_max_us_over_mm_range() {
+        // 50 mm across 1000..2000 µs: neutral in the middle, ±25 mm at the ends
+        let commands = actuator_commands(&config(ActuatorFormat::Servo), &legs(&[-25.0, -12.5, 0.0, 12.5, 25.0])).unwrap();
+        assert_eq!(commands.format, ActuatorFormat::Servo);
+        assert_eq!(commands.values, vec![1000, 1250, 1500, 1750, 2000]);
+    }
+
+    #[test]
+    fn servo_pulses_clamp_beyond_mm_range() {
+        let commands = actuator_commands(&config(ActuatorFormat::Servo), &legs(&[-40.0, 40.0])).unwrap();
+        assert_eq!(commands.values, vec![1000, 2000]);
+    }
+
+    #[test]
+    fn reversed_servo_range_maps_the_other_way() {
+        let config = ActuatorOutputConfig { min_us: 2000.0, max_us: 1000.0, ..config(ActuatorFormat::Servo) };
+        let commands = actuator_commands(&config, &legs(&[-25.0, 0.0, 25.0])).unwrap();
+        assert_eq!(commands.values, vec![2000, 1500, 1000]);
+    }
+}
@@ -0,0 +1,253 @@
+use crate::config::GeometryConfig;
+use crate::projection::Projection;
+use crate::theme::{Role, Theme};
+use ratatui::style::Color;
+use ratatui::symbols::Marker;
+use ratatui::text::Span;
+use ratatui::widgets::canvas::{Circle, Context, Line, Painter, Shape};
+use std::cell::Cell;
+use std::f64::consts::TAU;
+
+// Canvas units covered by one grid dot, horizontally and vertically
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Resolution {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Resolution {
+    // `columns`/`rows` are the canvas's inner size in cells; braille packs 2x4 dots per cell
+    pub fn new(columns: u16, rows: u16, x_span: f64, y_span: f64, marker: Marker) -> Self {
+        let (dots_x, dots_y) = if marker == Marker::Braille { (2.0, 4.0) } else { (1.0, 1.0) };
+        Self {
+            x: x_span / (f64::from(columns.max(1)) * dots_x),
+            y: y_span / (f64::from(rows.max(1)) * dots_y),
+        }
+    }
+}
+
+// Most strokes a thick line is drawn with; past this they are spread over the band
+const MAX_STROKES: usize = 8;
+
+// Parallel strokes about one dot apart that fill a band `width` canvas units wide across the
+// line, whatever its direction (the same width the SVG export draws). A stroke per dot is all
+// the grid can show, so a small terminal gets as few as a single line; a big one is capped
+// at MAX_STROKES.
+pub fn thick_strokes(
+    (x1, y1): (f64, f64),
+    (x2, y2): (f64, f64),
+    width: f64,
+    color: Color,
+    resolution: Resolution,
+) -> impl Iterator<Item = Line> {
+    let length = (x2 - x1).hypot(y2 - y1);
+    let (nx, ny) = if length > 0.0 { (-(y2 - y1) / length, (x2 - x1) / length) } else { (0.0, 0.0) };
+    // Dot spacing along the normal
+    let dot = nx.abs() * resolution.x + ny.abs() * resolution.y;
+    let count = if dot > 0.0 { ((width / dot) as usize + 1).min(MAX_STROKES) } else { 1 };
+    let step = if count > 1 { width / (count - 1) as f64 } else { 0.0 };
+    (0..count).map(move |k| {
+        let offset = (k as f64 - (count - 1) as f64 / 2.0) * step;
+        Line::new(x1 + nx * offset, y1 + ny * offset, x2 + nx * offset, y2 + ny * offset, color)
+    })
+}
+
+// Many shapes of one kind drawn as a single shape
+pub struct Batch<'a, S>(pub &'a [S]);
+
+impl<S: Shape> Shape for Batch<'_, S> {
+    fn draw(&self, painter: &mut Painter) {
+        for shape in self.0 {
+            shape.draw(painter);
+        }
+    }
+}
+
+// Wraps ctx.draw so the number of shapes per frame can be shown in the timing overlay
+pub struct Pen {
+    resolution: Resolution,
+    draws: Cell<usize>,
+}
+
+impl Pen {
+    pub fn new(resolution: Resolution) -> Self {
+        Self { resolution, draws: Cell::new(0) }
+    }
+
+    pub fn draws(&self) -> usize {
+        self.draws.get()
+    }
+
+    pub fn draw<S: Shape>(&self, ctx: &mut Context<'_>, shape: &S) {
+        self.draws.set(self.draws.get() + 1);
+        ctx.draw(shape);
+    }
+
+    pub fn batch<S: Shape>(&self, ctx: &mut Context<'_>, shapes: &[S]) {
+        self.draw(ctx, &Batch(shapes));
+    }
+
+    // Draws a scene in order, batching runs of lines and of circles into single shapes
+    pub fn render(&self, ctx: &mut Context<'_>, scene: &Scene, theme: &Theme) {
+        let mut lines: Vec<Line> = Vec::new();
+        let mut circles: Vec<Circle> = Vec::new();
+        for primitive in &scene.primitives {
+            match primitive {
+                Primitive::Line { .. } => {
+                    self.flush(ctx, &mut circles);
+                    lines.extend(primitive_strokes(primitive, self.resolution));
+                }
+                &Primitive::Circle { center: (x, y), radius, color } => {
+                    self.flush(ctx, &mut lines);
+                    circles.push(Circle { x, y, radius, color });
+                }
+                Primitive::Label { at, text, role } => {
+                    self.flush(ctx, &mut lines);
+                    self.flush(ctx, &mut circles);
+                    ctx.print(at.0, at.1, Span::styled(text.clone(), theme.style(*role)));
+                }
+            }
+        }
+        self.flush(ctx, &mut lines);
+        self.flush(ctx, &mut circles);
+    }
+
+    fn flush<S: Shape>(&self, ctx: &mut Context<'_>, shapes: &mut Vec<S>) {
+        if !shapes.is_empty() {
+            self.batch(ctx, shapes);
+            shapes.clear();
+        }
+    }
+}
+
+// One element of the gimbal drawing in canvas coordinates, independent of whether it ends
+// up on the terminal canvas or in an SVG file
+#[derive(Debug, Clone)]
+pub enum Primitive {
+    // `width` in canvas units; 0.0 is a hairline
+    Line { from: (f64, f64), to: (f64, f64), width: f64, color: Color },
+    // Outline only, like the canvas draws it
+    Circle { center: (f64, f64), radius: f64, color: Color },
+    Label { at: (f64, f64), text: String, role: Role },
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Scene {
+    primitives: Vec<Primitive>,
+}
+
+impl Scene {
+    pub fn primitives(&self) -> &[Primitive] {
+        &self.primitives
+    }
+
+    pub fn line(&mut self, from: (f64, f64), to: (f64, f64), color: Color) {
+        self.thick_line(from, to, 0.0, color);
+    }
+
+    pub fn thick_line(&mut self, from: (f64, f64), to: (f64, f64), width: f64, color: Color) {
+        self.primitives.push(Primitive::Line { from, to, width, color });
+    }
+
+    pub fn circle(&mut self, center: (f64, f64), radius: f64, color: Color) {
+        self.primitives.push(Primitive::Circle { center, radius, color });
+    }
+
+    pub fn label(&mut self, at: (f64, f64), text: &str, role: Role) {
+        self.primitives.push(Primitive::Label { at, text: text.to_string(), role });
+    }
+
+    pub fn append(&mut self, other: Scene) {
+        self.primitives.extend(other.primitives);
+    }
+}
+
+// Canvas lines for a line primitive: one for a hairline, parallel strokes for a thick one
+fn primitive_strokes(primitive: &Primitive, resolution: Resolution) -> Vec<Line> {
+    match *primitive {
+        Primitive::Line { from, to, width, color } if width > 0.0 => thick_strokes(from, to, width, color, resolution).collect(),
+        Primitive::Line { from: (x1, y1), to: (x2, y2), color, .. } => vec![Line::new(x1, y1, x2, y2, color)],
+        _ => Vec::new(),
+    }
+}
+
+// The base plate with its rings and the coordinate frame, which don't move with the pose
+pub fn static_scene(geometry: &GeometryConfig, projection: Projection, theme: &Theme) -> Scene {
+    let project = |x, y, z| projection.project(x, y, z);
+    let platform_radius = geometry.plate_radius;
+    let base_height = geometry.base_height;
+    let mut scene = Scene::default();
+
+    // Points around a ring on the base plate, closed back to the first
+    let ring = |radius: f64, points: usize| {
+        (0..points).map(move |i| {
+            let angle1 = i as f64 * TAU / points as f64;
+            let angle2 = (i + 1) as f64 * TAU / points as f64;
+            (
+                project(radius * angle1.cos(), base_height, radius * angle1.sin()),
+                project(radius * angle2.cos(), base_height, radius * angle2.sin()),
+            )
+        })
+    };
+
+    // Thick outer edge of the base plate
+    for (from, to) in ring(platform_radius, 32) {
+        scene.thick_line(from, to, 4.0, theme.color(Role::BasePlate));
+    }
+
+    // Inner rings on the base plate for depth
+    for ring_factor in [0.7, 0.5, 0.3] {
+        for (from, to) in ring(platform_radius * ring_factor, 24) {
+            scene.line(from, to, theme.color(Role::BaseDetail));
+        }
+    }
+
+    // Coordinate frame: X (roll), Y (height) and Z (pitch) axes
+    let (ox, oy, oz) = (-130.0, -70.0, 0.0);
+    let origin = project(ox, oy, oz);
+    for (end, color) in [
+        (project(ox + 25.0, oy, oz), theme.color(Role::FrameX)),
+        (project(ox, oy + 25.0, oz), theme.color(Role::FrameY)),
+        (project(ox, oy, oz + 25.0), theme.color(Role::FrameZ)),
+    ] {
+        scene.thick_line(origin, end, 2.0, color);
+    }
+    scene
+}
+
+// Half-width and half-height of the canvas: at least the view's extent (scaled with the
+// plate), widening whichever axis has spare room so the drawing keeps its proportions.
+// `aspect` is the drawing area's width over its height in square units.
+pub fn fit_bounds(projection: Projection, plate_radius: f64, aspect: f64) -> (f64, f64) {
+    let scale = plate_radius / 100.0;
+    let aspect = aspect.max(0.1);
+    let (min_x_half, min_y_half) = projection.extent();
+    let (mut x_half, mut y_half) = (min_y_half * aspect, min_y_half);
+    if x_half < min_x_half {
+        x_half = min_x_half;
+        y_half = x_half / aspect;
+    }
+    (x_half * scale, y_half * scale)
+}
+
+// Parts of the gimbal drawing that don't move: the base plate and its rings and the
+// coordinate frame. Built once and rebuilt only when the resolution, view or plate changes
+// (the theme is fixed for the session).
+pub struct StaticLayer {
+    key: (Resolution, Projection, f64, f64),
+    pub segments: Vec<Line>,
+}
+
+impl StaticLayer {
+    pub fn is_current(&self, geometry: &GeometryConfig, resolution: Resolution, projection: Projection) -> bool {
+        self.key == (resolution, projection, geometry.plate_radius, geometry.base_height)
+    }
+
+    pub fn build(geometry: &GeometryConfig, resolution: Resolution, projection: Projection, theme: &Theme) -> Self {
+        let scene = static_scene(geometry, projection, theme);
+        Self {
+            key: (resolution, projection, geometry.plate_radius, geometry.base_height),
+            segments: scene.primitives.iter().flat_map(|primitive| primitive_strokes(primitive, resolution)).collect(),
+        }
+    }
+}
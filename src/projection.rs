@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+/// How the 3D model of the gimbal is flattened onto the canvas. Points are (x, y, z) with
+/// x/z on the ground plane (kinematics x/y) and y up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Projection {
+    /// The whole gimbal at an angle
+    #[default]
+    Isometric,
+    /// Looking straight down: yaw and the actuator footprint
+    TopDown,
+    /// Looking along the roll axis: lift, pitch and leg extension
+    Side,
+}
+
+impl Projection {
+    /// The one after this, wrapping around; what the cycle_projection key steps to
+    pub fn next(self) -> Self {
+        match self {
+            Self::Isometric => Self::TopDown,
+            Self::TopDown => Self::Side,
+            Self::Side => Self::Isometric,
+        }
+    }
+
+    /// Display name, as shown in the canvas title
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Isometric => "Isometric",
+            Self::TopDown => "Top-Down",
+            Self::Side => "Side",
+        }
+    }
+
+    /// Canvas (x, y) of a model point
+    pub fn project(self, x: f64, y: f64, z: f64) -> (f64, f64) {
+        match self {
+            Self::Isometric => {
+                let iso_x = (x - z) * 0.866; // cos(30°) ≈ 0.866
+                let iso_y = (x + z) * 0.5 + y; // sin(30°) = 0.5
+                (iso_x, iso_y)
+            }
+            Self::TopDown => (x, z),
+            Self::Side => (z, y),
+        }
+    }
+
+    /// Smallest half width and height of the canvas, for a 100-unit plate, that fits the drawing
+    pub fn extent(self) -> (f64, f64) {
+        match self {
+            Self::Isometric => (180.0, 100.0),
+            Self::TopDown => (150.0, 120.0),
+            Self::Side => (180.0, 100.0),
+        }
+    }
+}
@@ -0,0 +1,336 @@
+use serde::Deserialize;
+use std::f64::consts::TAU;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+const RUN_LOG_HEADER: &str = "start_ms,end_ms,sequence,outcome";
+
+/// (pitch, roll, lift, yaw) in degrees and mm, as GimbalController::home_pose returns it
+pub type Pose = (f64, f64, f64, f64);
+
+/// How a ramp spreads its travel over its duration
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Easing {
+    /// Constant speed
+    #[default]
+    Linear,
+    /// Starts slow and speeds up
+    EaseIn,
+    /// Starts fast and slows down
+    EaseOut,
+    /// Slow at both ends
+    EaseInOut,
+}
+
+impl Easing {
+    // Maps 0..1 of the step's time onto 0..1 of its travel
+    fn apply(self, t: f64) -> f64 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// One of the four pose channels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Channel {
+    /// Degrees, forward edge up
+    Pitch,
+    /// Degrees, right edge up
+    Roll,
+    /// Millimetres of plate height
+    Lift,
+    /// Degrees about the vertical
+    Yaw,
+}
+
+impl Channel {
+    /// Every channel, in pose order
+    pub const ALL: [Channel; 4] = [Channel::Pitch, Channel::Roll, Channel::Lift, Channel::Yaw];
+
+    /// Lowercase name, as sequence files and commands spell it
+    pub fn name(self) -> &'static str {
+        match self {
+            Channel::Pitch => "pitch",
+            Channel::Roll => "roll",
+            Channel::Lift => "lift",
+            Channel::Yaw => "yaw",
+        }
+    }
+
+    /// The channel for a name, ignoring case
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|channel| channel.name().eq_ignore_ascii_case(name))
+    }
+
+    /// This channel's part of a pose
+    pub fn value(self, pose: Pose) -> f64 {
+        match self {
+            Channel::Pitch => pose.0,
+            Channel::Roll => pose.1,
+            Channel::Lift => pose.2,
+            Channel::Yaw => pose.3,
+        }
+    }
+
+    /// This channel's part of a pose, to change in place
+    pub fn of(self, pose: &mut Pose) -> &mut f64 {
+        match self {
+            Channel::Pitch => &mut pose.0,
+            Channel::Roll => &mut pose.1,
+            Channel::Lift => &mut pose.2,
+            Channel::Yaw => &mut pose.3,
+        }
+    }
+}
+
+/// One step of a sequence. Every step starts from where the previous one ended (the first
+/// from the pose the gimbal is in), so steps chain without jumps.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Step {
+    /// Ramps to a pose; channels left out keep their value
+    #[serde(alias = "ramp")]
+    Move {
+        /// Target pitch in degrees
+        pitch: Option<f64>,
+        /// Target roll in degrees
+        roll: Option<f64>,
+        /// Target lift in mm
+        lift: Option<f64>,
+        /// Target yaw in degrees
+        yaw: Option<f64>,
+        /// Seconds
+        duration: f64,
+        /// Shape of the ramp
+        #[serde(default)]
+        easing: Easing,
+    },
+    /// Stays where the previous step ended
+    Hold {
+        /// Seconds
+        duration: f64,
+    },
+    /// Oscillates one channel around its starting value
+    Sine {
+        /// The channel that moves
+        channel: Channel,
+        /// Peak offset from the starting value, in the channel's units
+        amplitude: f64,
+        /// Seconds per cycle
+        period: f64,
+        /// Seconds
+        duration: f64,
+    },
+    /// Ramps to the `[homing]` pose
+    Home {
+        /// Seconds
+        duration: f64,
+        /// Shape of the ramp
+        #[serde(default)]
+        easing: Easing,
+    },
+}
+
+impl Step {
+    /// Seconds the step takes
+    pub fn duration(&self) -> f64 {
+        match self {
+            Step::Move { duration, .. }
+            | Step::Hold { duration }
+            | Step::Sine { duration, .. }
+            | Step::Home { duration, .. } => *duration,
+        }
+    }
+
+    /// Short description for the status line, e.g. "move to pitch +10.0"
+    pub fn label(&self) -> String {
+        match self {
+            Step::Move { pitch, roll, lift, yaw, .. } => {
+                let targets: Vec<String> = [("pitch", pitch), ("roll", roll), ("lift", lift), ("yaw", yaw)]
+                    .into_iter()
+                    .filter_map(|(name, value)| value.map(|v| format!("{} {:+.1}", name, v)))
+                    .collect();
+                format!("move to {}", if targets.is_empty() { "same pose".to_string() } else { targets.join(" ") })
+            }
+            Step::Hold { .. } => "hold".to_string(),
+            Step::Sine { channel, amplitude, period, .. } => {
+                format!("sine {} ±{:.1} every {:.1}s", channel.name(), amplitude, period)
+            }
+            Step::Home { .. } => "home".to_string(),
+        }
+    }
+
+    // Pose `t` seconds into the step
+    fn pose_at(&self, start: Pose, home: Pose, t: f64) -> Pose {
+        let fraction = (t / self.duration()).clamp(0.0, 1.0);
+        let lerp = |from: f64, to: f64, easing: Easing| from + (to - from) * easing.apply(fraction);
+        match *self {
+            Step::Move { pitch, roll, lift, yaw, easing, .. } => (
+                lerp(start.0, pitch.unwrap_or(start.0), easing),
+                lerp(start.1, roll.unwrap_or(start.1), easing),
+                lerp(start.2, lift.unwrap_or(start.2), easing),
+                lerp(start.3, yaw.unwrap_or(start.3), easing),
+            ),
+            Step::Hold { .. } => start,
+            Step::Sine { channel, amplitude, period, duration } => {
+                let mut pose = start;
+                *channel.of(&mut pose) += amplitude * (TAU * t.min(duration) / period).sin();
+                pose
+            }
+            Step::Home { easing, .. } => (
+                lerp(start.0, home.0, easing),
+                lerp(start.1, home.1, easing),
+                lerp(start.2, home.2, easing),
+                lerp(start.3, home.3, easing),
+            ),
+        }
+    }
+
+    fn check(&self) -> Result<(), String> {
+        let duration = self.duration();
+        if !(duration.is_finite() && duration > 0.0) {
+            return Err("duration must be above zero".to_string());
+        }
+        match *self {
+            Step::Move { pitch, roll, lift, yaw, .. } => {
+                if [pitch, roll, lift, yaw].into_iter().flatten().any(|v| !v.is_finite()) {
+                    return Err("pose values must be finite numbers".to_string());
+                }
+            }
+            Step::Sine { amplitude, period, .. } => {
+                if !amplitude.is_finite() {
+                    return Err("amplitude must be a finite number".to_string());
+                }
+                if !(period.is_finite() && period > 0.0) {
+                    return Err("period must be above zero".to_string());
+                }
+            }
+            Step::Hold { .. } | Step::Home { .. } => {}
+        }
+        Ok(())
+    }
+}
+
+/// A named list of steps, from a TOML file of `[[step]]` tables or the same shape in JSON
+#[derive(Debug, Clone, Deserialize)]
+pub struct Sequence {
+    /// From the file, else its stem
+    #[serde(default)]
+    pub name: String,
+    /// Played in order; never empty once loaded
+    #[serde(rename = "step", alias = "steps")]
+    pub steps: Vec<Step>,
+}
+
+impl Sequence {
+    /// `.json` files are read as JSON, anything else as TOML; every step is checked
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let text = std::fs::read_to_string(path)?;
+        let mut sequence: Sequence = if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+            serde_json::from_str(&text)?
+        } else {
+            toml::from_str(&text)?
+        };
+        if sequence.steps.is_empty() {
+            return Err("sequence has no steps".into());
+        }
+        for (i, step) in sequence.steps.iter().enumerate() {
+            step.check().map_err(|e| format!("step {}: {}", i + 1, e))?;
+        }
+        if sequence.name.is_empty() {
+            sequence.name = path.file_stem().map_or("sequence".to_string(), |stem| stem.to_string_lossy().into_owned());
+        }
+        Ok(sequence)
+    }
+
+    /// Seconds for the whole sequence
+    pub fn duration(&self) -> f64 {
+        self.steps.iter().map(Step::duration).sum()
+    }
+}
+
+/// Plays a sequence against the time it is fed, so pausing the app pauses the run too
+pub struct SequencePlayer {
+    sequence: Sequence,
+    step: usize,
+    // Seconds into the current step, and the pose it started from
+    elapsed: f64,
+    start: Pose,
+    finished_secs: f64,
+}
+
+impl SequencePlayer {
+    /// Starts at the first step, from `pose`
+    pub fn new(sequence: Sequence, pose: Pose) -> Self {
+        Self { sequence, step: 0, elapsed: 0.0, start: pose, finished_secs: 0.0 }
+    }
+
+    /// The sequence's name
+    pub fn name(&self) -> &str {
+        &self.sequence.name
+    }
+
+    /// Target pose `dt` seconds on; once finished it stays at the end of the last step
+    pub fn advance(&mut self, dt: f64, home: Pose) -> Pose {
+        self.elapsed += dt;
+        while let Some(step) = self.sequence.steps.get(self.step) {
+            if self.elapsed < step.duration() {
+                return step.pose_at(self.start, home, self.elapsed);
+            }
+            self.start = step.pose_at(self.start, home, step.duration());
+            self.elapsed -= step.duration();
+            self.finished_secs += step.duration();
+            self.step += 1;
+        }
+        self.elapsed = 0.0;
+        self.start
+    }
+
+    /// True once every step has run
+    pub fn is_finished(&self) -> bool {
+        self.step >= self.sequence.steps.len()
+    }
+
+    /// 1-based index of the running step and the step count
+    pub fn position(&self) -> (usize, usize) {
+        let count = self.sequence.steps.len();
+        ((self.step + 1).min(count), count)
+    }
+
+    /// Label of the running step, or "done"
+    pub fn step_label(&self) -> String {
+        self.sequence.steps.get(self.step).map_or("done".to_string(), Step::label)
+    }
+
+    /// Seconds left in the running step
+    pub fn step_remaining(&self) -> f64 {
+        self.sequence.steps.get(self.step).map_or(0.0, |step| (step.duration() - self.elapsed).max(0.0))
+    }
+
+    /// Seconds left in the whole sequence
+    pub fn remaining(&self) -> f64 {
+        (self.sequence.duration() - self.finished_secs - self.elapsed).max(0.0)
+    }
+
+    /// Share of the sequence's time played so far, 0..1
+    pub fn progress(&self) -> f64 {
+        let total = self.sequence.duration();
+        ((total - self.remaining()) / total).clamp(0.0, 1.0)
+    }
+}
+
+/// One CSV line per run in `[sequence]` run_log; the header is written when the file is new
+pub fn append_run_log(path: &str, name: &str, started: u128, ended: u128, outcome: &str) -> std::io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if file.metadata()?.len() == 0 {
+        writeln!(file, "{}", RUN_LOG_HEADER)?;
+    }
+    writeln!(file, "{},{},\"{}\",{}", started, ended, name.replace('"', "\"\""), outcome)
+}
@@ -0,0 +1,142 @@
+use crate::sequence::Channel;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender, TrySendError};
+use std::thread;
+
+// Lines waiting to be written to one client; past this, streamed state lines are dropped
+const CLIENT_QUEUE: usize = 256;
+const USAGE: &str = "SET pitch roll lift [yaw], GET, RESET, PRESET name, ESTOP, STREAM on|off";
+
+#[derive(Debug, Clone)]
+pub enum ServerCommand {
+    Set(Vec<(Channel, f64)>),
+    Get,
+    Reset,
+    Preset(String),
+    Estop,
+    Stream(bool),
+}
+
+pub enum ServerEvent {
+    Connected(SocketAddr),
+    Disconnected(SocketAddr),
+    Request { command: ServerCommand, client: Client },
+}
+
+// Where replies and streamed lines for one connection go. Sending never blocks: each
+// connection has its own writer thread, so a stalled client only stalls itself.
+#[derive(Clone)]
+pub struct Client {
+    peer: SocketAddr,
+    lines: SyncSender<String>,
+}
+
+impl Client {
+    pub fn peer(&self) -> SocketAddr {
+        self.peer
+    }
+
+    // False once the connection is gone; a full queue drops the line but keeps the client
+    pub fn send(&self, line: String) -> bool {
+        !matches!(self.lines.try_send(line), Err(TrySendError::Disconnected(_)))
+    }
+}
+
+// Accepts connections on a background thread and hands parsed commands to the main loop,
+// which answers them between updates
+pub struct CommandServer {
+    address: SocketAddr,
+    events: Receiver<ServerEvent>,
+}
+
+impl CommandServer {
+    pub fn start(address: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let listener = TcpListener::bind(address)?;
+        let address = listener.local_addr()?;
+        let (events_tx, events) = mpsc::channel();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let events = events_tx.clone();
+                thread::spawn(move || serve_client(stream, events));
+            }
+        });
+        Ok(Self { address, events })
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    // Everything that arrived since the last call, without waiting
+    pub fn poll(&self) -> Vec<ServerEvent> {
+        self.events.try_iter().collect()
+    }
+}
+
+fn serve_client(stream: TcpStream, events: Sender<ServerEvent>) {
+    let (Ok(peer), Ok(mut writer)) = (stream.peer_addr(), stream.try_clone()) else { return };
+    let (lines_tx, lines) = mpsc::sync_channel::<String>(CLIENT_QUEUE);
+    thread::spawn(move || {
+        for line in lines {
+            if writeln!(writer, "{}", line).is_err() {
+                break;
+            }
+        }
+        // Makes the reader below (and any further sends) fail fast
+        let _ = writer.shutdown(Shutdown::Both);
+    });
+
+    let client = Client { peer, lines: lines_tx };
+    if events.send(ServerEvent::Connected(peer)).is_err() {
+        return;
+    }
+    for line in BufReader::new(&stream).lines() {
+        let Ok(line) = line else { break };
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse(&line) {
+            Ok(command) => {
+                if events.send(ServerEvent::Request { command, client: client.clone() }).is_err() {
+                    break;
+                }
+            }
+            Err(e) => {
+                client.send(format!("ERR {}", e));
+            }
+        }
+    }
+    let _ = stream.shutdown(Shutdown::Both);
+    let _ = events.send(ServerEvent::Disconnected(peer));
+}
+
+// Commands are case-insensitive; SET takes values in channel order, yaw optional. MQTT
+// command messages use the same syntax.
+pub fn parse(line: &str) -> Result<ServerCommand, String> {
+    let mut words = line.split_whitespace();
+    let verb = words.next().unwrap_or("").to_ascii_uppercase();
+    let args: Vec<&str> = words.collect();
+    match (verb.as_str(), args.as_slice()) {
+        ("SET", values) if (3..=4).contains(&values.len()) => {
+            let mut targets = Vec::new();
+            for (&channel, value) in Channel::ALL.iter().zip(values) {
+                let value: f64 = value
+                    .parse()
+                    .ok()
+                    .filter(|v: &f64| v.is_finite())
+                    .ok_or_else(|| format!("'{}' is not a number", value))?;
+                targets.push((channel, value));
+            }
+            Ok(ServerCommand::Set(targets))
+        }
+        ("SET", _) => Err("SET takes pitch roll lift [yaw]".to_string()),
+        ("GET", []) => Ok(ServerCommand::Get),
+        ("RESET", []) => Ok(ServerCommand::Reset),
+        ("ESTOP", []) => Ok(ServerCommand::Estop),
+        ("PRESET", [name]) => Ok(ServerCommand::Preset(name.to_string())),
+        ("STREAM", [mode]) if mode.eq_ignore_ascii_case("on") => Ok(ServerCommand::Stream(true)),
+        ("STREAM", [mode]) if mode.eq_ignore_ascii_case("off") => Ok(ServerCommand::Stream(false)),
+        _ => Err(format!("unknown command '{}' (expected {})", line.trim(), USAGE)),
+    }
+}
@@ -0,0 +1,345 @@
+use crate::config::Config;
+use crossterm::event::KeyCode;
+
+pub enum Kind {
+    // Stepped by +/-, or typed; anything outside min..=max is refused
+    Number {
+        min: f64,
+        max: f64,
+        step: f64,
+        get: fn(&Config) -> f64,
+        set: fn(&mut Config, f64),
+    },
+    Flag {
+        get: fn(&Config) -> bool,
+        set: fn(&mut Config, bool),
+    },
+    // Shown for reference only, with the reason it can't change mid-session
+    Fixed { get: fn(&Config) -> String, why: &'static str },
+}
+
+pub struct Field {
+    pub section: &'static str,
+    pub name: &'static str,
+    pub kind: Kind,
+}
+
+impl Field {
+    pub fn display(&self, config: &Config) -> String {
+        match &self.kind {
+            Kind::Number { get, step, .. } => format_number(get(config), *step),
+            Kind::Flag { get, .. } => get(config).to_string(),
+            Kind::Fixed { get, .. } => get(config),
+        }
+    }
+
+    fn same(&self, a: &Config, b: &Config) -> bool {
+        match &self.kind {
+            Kind::Number { get, .. } => get(a) == get(b),
+            Kind::Flag { get, .. } => get(a) == get(b),
+            Kind::Fixed { .. } => true,
+        }
+    }
+
+    fn copy(&self, from: &Config, to: &mut Config) {
+        match &self.kind {
+            Kind::Number { get, set, .. } => set(to, get(from)),
+            Kind::Flag { get, set } => set(to, get(from)),
+            Kind::Fixed { .. } => {}
+        }
+    }
+}
+
+// As many decimals as the step has, so 0.05 steps show 0.35 and whole steps show 20
+pub fn format_number(value: f64, step: f64) -> String {
+    let decimals = (0..4).find(|&d| (step * 10f64.powi(d)).fract().abs() < 1e-9).unwrap_or(4) as usize;
+    format!("{:.*}", decimals, value)
+}
+
+// Stepping lands on multiples of the step, so float error can't creep in over many presses
+fn snap(value: f64, step: f64) -> f64 {
+    ((value / step).round() * step * 1e9).round() / 1e9
+}
+
+macro_rules! number {
+    ($section:ident . $name:ident, $min:expr, $max:expr, $step:expr) => {
+        Field {
+            section: stringify!($section),
+            name: stringify!($name),
+            kind: Kind::Number {
+                min: $min,
+                max: $max,
+                step: $step,
+                get: |config| config.$section.$name,
+                set: |config, value| config.$section.$name = value,
+            },
+        }
+    };
+}
+
+macro_rules! flag {
+    ($section:ident . $name:ident) => {
+        Field {
+            section: stringify!($section),
+            name: stringify!($name),
+            kind: Kind::Flag {
+                get: |config| config.$section.$name,
+                set: |config, value| config.$section.$name = value,
+            },
+        }
+    };
+}
+
+// The settings the overlay can change, in the order shown. Only values the controller
+// picks up on its next update are editable; ones read once at startup are Fixed.
+fn fields() -> Vec<Field> {
+    vec![
+        number!(gimbal.max_pitch, 1.0, 90.0, 0.5),
+        number!(gimbal.max_roll, 1.0, 90.0, 0.5),
+        number!(gimbal.max_yaw, 0.0, 180.0, 1.0),
+        number!(gimbal.lift_min, -500.0, 500.0, 0.5),
+        number!(gimbal.lift_max, -500.0, 500.0, 0.5),
+        number!(gimbal.lift_home, -500.0, 500.0, 0.5),
+        number!(gimbal.pitch_sensitivity, 0.0, 5.0, 0.05),
+        number!(gimbal.roll_sensitivity, 0.0, 5.0, 0.05),
+        number!(gimbal.lift_sensitivity, 0.0, 5.0, 0.05),
+        number!(gimbal.yaw_sensitivity, 0.0, 5.0, 0.05),
+        number!(gimbal.velocity_rate, 0.05, 5.0, 0.05),
+        number!(controls.keyboard_step, 0.01, 1.0, 0.01),
+        number!(controls.mixing_deadzone, 0.0, 0.5, 0.01),
+        flag!(controls.rumble_on_limit),
+        number!(safety.rearm_hold_secs, 0.0, 10.0, 0.1),
+        number!(safety.zero_ramp_secs, 0.1, 30.0, 0.1),
+        number!(safety.soft_start_secs, 0.0, 10.0, 0.1),
+        flag!(safety.require_center_on_start),
+        number!(homing.move_secs, 0.1, 30.0, 0.1),
+        number!(homing.idle_timeout_secs, 0.0, 3600.0, 5.0),
+        Field {
+            section: "geometry",
+            name: "platform_type",
+            kind: Kind::Fixed {
+                get: |config| format!("{:?}", config.geometry.platform_type).to_lowercase(),
+                why: "set at startup",
+            },
+        },
+        Field {
+            section: "app",
+            name: "update_rate_hz",
+            kind: Kind::Fixed { get: |config| format!("{:.0}", config.app.update_rate_hz), why: "needs a restart" },
+        },
+        Field {
+            section: "app",
+            name: "render_rate_hz",
+            kind: Kind::Fixed { get: |config| format!("{:.0}", config.app.render_rate_hz), why: "needs a restart" },
+        },
+    ]
+}
+
+// One line of the overlay: a section heading or one of its fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Row {
+    Section(&'static str),
+    Field(usize),
+}
+
+// What the app should do after a key
+pub enum Outcome {
+    None,
+    // A changed config to check against the rest and, if it passes, apply
+    Change(Box<Config>),
+    Save,
+    Revert,
+    Close,
+}
+
+// The settings overlay: fields grouped by config section, each section foldable. Values
+// are checked against their range as they are entered; the app applies them live.
+pub struct SettingsEditor {
+    pub fields: Vec<Field>,
+    collapsed: Vec<&'static str>,
+    pub selected: usize,
+    // Text typed into the selected number, while entering one
+    pub entry: Option<String>,
+    // The last refusal (true) or save/revert notice (false), shown under the list
+    pub message: Option<(bool, String)>,
+    // Values at open or the last save, for the change markers and Revert
+    pub saved: Config,
+}
+
+impl SettingsEditor {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            fields: fields(),
+            collapsed: Vec::new(),
+            selected: 0,
+            entry: None,
+            message: None,
+            saved: config.clone(),
+        }
+    }
+
+    pub fn rows(&self) -> Vec<Row> {
+        let mut rows = Vec::new();
+        for (index, field) in self.fields.iter().enumerate() {
+            if !rows.contains(&Row::Section(field.section)) {
+                rows.push(Row::Section(field.section));
+            }
+            if !self.collapsed.contains(&field.section) {
+                rows.push(Row::Field(index));
+            }
+        }
+        rows
+    }
+
+    pub fn is_collapsed(&self, section: &str) -> bool {
+        self.collapsed.contains(&section)
+    }
+
+    pub fn changed(&self, field: &Field, config: &Config) -> bool {
+        !field.same(&self.saved, config)
+    }
+
+    pub fn changed_count(&self, config: &Config) -> usize {
+        self.fields.iter().filter(|field| self.changed(field, config)).count()
+    }
+
+    // `config` with every editable field put back to its saved value
+    pub fn reverted(&self, config: &Config) -> Config {
+        let mut reverted = config.clone();
+        for field in &self.fields {
+            field.copy(&self.saved, &mut reverted);
+        }
+        reverted
+    }
+
+    pub fn refuse(&mut self, message: String) {
+        self.message = Some((true, message));
+    }
+
+    pub fn handle_key(&mut self, key: KeyCode, config: &Config) -> Outcome {
+        let rows = self.rows();
+        let last = rows.len().saturating_sub(1);
+        self.selected = self.selected.min(last);
+        let row = rows.get(self.selected).copied();
+
+        if self.entry.is_some() {
+            return self.handle_entry_key(key, row, config);
+        }
+
+        self.message = None;
+        match (key, row) {
+            (KeyCode::Up, _) => self.selected = self.selected.saturating_sub(1),
+            (KeyCode::Down, _) => self.selected = (self.selected + 1).min(last),
+            (KeyCode::PageUp, _) => self.selected = self.selected.saturating_sub(10),
+            (KeyCode::PageDown, _) => self.selected = (self.selected + 10).min(last),
+            (KeyCode::Home, _) => self.selected = 0,
+            (KeyCode::End, _) => self.selected = last,
+            (KeyCode::Char('s'), _) => return Outcome::Save,
+            (KeyCode::Char('r'), _) => return Outcome::Revert,
+            (KeyCode::Esc, _) => return Outcome::Close,
+            // Left folds the section, from its heading or any of its fields
+            (KeyCode::Left, Some(row)) => {
+                let section = match row {
+                    Row::Section(section) => section,
+                    Row::Field(index) => self.fields[index].section,
+                };
+                if !self.collapsed.contains(&section) {
+                    self.collapsed.push(section);
+                }
+                self.selected = self.rows().iter().position(|row| *row == Row::Section(section)).unwrap_or(0);
+            }
+            (KeyCode::Right, Some(Row::Section(section))) => self.collapsed.retain(|s| *s != section),
+            (KeyCode::Enter, Some(Row::Section(section))) => {
+                if self.collapsed.contains(&section) {
+                    self.collapsed.retain(|s| *s != section);
+                } else {
+                    self.collapsed.push(section);
+                }
+            }
+            (key, Some(Row::Field(index))) => match self.edit(index, key, config) {
+                Ok(Some(changed)) => return Outcome::Change(Box::new(changed)),
+                Ok(None) => {}
+                Err(e) => self.refuse(e),
+            },
+            _ => {}
+        }
+        Outcome::None
+    }
+
+    fn handle_entry_key(&mut self, key: KeyCode, row: Option<Row>, config: &Config) -> Outcome {
+        let Some(text) = self.entry.as_mut() else { return Outcome::None };
+        match key {
+            KeyCode::Char(c) if c.is_ascii_digit() || c == '.' || c == '-' => text.push(c),
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Esc => self.entry = None,
+            KeyCode::Enter => {
+                let text = self.entry.take().unwrap_or_default();
+                let Some(Row::Field(index)) = row else { return Outcome::None };
+                let result = match text.trim().parse::<f64>() {
+                    Ok(value) if value.is_finite() => self.checked(index, config, value),
+                    _ => Err(format!("'{}' is not a number", text)),
+                };
+                match result {
+                    Ok(changed) => return Outcome::Change(Box::new(changed)),
+                    Err(e) => self.refuse(e),
+                }
+            }
+            _ => {}
+        }
+        Outcome::None
+    }
+
+    // +/- step a number, Enter or a digit starts typing one, and Enter or Space flips a flag
+    fn edit(&mut self, index: usize, key: KeyCode, config: &Config) -> Result<Option<Config>, String> {
+        let field = &self.fields[index];
+        match (&field.kind, key) {
+            (Kind::Fixed { why, .. }, KeyCode::Char('+' | '=' | '-' | ' ') | KeyCode::Enter) => {
+                Err(format!("{} is read-only ({})", field.name, why))
+            }
+            (Kind::Number { get, step, .. }, KeyCode::Char('+' | '=')) => {
+                let value = snap(get(config) + step, *step);
+                self.checked(index, config, value).map(Some)
+            }
+            (Kind::Number { get, step, .. }, KeyCode::Char('-')) => {
+                let value = snap(get(config) - step, *step);
+                self.checked(index, config, value).map(Some)
+            }
+            (Kind::Number { .. }, KeyCode::Enter) => {
+                self.entry = Some(String::new());
+                Ok(None)
+            }
+            (Kind::Number { .. }, KeyCode::Char(c)) if c.is_ascii_digit() || c == '.' => {
+                self.entry = Some(c.to_string());
+                Ok(None)
+            }
+            (Kind::Flag { get, set }, KeyCode::Char(' ' | '+' | '=' | '-') | KeyCode::Enter) => {
+                let mut changed = config.clone();
+                set(&mut changed, !get(config));
+                Ok(Some(changed))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    // The range check happens here, at entry; the app then checks the result against the
+    // rest of the config (lift_min below lift_max and so on)
+    fn checked(&self, index: usize, config: &Config, value: f64) -> Result<Config, String> {
+        let field = &self.fields[index];
+        let Kind::Number { min, max, step, set, .. } = field.kind else {
+            return Err(format!("{} is not a number", field.name));
+        };
+        if !(min..=max).contains(&value) {
+            return Err(format!(
+                "{} must be between {} and {}",
+                field.name,
+                format_number(min, step),
+                format_number(max, step)
+            ));
+        }
+        let mut changed = config.clone();
+        set(&mut changed, value);
+        Ok(changed)
+    }
+}
@@ -0,0 +1,52 @@
+use std::f64::consts::TAU;
+
+// Synthetic stick motion for running without hardware, in axis units (±1.0)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimulationPattern {
+    Sine,   // Pitch, roll and lift swept by sines at different rates
+    Circle, // Pitch and roll a quarter turn apart, so the plate wobbles in a circle
+    Step,   // Each channel jumps between full deflections in turn
+}
+
+impl SimulationPattern {
+    pub const NAMES: &'static str = "sine, circle, step";
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "sine" => Some(Self::Sine),
+            "circle" => Some(Self::Circle),
+            "step" => Some(Self::Step),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sine => "sine",
+            Self::Circle => "circle",
+            Self::Step => "step",
+        }
+    }
+
+    // (pitch, roll, lift) at `t` seconds into the run
+    pub fn sample(self, t: f64) -> (f64, f64, f64) {
+        match self {
+            Self::Sine => (
+                (TAU * t / 4.0).sin(),
+                (TAU * t / 6.0).sin(),
+                (TAU * t / 10.0).sin(),
+            ),
+            Self::Circle => ((TAU * t / 5.0).sin(), (TAU * t / 5.0).cos(), 0.0),
+            Self::Step => {
+                // Two seconds per step: pitch +/-, roll +/-, lift +/-, then repeat
+                let step = (t / 2.0) as u64 % 6;
+                let value = if step.is_multiple_of(2) { 1.0 } else { -1.0 };
+                match step / 2 {
+                    0 => (value, 0.0, 0.0),
+                    1 => (0.0, value, 0.0),
+                    _ => (0.0, 0.0, value),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, Instant};
+
+// How long a message stays up when others are queued behind it
+const MIN_DISPLAY: Duration = Duration::from_millis(1200);
+const MAX_QUEUED: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Warn,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct StatusMessage {
+    pub severity: Severity,
+    pub text: String,
+}
+
+// Transient messages are shown one at a time from a small queue and expire;
+// pinned messages describe ongoing conditions and stay until cleared by key
+pub struct StatusBar {
+    timeout: Duration,
+    current: Option<(StatusMessage, Instant)>,
+    queue: VecDeque<StatusMessage>,
+    pinned: BTreeMap<String, StatusMessage>,
+}
+
+impl StatusBar {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            current: None,
+            queue: VecDeque::new(),
+            pinned: BTreeMap::new(),
+        }
+    }
+
+    pub fn push(&mut self, severity: Severity, text: String) {
+        if self.queue.len() == MAX_QUEUED {
+            self.queue.pop_front();
+        }
+        self.queue.push_back(StatusMessage { severity, text });
+        self.tick();
+    }
+
+    // Returns true if this is a new or changed condition
+    pub fn pin(&mut self, key: &str, severity: Severity, text: String) -> bool {
+        let changed = self
+            .pinned
+            .get(key)
+            .is_none_or(|existing| existing.severity != severity || existing.text != text);
+        if changed {
+            self.pinned.insert(key.to_string(), StatusMessage { severity, text });
+        }
+        changed
+    }
+
+    pub fn unpin(&mut self, key: &str) -> bool {
+        self.pinned.remove(key).is_some()
+    }
+
+    // Returns true if the shown message changed
+    pub fn tick(&mut self) -> bool {
+        let expired = match &self.current {
+            Some((_, shown)) => {
+                let elapsed = shown.elapsed();
+                elapsed >= self.timeout || (!self.queue.is_empty() && elapsed >= MIN_DISPLAY)
+            }
+            None => true,
+        };
+        if expired && (self.current.is_some() || !self.queue.is_empty()) {
+            self.current = self.queue.pop_front().map(|message| (message, Instant::now()));
+            return true;
+        }
+        false
+    }
+
+    pub fn current(&self) -> Option<&StatusMessage> {
+        self.current.as_ref().map(|(message, _)| message)
+    }
+
+    pub fn pinned(&self) -> impl Iterator<Item = &StatusMessage> {
+        self.pinned.values()
+    }
+}
@@ -0,0 +1,155 @@
+use crate::paint::{Primitive, Scene};
+use crate::theme::{Role, Theme};
+use ratatui::style::Color;
+use std::fmt::Write;
+
+// Output width in pixels; the drawing below the header is ASPECT wide for each unit tall
+pub const WIDTH: f64 = 1600.0;
+pub const ASPECT: f64 = 4.0 / 3.0;
+// Where the export key writes; `{timestamp}` becomes the Unix time in milliseconds
+pub const DEFAULT_SNAPSHOT: &str = "gimbal-{timestamp}.svg";
+// Room above the drawing for the title and the pose readout
+const HEADER_HEIGHT: f64 = 70.0;
+const HAIRLINE: f64 = 1.5;
+const FONT: &str = "DejaVu Sans Mono, Menlo, Consolas, monospace";
+
+// The xterm defaults for the 16 named colors, which is what most terminals show
+const ANSI: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+// `#rrggbb` for a terminal color; Reset (the terminal's own color) becomes `default`
+fn hex(color: Color, default: &str) -> String {
+    let (r, g, b) = match color {
+        Color::Reset => return default.to_string(),
+        Color::Black => ANSI[0],
+        Color::Red => ANSI[1],
+        Color::Green => ANSI[2],
+        Color::Yellow => ANSI[3],
+        Color::Blue => ANSI[4],
+        Color::Magenta => ANSI[5],
+        Color::Cyan => ANSI[6],
+        Color::Gray => ANSI[7],
+        Color::DarkGray => ANSI[8],
+        Color::LightRed => ANSI[9],
+        Color::LightGreen => ANSI[10],
+        Color::LightYellow => ANSI[11],
+        Color::LightBlue => ANSI[12],
+        Color::LightMagenta => ANSI[13],
+        Color::LightCyan => ANSI[14],
+        Color::White => ANSI[15],
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Indexed(i) if i < 16 => ANSI[i as usize],
+        // 6x6x6 color cube, then a 24-step gray ramp
+        Color::Indexed(i) if i < 232 => {
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            let i = i - 16;
+            (level(i / 36), level(i / 6 % 6), level(i % 6))
+        }
+        Color::Indexed(i) => {
+            let v = 8 + (i - 232) * 10;
+            (v, v, v)
+        }
+    };
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// The scene as a standalone SVG document, WIDTH pixels wide, showing the canvas area
+// [-x_half, x_half] x [-y_half, y_half] under a header with `title` and `readout`
+pub fn render(scene: &Scene, theme: &Theme, (x_half, y_half): (f64, f64), title: &str, readout: &str) -> String {
+    let scale = WIDTH / (x_half * 2.0);
+    let height = HEADER_HEIGHT + y_half * 2.0 * scale;
+    // Canvas y points up, SVG y points down
+    let point = |(x, y): (f64, f64)| ((x + x_half) * scale, HEADER_HEIGHT + (y_half - y) * scale);
+
+    // Monochrome themes draw in the terminal's own colors; assume dark text on white
+    let background = hex(theme.color(Role::Background), "#ffffff");
+    let light_background = matches!(theme.color(Role::Background), Color::White | Color::Gray | Color::Reset);
+    let foreground = if light_background { "#000000" } else { "#e5e5e5" };
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w:.0}" height="{h:.0}" viewBox="0 0 {w:.0} {h:.0}" font-family="{font}">"#,
+        w = WIDTH,
+        h = height,
+        font = FONT
+    );
+    let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="{}"/>"#, background);
+    let _ = writeln!(
+        svg,
+        r#"<text x="20" y="30" font-size="22" fill="{}">{}</text>"#,
+        hex(theme.color(Role::Text), foreground),
+        escape(title)
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text x="20" y="58" font-size="18" fill="{}">{}</text>"#,
+        hex(theme.color(Role::Header), foreground),
+        escape(readout)
+    );
+
+    let _ = writeln!(svg, r#"<g fill="none" stroke-linecap="round">"#);
+    for primitive in scene.primitives() {
+        match primitive {
+            Primitive::Line { from, to, width, color } => {
+                let ((x1, y1), (x2, y2)) = (point(*from), point(*to));
+                let stroke_width = if *width > 0.0 { width * scale } else { HAIRLINE };
+                let _ = writeln!(
+                    svg,
+                    r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{}" stroke-width="{:.1}"/>"#,
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    hex(*color, foreground),
+                    stroke_width
+                );
+            }
+            Primitive::Circle { center, radius, color } => {
+                let (cx, cy) = point(*center);
+                let _ = writeln!(
+                    svg,
+                    r#"<circle cx="{:.1}" cy="{:.1}" r="{:.1}" stroke="{}" stroke-width="{:.1}"/>"#,
+                    cx,
+                    cy,
+                    radius * scale,
+                    hex(*color, foreground),
+                    HAIRLINE
+                );
+            }
+            Primitive::Label { at, text, role } => {
+                let (x, y) = point(*at);
+                let _ = writeln!(
+                    svg,
+                    r#"<text x="{:.1}" y="{:.1}" font-size="18" text-anchor="middle" fill="{}" stroke="none">{}</text>"#,
+                    x,
+                    y,
+                    hex(theme.color(*role), foreground),
+                    escape(text)
+                );
+            }
+        }
+    }
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
@@ -0,0 +1,248 @@
+use crate::config::{ThemeConfig, ThemePreset};
+use ratatui::style::{Color, Modifier, Style};
+
+/// What a color is used for; every color the UI draws with comes from one of these
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// Ordinary text
+    Text,
+    /// Hints, idle values, separators
+    Muted,
+    /// Less important text and mid-height plate edges
+    Secondary,
+    /// Headers, section headings and info messages
+    Header,
+    /// Connected pads, active axes
+    Ok,
+    /// Warning messages
+    Warning,
+    /// Error messages and the e-stop banner
+    Error,
+    /// Pressed buttons
+    Highlight,
+    /// Behind everything
+    Background,
+    /// The fixed base plate
+    BasePlate,
+    /// Base rings, motor housings and brackets
+    BaseDetail,
+    /// The moving upper plate
+    UpperPlate,
+    /// Leg joints
+    Joint,
+    /// Stepper motors
+    Motor,
+    /// Scissor drive shafts
+    WormGear,
+    /// Scissor pivots and the housing at the top of each leg
+    Bearing,
+    /// What the plate carries
+    Payload,
+    /// A leg above its neutral length
+    ActuatorExtended,
+    /// A leg below its neutral length
+    ActuatorRetracted,
+    /// A leg at its neutral length
+    ActuatorNeutral,
+    /// Line across the upper plate showing roll
+    TiltLineRoll,
+    /// Line across the upper plate showing pitch
+    TiltLinePitch,
+    /// X axis of the coordinate frame
+    FrameX,
+    /// Y axis of the coordinate frame
+    FrameY,
+    /// Z axis of the coordinate frame
+    FrameZ,
+    /// Ghost plate at the pose the hardware reports back
+    Feedback,
+}
+
+impl Role {
+    /// Every role, in declaration order
+    pub const ALL: [Role; 26] = [
+        Role::Text,
+        Role::Muted,
+        Role::Secondary,
+        Role::Header,
+        Role::Ok,
+        Role::Warning,
+        Role::Error,
+        Role::Highlight,
+        Role::Background,
+        Role::BasePlate,
+        Role::BaseDetail,
+        Role::UpperPlate,
+        Role::Joint,
+        Role::Motor,
+        Role::WormGear,
+        Role::Bearing,
+        Role::Payload,
+        Role::ActuatorExtended,
+        Role::ActuatorRetracted,
+        Role::ActuatorNeutral,
+        Role::TiltLineRoll,
+        Role::TiltLinePitch,
+        Role::FrameX,
+        Role::FrameY,
+        Role::FrameZ,
+        Role::Feedback,
+    ];
+
+    /// The key used for the role under `[theme.colors]`
+    pub fn name(self) -> &'static str {
+        match self {
+            Role::Text => "text",
+            Role::Muted => "muted",
+            Role::Secondary => "secondary",
+            Role::Header => "header",
+            Role::Ok => "ok",
+            Role::Warning => "warning",
+            Role::Error => "error",
+            Role::Highlight => "highlight",
+            Role::Background => "background",
+            Role::BasePlate => "base_plate",
+            Role::BaseDetail => "base_detail",
+            Role::UpperPlate => "upper_plate",
+            Role::Joint => "joint",
+            Role::Motor => "motor",
+            Role::WormGear => "worm_gear",
+            Role::Bearing => "bearing",
+            Role::Payload => "payload",
+            Role::ActuatorExtended => "actuator_extended",
+            Role::ActuatorRetracted => "actuator_retracted",
+            Role::ActuatorNeutral => "actuator_neutral",
+            Role::TiltLineRoll => "tilt_line_roll",
+            Role::TiltLinePitch => "tilt_line_pitch",
+            Role::FrameX => "frame_x",
+            Role::FrameY => "frame_y",
+            Role::FrameZ => "frame_z",
+            Role::Feedback => "feedback",
+        }
+    }
+
+    /// The role for a `[theme.colors]` key. The tilt lines were first called
+    /// roll_axis/pitch_axis; those names still work.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "roll_axis" => Some(Role::TiltLineRoll),
+            "pitch_axis" => Some(Role::TiltLinePitch),
+            _ => Self::ALL.into_iter().find(|role| role.name() == name),
+        }
+    }
+}
+
+// Built-in colors for a role. "dark" is the original look; "light" avoids the gray and white
+// shades that vanish on a light background; "high-contrast" uses brighter colors and keeps
+// extended/retracted apart by blue/orange rather than green/red.
+fn preset_color(preset: ThemePreset, role: Role) -> Color {
+    match preset {
+        ThemePreset::Dark => match role {
+            Role::Text | Role::UpperPlate | Role::Bearing => Color::White,
+            Role::Muted | Role::BaseDetail | Role::WormGear => Color::DarkGray,
+            Role::Secondary | Role::BasePlate => Color::Gray,
+            Role::Header | Role::TiltLinePitch => Color::Cyan,
+            Role::Ok | Role::FrameY => Color::Green,
+            Role::Warning | Role::ActuatorNeutral => Color::Yellow,
+            Role::Error | Role::Highlight | Role::FrameX => Color::Red,
+            Role::Background => Color::Black,
+            Role::Joint => Color::LightBlue,
+            Role::Motor | Role::FrameZ => Color::Blue,
+            Role::Payload => Color::LightCyan,
+            Role::ActuatorExtended => Color::LightGreen,
+            Role::ActuatorRetracted => Color::LightRed,
+            Role::TiltLineRoll => Color::Magenta,
+            Role::Feedback => Color::LightMagenta,
+        },
+        ThemePreset::Light => match role {
+            Role::Text | Role::UpperPlate | Role::Bearing => Color::Black,
+            Role::Muted => Color::Indexed(244),
+            Role::Secondary => Color::Indexed(238),
+            Role::Header | Role::Joint | Role::Motor | Role::FrameZ => Color::Blue,
+            Role::Ok | Role::FrameY | Role::ActuatorExtended => Color::Indexed(28),
+            Role::Warning => Color::Indexed(130),
+            Role::Error | Role::Highlight | Role::FrameX => Color::Red,
+            Role::Background => Color::White,
+            Role::BasePlate => Color::Indexed(240),
+            Role::BaseDetail | Role::WormGear => Color::Indexed(248),
+            Role::Payload => Color::Indexed(30),
+            Role::ActuatorRetracted => Color::Indexed(124),
+            Role::ActuatorNeutral => Color::Indexed(136),
+            Role::TiltLineRoll => Color::Magenta,
+            Role::TiltLinePitch => Color::Indexed(25),
+            Role::Feedback => Color::Indexed(90),
+        },
+        ThemePreset::HighContrast => match role {
+            Role::Text | Role::Secondary | Role::BasePlate | Role::UpperPlate | Role::Bearing | Role::ActuatorNeutral => Color::White,
+            Role::Muted | Role::BaseDetail | Role::WormGear => Color::Gray,
+            Role::Header | Role::Payload | Role::TiltLinePitch | Role::FrameY => Color::LightCyan,
+            Role::Ok | Role::Joint | Role::Motor | Role::ActuatorExtended => Color::LightBlue,
+            Role::Warning | Role::FrameZ => Color::LightYellow,
+            Role::Error => Color::LightRed,
+            Role::Highlight | Role::TiltLineRoll | Role::FrameX => Color::LightMagenta,
+            Role::Background => Color::Black,
+            Role::ActuatorRetracted => Color::Indexed(208),
+            Role::Feedback => Color::LightGreen,
+        },
+    }
+}
+
+/// The colors and styles the UI draws with: a preset, the config's overrides and NO_COLOR
+pub struct Theme {
+    colors: [Color; Role::ALL.len()],
+    // NO_COLOR: no hue at all, only modifiers and symbols
+    monochrome: bool,
+    markers: bool,
+}
+
+impl Theme {
+    /// Overrides are expected to have been checked by Config::repair; any that don't parse are skipped
+    pub fn new(config: &ThemeConfig, no_color: bool) -> Self {
+        let mut colors = Role::ALL.map(|role| preset_color(config.preset, role));
+        for (name, value) in &config.colors {
+            if let Some(role) = Role::parse(name)
+                && let Ok(color) = value.parse()
+            {
+                colors[role as usize] = color;
+            }
+        }
+        Self {
+            colors,
+            monochrome: no_color,
+            markers: no_color || config.preset == ThemePreset::HighContrast,
+        }
+    }
+
+    /// The role's color; `Color::Reset` without color
+    pub fn color(&self, role: Role) -> Color {
+        if self.monochrome { Color::Reset } else { self.colors[role as usize] }
+    }
+
+    /// Without color, roles that carry meaning are told apart by weight and inversion
+    pub fn style(&self, role: Role) -> Style {
+        if !self.monochrome {
+            return Style::default().fg(self.colors[role as usize]);
+        }
+        match role {
+            Role::Header | Role::Warning => Style::default().add_modifier(Modifier::BOLD),
+            Role::Error | Role::Highlight => Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            Role::Muted | Role::BaseDetail => Style::default().add_modifier(Modifier::DIM),
+            _ => Style::default(),
+        }
+    }
+
+    /// The two alternating styles of the flashing e-stop banner
+    pub fn alarm(&self, on: bool) -> Style {
+        match (self.monochrome, on) {
+            (true, true) => Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            (true, false) => Style::default().add_modifier(Modifier::BOLD),
+            (false, true) => Style::default().fg(self.color(Role::Text)).bg(self.color(Role::Error)),
+            (false, false) => Style::default().fg(self.color(Role::Error)).bg(self.color(Role::Background)),
+        }
+    }
+
+    /// Extended/retracted legs are also marked with ▲/▼, for when hue alone isn't enough
+    pub fn markers(&self) -> bool {
+        self.markers
+    }
+}
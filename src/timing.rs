@@ -0,0 +1,179 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+// Min/max and averages cover this much history
+const WINDOW: Duration = Duration::from_secs(5);
+// Enough for a 5 ms tick over the whole window; the buffers never grow past it
+const CAPACITY: usize = 1024;
+// Weight of the newest sample in the running frame and update time averages
+const EMA_WEIGHT: f64 = 0.1;
+
+// Average, min and max of a series over the window
+#[derive(Debug, Clone, Copy)]
+pub struct Summary {
+    pub avg: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+// Timestamped values in a preallocated ring, oldest first
+struct Series {
+    samples: VecDeque<(Instant, f64)>,
+}
+
+impl Series {
+    fn new() -> Self {
+        Self { samples: VecDeque::with_capacity(CAPACITY) }
+    }
+
+    fn push(&mut self, at: Instant, value: f64) {
+        while self
+            .samples
+            .front()
+            .is_some_and(|(t, _)| self.samples.len() == CAPACITY || at.duration_since(*t) > WINDOW)
+        {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((at, value));
+    }
+
+    fn summary(&self, now: Instant) -> Option<Summary> {
+        let mut count = 0usize;
+        let mut sum = 0.0;
+        let mut min = f64::INFINITY;
+        let mut max = f64::NEG_INFINITY;
+        for &(_, value) in self.samples.iter().filter(|(t, _)| now.duration_since(*t) <= WINDOW) {
+            count += 1;
+            sum += value;
+            min = min.min(value);
+            max = max.max(value);
+        }
+        (count > 0).then(|| Summary { avg: sum / count as f64, min, max })
+    }
+
+    // Samples per second, from the spacing of the ones still in the window
+    fn rate(&self, now: Instant) -> Option<f64> {
+        let mut recent = self.samples.iter().filter(|(t, _)| now.duration_since(*t) <= WINDOW);
+        let first = recent.next()?.0;
+        let (count, last) = recent.fold((1usize, first), |(count, _), (t, _)| (count + 1, *t));
+        let span = last.duration_since(first).as_secs_f64();
+        (count > 1 && span > 0.0).then(|| (count - 1) as f64 / span)
+    }
+}
+
+// Loop timing for the diagnostics overlay. Recording is Instant arithmetic into fixed-size
+// buffers, so it stays on even while the overlay is hidden.
+pub struct FrameStats {
+    draw_ms: Series,
+    update_ms: Series,
+    // One sample per fixed gimbal step, for the achieved update rate
+    steps: Series,
+    events: Series,
+    latency_ms: Series,
+    // Events taken since the last update finished
+    events_this_tick: u32,
+    // First gamepad event not yet shown by a completed draw
+    pending_input: Option<Instant>,
+    // Running averages for the debug panel's FPS line: time between completed draws and
+    // time per update, both in ms
+    last_frame: Option<Instant>,
+    frame_ema: Option<f64>,
+    update_ema: Option<f64>,
+}
+
+impl FrameStats {
+    pub fn new() -> Self {
+        Self {
+            draw_ms: Series::new(),
+            update_ms: Series::new(),
+            steps: Series::new(),
+            events: Series::new(),
+            latency_ms: Series::new(),
+            events_this_tick: 0,
+            pending_input: None,
+            last_frame: None,
+            frame_ema: None,
+            update_ema: None,
+        }
+    }
+
+    // Any terminal or gamepad event handled this tick
+    pub fn count_event(&mut self) {
+        self.events_this_tick += 1;
+    }
+
+    // A gamepad event arrived; the latency clock runs until the next draw completes
+    pub fn gamepad_event(&mut self, at: Instant) {
+        self.count_event();
+        self.pending_input.get_or_insert(at);
+    }
+
+    pub fn record_update(&mut self, started: Instant) {
+        let now = Instant::now();
+        self.update_ms.push(now, millis(now - started));
+        ema(&mut self.update_ema, millis(now - started));
+        self.events.push(now, f64::from(self.events_this_tick));
+        self.events_this_tick = 0;
+    }
+
+    pub fn record_step(&mut self, at: Instant) {
+        self.steps.push(at, 0.0);
+    }
+
+    pub fn record_draw(&mut self, started: Instant) {
+        let now = Instant::now();
+        self.draw_ms.push(now, millis(now - started));
+        if let Some(last) = self.last_frame.replace(now) {
+            ema(&mut self.frame_ema, millis(now - last));
+        }
+        if let Some(input) = self.pending_input.take() {
+            self.latency_ms.push(now, millis(now - input));
+        }
+    }
+
+    pub fn fps(&self, now: Instant) -> Option<f64> {
+        self.draw_ms.rate(now)
+    }
+
+    pub fn step_rate(&self, now: Instant) -> Option<f64> {
+        self.steps.rate(now)
+    }
+
+    pub fn draw_time(&self, now: Instant) -> Option<Summary> {
+        self.draw_ms.summary(now)
+    }
+
+    pub fn update_time(&self, now: Instant) -> Option<Summary> {
+        self.update_ms.summary(now)
+    }
+
+    pub fn events_per_tick(&self, now: Instant) -> Option<Summary> {
+        self.events.summary(now)
+    }
+
+    pub fn input_latency(&self, now: Instant) -> Option<Summary> {
+        self.latency_ms.summary(now)
+    }
+
+    // Frames per second from the running average of the time between draws
+    pub fn current_fps(&self) -> Option<f64> {
+        self.frame_ema.filter(|ms| *ms > 0.0).map(|ms| 1000.0 / ms)
+    }
+
+    pub fn frame_ms(&self) -> Option<f64> {
+        self.frame_ema
+    }
+
+    pub fn average_update_ms(&self) -> Option<f64> {
+        self.update_ema
+    }
+}
+
+// The first sample seeds the average so it doesn't start out ramping up from zero
+fn ema(average: &mut Option<f64>, sample: f64) {
+    *average = Some(average.map_or(sample, |avg| avg + (sample - avg) * EMA_WEIGHT));
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}